@@ -6,6 +6,9 @@
 //!
 //! Preprocess-only failures are WARN and do not fail the process.
 //! Any file that preprocesses cleanly but still has parse errors fails the process.
+//! Every file is also parsed from its raw tokens and must round-trip through
+//! `SyntaxTree::to_source`; a mismatch fails the process. The same tokens must
+//! also survive `erl_parse::format::format`, whose output must format to itself.
 
 use std::fs;
 use std::io::Write;
@@ -52,6 +55,8 @@ fn main() -> noargs::Result<ExitCode> {
     let mut tokenize_err_files = 0usize;
     let mut preprocess_err_files = 0usize;
     let mut parse_err_files = 0usize;
+    let mut round_trip_err_files = 0usize;
+    let mut format_err_files = 0usize;
     let mut token_total = 0usize;
     let mut warning_total = 0usize;
     let mut diag_error_total = 0usize;
@@ -71,6 +76,14 @@ fn main() -> noargs::Result<ExitCode> {
                 continue;
             }
         };
+        if !otp_conformance::round_trips(&text) {
+            round_trip_err_files += 1;
+            eprintln!("ERROR {display}: to_source does not reproduce the file");
+        }
+        if !otp_conformance::formats(&text) {
+            format_err_files += 1;
+            eprintln!("ERROR {display}: formatting changes tokens or is not idempotent");
+        }
         let include_paths = otp_conformance::build_include_paths(
             path,
             &root,
@@ -124,16 +137,20 @@ fn main() -> noargs::Result<ExitCode> {
     }
 
     println!(
-        "FILES: {}\nTOKENIZE ERROR FILES: {}\nPREPROCESS ERROR FILES: {}\nPARSE ERROR FILES: {}\nTOTAL TOKENS: {}\nTOTAL PREPROCESS DIAGNOSTICS: {} warnings / {} errors\nELAPSED: {:?}",
+        "FILES: {}\nTOKENIZE ERROR FILES: {}\nPREPROCESS ERROR FILES: {}\nPARSE ERROR FILES: {}\nROUND-TRIP ERROR FILES: {}\nFORMAT ERROR FILES: {}\nTOTAL TOKENS: {}\nTOTAL PREPROCESS DIAGNOSTICS: {} warnings / {} errors\nELAPSED: {:?}",
         files.len(),
         tokenize_err_files,
         preprocess_err_files,
         parse_err_files,
+        round_trip_err_files,
+        format_err_files,
         token_total,
         warning_total,
         diag_error_total,
         start.elapsed(),
     );
     let _ = std::io::stdout().flush();
-    Ok(ExitCode::from(u8::from(parse_err_files > 0)))
+    Ok(ExitCode::from(u8::from(
+        parse_err_files > 0 || round_trip_err_files > 0 || format_err_files > 0,
+    )))
 }
@@ -9,6 +9,9 @@
 //! ```
 //!
 //! Tokenize / preprocess XOR is WARN (exit 0). Parse XOR is ERROR (exit 1).
+//! Accepted synthetic snippets are compared as full abstract format
+//! (`~p` text) when the fixture carries it, and as operator-tree shapes
+//! otherwise.
 //! Per-form comparison drops OTP `epp`-only forms (`-file` in the fixture;
 //! `-feature` on the Rust side).
 
@@ -336,6 +339,21 @@ fn compare_tree(
     run: &otp_conformance::ParseRun,
     tree: &erl_parse::SyntaxTree,
 ) -> Compare {
+    match opt_str(v, "abstract") {
+        Ok(Some(expected)) => {
+            let Some(actual) = otp_conformance::abstract_text(tree, &run.source, &run.roots) else {
+                return Compare::Warn(format!("{id}: no abstract format for accepted input"));
+            };
+            if actual != expected {
+                return Compare::Error(format!(
+                    "{id}: abstract format differs\n--- otp\n{expected}\n--- rust\n{actual}"
+                ));
+            }
+            return Compare::Ok;
+        }
+        Ok(None) => {}
+        Err(e) => return Compare::Error(format!("{id}: {e}")),
+    }
     if let Some(expected_tree) = match opt_tree(v) {
         Ok(t) => t,
         Err(e) => return Compare::Error(format!("{id}: {e}")),
@@ -222,6 +222,48 @@ pub fn accepted(tree: &erl_parse::SyntaxTree) -> bool {
     tree.diagnostics().is_empty() && !has_error_node(tree)
 }
 
+/// True when parsing the raw (unpreprocessed) tokens of `text` in Module mode and
+/// calling `SyntaxTree::to_source` gives `text` back byte for byte. Text that does
+/// not tokenize has nothing to reconstruct and counts as a round trip.
+pub fn round_trips(text: &str) -> bool {
+    let Ok(tokens) = erl_tokenize::scan_tokens(text) else {
+        return true;
+    };
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for token in tokens {
+        parser.feed_token(token);
+    }
+    parser.finish().to_source(text) == text
+}
+
+/// True when formatting `text` with `erl_parse::format::format` keeps its lexical
+/// tokens and formatting the result again changes nothing. Like [`round_trips`], this
+/// parses the raw tokens in Module mode; text that does not tokenize counts as formatted.
+pub fn formats(text: &str) -> bool {
+    let format = |text: &str| {
+        let tokens = erl_tokenize::scan_tokens(text).ok()?;
+        let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+        for token in &tokens {
+            parser.feed_token(*token);
+        }
+        let formatted =
+            erl_parse::format::format(&parser.finish(), text, erl_parse::format::Options::new());
+        let lexical = tokens
+            .iter()
+            .filter(|token| token.kind().is_lexical())
+            .map(|token| token.text(text).to_owned())
+            .collect::<Vec<_>>();
+        Some((formatted, lexical))
+    };
+    let Some((formatted, lexical)) = format(text) else {
+        return true;
+    };
+    match format(&formatted) {
+        Some((again, relexed)) => again == formatted && relexed == lexical,
+        None => false,
+    }
+}
+
 fn has_error_node(tree: &erl_parse::SyntaxTree) -> bool {
     tree.roots().any(|root| {
         root.kind() == erl_parse::SyntaxKind::Error
@@ -540,6 +582,38 @@ pub fn tree_shape(
     Some(shape_node(tree, source, view))
 }
 
+/// `io:format("~p", [List])` text for the abstract format of every root,
+/// as the fixture's `abstract` field records it.
+///
+/// Annotations are `{Line, Column}` (the escript scans from `{1, 1}`).
+/// Module roots become forms, expression roots expressions, and term-list
+/// roots the plain terms `erl_parse:parse_term/1` returns. `None` when any
+/// root has no abstract form.
+pub fn abstract_text(
+    tree: &erl_parse::SyntaxTree,
+    source: &str,
+    roots: &[erl_parse::NodeId],
+) -> Option<String> {
+    use erl_parse::abstract_format::{self, Location, Term};
+
+    let location = Location::LineColumn;
+    let mut terms = Vec::new();
+    for id in roots {
+        let view = tree.view(*id)?;
+        terms.push(match tree.mode() {
+            erl_parse::ParseMode::Module => abstract_format::form(tree, source, view, location)?,
+            erl_parse::ParseMode::Type => abstract_format::ty(tree, source, view, location)?,
+            erl_parse::ParseMode::Expression => {
+                abstract_format::expr(tree, source, view, location)?
+            }
+            erl_parse::ParseMode::TermList => {
+                abstract_format::normalise(&abstract_format::expr(tree, source, view, location)?)?
+            }
+        });
+    }
+    Some(Term::list(terms).pretty())
+}
+
 fn unwrap_paren(node: erl_parse::NodeView<'_>) -> erl_parse::NodeView<'_> {
     let mut cur = node;
     while cur.kind() == erl_parse::SyntaxKind::ParenExpr
@@ -0,0 +1,225 @@
+//! Conversion to OTP's abstract format, the terms `erl_parse` returns.
+//!
+//! [`form`] turns a top-level node into the term `erl_parse:parse_form/1`
+//! builds for the same tokens (`{function, Anno, Name, Arity,
+//! Clauses}`, `{attribute, Anno, Name, Value}`, ...); [`expr`] and [`ty`]
+//! do the same for a single expression or type, and [`normalise`] turns
+//! an abstract literal back into the plain term it denotes, as
+//! `erl_parse:normalise/1` does. [`Term::pretty`] prints the result
+//! exactly as `io:format("~p", [Term])` would, so output can be diffed
+//! against OTP or pasted into an Erlang shell, and [`Term::to_etf`]
+//! encodes it as `term_to_binary/1` would, for handing forms to a BEAM
+//! node over a port.
+//!
+//! Conversion is strict: every function returns `None` where
+//! `erl_parse` would reject the input, where error recovery left a
+//! hole, or for constructs that have no abstract form here
+//! (preprocessor directives, native records). Annotations come from
+//! [`SyntaxTree::tokens`] positions; [`Location`] selects whether they
+//! carry a column.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::abstract_format::{self, Location};
+//!
+//! let source = "f(X) -> X + 1.";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let root = tree.roots().next().unwrap();
+//! let form = abstract_format::form(&tree, source, root, Location::Line).unwrap();
+//! assert_eq!(
+//!     form.pretty(),
+//!     "{function,1,f,1,\n          [{clause,1,\n                   [{var,1,'X'}],\n                   [],\n                   [{op,1,'+',{var,1,'X'},{integer,1,1}}]}]}",
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+mod convert;
+mod etf;
+pub(crate) mod pretty;
+mod term;
+
+pub use self::term::{Integer, Term};
+
+use self::convert::Converter;
+use crate::ast::Node;
+use crate::node::NodeView;
+use crate::syntax_tree::SyntaxTree;
+
+/// The annotation attached to each abstract-format node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// `Line`, as `erl_scan:string/1` produces.
+    Line,
+    /// `{Line, Column}`, as `erl_scan:string(S, {1, 1})` produces.
+    LineColumn,
+}
+
+/// Converts a top-level form: a function or an attribute.
+///
+/// Attribute values follow `erl_parse:build_attribute/2`: `-module`,
+/// `-export`, `-import`, `-file`, `-spec`, `-callback`, `-type`,
+/// `-opaque`, `-nominal`, and `-record` get their dedicated shapes;
+/// any other attribute with a single argument gets that argument as a
+/// normalised literal. Returns `None` for preprocessor directives and
+/// attributes OTP would reject.
+pub fn form(
+    tree: &SyntaxTree,
+    source: &str,
+    node: NodeView<'_>,
+    location: Location,
+) -> Option<Term> {
+    let converter = Converter::new(tree, source, location);
+    converter
+        .is_clean(node)
+        .then(|| converter.form(Node::new(node)))?
+}
+
+/// Converts an expression or pattern.
+pub fn expr(
+    tree: &SyntaxTree,
+    source: &str,
+    node: NodeView<'_>,
+    location: Location,
+) -> Option<Term> {
+    let converter = Converter::new(tree, source, location);
+    converter
+        .is_clean(node)
+        .then(|| converter.expr(Node::new(node)))?
+}
+
+/// Converts a type, as it appears in a `-type` body or a `-spec`
+/// clause.
+pub fn ty(tree: &SyntaxTree, source: &str, node: NodeView<'_>, location: Location) -> Option<Term> {
+    let converter = Converter::new(tree, source, location);
+    converter
+        .is_clean(node)
+        .then(|| converter.ty(Node::new(node)))?
+}
+
+/// Turns an abstract literal into the term it denotes, as
+/// `erl_parse:normalise/1` does.
+///
+/// Accepts atoms, numbers, characters, strings, lists, tuples, maps
+/// with `=>` fields, binaries of integer and string segments, and
+/// `+`/`-` applied to a number. Returns `None` for anything else.
+pub fn normalise(term: &Term) -> Option<Term> {
+    let elements = term.as_tuple()?;
+    let (tag, rest) = elements.split_first()?;
+    match (tag.as_atom()?, rest) {
+        ("char" | "integer" | "float" | "atom", [_, value]) => Some(value.clone()),
+        ("string", [_, chars]) => Some(chars.clone()),
+        ("nil", [_]) => Some(Term::List(Vec::new())),
+        ("cons", [_, head, tail]) => {
+            let head = normalise(head)?;
+            match normalise(tail)? {
+                Term::List(mut items) => {
+                    items.insert(0, head);
+                    Some(Term::List(items))
+                }
+                Term::ImproperList(mut items, tail) => {
+                    items.insert(0, head);
+                    Some(Term::ImproperList(items, tail))
+                }
+                tail => Some(Term::ImproperList(vec![head], Box::new(tail))),
+            }
+        }
+        ("tuple", [_, Term::List(items)]) => items
+            .iter()
+            .map(normalise)
+            .collect::<Option<_>>()
+            .map(Term::Tuple),
+        ("map", [_, Term::List(fields)]) => {
+            let mut pairs: Vec<(Term, Term)> = Vec::new();
+            for field in fields {
+                let [tag, _, key, value] = field.as_tuple()? else {
+                    return None;
+                };
+                if tag.as_atom()? != "map_field_assoc" {
+                    return None;
+                }
+                let (key, value) = (normalise(key)?, normalise(value)?);
+                match pairs.iter_mut().find(|(k, _)| *k == key) {
+                    Some(pair) => pair.1 = value,
+                    None => pairs.push((key, value)),
+                }
+            }
+            Some(Term::Map(pairs))
+        }
+        ("bin", [_, Term::List(segments)]) => {
+            let mut bytes = Vec::new();
+            for segment in segments {
+                normalise_segment(segment, &mut bytes)?;
+            }
+            Some(Term::Binary(bytes))
+        }
+        ("op", [_, op, operand]) => match (op.as_atom()?, normalise(operand)?) {
+            ("+", n @ (Term::Integer(_) | Term::Float(_))) => Some(n),
+            ("-", Term::Integer(n)) => Some(Term::Integer(n.negate())),
+            ("-", Term::Float(f)) => Some(Term::Float(-f)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Appends one `{bin_element, ...}` of a literal binary: an integer
+/// byte, an integer of a whole number of bytes, or a string.
+fn normalise_segment(segment: &Term, bytes: &mut Vec<u8>) -> Option<()> {
+    let [tag, _, value, size, specs] = segment.as_tuple()? else {
+        return None;
+    };
+    if tag.as_atom()? != "bin_element" {
+        return None;
+    }
+    let utf8 = match specs {
+        Term::Atom(a) if a == "default" => false,
+        Term::List(specs) => match specs.as_slice() {
+            [Term::Atom(a)] if a == "utf8" => true,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let bits = match size {
+        Term::Atom(a) if a == "default" => 8,
+        size => usize::try_from(normalise(size)?.as_i64()?).ok()?,
+    };
+    match normalise(value)? {
+        Term::Integer(n) if !utf8 && bits % 8 == 0 => {
+            let width = bits / 8;
+            let mut le = n.magnitude().to_vec();
+            le.resize(le.len().max(width), 0);
+            if n.is_negative() {
+                // Two's complement over `width` bytes.
+                let mut carry = true;
+                for byte in &mut le {
+                    *byte = !*byte;
+                    if carry {
+                        let (sum, overflow) = byte.overflowing_add(1);
+                        *byte = sum;
+                        carry = overflow;
+                    }
+                }
+            }
+            bytes.extend(le[..width].iter().rev());
+            Some(())
+        }
+        Term::List(chars) if matches!(size, Term::Atom(_)) => {
+            for c in chars {
+                let code = u32::try_from(c.as_i64()?).ok()?;
+                if utf8 {
+                    let mut buf = [0; 4];
+                    bytes.extend_from_slice(char::from_u32(code)?.encode_utf8(&mut buf).as_bytes());
+                } else {
+                    bytes.push(code as u8);
+                }
+            }
+            Some(())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,1395 @@
+//! [`SyntaxTree`] nodes to abstract-format [`Term`]s.
+//!
+//! Each production maps to the tuple `erl_parse.yrl` builds for it,
+//! annotated with the token OTP's grammar action picks: the first
+//! token for most constructs, the operator for `{op, ...}`, `{match,
+//! ...}`, `{remote, ...}`, generators, and map associations, and the
+//! first sub-term's annotation where the action uses `?anno('$1')` on a
+//! built term (calls, clause heads, unions, ranges). Every conversion
+//! returns `None` where OTP's parser would reject the input or where
+//! recovery left a hole.
+
+use erl_tokenize::{Keyword, Symbol, Token, TokenKind, TokenValue};
+
+use super::Location;
+use super::term::{Integer, Term};
+use crate::ast::{self, AstNode, Node};
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenIndex;
+
+pub(crate) struct Converter<'a> {
+    tree: &'a SyntaxTree,
+    source: &'a str,
+    location: Location,
+}
+
+fn tagged(tag: &str, anno: Term, rest: impl IntoIterator<Item = Term>) -> Term {
+    let mut elements = vec![Term::atom(tag), anno];
+    elements.extend(rest);
+    Term::Tuple(elements)
+}
+
+/// The annotation of an abstract-format tuple (its second element).
+fn anno_of(term: &Term) -> Option<Term> {
+    term.as_tuple()?.get(1).cloned()
+}
+
+/// `{cons, A, H, T}` chains over `elements` ending in `tail`; the first
+/// cell takes `first`, later cells their element's annotation.
+fn cons_chain(first: Term, elements: Vec<Term>, tail: Term) -> Option<Term> {
+    let mut list = tail;
+    let count = elements.len();
+    for (i, element) in elements.into_iter().enumerate().rev() {
+        let anno = if i == 0 {
+            first.clone()
+        } else {
+            anno_of(&element)?
+        };
+        list = tagged("cons", anno, [element, list]);
+    }
+    (count > 0).then_some(list)
+}
+
+fn all<T>(items: impl Iterator<Item = Option<T>>) -> Option<Vec<T>> {
+    items.collect()
+}
+
+impl<'a> Converter<'a> {
+    pub(crate) fn new(tree: &'a SyntaxTree, source: &'a str, location: Location) -> Self {
+        Self {
+            tree,
+            source,
+            location,
+        }
+    }
+
+    /// Rejects nodes that contain a recovery hole or overlap a
+    /// diagnostic.
+    pub(crate) fn is_clean(&self, node: NodeView<'_>) -> bool {
+        let range = node.range();
+        node.kind() != SyntaxKind::Error
+            && !node.descendants().any(|d| d.kind() == SyntaxKind::Error)
+            && !self.tree.diagnostics().iter().any(|d| {
+                let r = d.range();
+                r.start() < range.end()
+                    && range.start() < r.end().max(TokenIndex::new(r.start().get() + 1))
+            })
+    }
+
+    // -----------------------------------------------------------------
+    // Tokens.
+    // -----------------------------------------------------------------
+
+    fn token(&self, index: TokenIndex) -> Token {
+        self.tree.tokens()[index.get()]
+    }
+
+    fn anno(&self, index: TokenIndex) -> Term {
+        let start = self.token(index).start();
+        let line = start.line().get() as i64;
+        match self.location {
+            Location::Line => Term::from(line),
+            Location::LineColumn => {
+                Term::tuple([Term::from(line), Term::from(start.column().get() as i64)])
+            }
+        }
+    }
+
+    fn first_token(&self, node: NodeView<'_>) -> Option<TokenIndex> {
+        node.tokens_in_range()
+            .find(|(_, t)| t.kind().is_lexical())
+            .map(|(i, _)| i)
+    }
+
+    fn last_token(&self, node: NodeView<'_>) -> Option<TokenIndex> {
+        node.tokens_in_range()
+            .filter(|(_, t)| t.kind().is_lexical())
+            .last()
+            .map(|(i, _)| i)
+    }
+
+    fn node_anno(&self, node: NodeView<'_>) -> Option<Term> {
+        Some(self.anno(self.first_token(node)?))
+    }
+
+    /// The first lexical token at or after `from` and before `to`: the
+    /// operator or separator between two children.
+    fn token_between(&self, from: TokenIndex, to: TokenIndex) -> Option<TokenIndex> {
+        (from.get()..to.get())
+            .map(TokenIndex::new)
+            .find(|&i| self.token(i).kind().is_lexical())
+    }
+
+    fn atom_name(&self, index: TokenIndex) -> Option<String> {
+        match self.token(index).value(self.source) {
+            TokenValue::Atom(name) => Some(name.into_owned()),
+            _ => None,
+        }
+    }
+
+    fn var_name(&self, index: TokenIndex) -> Option<String> {
+        let token = self.token(index);
+        (token.kind() == TokenKind::Variable).then(|| token.text(self.source).to_string())
+    }
+
+    /// `{atom, A, Name}` or `{var, A, Name}` for a name token.
+    fn name_term(&self, index: TokenIndex) -> Option<Term> {
+        let anno = self.anno(index);
+        match self.token(index).kind() {
+            TokenKind::Atom => Some(tagged("atom", anno, [Term::Atom(self.atom_name(index)?)])),
+            TokenKind::Variable => Some(tagged("var", anno, [Term::Atom(self.var_name(index)?)])),
+            _ => None,
+        }
+    }
+
+    fn atom_term(&self, index: TokenIndex) -> Option<Term> {
+        let name = self.atom_name(index)?;
+        Some(tagged("atom", self.anno(index), [Term::Atom(name)]))
+    }
+
+    fn integer_value(&self, index: TokenIndex) -> Option<Integer> {
+        let token = self.token(index);
+        (token.kind() == TokenKind::Integer)
+            .then(|| Integer::parse_literal(token.text(self.source)))?
+    }
+
+    /// `{integer, A, N}`, `{var, A, Name}`, or `{atom, A, Name}` for a
+    /// fun-reference part.
+    fn literal_term(&self, index: TokenIndex) -> Option<Term> {
+        match self.token(index).kind() {
+            TokenKind::Integer => Some(tagged(
+                "integer",
+                self.anno(index),
+                [Term::Integer(self.integer_value(index)?)],
+            )),
+            _ => self.name_term(index),
+        }
+    }
+
+    /// The operator's name: the token text (`+`, `=:=`, `andalso`, ...).
+    fn operator(&self, index: TokenIndex) -> Term {
+        Term::atom(self.token(index).text(self.source))
+    }
+
+    // -----------------------------------------------------------------
+    // Expressions and patterns.
+    // -----------------------------------------------------------------
+
+    pub(crate) fn expr(&self, node: Node<'_>) -> Option<Term> {
+        let view = node.syntax();
+        match node {
+            Node::AtomExpr(n) => self.atom_term(n.token()?),
+            Node::VarExpr(n) => self.name_term(n.token()?),
+            Node::IntegerExpr(n) => {
+                let token = n.token()?;
+                Some(tagged(
+                    "integer",
+                    self.anno(token),
+                    [Term::Integer(self.integer_value(token)?)],
+                ))
+            }
+            Node::FloatExpr(n) => {
+                let token = n.token()?;
+                match self.token(token).value(self.source) {
+                    TokenValue::Float(f) => {
+                        Some(tagged("float", self.anno(token), [Term::Float(f)]))
+                    }
+                    _ => None,
+                }
+            }
+            Node::CharExpr(n) => {
+                let token = n.token()?;
+                match self.token(token).value(self.source) {
+                    TokenValue::Char(c) => Some(tagged(
+                        "char",
+                        self.anno(token),
+                        [Term::from(i64::from(u32::from(c)))],
+                    )),
+                    _ => None,
+                }
+            }
+            Node::StringExpr(n) => {
+                let mut tokens = n.tokens().peekable();
+                let anno = self.anno(*tokens.peek()?);
+                let mut text = String::new();
+                for token in tokens {
+                    match self.token(token).value(self.source) {
+                        TokenValue::String(s) => text.push_str(&s),
+                        _ => return None,
+                    }
+                }
+                Some(tagged("string", anno, [Term::string(&text)]))
+            }
+            Node::SigilStringExpr(n) => {
+                let token = n.token()?;
+                let TokenValue::SigilString {
+                    prefix,
+                    content,
+                    suffix,
+                } = self.token(token).value(self.source)
+                else {
+                    return None;
+                };
+                if !matches!(prefix, "" | "b" | "B" | "s" | "S") || !suffix.is_empty() {
+                    return None;
+                }
+                let a = self.anno(token);
+                let element = tagged(
+                    "bin_element",
+                    a.clone(),
+                    [
+                        tagged("string", a.clone(), [Term::string(&content)]),
+                        Term::atom("default"),
+                        Term::list([Term::atom("utf8")]),
+                    ],
+                );
+                Some(tagged("bin", a, [Term::list([element])]))
+            }
+            Node::TupleExpr(n) => Some(tagged(
+                "tuple",
+                self.node_anno(view)?,
+                [Term::List(self.exprs(n.elements())?)],
+            )),
+            Node::ListExpr(n) => {
+                let open = self.node_anno(view)?;
+                let nil = tagged("nil", self.anno(self.last_token(view)?), []);
+                let elements = self.exprs(n.elements())?;
+                if elements.is_empty() {
+                    return Some(tagged("nil", open, []));
+                }
+                cons_chain(open, elements, nil)
+            }
+            Node::ConsExpr(n) => {
+                let tail = self.expr(n.tail()?)?;
+                cons_chain(self.node_anno(view)?, self.exprs(n.heads())?, tail)
+            }
+            Node::ParenExpr(n) => self.expr(n.expr()?),
+            Node::BitstringExpr(n) => {
+                let elements = all(n.elements().map(|e| self.bin_element(e)))?;
+                Some(tagged("bin", self.node_anno(view)?, [Term::List(elements)]))
+            }
+            Node::MapExpr(n) => {
+                let fields = all(n.fields().map(|f| self.map_field(f)))?;
+                Some(tagged("map", self.node_anno(view)?, [Term::List(fields)]))
+            }
+            Node::MapUpdateExpr(n) => {
+                let base = n.base()?;
+                let sharp = self.token_between(base.syntax().range().end(), view.range().end())?;
+                let fields = all(n.fields().map(|f| self.map_field(f)))?;
+                Some(tagged(
+                    "map",
+                    self.anno(sharp),
+                    [self.expr(base)?, Term::List(fields)],
+                ))
+            }
+            Node::RecordExpr(n) => {
+                if n.module().is_some() {
+                    return None;
+                }
+                let fields = all(n.fields().map(|f| self.record_field(f)))?;
+                Some(tagged(
+                    "record",
+                    self.node_anno(view)?,
+                    [Term::Atom(self.atom_name(n.name()?)?), Term::List(fields)],
+                ))
+            }
+            Node::RecordUpdateExpr(n) => {
+                if n.module().is_some() {
+                    return None;
+                }
+                let base = n.base()?;
+                let sharp = self.token_between(base.syntax().range().end(), view.range().end())?;
+                let fields = all(n.fields().map(|f| self.record_field(f)))?;
+                Some(tagged(
+                    "record",
+                    self.anno(sharp),
+                    [
+                        self.expr(base)?,
+                        Term::Atom(self.atom_name(n.name()?)?),
+                        Term::List(fields),
+                    ],
+                ))
+            }
+            Node::RecordFieldAccessExpr(n) => {
+                if n.module().is_some() {
+                    return None;
+                }
+                let base = n.base()?;
+                let sharp = self.token_between(base.syntax().range().end(), view.range().end())?;
+                Some(tagged(
+                    "record_field",
+                    self.anno(sharp),
+                    [
+                        self.expr(base)?,
+                        Term::Atom(self.atom_name(n.name()?)?),
+                        self.atom_term(n.field()?)?,
+                    ],
+                ))
+            }
+            Node::RecordIndexExpr(n) => {
+                if n.module().is_some() {
+                    return None;
+                }
+                Some(tagged(
+                    "record_index",
+                    self.node_anno(view)?,
+                    [
+                        Term::Atom(self.atom_name(n.name()?)?),
+                        self.atom_term(n.field()?)?,
+                    ],
+                ))
+            }
+            Node::BinaryOpExpr(n) => {
+                let op = n.operator()?;
+                Some(tagged(
+                    "op",
+                    self.anno(op),
+                    [
+                        self.operator(op),
+                        self.expr(n.lhs()?)?,
+                        self.expr(n.rhs()?)?,
+                    ],
+                ))
+            }
+            Node::SendExpr(n) => {
+                let op = n.operator()?;
+                Some(tagged(
+                    "op",
+                    self.anno(op),
+                    [
+                        self.operator(op),
+                        self.expr(n.target()?)?,
+                        self.expr(n.message()?)?,
+                    ],
+                ))
+            }
+            Node::UnaryOpExpr(n) => {
+                let op = n.operator()?;
+                Some(tagged(
+                    "op",
+                    self.anno(op),
+                    [self.operator(op), self.expr(n.operand()?)?],
+                ))
+            }
+            Node::MatchExpr(n) => Some(tagged(
+                "match",
+                self.anno(n.operator()?),
+                [self.expr(n.pattern()?)?, self.expr(n.expr()?)?],
+            )),
+            Node::MaybeMatchExpr(n) => Some(tagged(
+                "maybe_match",
+                self.anno(n.operator()?),
+                [self.expr(n.pattern()?)?, self.expr(n.expr()?)?],
+            )),
+            Node::CallExpr(n) => {
+                let target = self.expr(n.target()?)?;
+                let args = self.exprs(n.arguments()?.args())?;
+                Some(tagged(
+                    "call",
+                    anno_of(&target)?,
+                    [target, Term::List(args)],
+                ))
+            }
+            Node::RemoteExpr(n) => {
+                let module = n.module()?;
+                let function = n.function()?;
+                let colon = self.token_between(
+                    module.syntax().range().end(),
+                    function.syntax().range().start(),
+                )?;
+                Some(tagged(
+                    "remote",
+                    self.anno(colon),
+                    [self.expr(module)?, self.expr(function)?],
+                ))
+            }
+            Node::BeginExpr(n) => Some(tagged(
+                "block",
+                self.node_anno(view)?,
+                [Term::List(self.body(n.body())?)],
+            )),
+            Node::CatchExpr(n) => Some(tagged(
+                "catch",
+                self.node_anno(view)?,
+                [self.expr(n.expr()?)?],
+            )),
+            Node::CaseExpr(n) => {
+                let clauses = all(n.clauses().map(|c| self.cr_clause(c)))?;
+                Some(tagged(
+                    "case",
+                    self.node_anno(view)?,
+                    [self.expr(n.subject()?)?, Term::List(clauses)],
+                ))
+            }
+            Node::IfExpr(n) => {
+                let clauses = all(n.clauses().map(|c| self.if_clause(c)))?;
+                Some(tagged("if", self.node_anno(view)?, [Term::List(clauses)]))
+            }
+            Node::ReceiveExpr(n) => {
+                let clauses = Term::List(all(n.clauses().map(|c| self.cr_clause(c)))?);
+                let anno = self.node_anno(view)?;
+                match n.after() {
+                    None => Some(tagged("receive", anno, [clauses])),
+                    Some(after) => Some(tagged(
+                        "receive",
+                        anno,
+                        [
+                            clauses,
+                            self.expr(after.timeout()?)?,
+                            Term::List(self.body(after.body())?),
+                        ],
+                    )),
+                }
+            }
+            Node::TryExpr(n) => {
+                let of = match n.of_section() {
+                    Some(of) => all(of.clauses().map(|c| self.cr_clause(c)))?,
+                    None => Vec::new(),
+                };
+                let catches = match n.catch_section() {
+                    Some(catch) => all(catch.clauses().map(|c| self.try_clause(c)))?,
+                    None => Vec::new(),
+                };
+                let after = match n.after_section() {
+                    Some(after) => self.body(after.body())?,
+                    None => Vec::new(),
+                };
+                if catches.is_empty() && after.is_empty() {
+                    return None;
+                }
+                Some(tagged(
+                    "try",
+                    self.node_anno(view)?,
+                    [
+                        Term::List(self.body(n.body())?),
+                        Term::List(of),
+                        Term::List(catches),
+                        Term::List(after),
+                    ],
+                ))
+            }
+            Node::MaybeExpr(n) => {
+                let anno = self.node_anno(view)?;
+                let body = Term::List(self.body(n.body())?);
+                match n.else_section() {
+                    None => Some(tagged("maybe", anno, [body])),
+                    Some(section) => {
+                        let keyword = section
+                            .syntax()
+                            .tokens_in_range()
+                            .find(|(_, t)| t.kind() == TokenKind::Keyword(Keyword::Else))?
+                            .0;
+                        let clauses = all(section.clauses().map(|c| self.cr_clause(c)))?;
+                        Some(tagged(
+                            "maybe",
+                            anno,
+                            [
+                                body,
+                                tagged("else", self.anno(keyword), [Term::List(clauses)]),
+                            ],
+                        ))
+                    }
+                }
+            }
+            Node::AnonymousFun(n) => {
+                let clauses = all(n.clauses().map(|c| self.fun_clause(c, None)))?;
+                same_arity(&clauses)?;
+                Some(tagged(
+                    "fun",
+                    self.node_anno(view)?,
+                    [Term::tuple([Term::atom("clauses"), Term::List(clauses)])],
+                ))
+            }
+            Node::NamedFun(n) => {
+                let name = self.var_name(n.name()?)?;
+                let clauses = all(n.clauses().map(|c| self.fun_clause(c, Some(&name))))?;
+                same_arity(&clauses)?;
+                Some(tagged(
+                    "named_fun",
+                    self.node_anno(view)?,
+                    [Term::Atom(name), Term::List(clauses)],
+                ))
+            }
+            Node::LocalFunRef(n) => Some(tagged(
+                "fun",
+                self.node_anno(view)?,
+                [Term::tuple([
+                    Term::atom("function"),
+                    Term::Atom(self.atom_name(n.name()?)?),
+                    Term::Integer(self.integer_value(n.arity()?)?),
+                ])],
+            )),
+            Node::RemoteFunRef(n) => Some(tagged(
+                "fun",
+                self.node_anno(view)?,
+                [Term::tuple([
+                    Term::atom("function"),
+                    self.name_term(n.module()?)?,
+                    self.name_term(n.name()?)?,
+                    self.literal_term(n.arity()?)?,
+                ])],
+            )),
+            Node::ListComprehension(n) => Some(tagged(
+                "lc",
+                self.node_anno(view)?,
+                [
+                    self.expr(n.template()?)?,
+                    Term::List(self.qualifiers(n.qualifiers())?),
+                ],
+            )),
+            Node::BinaryComprehension(n) => {
+                // The template is an `expr_max`; the tree wraps it in a
+                // segment, which must carry no size or type list.
+                let template = match n.template()? {
+                    Node::BitstringElement(e) if e.size().is_some() => return None,
+                    Node::BitstringElement(e) if e.type_specifiers().next().is_some() => {
+                        return None;
+                    }
+                    Node::BitstringElement(e) => e.value()?,
+                    other => other,
+                };
+                Some(tagged(
+                    "bc",
+                    self.node_anno(view)?,
+                    [
+                        self.expr(template)?,
+                        Term::List(self.qualifiers(n.qualifiers())?),
+                    ],
+                ))
+            }
+            Node::MapComprehension(n) => {
+                let Node::MapField(template) = n.template()? else {
+                    return None;
+                };
+                if template.is_exact() {
+                    return None;
+                }
+                Some(tagged(
+                    "mc",
+                    self.node_anno(view)?,
+                    [
+                        self.map_field(template)?,
+                        Term::List(self.qualifiers(n.qualifiers())?),
+                    ],
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn exprs<'n>(&self, nodes: impl Iterator<Item = Node<'n>>) -> Option<Vec<Term>> {
+        all(nodes.map(|n| self.expr(n)))
+    }
+
+    fn body(&self, body: Option<ast::Body<'_>>) -> Option<Vec<Term>> {
+        let exprs = self.exprs(body?.exprs())?;
+        (!exprs.is_empty()).then_some(exprs)
+    }
+
+    fn guard(&self, guard: Option<ast::GuardSequence<'_>>) -> Option<Term> {
+        let Some(guard) = guard else {
+            return Some(Term::List(Vec::new()));
+        };
+        let guards = all(guard
+            .guards()
+            .map(|g| self.exprs(g.exprs()).map(Term::List)))?;
+        (!guards.is_empty()).then_some(Term::List(guards))
+    }
+
+    fn bin_element(&self, element: ast::BitstringElement<'_>) -> Option<Term> {
+        let value = self.expr(element.value()?)?;
+        let size = match element.size() {
+            Some(size) => self.expr(size)?,
+            None => Term::atom("default"),
+        };
+        let view = element.syntax();
+        let specs = match view
+            .tokens_in_range()
+            .filter(|(_, t)| t.kind().is_lexical())
+            .skip_while(|(_, t)| t.kind() != TokenKind::Symbol(Symbol::Slash))
+            .skip(1)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+        {
+            specs if specs.is_empty() => Term::atom("default"),
+            specs => self.bit_type_list(&specs)?,
+        };
+        Some(tagged(
+            "bin_element",
+            anno_of(&value)?,
+            [value, size, specs],
+        ))
+    }
+
+    /// `Type[:Unit]-Type...` after a segment's `/`: names become atoms,
+    /// `unit:N` becomes `{unit, N}`.
+    fn bit_type_list(&self, tokens: &[TokenIndex]) -> Option<Term> {
+        let mut out = Vec::new();
+        let mut rest = tokens;
+        loop {
+            let (&name, tail) = rest.split_first()?;
+            let name = Term::Atom(self.atom_name(name)?);
+            rest = tail;
+            match rest {
+                [colon, value, tail @ ..]
+                    if self.token(*colon).kind() == TokenKind::Symbol(Symbol::Colon) =>
+                {
+                    out.push(Term::tuple([
+                        name,
+                        Term::Integer(self.integer_value(*value)?),
+                    ]));
+                    rest = tail;
+                }
+                _ => out.push(name),
+            }
+            match rest.split_first() {
+                None => return Some(Term::List(out)),
+                Some((dash, tail))
+                    if self.token(*dash).kind() == TokenKind::Symbol(Symbol::Hyphen) =>
+                {
+                    rest = tail;
+                }
+                Some(_) => return None,
+            }
+        }
+    }
+
+    fn map_field(&self, field: ast::MapField<'_>) -> Option<Term> {
+        let tag = if field.is_exact() {
+            "map_field_exact"
+        } else {
+            "map_field_assoc"
+        };
+        Some(tagged(
+            tag,
+            self.anno(field.operator()?),
+            [self.expr(field.key()?)?, self.expr(field.value()?)?],
+        ))
+    }
+
+    fn record_field(&self, field: ast::RecordField<'_>) -> Option<Term> {
+        let name = field.name()?;
+        Some(tagged(
+            "record_field",
+            self.anno(name),
+            [self.name_term(name)?, self.expr(field.value()?)?],
+        ))
+    }
+
+    fn qualifiers<'n>(&self, nodes: impl Iterator<Item = Node<'n>>) -> Option<Vec<Term>> {
+        all(nodes.map(|n| self.qualifier(n)))
+    }
+
+    fn qualifier(&self, node: Node<'_>) -> Option<Term> {
+        let view = node.syntax();
+        let generate = |tag: &str, pattern: Node<'_>, expr: Node<'_>| {
+            let arrow = self.token_between(
+                pattern.syntax().range().end(),
+                expr.syntax().range().start(),
+            )?;
+            Some(tagged(
+                tag,
+                self.anno(arrow),
+                [self.expr(pattern)?, self.expr(expr)?],
+            ))
+        };
+        let map_generate = |tag: &str, key: Node<'_>, value: Node<'_>, expr: Node<'_>| {
+            let exact =
+                self.token_between(key.syntax().range().end(), value.syntax().range().start())?;
+            let arrow =
+                self.token_between(value.syntax().range().end(), expr.syntax().range().start())?;
+            let field = tagged(
+                "map_field_exact",
+                self.anno(exact),
+                [self.expr(key)?, self.expr(value)?],
+            );
+            Some(tagged(tag, self.anno(arrow), [field, self.expr(expr)?]))
+        };
+        match node {
+            Node::Generator(n) => generate("generate", n.pattern()?, n.expr()?),
+            Node::StrictGenerator(n) => generate("generate_strict", n.pattern()?, n.expr()?),
+            Node::BitstringGenerator(n) => generate("b_generate", n.pattern()?, n.expr()?),
+            Node::StrictBitstringGenerator(n) => {
+                generate("b_generate_strict", n.pattern()?, n.expr()?)
+            }
+            Node::MapGenerator(n) => map_generate("m_generate", n.key()?, n.value()?, n.expr()?),
+            Node::StrictMapGenerator(n) => {
+                map_generate("m_generate_strict", n.key()?, n.value()?, n.expr()?)
+            }
+            Node::ZipQualifier(n) => {
+                let generators = self.qualifiers(n.generators())?;
+                Some(tagged(
+                    "zip",
+                    anno_of(generators.first()?)?,
+                    [Term::List(generators)],
+                ))
+            }
+            Node::Filter(n) => self.expr(n.expr()?),
+            _ if view.kind() != SyntaxKind::Error => self.expr(node),
+            _ => None,
+        }
+    }
+
+    /// A `case` / `receive` / `of` / `else` clause:
+    /// `{clause, A, [Pattern], Guard, Body}`.
+    fn cr_clause(&self, clause: ast::Clause<'_>) -> Option<Term> {
+        let pattern = self.expr(clause.pattern()?)?;
+        Some(tagged(
+            "clause",
+            anno_of(&pattern)?,
+            [
+                Term::list([pattern]),
+                self.guard(clause.guard())?,
+                Term::List(self.body(clause.body())?),
+            ],
+        ))
+    }
+
+    fn if_clause(&self, clause: ast::IfClause<'_>) -> Option<Term> {
+        let guard = self.guard(clause.guard())?;
+        let first = guard.as_list()?.first()?.as_list()?.first()?;
+        Some(tagged(
+            "clause",
+            anno_of(first)?,
+            [
+                Term::List(Vec::new()),
+                guard.clone(),
+                Term::List(self.body(clause.body())?),
+            ],
+        ))
+    }
+
+    /// A `try ... catch` clause; the pattern becomes the
+    /// `{Class, Reason, Stacktrace}` triple OTP's grammar builds.
+    fn try_clause(&self, clause: Node<'_>) -> Option<Term> {
+        let (anno, class, pattern, stack, guard, body) = match clause {
+            Node::Clause(c) => {
+                let pattern = self.expr(c.pattern()?)?;
+                let anno = anno_of(&pattern)?;
+                let class = tagged("atom", anno.clone(), [Term::atom("throw")]);
+                (anno, class, pattern, "_".to_string(), c.guard(), c.body())
+            }
+            Node::CatchClause(c) => {
+                let class = c.class()?;
+                let stack = match c.stacktrace() {
+                    Some(s) => self.var_name(s)?,
+                    None => "_".to_string(),
+                };
+                (
+                    self.anno(class),
+                    self.name_term(class)?,
+                    self.expr(c.pattern()?)?,
+                    stack,
+                    c.guard(),
+                    c.body(),
+                )
+            }
+            _ => return None,
+        };
+        let stack = tagged("var", anno.clone(), [Term::Atom(stack)]);
+        Some(tagged(
+            "clause",
+            anno.clone(),
+            [
+                Term::list([tagged("tuple", anno, [Term::list([class, pattern, stack])])]),
+                self.guard(guard)?,
+                Term::List(self.body(body)?),
+            ],
+        ))
+    }
+
+    /// A `fun` clause. Anonymous clauses are annotated with their `(`;
+    /// named ones with their name, which must be `name`.
+    fn fun_clause(&self, clause: ast::Clause<'_>, name: Option<&str>) -> Option<Term> {
+        let args = clause.arguments()?;
+        let anno = match (name, clause.name()) {
+            (None, None) => self.node_anno(args.syntax())?,
+            (Some(name), Some(token)) if self.var_name(token)? == name => self.anno(token),
+            _ => return None,
+        };
+        Some(tagged(
+            "clause",
+            anno,
+            [
+                Term::List(self.exprs(args.args())?),
+                self.guard(clause.guard())?,
+                Term::List(self.body(clause.body())?),
+            ],
+        ))
+    }
+
+    // -----------------------------------------------------------------
+    // Types.
+    // -----------------------------------------------------------------
+
+    pub(crate) fn ty(&self, node: Node<'_>) -> Option<Term> {
+        let view = node.syntax();
+        let typ =
+            |anno: Term, name: &str, args: Term| tagged("type", anno, [Term::atom(name), args]);
+        match node {
+            Node::AtomExpr(_) | Node::VarExpr(_) | Node::IntegerExpr(_) | Node::CharExpr(_) => {
+                self.expr(node)
+            }
+            Node::ParenExpr(n) => self.ty(n.expr()?),
+            Node::TupleType(n) => Some(typ(
+                self.node_anno(view)?,
+                "tuple",
+                Term::List(self.types(n.elements())?),
+            )),
+            Node::ListType(n) => {
+                let anno = self.node_anno(view)?;
+                match n.element() {
+                    None => Some(typ(anno, "nil", Term::List(Vec::new()))),
+                    Some(element) => Some(typ(anno, "list", Term::list([self.ty(element)?]))),
+                }
+            }
+            Node::NonemptyListType(n) => Some(typ(
+                self.node_anno(view)?,
+                "nonempty_list",
+                Term::list([self.ty(n.element()?)?]),
+            )),
+            Node::MapType(n) => {
+                let fields = all(n.fields().map(|f| {
+                    let tag = if f.is_exact() {
+                        "map_field_exact"
+                    } else {
+                        "map_field_assoc"
+                    };
+                    Some(typ(
+                        self.anno(f.operator()?),
+                        tag,
+                        Term::list([self.ty(f.key()?)?, self.ty(f.value()?)?]),
+                    ))
+                }))?;
+                Some(typ(self.node_anno(view)?, "map", Term::List(fields)))
+            }
+            Node::RecordType(n) => {
+                if n.module().is_some() {
+                    return None;
+                }
+                let mut args = vec![self.atom_term(n.name()?)?];
+                for field in n.fields() {
+                    let name = field.name()?;
+                    args.push(typ(
+                        self.anno(name),
+                        "field_type",
+                        Term::list([self.atom_term(name)?, self.ty(field.ty()?)?]),
+                    ));
+                }
+                Some(typ(self.node_anno(view)?, "record", Term::List(args)))
+            }
+            Node::BitstringType(n) => {
+                let anno = self.node_anno(view)?;
+                let zero = || tagged("integer", anno.clone(), [Term::from(0)]);
+                let (mut base, mut unit) = (None, None);
+                for segment in n.segments() {
+                    match segment.unit() {
+                        Some(u) if unit.is_none() => unit = Some(self.ty(u)?),
+                        Some(_) => return None,
+                        None if base.is_none() && unit.is_none() => {
+                            base = Some(self.ty(segment.size()?)?);
+                        }
+                        None => return None,
+                    }
+                }
+                Some(typ(
+                    anno.clone(),
+                    "binary",
+                    Term::list([base.unwrap_or_else(zero), unit.unwrap_or_else(zero)]),
+                ))
+            }
+            Node::FunctionType(n) => self.fun_type(n),
+            Node::TypeCall(n) => {
+                let name = n.name()?;
+                let args = match n.arguments() {
+                    Some(args) => self.types(args.args())?,
+                    None => Vec::new(),
+                };
+                if let Some(module) = n.module() {
+                    return Some(tagged(
+                        "remote_type",
+                        self.anno(module),
+                        [Term::list([
+                            self.atom_term(module)?,
+                            self.atom_term(name)?,
+                            Term::List(args),
+                        ])],
+                    ));
+                }
+                let anno = self.anno(name);
+                let name = self.atom_name(name)?;
+                if args.is_empty() && matches!(name.as_str(), "tuple" | "map") {
+                    return Some(typ(anno, &name, Term::atom("any")));
+                }
+                let tag = if is_builtin_type(&name, args.len()) {
+                    "type"
+                } else {
+                    "user_type"
+                };
+                Some(tagged(tag, anno, [Term::Atom(name), Term::List(args)]))
+            }
+            Node::UnionType(n) => {
+                let members = self.union_members(n)?;
+                Some(typ(
+                    anno_of(members.first()?)?,
+                    "union",
+                    Term::List(members),
+                ))
+            }
+            Node::RangeType(n) => {
+                let low = self.ty(n.low()?)?;
+                Some(typ(
+                    anno_of(&low)?,
+                    "range",
+                    Term::list([low, self.ty(n.high()?)?]),
+                ))
+            }
+            Node::AnnotatedType(n) => {
+                let var = self.expr(n.var()?)?;
+                Some(tagged(
+                    "ann_type",
+                    anno_of(&var)?,
+                    [Term::list([var, self.ty(n.ty()?)?])],
+                ))
+            }
+            Node::BinaryOpType(n) => {
+                let op = n.operator()?;
+                Some(tagged(
+                    "op",
+                    self.anno(op),
+                    [self.operator(op), self.ty(n.lhs()?)?, self.ty(n.rhs()?)?],
+                ))
+            }
+            Node::UnaryOpType(n) => {
+                let op = n.operator()?;
+                Some(tagged(
+                    "op",
+                    self.anno(op),
+                    [self.operator(op), self.ty(n.operand()?)?],
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn types<'n>(&self, nodes: impl Iterator<Item = Node<'n>>) -> Option<Vec<Term>> {
+        all(nodes.map(|n| self.ty(n)))
+    }
+
+    /// Union members as `erl_parse:lift_unions/2` collects them: the
+    /// chain `A | B | C` is flat, and a union on the right of `|` is
+    /// spliced in even when parenthesized.
+    fn union_members(&self, union: ast::UnionType<'_>) -> Option<Vec<Term>> {
+        let members: Vec<_> = union.members().collect();
+        let mut out = Vec::new();
+        for (i, member) in members.iter().enumerate() {
+            let mut unwrapped = *member;
+            if i + 1 == members.len() {
+                while let Node::ParenExpr(p) = unwrapped {
+                    unwrapped = p.expr()?;
+                }
+            }
+            match unwrapped {
+                Node::UnionType(inner) if i == 0 || i + 1 == members.len() => {
+                    out.extend(self.union_members(inner)?);
+                }
+                _ => out.push(self.ty(*member)?),
+            }
+        }
+        Some(out)
+    }
+
+    /// `fun()`, `fun((...) -> T)`, `fun((Args) -> T)`, or a spec clause
+    /// `(Args) -> T [when Constraints]`.
+    fn fun_type(&self, ty: ast::FunctionType<'_>) -> Option<Term> {
+        let typ =
+            |anno: Term, name: &str, args: Term| tagged("type", anno, [Term::atom(name), args]);
+        let Some(params) = ty.params() else {
+            return Some(typ(
+                self.node_anno(ty.syntax())?,
+                "fun",
+                Term::List(Vec::new()),
+            ));
+        };
+        let anno = self.node_anno(params.syntax())?;
+        let args = if params.is_any_arity() {
+            tagged("type", anno.clone(), [Term::atom("any")])
+        } else {
+            typ(
+                anno.clone(),
+                "product",
+                Term::List(self.types(params.params())?),
+            )
+        };
+        let ret = self.ty(ty.return_type()?.ty()?)?;
+        let fun = typ(anno.clone(), "fun", Term::list([args, ret]));
+        let Some(guard) = ty.guard() else {
+            return Some(fun);
+        };
+        let constraints = all(guard.constraints().map(|c| self.constraint(c)))?;
+        if constraints.is_empty() {
+            return None;
+        }
+        Some(typ(
+            anno,
+            "bounded_fun",
+            Term::list([fun, Term::List(constraints)]),
+        ))
+    }
+
+    /// `Var :: Type`, or the older `is_subtype(Var, Type)`.
+    fn constraint(&self, constraint: ast::TypeConstraint<'_>) -> Option<Term> {
+        let (var, ty) = match constraint.arguments() {
+            None => (
+                self.name_term(constraint.name()?)?,
+                self.ty(constraint.ty()?)?,
+            ),
+            Some(args) => {
+                if self.atom_name(constraint.name()?)? != "is_subtype" {
+                    return None;
+                }
+                let [var, ty] = <[Node<'_>; 2]>::try_from(args.args().collect::<Vec<_>>()).ok()?;
+                (self.expr(var)?, self.ty(ty)?)
+            }
+        };
+        if var.as_tuple()?.first()?.as_atom()? != "var" {
+            return None;
+        }
+        let anno = anno_of(&var)?;
+        Some(tagged(
+            "type",
+            anno.clone(),
+            [
+                Term::atom("constraint"),
+                Term::list([
+                    tagged("atom", anno, [Term::atom("is_subtype")]),
+                    Term::list([var, ty]),
+                ]),
+            ],
+        ))
+    }
+
+    // -----------------------------------------------------------------
+    // Forms.
+    // -----------------------------------------------------------------
+
+    pub(crate) fn form(&self, node: Node<'_>) -> Option<Term> {
+        match node {
+            Node::FunctionDecl(n) => self.function(n),
+            Node::Attribute(n) => self.attribute(n),
+            _ => None,
+        }
+    }
+
+    fn function(&self, function: ast::FunctionDecl<'_>) -> Option<Term> {
+        let first = function.name()?;
+        let name = self.atom_name(first)?;
+        let mut clauses = Vec::new();
+        for clause in function.clauses() {
+            let token = clause.name()?;
+            if self.atom_name(token)? != name {
+                return None;
+            }
+            clauses.push(tagged(
+                "clause",
+                self.anno(token),
+                [
+                    Term::List(self.exprs(clause.arguments()?.args())?),
+                    self.guard(clause.guard())?,
+                    Term::List(self.body(clause.body())?),
+                ],
+            ));
+        }
+        let arity = same_arity(&clauses)?;
+        Some(tagged(
+            "function",
+            self.anno(first),
+            [
+                Term::Atom(name),
+                Term::from(arity as i64),
+                Term::List(clauses),
+            ],
+        ))
+    }
+
+    fn attribute(&self, attribute: ast::Attribute<'_>) -> Option<Term> {
+        let name_token = attribute.name()?.token()?;
+        let name = self.atom_name(name_token)?;
+        let anno = self.anno(name_token);
+        let payload = self
+            .tree
+            .parse_attribute_payload(attribute.syntax(), self.source)?;
+        let root = payload.roots().next()?;
+        if payload.roots().count() != 1 || !payload.diagnostics().is_empty() {
+            return None;
+        }
+        let sub = Converter::new(&payload, self.source, self.location);
+        let value = match Node::new(root) {
+            Node::SpecAttribute(spec) => sub.spec(spec)?,
+            Node::TypeDecl(decl) => sub.type_decl(decl)?,
+            Node::RecordDecl(record) => sub.record_decl(record)?,
+            Node::ExportList(list) => Term::List(all(list.entries().map(|e| {
+                Some(Term::tuple([
+                    Term::Atom(sub.atom_name(e.name()?)?),
+                    Term::Integer(sub.integer_value(e.arity()?)?),
+                ]))
+            }))?),
+            Node::ArgumentList(args) => sub.attribute_value(&name, sub.exprs(args.args())?)?,
+            other => sub.attribute_value(&name, vec![sub.expr(other)?])?,
+        };
+        Some(tagged("attribute", anno, [Term::Atom(name), value]))
+    }
+
+    /// `-spec` / `-callback`: `{{Name, Arity}, Clauses}` or
+    /// `{{Module, Name, Arity}, Clauses}`.
+    fn spec(&self, spec: ast::SpecAttribute<'_>) -> Option<Term> {
+        let clauses = all(spec.clauses().map(|c| self.fun_type(c)))?;
+        let arity = spec.clauses().next()?.params()?;
+        if arity.is_any_arity() {
+            return None;
+        }
+        let arity = Term::from(arity.params().count() as i64);
+        let name = Term::Atom(self.atom_name(spec.name()?)?);
+        let key = match spec.module() {
+            Some(module) => Term::tuple([Term::Atom(self.atom_name(module)?), name, arity]),
+            None => Term::tuple([name, arity]),
+        };
+        Some(Term::tuple([key, Term::List(clauses)]))
+    }
+
+    /// `-type` / `-opaque` / `-nominal`: `{Name, Type, Params}`; every
+    /// parameter must be a variable other than `_`.
+    fn type_decl(&self, decl: ast::TypeDecl<'_>) -> Option<Term> {
+        let params = match decl.params() {
+            Some(params) => all(params.args().map(|p| match p {
+                Node::VarExpr(v) if self.var_name(v.token()?)? != "_" => self.expr(p),
+                _ => None,
+            }))?,
+            None => Vec::new(),
+        };
+        Some(Term::tuple([
+            Term::Atom(self.atom_name(decl.name()?)?),
+            self.ty(decl.ty()?)?,
+            Term::List(params),
+        ]))
+    }
+
+    /// `-record`: `{Name, Fields}`.
+    fn record_decl(&self, record: ast::RecordDecl<'_>) -> Option<Term> {
+        let fields = all(record.fields().map(|field| {
+            let name = field.name()?;
+            let mut elements = vec![self.atom_term(name)?];
+            if let Some(default) = field.default() {
+                elements.push(self.expr(default)?);
+            }
+            let plain = tagged("record_field", self.anno(name), elements);
+            match field.ty() {
+                None => Some(plain),
+                Some(ty) => Some(Term::tuple([
+                    Term::atom("typed_record_field"),
+                    plain,
+                    self.ty(ty)?,
+                ])),
+            }
+        }))?;
+        Some(Term::tuple([
+            Term::Atom(self.atom_name(record.name()?)?),
+            Term::List(fields),
+        ]))
+    }
+
+    /// `erl_parse:build_attribute/2` for attributes whose payload is a
+    /// plain expression list.
+    fn attribute_value(&self, name: &str, values: Vec<Term>) -> Option<Term> {
+        let atom = |t: &Term| abstract_atom(t).map(Term::atom);
+        match (name, values.as_slice()) {
+            ("module", [module]) => atom(module),
+            ("module", [module, vars]) => Some(Term::tuple([atom(module)?, var_list(vars)?])),
+            ("export", [list]) => farity_list(list),
+            ("import", [module, list]) => Some(Term::tuple([atom(module)?, farity_list(list)?])),
+            ("file", [file, line]) => {
+                let file = match file.as_tuple()? {
+                    [tag, _, s] if tag.as_atom() == Some("string") => s.clone(),
+                    _ => return None,
+                };
+                let line = match line.as_tuple()? {
+                    [tag, _, n] if tag.as_atom() == Some("integer") => n.clone(),
+                    _ => return None,
+                };
+                Some(Term::tuple([file, line]))
+            }
+            ("module" | "export" | "import" | "file", _) => None,
+            (_, [value]) => super::normalise(&attribute_farity(value)),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that every clause has the same number of arguments and
+/// returns it.
+fn same_arity(clauses: &[Term]) -> Option<usize> {
+    let arity = |c: &Term| c.as_tuple()?.get(2)?.as_list().map(<[Term]>::len);
+    let first = arity(clauses.first()?)?;
+    clauses
+        .iter()
+        .all(|c| arity(c) == Some(first))
+        .then_some(first)
+}
+
+fn abstract_atom(term: &Term) -> Option<&str> {
+    match term.as_tuple()? {
+        [tag, _, Term::Atom(name)] if tag.as_atom() == Some("atom") => Some(name),
+        _ => None,
+    }
+}
+
+/// Walks an abstract `{cons, ...}` / `{nil, ...}` list.
+fn abstract_list(mut term: &Term) -> Option<Vec<&Term>> {
+    let mut out = Vec::new();
+    loop {
+        match term.as_tuple()? {
+            [tag, _] if tag.as_atom() == Some("nil") => return Some(out),
+            [tag, _, head, tail] if tag.as_atom() == Some("cons") => {
+                out.push(head);
+                term = tail;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// `Name/Arity` as an abstract `{op, _, '/', {atom, ...}, {integer, ...}}`.
+fn abstract_farity(term: &Term) -> Option<(&str, &Term)> {
+    match term.as_tuple()? {
+        [tag, _, op, name, arity] if tag.as_atom() == Some("op") && op.as_atom() == Some("/") => {
+            match arity.as_tuple()? {
+                [t, _, n] if t.as_atom() == Some("integer") => Some((abstract_atom(name)?, n)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn farity_list(term: &Term) -> Option<Term> {
+    let entries = abstract_list(term)?;
+    all(entries.into_iter().map(|e| {
+        let (name, arity) = abstract_farity(e)?;
+        Some(Term::tuple([Term::atom(name), arity.clone()]))
+    }))
+    .map(Term::List)
+}
+
+fn var_list(term: &Term) -> Option<Term> {
+    let entries = abstract_list(term)?;
+    all(entries.into_iter().map(|e| match e.as_tuple()? {
+        [tag, _, name] if tag.as_atom() == Some("var") => Some(name.clone()),
+        _ => None,
+    }))
+    .map(Term::List)
+}
+
+/// `erl_parse:attribute_farity/1`: rewrites `Name/Arity` inside lists,
+/// tuples, and maps to `{Name, Arity}` tuples before normalisation.
+fn attribute_farity(term: &Term) -> Term {
+    let Some(elements) = term.as_tuple() else {
+        return term.clone();
+    };
+    let tag = elements.first().and_then(Term::as_atom);
+    match (tag, elements) {
+        (Some("cons"), [t, a, head, tail]) => Term::tuple([
+            t.clone(),
+            a.clone(),
+            attribute_farity(head),
+            attribute_farity(tail),
+        ]),
+        (Some("tuple"), [t, a, Term::List(items)]) => Term::tuple([
+            t.clone(),
+            a.clone(),
+            Term::List(items.iter().map(attribute_farity).collect()),
+        ]),
+        (Some("map"), [t, a, Term::List(fields)]) => Term::tuple([
+            t.clone(),
+            a.clone(),
+            Term::List(
+                fields
+                    .iter()
+                    .map(|f| match f.as_tuple() {
+                        Some([op, fa, k, v]) => Term::tuple([
+                            op.clone(),
+                            fa.clone(),
+                            attribute_farity(k),
+                            attribute_farity(v),
+                        ]),
+                        _ => f.clone(),
+                    })
+                    .collect(),
+            ),
+        ]),
+        (Some("op"), [_, a, _, name, arity]) if abstract_farity(term).is_some() => Term::tuple([
+            Term::atom("tuple"),
+            a.clone(),
+            Term::list([name.clone(), arity.clone()]),
+        ]),
+        _ => term.clone(),
+    }
+}
+
+/// `erl_internal:is_type/2`: the built-in types, which OTP tags `type`
+/// rather than `user_type`.
+fn is_builtin_type(name: &str, arity: usize) -> bool {
+    match arity {
+        0 => matches!(
+            name,
+            "any"
+                | "arity"
+                | "atom"
+                | "binary"
+                | "bitstring"
+                | "bool"
+                | "boolean"
+                | "byte"
+                | "char"
+                | "dynamic"
+                | "float"
+                | "function"
+                | "identifier"
+                | "integer"
+                | "iodata"
+                | "iolist"
+                | "list"
+                | "map"
+                | "maybe_improper_list"
+                | "mfa"
+                | "module"
+                | "neg_integer"
+                | "nil"
+                | "no_return"
+                | "node"
+                | "non_neg_integer"
+                | "none"
+                | "nonempty_binary"
+                | "nonempty_bitstring"
+                | "nonempty_list"
+                | "nonempty_maybe_improper_list"
+                | "nonempty_string"
+                | "number"
+                | "pid"
+                | "port"
+                | "pos_integer"
+                | "reference"
+                | "string"
+                | "term"
+                | "timeout"
+                | "tuple"
+        ),
+        1 => matches!(name, "list" | "nonempty_list"),
+        2 => matches!(
+            name,
+            "maybe_improper_list" | "nonempty_improper_list" | "nonempty_maybe_improper_list"
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,132 @@
+//! External Term Format encoding, as `erlang:term_to_binary/1` writes it.
+//!
+//! Uses the tags OTP 26+ emits by default: UTF-8 atoms, `NEW_FLOAT_EXT`
+//! floats, and `STRING_EXT` for short lists of bytes.
+
+use super::term::{Integer, Term, sorted_pairs};
+
+const VERSION: u8 = 131;
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const MAP_EXT: u8 = 116;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// Encodes `term` with the leading version byte.
+pub(crate) fn encode(term: &Term) -> Vec<u8> {
+    let mut out = vec![VERSION];
+    encode_term(term, &mut out);
+    out
+}
+
+fn encode_term(term: &Term, out: &mut Vec<u8>) {
+    match term {
+        Term::Atom(name) => encode_atom(name, out),
+        Term::Integer(n) => encode_integer(n, out),
+        Term::Float(f) => {
+            out.push(NEW_FLOAT_EXT);
+            out.extend_from_slice(&f.to_be_bytes());
+        }
+        Term::List(items) if items.is_empty() => out.push(NIL_EXT),
+        Term::List(items) => match string_bytes(items) {
+            Some(bytes) => {
+                out.push(STRING_EXT);
+                out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+                out.extend_from_slice(&bytes);
+            }
+            None => encode_list(items, None, out),
+        },
+        Term::ImproperList(items, tail) => encode_list(items, Some(tail), out),
+        Term::Tuple(elements) => {
+            match u8::try_from(elements.len()) {
+                Ok(arity) => out.extend_from_slice(&[SMALL_TUPLE_EXT, arity]),
+                Err(_) => {
+                    out.push(LARGE_TUPLE_EXT);
+                    out.extend_from_slice(&(elements.len() as u32).to_be_bytes());
+                }
+            }
+            for element in elements {
+                encode_term(element, out);
+            }
+        }
+        Term::Map(pairs) => {
+            out.push(MAP_EXT);
+            out.extend_from_slice(&(pairs.len() as u32).to_be_bytes());
+            for (key, value) in sorted_pairs(pairs) {
+                encode_term(key, out);
+                encode_term(value, out);
+            }
+        }
+        Term::Binary(bytes) => {
+            out.push(BINARY_EXT);
+            out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            out.extend_from_slice(bytes);
+        }
+    }
+}
+
+fn encode_atom(name: &str, out: &mut Vec<u8>) {
+    match u8::try_from(name.len()) {
+        Ok(len) => out.extend_from_slice(&[SMALL_ATOM_UTF8_EXT, len]),
+        Err(_) => {
+            out.push(ATOM_UTF8_EXT);
+            out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(name.as_bytes());
+}
+
+fn encode_integer(n: &Integer, out: &mut Vec<u8>) {
+    match n.to_i64() {
+        Some(small @ 0..=255) => out.extend_from_slice(&[SMALL_INTEGER_EXT, small as u8]),
+        Some(value) if i32::try_from(value).is_ok() => {
+            out.push(INTEGER_EXT);
+            out.extend_from_slice(&(value as i32).to_be_bytes());
+        }
+        _ => {
+            let digits = n.magnitude();
+            match u8::try_from(digits.len()) {
+                Ok(len) => out.extend_from_slice(&[SMALL_BIG_EXT, len]),
+                Err(_) => {
+                    out.push(LARGE_BIG_EXT);
+                    out.extend_from_slice(&(digits.len() as u32).to_be_bytes());
+                }
+            }
+            out.push(u8::from(n.is_negative()));
+            out.extend_from_slice(digits);
+        }
+    }
+}
+
+fn encode_list(items: &[Term], tail: Option<&Term>, out: &mut Vec<u8>) {
+    out.push(LIST_EXT);
+    out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+    for item in items {
+        encode_term(item, out);
+    }
+    match tail {
+        Some(tail) => encode_term(tail, out),
+        None => out.push(NIL_EXT),
+    }
+}
+
+/// The bytes of a list that `STRING_EXT` can hold: at most 65535
+/// integers in `0..=255`.
+fn string_bytes(items: &[Term]) -> Option<Vec<u8>> {
+    if items.len() > usize::from(u16::MAX) {
+        return None;
+    }
+    items
+        .iter()
+        .map(|item| u8::try_from(item.as_i64()?).ok())
+        .collect()
+}
@@ -0,0 +1,701 @@
+//! `io_lib`-compatible term text: `~w` ([`write`]) and `~p`
+//! ([`pretty`]).
+//!
+//! [`pretty`] follows `io_lib_pretty` with its `io:format/2` defaults
+//! (column 1, line length 80, no depth limit, Latin-1 printable
+//! range): each term is measured once, written flat when it fits, and
+//! otherwise broken element by element, with atomic elements packed
+//! onto shared lines and compound ones given a line each. Tagged
+//! tuples (`{Atom, ...}`) keep their tag on the opening line.
+
+use super::term::{Term, sorted_pairs};
+
+const LINE_LENGTH: i64 = 80;
+
+/// Writes `term` on one line, as `io_lib:write/1` (`~w`) does.
+pub(crate) fn write(term: &Term) -> String {
+    let mut out = String::new();
+    write_term(term, false, &mut out);
+    out
+}
+
+/// Writes `term` as `io_lib_pretty` (`~p`) does.
+pub(crate) fn pretty(term: &Term) -> String {
+    let item = Item::build(term);
+    let mut out = String::new();
+    let (col, m) = (1, item.len);
+    if item.len < LINE_LENGTH - col && item.len <= m {
+        item.write(&mut out);
+        return out;
+    }
+    let printer = Printer { ll: LINE_LENGTH, m };
+    let tind = [-1, 4]
+        .into_iter()
+        .find(|&i| printer.cind(&item, col, i, 0, 0).is_ok())
+        .unwrap_or(1);
+    printer.pp(&item, col, tind, (col - 1) as usize, 0, 0, &mut out);
+    out
+}
+
+fn write_term(term: &Term, strings: bool, out: &mut String) {
+    match term {
+        Term::Atom(name) => write_atom(name, out),
+        Term::Integer(i) => out.push_str(&i.to_string()),
+        Term::Float(f) => out.push_str(&format_float(*f)),
+        Term::List(items) if items.is_empty() => out.push_str("[]"),
+        Term::List(items) if strings && printable_chars(items).is_some() => {
+            write_string(&printable_chars(items).unwrap_or_default(), '"', out);
+        }
+        Term::List(items) => write_seq('[', items, None, ']', strings, out),
+        Term::ImproperList(items, tail) => write_seq('[', items, Some(tail), ']', strings, out),
+        Term::Tuple(items) => write_seq('{', items, None, '}', strings, out),
+        Term::Map(pairs) => {
+            out.push_str("#{");
+            for (i, (k, v)) in sorted_pairs(pairs).into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_term(k, strings, out);
+                out.push_str(" => ");
+                write_term(v, strings, out);
+            }
+            out.push('}');
+        }
+        Term::Binary(bytes) => write_binary(bytes, out),
+    }
+}
+
+fn write_seq(
+    open: char,
+    items: &[Term],
+    tail: Option<&Term>,
+    close: char,
+    strings: bool,
+    out: &mut String,
+) {
+    out.push(open);
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_term(item, strings, out);
+    }
+    if let Some(tail) = tail {
+        out.push('|');
+        write_term(tail, strings, out);
+    }
+    out.push(close);
+}
+
+/// The characters of a list that `io_lib:printable_latin1_list/1`
+/// accepts, or `None`.
+fn printable_chars(items: &[Term]) -> Option<String> {
+    items
+        .iter()
+        .map(|item| {
+            let c = u32::try_from(item.as_i64()?).ok()?;
+            is_printable_latin1(c).then(|| char::from_u32(c))?
+        })
+        .collect()
+}
+
+fn is_printable_latin1(c: u32) -> bool {
+    matches!(c, 0x20..=0x7e | 0xa0..=0xff | 8..=13 | 27)
+}
+
+fn write_binary(bytes: &[u8], out: &mut String) {
+    out.push_str("<<");
+    if !bytes.is_empty() && bytes.iter().all(|&b| is_printable_latin1(u32::from(b))) {
+        let text: String = bytes.iter().map(|&b| char::from(b)).collect();
+        write_string(&text, '"', out);
+    } else {
+        for (i, b) in bytes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&b.to_string());
+        }
+    }
+    out.push_str(">>");
+}
+
+/// Writes `text` between `quote`s with `io_lib`'s Latin-1 escapes.
+pub(crate) fn write_string(text: &str, quote: char, out: &mut String) {
+    out.push(quote);
+    for c in text.chars() {
+        match c {
+            _ if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\\\"),
+            ' '..='~' | '\u{a0}'..='\u{ff}' => out.push(c),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{b}' => out.push_str("\\v"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\u{1b}' => out.push_str("\\e"),
+            '\u{7f}' => out.push_str("\\d"),
+            _ if u32::from(c) < 0xa0 => out.push_str(&format!("\\{:03o}", u32::from(c))),
+            _ => out.push_str(&format!("\\x{{{:X}}}", u32::from(c))),
+        }
+    }
+    out.push(quote);
+}
+
+/// Reserved words, which `io_lib:write_atom/1` always quotes.
+const RESERVED: &[&str] = &[
+    "after", "and", "andalso", "band", "begin", "bnot", "bor", "bsl", "bsr", "bxor", "case",
+    "catch", "cond", "div", "else", "end", "fun", "if", "let", "maybe", "not", "of", "or",
+    "orelse", "receive", "rem", "try", "when", "xor",
+];
+
+fn write_atom(name: &str, out: &mut String) {
+    let is_lower = |c: char| c.is_ascii_lowercase() || matches!(c, 'ß'..='ÿ' if c != '÷');
+    let is_name_char = |c: char| {
+        is_lower(c)
+            || c.is_ascii_alphanumeric()
+            || matches!(c, '_' | '@')
+            || matches!(c, 'À'..='Þ' if c != '×')
+    };
+    let bare = name.chars().next().is_some_and(is_lower)
+        && name.chars().all(is_name_char)
+        && !RESERVED.contains(&name);
+    if bare {
+        out.push_str(name);
+    } else {
+        write_string(name, '\'', out);
+    }
+}
+
+/// Formats a float as `float_to_list(F, [short])` does: the shortest
+/// digits that read back as `f`, in whichever of plain or scientific
+/// notation is shorter (plain on a tie), and always scientific at or
+/// beyond 2^53 in magnitude.
+pub(crate) fn format_float(f: f64) -> String {
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+    let sign = if f.is_sign_negative() { "-" } else { "" };
+
+    let scientific = format!(
+        "{sign}{}.{}e{exp}",
+        &digits[..1],
+        if digits.len() > 1 { &digits[1..] } else { "0" }
+    );
+    if f.abs() >= 9_007_199_254_740_992.0 {
+        return scientific;
+    }
+    let n = digits.len() as i32;
+    let plain = if exp < 0 {
+        format!("{sign}0.{}{digits}", "0".repeat((-exp - 1) as usize))
+    } else if exp >= n - 1 {
+        format!("{sign}{digits}{}.0", "0".repeat((exp - n + 1) as usize))
+    } else {
+        let point = (exp + 1) as usize;
+        format!("{sign}{}.{}", &digits[..point], &digits[point..])
+    };
+    if scientific.len() < plain.len() {
+        scientific
+    } else {
+        plain
+    }
+}
+
+// ---------------------------------------------------------------------
+// `io_lib_pretty` layout.
+// ---------------------------------------------------------------------
+
+/// A measured term: `len` is its width when written flat.
+struct Item {
+    len: i64,
+    kind: Kind,
+}
+
+enum Kind {
+    /// Written as is; never broken.
+    Leaf(String),
+    List(Vec<Item>, Option<Box<Item>>),
+    Tuple {
+        tagged: bool,
+        items: Vec<Item>,
+    },
+    Map(Vec<Item>),
+    /// One `Key => Value` association of a map.
+    Pair(Box<Item>, Box<Item>),
+}
+
+impl Item {
+    fn build(term: &Term) -> Self {
+        let leaf = |text: String| Self {
+            len: text.chars().count() as i64,
+            kind: Kind::Leaf(text),
+        };
+        match term {
+            Term::List(items) if items.is_empty() || printable_chars(items).is_some() => {
+                let mut text = String::new();
+                write_term(term, true, &mut text);
+                leaf(text)
+            }
+            Term::List(items) => Self::seq(items, None),
+            Term::ImproperList(items, tail) => Self::seq(items, Some(tail)),
+            Term::Tuple(items) if !items.is_empty() => {
+                let items: Vec<_> = items.iter().map(Self::build).collect();
+                Self {
+                    len: seq_len(&items) + 2,
+                    kind: Kind::Tuple {
+                        tagged: items.len() > 1
+                            && matches!(term.as_tuple(), Some([Term::Atom(_), ..])),
+                        items,
+                    },
+                }
+            }
+            Term::Map(pairs) if !pairs.is_empty() => {
+                let pairs: Vec<_> = sorted_pairs(pairs)
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let (k, v) = (Self::build(k), Self::build(v));
+                        Self {
+                            len: k.len + v.len + 4,
+                            kind: Kind::Pair(Box::new(k), Box::new(v)),
+                        }
+                    })
+                    .collect();
+                Self {
+                    len: seq_len(&pairs) + 3,
+                    kind: Kind::Map(pairs),
+                }
+            }
+            _ => {
+                let mut text = String::new();
+                write_term(term, true, &mut text);
+                leaf(text)
+            }
+        }
+    }
+
+    fn seq(items: &[Term], tail: Option<&Term>) -> Self {
+        let items: Vec<_> = items.iter().map(Self::build).collect();
+        let tail = tail.map(|t| Box::new(Self::build(t)));
+        let tail_len = tail.as_ref().map_or(0, |t| t.len + 1);
+        Self {
+            len: seq_len(&items) + tail_len + 2,
+            kind: Kind::List(items, tail),
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        match &self.kind {
+            Kind::Leaf(_) => true,
+            Kind::Pair(k, v) => k.is_atomic() && v.is_atomic(),
+            _ => false,
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        match &self.kind {
+            Kind::Leaf(text) => out.push_str(text),
+            Kind::List(items, tail) => {
+                out.push('[');
+                write_items(items, out);
+                if let Some(tail) = tail {
+                    out.push('|');
+                    tail.write(out);
+                }
+                out.push(']');
+            }
+            Kind::Tuple { items, .. } => {
+                out.push('{');
+                write_items(items, out);
+                out.push('}');
+            }
+            Kind::Map(pairs) => {
+                out.push_str("#{");
+                write_items(pairs, out);
+                out.push('}');
+            }
+            Kind::Pair(k, v) => {
+                k.write(out);
+                out.push_str(" => ");
+                v.write(out);
+            }
+        }
+    }
+}
+
+fn seq_len(items: &[Item]) -> i64 {
+    items.iter().map(|i| i.len).sum::<i64>() + (items.len() as i64 - 1).max(0)
+}
+
+fn write_items(items: &[Item], out: &mut String) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        item.write(out);
+    }
+}
+
+/// `io_lib_pretty:last_depth/2`: how many closing brackets follow the
+/// element before `rest`.
+fn last_depth(more: bool, ld: i64) -> i64 {
+    if more { 0 } else { ld + 1 }
+}
+
+fn newline(ind: usize, out: &mut String) {
+    out.push('\n');
+    out.extend(std::iter::repeat_n(' ', ind));
+}
+
+/// Layout parameters: line length and the per-line character budget
+/// (the whole term's width, as `io_lib_pretty` uses when no
+/// `chars_limit` is given).
+struct Printer {
+    ll: i64,
+    m: i64,
+}
+
+type NoGood = ();
+
+impl Printer {
+    fn fits(&self, item: &Item, col: i64, ld: i64, w: i64) -> bool {
+        item.len < self.ll - col - ld && item.len + w + ld <= self.m
+    }
+
+    /// Whether the next element of a broken sequence may stay on the
+    /// current line.
+    fn fits_inline(&self, item: &Item, col: i64, ld1: i64, w: i64) -> bool {
+        let elen = 1 + item.len;
+        item.is_atomic()
+            && if ld1 == 0 {
+                elen + 1 < self.ll - col && w + elen < self.m
+            } else {
+                elen < self.ll - col - ld1 && w + elen + ld1 <= self.m
+            }
+    }
+
+    #[expect(clippy::too_many_arguments, reason = "mirrors io_lib_pretty:pp/8")]
+    fn pp(&self, item: &Item, col: i64, tind: i64, ind: usize, ld: i64, w: i64, out: &mut String) {
+        if self.fits(item, col, ld, w) {
+            item.write(out);
+            return;
+        }
+        match &item.kind {
+            Kind::Leaf(text) => out.push_str(text),
+            Kind::List(items, tail) => {
+                out.push('[');
+                self.pp_list(
+                    items,
+                    tail.as_deref(),
+                    col + 1,
+                    tind,
+                    ind + 1,
+                    ld,
+                    '|',
+                    w + 1,
+                    out,
+                );
+                out.push(']');
+            }
+            Kind::Tuple {
+                tagged: true,
+                items,
+            } => {
+                out.push('{');
+                self.pp_tag_tuple(items, col, tind, ind, ld, w + 1, out);
+                out.push('}');
+            }
+            Kind::Tuple {
+                tagged: false,
+                items,
+            } => {
+                out.push('{');
+                self.pp_list(items, None, col + 1, tind, ind + 1, ld, ',', w + 1, out);
+                out.push('}');
+            }
+            Kind::Map(pairs) => {
+                out.push_str("#{");
+                self.pp_list(pairs, None, col + 2, tind, ind + 2, ld, ',', w + 1, out);
+                out.push('}');
+            }
+            Kind::Pair(..) => {
+                self.pp_element(item, col, tind, ind, ld, w, out);
+            }
+        }
+    }
+
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors io_lib_pretty:pp_tag_tuple/8"
+    )]
+    fn pp_tag_tuple(
+        &self,
+        items: &[Item],
+        col: i64,
+        tind: i64,
+        ind: usize,
+        ld: i64,
+        w: i64,
+        out: &mut String,
+    ) {
+        let (tag, rest) = items.split_first().expect("tagged tuples are not empty");
+        let tag_ind = tag.len + 2;
+        let tcol = col + tag_ind;
+        tag.write(out);
+        if tind > 0 && tag_ind > tind {
+            let col1 = col + tind;
+            self.pp_tail(
+                rest,
+                None,
+                col1,
+                tcol,
+                tind,
+                ind + tind as usize,
+                ld,
+                ',',
+                w + tag.len,
+                out,
+            );
+        } else {
+            out.push(',');
+            self.pp_list(
+                rest,
+                None,
+                tcol,
+                tind,
+                ind + tag_ind as usize,
+                ld,
+                ',',
+                w + tag.len + 1,
+                out,
+            );
+        }
+    }
+
+    #[expect(clippy::too_many_arguments, reason = "mirrors io_lib_pretty:pp_list/9")]
+    fn pp_list(
+        &self,
+        items: &[Item],
+        tail: Option<&Item>,
+        col0: i64,
+        tind: i64,
+        ind: usize,
+        ld: i64,
+        sep: char,
+        w: i64,
+        out: &mut String,
+    ) {
+        let Some((first, rest)) = items.split_first() else {
+            return;
+        };
+        let more = !rest.is_empty() || tail.is_some();
+        let w1 = self.pp_element(first, col0, tind, ind, last_depth(more, ld), w, out);
+        self.pp_tail(rest, tail, col0, col0 + w1, tind, ind, ld, sep, w1, out);
+    }
+
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors io_lib_pretty:pp_tail/10"
+    )]
+    fn pp_tail(
+        &self,
+        items: &[Item],
+        tail: Option<&Item>,
+        col0: i64,
+        mut col: i64,
+        tind: i64,
+        ind: usize,
+        ld: i64,
+        sep: char,
+        mut w: i64,
+        out: &mut String,
+    ) {
+        for (i, item) in items.iter().enumerate() {
+            let more = i + 1 < items.len() || tail.is_some();
+            let ld1 = last_depth(more, ld);
+            out.push(',');
+            if self.fits_inline(item, col, ld1, w) {
+                item.write(out);
+                col += 1 + item.len;
+                w += 1 + item.len;
+            } else {
+                newline(ind, out);
+                let w1 = self.pp_element(item, col0, tind, ind, ld1, 0, out);
+                col = col0 + w1;
+                w = w1;
+            }
+        }
+        if let Some(tail) = tail {
+            out.push(sep);
+            if tail.is_atomic() && self.fits(tail, col + 1, ld + 1, w + 1) {
+                tail.write(out);
+            } else {
+                newline(ind, out);
+                self.pp(tail, col0, tind, ind, ld + 1, 0, out);
+            }
+        }
+    }
+
+    /// Prints one element and returns the width it leaves on its last
+    /// line; a compound element reports the full line so that the
+    /// next element starts a new one.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors io_lib_pretty:pp_element/8"
+    )]
+    fn pp_element(
+        &self,
+        item: &Item,
+        col: i64,
+        tind: i64,
+        ind: usize,
+        ld: i64,
+        w: i64,
+        out: &mut String,
+    ) -> i64 {
+        if let Kind::Pair(k, v) = &item.kind {
+            if self.fits(item, col, ld, w) {
+                item.write(out);
+                return if item.is_atomic() { item.len } else { self.ll };
+            }
+            let value_ind = if tind > 0 { tind } else { 4 };
+            self.pp(k, col, tind, ind, 0, w, out);
+            out.push_str(" =>");
+            newline(ind + value_ind as usize, out);
+            self.pp(
+                v,
+                col + value_ind,
+                tind,
+                ind + value_ind as usize,
+                ld,
+                0,
+                out,
+            );
+            return self.ll;
+        }
+        if item.is_atomic() && self.fits(item, col, ld, w) {
+            item.write(out);
+            item.len
+        } else {
+            self.pp(item, col, tind, ind, ld, w, out);
+            self.ll
+        }
+    }
+
+    /// `io_lib_pretty:cind/7`: checks that tag indentation `tind`
+    /// keeps every tagged tuple's elements within the line.
+    fn cind(&self, item: &Item, col: i64, tind: i64, ld: i64, w: i64) -> Result<(), NoGood> {
+        if self.fits(item, col, ld, w) {
+            return Ok(());
+        }
+        match &item.kind {
+            Kind::Leaf(_) => Ok(()),
+            Kind::List(items, tail) => {
+                self.cind_list(items, tail.as_deref(), col + 1, tind, ld, w + 1)
+            }
+            Kind::Tuple {
+                tagged: true,
+                items,
+            } => {
+                let (tag, rest) = items.split_first().expect("tagged tuples are not empty");
+                let tag_ind = tag.len + 2;
+                let tcol = col + tag_ind;
+                if tind > 0 && tag_ind > tind {
+                    let col1 = col + tind;
+                    if self.m + col1 <= self.ll || col1 <= self.ll / 2 {
+                        self.cind_tail(rest, None, col1, tcol, tind, ld, w + tag.len)
+                    } else {
+                        Err(())
+                    }
+                } else if self.m + tcol < self.ll || tcol < self.ll / 2 {
+                    self.cind_list(rest, None, tcol, tind, ld, w + tag.len + 1)
+                } else {
+                    Err(())
+                }
+            }
+            Kind::Tuple {
+                tagged: false,
+                items,
+            } => self.cind_list(items, None, col + 1, tind, ld, w + 1),
+            Kind::Map(pairs) => self.cind_list(pairs, None, col + 2, tind, ld, w + 1),
+            Kind::Pair(..) => self.cind_element(item, col, tind, ld, w).map(|_| ()),
+        }
+    }
+
+    fn cind_list(
+        &self,
+        items: &[Item],
+        tail: Option<&Item>,
+        col0: i64,
+        tind: i64,
+        ld: i64,
+        w: i64,
+    ) -> Result<(), NoGood> {
+        let Some((first, rest)) = items.split_first() else {
+            return Ok(());
+        };
+        let more = !rest.is_empty() || tail.is_some();
+        let we = self.cind_element(first, col0, tind, last_depth(more, ld), w)?;
+        self.cind_tail(rest, tail, col0, col0 + we, tind, ld, w + we)
+    }
+
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors io_lib_pretty:cind_tail/8"
+    )]
+    fn cind_tail(
+        &self,
+        items: &[Item],
+        tail: Option<&Item>,
+        col0: i64,
+        mut col: i64,
+        tind: i64,
+        ld: i64,
+        mut w: i64,
+    ) -> Result<(), NoGood> {
+        for (i, item) in items.iter().enumerate() {
+            let more = i + 1 < items.len() || tail.is_some();
+            let ld1 = last_depth(more, ld);
+            if self.fits_inline(item, col, ld1, w) {
+                col += 1 + item.len;
+                w += 1 + item.len;
+            } else {
+                let we = self.cind_element(item, col0, tind, ld1, 0)?;
+                col = col0 + we;
+                w = we;
+            }
+        }
+        if let Some(tail) = tail {
+            self.cind_element(tail, col + 1, tind, ld + 1, w + 1)?;
+        }
+        Ok(())
+    }
+
+    fn cind_element(
+        &self,
+        item: &Item,
+        col: i64,
+        tind: i64,
+        ld: i64,
+        w: i64,
+    ) -> Result<i64, NoGood> {
+        if let Kind::Pair(k, v) = &item.kind {
+            if self.fits(item, col, ld, w) {
+                return Ok(if item.is_atomic() { item.len } else { self.ll });
+            }
+            let value_ind = if tind > 0 { tind } else { 4 };
+            self.cind(k, col, tind, 0, w)?;
+            self.cind(v, col + value_ind, tind, ld, 0)?;
+            return Ok(self.ll);
+        }
+        if item.is_atomic() && self.fits(item, col, ld, w) {
+            Ok(item.len)
+        } else {
+            self.cind(item, col, tind, ld, w)?;
+            Ok(self.ll)
+        }
+    }
+}
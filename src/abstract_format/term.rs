@@ -0,0 +1,404 @@
+//! Plain Erlang terms: the values abstract forms are built from.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An Erlang term.
+///
+/// Covers the term types that abstract forms and literal attribute
+/// values are made of. Strings are lists of character codes, as in
+/// Erlang; [`Term::string`] builds one. Map entries keep insertion
+/// order; printers sort them the way the runtime does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// An atom, by name.
+    Atom(String),
+    /// An integer of any size.
+    Integer(Integer),
+    /// A float.
+    Float(f64),
+    /// A proper list; the empty list is `[]`.
+    List(Vec<Term>),
+    /// A list whose tail is not `[]`: `[Head, ... | Tail]`. The head
+    /// is never empty.
+    ImproperList(Vec<Term>, Box<Term>),
+    /// A tuple.
+    Tuple(Vec<Term>),
+    /// A map, as `(key, value)` pairs with unique keys.
+    Map(Vec<(Term, Term)>),
+    /// A binary.
+    Binary(Vec<u8>),
+}
+
+impl Term {
+    /// Builds an atom.
+    pub fn atom(name: &str) -> Self {
+        Self::Atom(name.to_string())
+    }
+
+    /// Builds a string: a list of the character codes of `s`.
+    pub fn string(s: &str) -> Self {
+        Self::List(
+            s.chars()
+                .map(|c| Self::from(i64::from(u32::from(c))))
+                .collect(),
+        )
+    }
+
+    /// Builds a tuple.
+    pub fn tuple(elements: impl IntoIterator<Item = Term>) -> Self {
+        Self::Tuple(elements.into_iter().collect())
+    }
+
+    /// Builds a proper list.
+    pub fn list(elements: impl IntoIterator<Item = Term>) -> Self {
+        Self::List(elements.into_iter().collect())
+    }
+
+    /// Returns the atom's name.
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            Self::Atom(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the tuple's elements.
+    pub fn as_tuple(&self) -> Option<&[Term]> {
+        match self {
+            Self::Tuple(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Returns the proper list's elements.
+    pub fn as_list(&self) -> Option<&[Term]> {
+        match self {
+            Self::List(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer that fits in `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => i.to_i64(),
+            _ => None,
+        }
+    }
+
+    /// Formats the term as `io:format("~p", [Term])` does: strings of
+    /// printable Latin-1 characters are written as `"..."`, and terms
+    /// wider than 80 columns are broken over several lines.
+    pub fn pretty(&self) -> String {
+        super::pretty::pretty(self)
+    }
+
+    /// Encodes the term in the External Term Format, byte for byte as
+    /// `term_to_binary/1` does on OTP 26 and later.
+    ///
+    /// Maps are written in map key order, which is what the runtime
+    /// emits for maps of up to 32 keys; larger maps decode to the same
+    /// term but their bytes may differ from the runtime's hash order.
+    pub fn to_etf(&self) -> Vec<u8> {
+        super::etf::encode(self)
+    }
+
+    /// Orders two terms by the Erlang term order (`number < atom <
+    /// tuple < map < list < binary`), with integers before floats of
+    /// equal value so that the order is total.
+    pub fn term_cmp(&self, other: &Self) -> Ordering {
+        fn rank(t: &Term) -> u8 {
+            match t {
+                Term::Integer(_) | Term::Float(_) => 0,
+                Term::Atom(_) => 1,
+                Term::Tuple(_) => 2,
+                Term::Map(_) => 3,
+                Term::List(_) | Term::ImproperList(..) => 4,
+                Term::Binary(_) => 5,
+            }
+        }
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Integer(a), Self::Float(b)) => a.to_f64().total_cmp(b).then(Ordering::Less),
+            (Self::Float(a), Self::Integer(b)) => a.total_cmp(&b.to_f64()).then(Ordering::Greater),
+            (Self::Atom(a), Self::Atom(b)) => a.cmp(b),
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                a.len().cmp(&b.len()).then_with(|| cmp_elements(a, b))
+            }
+            (Self::Map(a), Self::Map(b)) => a.len().cmp(&b.len()).then_with(|| {
+                let (a, b) = (sorted_pairs(a), sorted_pairs(b));
+                let keys = a.iter().zip(&b).map(|(x, y)| x.0.term_cmp(&y.0));
+                let values = a.iter().zip(&b).map(|(x, y)| x.1.term_cmp(&y.1));
+                keys.chain(values)
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            }),
+            (Self::List(_) | Self::ImproperList(..), Self::List(_) | Self::ImproperList(..)) => {
+                cmp_lists(self, other)
+            }
+            (Self::Binary(a), Self::Binary(b)) => a.cmp(b),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+fn cmp_elements(a: &[Term], b: &[Term]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.term_cmp(y))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Compares two lists cell by cell; an improper tail is compared
+/// against whatever the other list has in the same position.
+fn cmp_lists(a: &Term, b: &Term) -> Ordering {
+    fn split(t: &Term) -> (&[Term], Option<&Term>) {
+        match t {
+            Term::List(items) => (items, None),
+            Term::ImproperList(items, tail) => (items, Some(tail)),
+            _ => (&[], Some(t)),
+        }
+    }
+    fn rest(items: &[Term], tail: Option<&Term>) -> Term {
+        match tail {
+            None => Term::List(items.to_vec()),
+            Some(tail) if items.is_empty() => tail.clone(),
+            Some(tail) => Term::ImproperList(items.to_vec(), Box::new(tail.clone())),
+        }
+    }
+    fn cells(a: (&[Term], Option<&Term>), b: (&[Term], Option<&Term>)) -> Ordering {
+        match (a.0.split_first(), b.0.split_first()) {
+            (Some((x, xs)), Some((y, ys))) => {
+                x.term_cmp(y).then_with(|| cells((xs, a.1), (ys, b.1)))
+            }
+            _ => rest(a.0, a.1).term_cmp_tail(&rest(b.0, b.1)),
+        }
+    }
+    cells(split(a), split(b))
+}
+
+impl Term {
+    /// Compares what is left of two lists once one has run out of
+    /// cells.
+    fn term_cmp_tail(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::List(a), Self::List(b)) => a.len().cmp(&b.len()),
+            (Self::List(a), Self::ImproperList(..)) if a.is_empty() => Ordering::Less,
+            (Self::ImproperList(..), Self::List(b)) if b.is_empty() => Ordering::Greater,
+            _ => self.term_cmp(other),
+        }
+    }
+}
+
+/// Returns `pairs` sorted by map key order: term order, except that
+/// every integer key sorts before every float key.
+pub(crate) fn sorted_pairs(pairs: &[(Term, Term)]) -> Vec<&(Term, Term)> {
+    let mut sorted: Vec<_> = pairs.iter().collect();
+    sorted.sort_by(|a, b| match (&a.0, &b.0) {
+        (Term::Integer(_), Term::Float(_)) => Ordering::Less,
+        (Term::Float(_), Term::Integer(_)) => Ordering::Greater,
+        (x, y) => x.term_cmp(y),
+    });
+    sorted
+}
+
+impl From<i64> for Term {
+    fn from(value: i64) -> Self {
+        Self::Integer(Integer::from(value))
+    }
+}
+
+impl From<Integer> for Term {
+    fn from(value: Integer) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for Term {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl fmt::Display for Term {
+    /// Writes the term on one line as `io:format("~w", [Term])` does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&super::pretty::write(self))
+    }
+}
+
+/// An arbitrary-precision integer, stored as a sign and a little-endian
+/// magnitude (the layout of the external term format's big integers).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Integer {
+    negative: bool,
+    // Little-endian, no trailing zero bytes; zero is empty.
+    magnitude: Vec<u8>,
+}
+
+impl Integer {
+    /// Parses the digits of an Erlang integer literal: decimal
+    /// (`1_000`) or `Base#Digits` (`16#fF`), with optional `_`
+    /// separators. Returns `None` when `text` is not such a literal.
+    pub fn parse_literal(text: &str) -> Option<Self> {
+        let (radix, digits) = match text.split_once('#') {
+            Some((base, digits)) => {
+                let base: u32 = base.replace('_', "").parse().ok()?;
+                ((2..=36).contains(&base).then_some(base)?, digits)
+            }
+            None => (10, text),
+        };
+        let mut value = Self::from(0);
+        let mut any = false;
+        for c in digits.chars() {
+            if c == '_' {
+                continue;
+            }
+            let digit = c.to_digit(radix)?;
+            value.mul_add(radix, digit);
+            any = true;
+        }
+        any.then_some(value)
+    }
+
+    /// Builds an integer from a sign and a little-endian magnitude.
+    pub fn from_le_bytes(negative: bool, magnitude: &[u8]) -> Self {
+        let mut magnitude = magnitude.to_vec();
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    /// Returns `true` for values below zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the little-endian magnitude; empty for zero.
+    pub fn magnitude(&self) -> &[u8] {
+        &self.magnitude
+    }
+
+    /// Returns the value when it fits in `i64`.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 8 {
+            return None;
+        }
+        let mut bytes = [0u8; 8];
+        bytes[..self.magnitude.len()].copy_from_slice(&self.magnitude);
+        let magnitude = u64::from_le_bytes(bytes);
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Returns the nearest float.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &b| acc * 256.0 + f64::from(b));
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Returns the negated value.
+    pub fn negate(&self) -> Self {
+        Self::from_le_bytes(!self.negative, &self.magnitude)
+    }
+
+    fn mul_add(&mut self, factor: u32, addend: u32) {
+        let mut carry = u64::from(addend);
+        for byte in &mut self.magnitude {
+            let v = u64::from(*byte) * u64::from(factor) + carry;
+            *byte = (v & 0xff) as u8;
+            carry = v >> 8;
+        }
+        while carry > 0 {
+            self.magnitude.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    /// Divides the magnitude by `divisor` in place and returns the
+    /// remainder.
+    fn div_rem(magnitude: &mut Vec<u8>, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for byte in magnitude.iter_mut().rev() {
+            let v = (rem << 8) | u64::from(*byte);
+            *byte = (v / u64::from(divisor)) as u8;
+            rem = v % u64::from(divisor);
+        }
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        rem as u32
+    }
+}
+
+impl From<i64> for Integer {
+    fn from(value: i64) -> Self {
+        Self::from_le_bytes(value < 0, &value.unsigned_abs().to_le_bytes())
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude = self
+            .magnitude
+            .len()
+            .cmp(&other.magnitude.len())
+            .then_with(|| {
+                self.magnitude
+                    .iter()
+                    .rev()
+                    .cmp(other.magnitude.iter().rev())
+            });
+        match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(v) = self.to_i64() {
+            return write!(f, "{v}");
+        }
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(Self::div_rem(&mut magnitude, 1_000_000_000));
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{first}")?;
+        }
+        for chunk in chunks {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
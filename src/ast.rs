@@ -0,0 +1,1467 @@
+//! Typed, zero-copy wrappers over [`NodeView`].
+//!
+//! Every [`SyntaxKind`] variant has a same-named wrapper struct here
+//! (`ast::FunctionDecl`, `ast::CaseExpr`, `ast::RecordField`, ...). A
+//! wrapper is a [`NodeView`] whose kind has been checked once by
+//! [`AstNode::cast`]; its named accessors (`clauses()`, `guard()`,
+//! `body()`, `subject()`, ...) encode the child layout the grammar
+//! emits so callers do not re-derive it from `children()` positions.
+//!
+//! Accessors return `Option` wherever error recovery may have left a
+//! hole. Positions that can hold any expression, pattern, or type
+//! return [`Node`], the sum over every wrapper; match on it or call
+//! [`Node::syntax`] to drop back to the untyped view. Identifier
+//! pieces that the grammar keeps as bare tokens (function names,
+//! record names, fun-reference arities, ...) are returned as
+//! [`TokenIndex`]es into [`SyntaxTree::tokens`](crate::SyntaxTree::tokens).
+//!
+//! The wrappers borrow the tree exactly as [`NodeView`] does and are
+//! `Copy`; nothing here allocates.
+
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// A typed wrapper over a [`NodeView`] of one specific [`SyntaxKind`].
+pub trait AstNode<'a>: Copy {
+    /// The kind every value of this type is anchored on.
+    const KIND: SyntaxKind;
+
+    /// Wraps `node` when its kind is [`Self::KIND`].
+    fn cast(node: NodeView<'a>) -> Option<Self>;
+
+    /// Returns the underlying untyped view.
+    fn syntax(self) -> NodeView<'a>;
+
+    /// Returns the node's [`TokenRange`].
+    fn range(self) -> TokenRange {
+        self.syntax().range()
+    }
+}
+
+macro_rules! ast_nodes {
+    ($($name:ident => $visit:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Typed view of a [`SyntaxKind::", stringify!($name), "`] node.")]
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name<'a>(NodeView<'a>);
+
+            impl<'a> AstNode<'a> for $name<'a> {
+                const KIND: SyntaxKind = SyntaxKind::$name;
+
+                fn cast(node: NodeView<'a>) -> Option<Self> {
+                    (node.kind() == Self::KIND).then_some(Self(node))
+                }
+
+                fn syntax(self) -> NodeView<'a> {
+                    self.0
+                }
+            }
+        )*
+
+        /// Any typed node: one variant per [`SyntaxKind`].
+        ///
+        /// Returned by accessors whose position admits more than one
+        /// kind (an expression, a pattern, a type, a comprehension
+        /// qualifier, ...).
+        #[derive(Debug, Clone, Copy)]
+        pub enum Node<'a> {
+            $(
+                #[doc = concat!("A [`SyntaxKind::", stringify!($name), "`] node.")]
+                $name($name<'a>),
+            )*
+        }
+
+        impl<'a> Node<'a> {
+            /// Wraps `node` in the variant matching its kind.
+            pub fn new(node: NodeView<'a>) -> Self {
+                match node.kind() {
+                    $(SyntaxKind::$name => Self::$name($name(node)),)*
+                }
+            }
+
+            /// Returns the underlying untyped view.
+            pub fn syntax(self) -> NodeView<'a> {
+                match self {
+                    $(Self::$name(n) => n.0,)*
+                }
+            }
+        }
+
+        /// Returns the [`SyntaxKind`] whose variant is named `name`.
+        pub(crate) fn kind_from_name(name: &str) -> Option<SyntaxKind> {
+            match name {
+                $(stringify!($name) => Some(SyntaxKind::$name),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+/// Invokes `$callback!` with every kind as `Name => visit_name`, in
+/// [`SyntaxKind`] order. The wrappers below and the hooks of
+/// [`Visitor`](crate::visit::Visitor) are both generated from it.
+macro_rules! for_each_ast_node {
+    ($callback:ident) => {
+        $callback! {
+            Error => visit_error,
+            AtomExpr => visit_atom_expr,
+            VarExpr => visit_var_expr,
+            IntegerExpr => visit_integer_expr,
+            FloatExpr => visit_float_expr,
+            CharExpr => visit_char_expr,
+            StringExpr => visit_string_expr,
+            SigilStringExpr => visit_sigil_string_expr,
+            TupleExpr => visit_tuple_expr,
+            ListExpr => visit_list_expr,
+            ConsExpr => visit_cons_expr,
+            ParenExpr => visit_paren_expr,
+            BitstringExpr => visit_bitstring_expr,
+            MapExpr => visit_map_expr,
+            MapUpdateExpr => visit_map_update_expr,
+            RecordExpr => visit_record_expr,
+            RecordUpdateExpr => visit_record_update_expr,
+            RecordFieldAccessExpr => visit_record_field_access_expr,
+            RecordIndexExpr => visit_record_index_expr,
+            BinaryOpExpr => visit_binary_op_expr,
+            UnaryOpExpr => visit_unary_op_expr,
+            MatchExpr => visit_match_expr,
+            SendExpr => visit_send_expr,
+            MaybeMatchExpr => visit_maybe_match_expr,
+            CallExpr => visit_call_expr,
+            RemoteExpr => visit_remote_expr,
+            BeginExpr => visit_begin_expr,
+            CatchExpr => visit_catch_expr,
+            CaseExpr => visit_case_expr,
+            IfExpr => visit_if_expr,
+            ReceiveExpr => visit_receive_expr,
+            ReceiveAfterSection => visit_receive_after_section,
+            TryExpr => visit_try_expr,
+            TryOfSection => visit_try_of_section,
+            TryCatchSection => visit_try_catch_section,
+            TryAfterSection => visit_try_after_section,
+            MaybeExpr => visit_maybe_expr,
+            MaybeElseSection => visit_maybe_else_section,
+            AnonymousFun => visit_anonymous_fun,
+            NamedFun => visit_named_fun,
+            LocalFunRef => visit_local_fun_ref,
+            RemoteFunRef => visit_remote_fun_ref,
+            ListComprehension => visit_list_comprehension,
+            MapComprehension => visit_map_comprehension,
+            BinaryComprehension => visit_binary_comprehension,
+            Generator => visit_generator,
+            BitstringGenerator => visit_bitstring_generator,
+            MapGenerator => visit_map_generator,
+            StrictGenerator => visit_strict_generator,
+            StrictBitstringGenerator => visit_strict_bitstring_generator,
+            StrictMapGenerator => visit_strict_map_generator,
+            ZipQualifier => visit_zip_qualifier,
+            Filter => visit_filter,
+            Body => visit_body,
+            Clause => visit_clause,
+            IfClause => visit_if_clause,
+            CatchClause => visit_catch_clause,
+            Guard => visit_guard,
+            GuardSequence => visit_guard_sequence,
+            ArgumentList => visit_argument_list,
+            RecordField => visit_record_field,
+            MapField => visit_map_field,
+            BitstringElement => visit_bitstring_element,
+            TupleType => visit_tuple_type,
+            ListType => visit_list_type,
+            NonemptyListType => visit_nonempty_list_type,
+            MapType => visit_map_type,
+            RecordType => visit_record_type,
+            BitstringType => visit_bitstring_type,
+            FunctionType => visit_function_type,
+            TypeCall => visit_type_call,
+            RemoteType => visit_remote_type,
+            UnionType => visit_union_type,
+            RangeType => visit_range_type,
+            AnnotatedType => visit_annotated_type,
+            BinaryOpType => visit_binary_op_type,
+            UnaryOpType => visit_unary_op_type,
+            TypeArgumentList => visit_type_argument_list,
+            MapTypeField => visit_map_type_field,
+            RecordTypeField => visit_record_type_field,
+            BitstringTypeSegment => visit_bitstring_type_segment,
+            FunctionTypeParams => visit_function_type_params,
+            FunctionTypeReturn => visit_function_type_return,
+            TypeConstraint => visit_type_constraint,
+            TypeGuard => visit_type_guard,
+            Attribute => visit_attribute,
+            AttributeName => visit_attribute_name,
+            AttributePayload => visit_attribute_payload,
+            FunctionDecl => visit_function_decl,
+            FunctionClause => visit_function_clause,
+            SpecAttribute => visit_spec_attribute,
+            TypeDecl => visit_type_decl,
+            RecordDecl => visit_record_decl,
+            RecordDeclField => visit_record_decl_field,
+            ExportList => visit_export_list,
+            NameArity => visit_name_arity,
+        }
+    };
+}
+
+pub(crate) use for_each_ast_node;
+
+for_each_ast_node!(ast_nodes);
+
+// ---------------------------------------------------------------------
+// Layout helpers.
+//
+// Children are in token order and their ranges never overlap, so
+// "the child before / after this terminal token" is enough to tell
+// the operands of every production apart even when recovery inserted
+// an `Error` node somewhere in between.
+// ---------------------------------------------------------------------
+
+type Token = (TokenIndex, erl_tokenize::Token);
+
+/// Lexical tokens of `node` that no child covers: keywords,
+/// punctuation, operators, and bare identifier tokens.
+fn own_tokens(node: NodeView<'_>) -> impl Iterator<Item = Token> {
+    let mut children = node
+        .children()
+        .map(|c| c.range())
+        .filter(|r| !r.is_empty())
+        .peekable();
+    node.tokens_in_range().filter(move |&(index, token)| {
+        if token.kind().is_hidden() {
+            return false;
+        }
+        while children.next_if(|r| r.end() <= index).is_some() {}
+        children.peek().is_none_or(|r| index < r.start())
+    })
+}
+
+fn is_name(token: erl_tokenize::Token) -> bool {
+    matches!(
+        token.kind(),
+        erl_tokenize::TokenKind::Atom | erl_tokenize::TokenKind::Variable
+    )
+}
+
+fn is_symbol(token: erl_tokenize::Token, sym: erl_tokenize::Symbol) -> bool {
+    token.kind() == erl_tokenize::TokenKind::Symbol(sym)
+}
+
+fn is_keyword(token: erl_tokenize::Token, kw: erl_tokenize::Keyword) -> bool {
+    token.kind() == erl_tokenize::TokenKind::Keyword(kw)
+}
+
+fn own_symbol(node: NodeView<'_>, sym: erl_tokenize::Symbol) -> Option<TokenIndex> {
+    own_tokens(node)
+        .find(|&(_, t)| is_symbol(t, sym))
+        .map(|(i, _)| i)
+}
+
+fn own_keyword(node: NodeView<'_>, kw: erl_tokenize::Keyword) -> Option<TokenIndex> {
+    own_tokens(node)
+        .find(|&(_, t)| is_keyword(t, kw))
+        .map(|(i, _)| i)
+}
+
+/// First own name token (atom or variable) at or after `from`.
+fn own_name_after(node: NodeView<'_>, from: TokenIndex) -> Option<TokenIndex> {
+    own_tokens(node)
+        .find(|&(i, t)| i >= from && is_name(t))
+        .map(|(i, _)| i)
+}
+
+fn first_child<'a, T: AstNode<'a>>(node: NodeView<'a>) -> Option<T> {
+    node.children().find_map(T::cast)
+}
+
+fn typed_children<'a, T: AstNode<'a>>(node: NodeView<'a>) -> impl Iterator<Item = T> {
+    node.children().filter_map(T::cast)
+}
+
+fn nodes(node: NodeView<'_>) -> impl Iterator<Item = Node<'_>> {
+    node.children().map(Node::new)
+}
+
+/// Last child that ends at or before `at`.
+fn child_before(node: NodeView<'_>, at: TokenIndex) -> Option<Node<'_>> {
+    node.children()
+        .take_while(|c| c.range().end() <= at)
+        .last()
+        .map(Node::new)
+}
+
+/// First child that starts after `at`.
+fn child_after(node: NodeView<'_>, at: TokenIndex) -> Option<Node<'_>> {
+    node.children()
+        .find(|c| c.range().start() > at)
+        .map(Node::new)
+}
+
+/// First own lexical token: the operator of a binary / unary
+/// production, since operands are always child nodes.
+fn first_own_token(node: NodeView<'_>) -> Option<TokenIndex> {
+    own_tokens(node).next().map(|(i, _)| i)
+}
+
+fn lhs(node: NodeView<'_>) -> Option<Node<'_>> {
+    child_before(node, first_own_token(node)?)
+}
+
+fn rhs(node: NodeView<'_>) -> Option<Node<'_>> {
+    child_after(node, first_own_token(node)?)
+}
+
+/// `(module, name)` tokens of a `#Name` / `#Module:Name` record head
+/// whose `#` sits at or after `from`.
+fn record_head(node: NodeView<'_>, from: TokenIndex) -> (Option<TokenIndex>, Option<TokenIndex>) {
+    let mut tokens = own_tokens(node)
+        .skip_while(|&(i, t)| i < from || !is_symbol(t, erl_tokenize::Symbol::Sharp));
+    let _sharp = tokens.next();
+    let first = tokens.next().filter(|&(_, t)| is_name(t));
+    let Some((first, _)) = first else {
+        return (None, None);
+    };
+    match tokens.next() {
+        Some((_, t)) if is_symbol(t, erl_tokenize::Symbol::Colon) => {
+            let name = tokens.next().filter(|&(_, t)| is_name(t)).map(|(i, _)| i);
+            (Some(first), name)
+        }
+        _ => (None, Some(first)),
+    }
+}
+
+/// The start of the node's own `#` token, or the node start when the
+/// production has none.
+fn sharp_or_start(node: NodeView<'_>) -> TokenIndex {
+    own_symbol(node, erl_tokenize::Symbol::Sharp).unwrap_or(node.range().start())
+}
+
+fn first_token(node: NodeView<'_>) -> Option<TokenIndex> {
+    node.tokens_in_range()
+        .find(|(_, t)| !t.kind().is_hidden())
+        .map(|(i, _)| i)
+}
+
+// ---------------------------------------------------------------------
+// Atomic expressions.
+// ---------------------------------------------------------------------
+
+macro_rules! single_token {
+    ($($name:ident),*) => {
+        $(
+            impl $name<'_> {
+                /// Returns the literal's lexical token.
+                pub fn token(self) -> Option<TokenIndex> {
+                    first_token(self.0)
+                }
+            }
+        )*
+    };
+}
+
+single_token!(
+    AtomExpr,
+    VarExpr,
+    IntegerExpr,
+    FloatExpr,
+    CharExpr,
+    SigilStringExpr
+);
+
+impl StringExpr<'_> {
+    /// Returns the adjacent string tokens that make up the literal, in
+    /// source order.
+    pub fn tokens(self) -> impl Iterator<Item = TokenIndex> {
+        own_tokens(self.0)
+            .filter(|(_, t)| t.kind() == erl_tokenize::TokenKind::String)
+            .map(|(i, _)| i)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Containers.
+// ---------------------------------------------------------------------
+
+impl<'a> TupleExpr<'a> {
+    /// Returns the tuple elements.
+    pub fn elements(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> ListExpr<'a> {
+    /// Returns the list elements.
+    pub fn elements(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> ConsExpr<'a> {
+    /// Returns the elements before `|`.
+    pub fn heads(self) -> impl Iterator<Item = Node<'a>> {
+        let bar = own_symbol(self.0, erl_tokenize::Symbol::VerticalBar);
+        nodes(self.0).take_while(move |n| bar.is_none_or(|b| n.syntax().range().end() <= b))
+    }
+
+    /// Returns the tail after `|`.
+    pub fn tail(self) -> Option<Node<'a>> {
+        child_after(
+            self.0,
+            own_symbol(self.0, erl_tokenize::Symbol::VerticalBar)?,
+        )
+    }
+}
+
+impl<'a> ParenExpr<'a> {
+    /// Returns the parenthesized expression.
+    pub fn expr(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> BitstringExpr<'a> {
+    /// Returns the bitstring segments.
+    pub fn elements(self) -> impl Iterator<Item = BitstringElement<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> BitstringElement<'a> {
+    /// Returns the segment value.
+    pub fn value(self) -> Option<Node<'a>> {
+        let end = self.specifier_start();
+        nodes(self.0)
+            .next()
+            .filter(|n| end.is_none_or(|e| n.syntax().range().end() <= e))
+    }
+
+    /// Returns the `: Size` expression, if present.
+    pub fn size(self) -> Option<Node<'a>> {
+        let colon = own_symbol(self.0, erl_tokenize::Symbol::Colon)?;
+        let end = self.specifier_start();
+        child_after(self.0, colon).filter(|n| end.is_none_or(|e| n.syntax().range().end() <= e))
+    }
+
+    /// Returns the type specifier names after `/` (`integer`,
+    /// `unit`, `little`, ...). Unit sizes are left in the token buffer.
+    pub fn type_specifiers(self) -> impl Iterator<Item = TokenIndex> {
+        let start = self.specifier_start();
+        own_tokens(self.0)
+            .filter(move |&(i, t)| start.is_some_and(|s| i > s) && is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    fn specifier_start(self) -> Option<TokenIndex> {
+        own_symbol(self.0, erl_tokenize::Symbol::Slash)
+    }
+}
+
+impl<'a> MapExpr<'a> {
+    /// Returns the `K => V` / `K := V` entries.
+    pub fn fields(self) -> impl Iterator<Item = MapField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> MapUpdateExpr<'a> {
+    /// Returns the map being updated.
+    pub fn base(self) -> Option<Node<'a>> {
+        child_before(self.0, own_symbol(self.0, erl_tokenize::Symbol::Sharp)?)
+    }
+
+    /// Returns the `K => V` / `K := V` entries.
+    pub fn fields(self) -> impl Iterator<Item = MapField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> MapField<'a> {
+    /// Returns the key expression.
+    pub fn key(self) -> Option<Node<'a>> {
+        child_before(self.0, self.operator()?)
+    }
+
+    /// Returns the `=>` or `:=` token.
+    pub fn operator(self) -> Option<TokenIndex> {
+        first_own_token(self.0)
+    }
+
+    /// Returns `true` for an exact (`:=`) association.
+    pub fn is_exact(self) -> bool {
+        own_symbol(self.0, erl_tokenize::Symbol::MapMatch).is_some()
+    }
+
+    /// Returns the value expression.
+    pub fn value(self) -> Option<Node<'a>> {
+        child_after(self.0, self.operator()?)
+    }
+}
+
+impl<'a> RecordExpr<'a> {
+    /// Returns the `Module` of a native-record `#Module:Name{...}`.
+    pub fn module(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).0
+    }
+
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).1
+    }
+
+    /// Returns the `Field = Expr` entries.
+    pub fn fields(self) -> impl Iterator<Item = RecordField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> RecordUpdateExpr<'a> {
+    /// Returns the record being updated.
+    pub fn base(self) -> Option<Node<'a>> {
+        child_before(self.0, own_symbol(self.0, erl_tokenize::Symbol::Sharp)?)
+    }
+
+    /// Returns the `Module` of a native-record update.
+    pub fn module(self) -> Option<TokenIndex> {
+        record_head(self.0, sharp_or_start(self.0)).0
+    }
+
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        record_head(self.0, sharp_or_start(self.0)).1
+    }
+
+    /// Returns the `Field = Expr` entries.
+    pub fn fields(self) -> impl Iterator<Item = RecordField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> RecordFieldAccessExpr<'a> {
+    /// Returns the record expression being read.
+    pub fn base(self) -> Option<Node<'a>> {
+        child_before(self.0, own_symbol(self.0, erl_tokenize::Symbol::Sharp)?)
+    }
+
+    /// Returns the `Module` of a native-record access.
+    pub fn module(self) -> Option<TokenIndex> {
+        record_head(self.0, sharp_or_start(self.0)).0
+    }
+
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        record_head(self.0, sharp_or_start(self.0)).1
+    }
+
+    /// Returns the field name token after `.`.
+    pub fn field(self) -> Option<TokenIndex> {
+        own_name_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Dot)?)
+    }
+}
+
+impl RecordIndexExpr<'_> {
+    /// Returns the `Module` of a native-record index.
+    pub fn module(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).0
+    }
+
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).1
+    }
+
+    /// Returns the field name token after `.`.
+    pub fn field(self) -> Option<TokenIndex> {
+        own_name_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Dot)?)
+    }
+}
+
+impl<'a> RecordField<'a> {
+    /// Returns the field name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the field value expression.
+    pub fn value(self) -> Option<Node<'a>> {
+        child_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Match)?)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Operations.
+// ---------------------------------------------------------------------
+
+macro_rules! binary_shape {
+    ($name:ident, $left:ident, $right:ident, $what:literal) => {
+        impl<'a> $name<'a> {
+            #[doc = concat!("Returns the ", $what, "'s left operand.")]
+            pub fn $left(self) -> Option<Node<'a>> {
+                lhs(self.0)
+            }
+
+            #[doc = concat!("Returns the ", $what, "'s operator token.")]
+            pub fn operator(self) -> Option<TokenIndex> {
+                first_own_token(self.0)
+            }
+
+            #[doc = concat!("Returns the ", $what, "'s right operand.")]
+            pub fn $right(self) -> Option<Node<'a>> {
+                rhs(self.0)
+            }
+        }
+    };
+}
+
+binary_shape!(BinaryOpExpr, lhs, rhs, "operation");
+binary_shape!(MatchExpr, pattern, expr, "match");
+binary_shape!(SendExpr, target, message, "send");
+binary_shape!(MaybeMatchExpr, pattern, expr, "conditional match");
+binary_shape!(BinaryOpType, lhs, rhs, "operation");
+binary_shape!(RangeType, low, high, "range");
+binary_shape!(AnnotatedType, var, ty, "annotation");
+
+impl<'a> UnaryOpExpr<'a> {
+    /// Returns the prefix operator token.
+    pub fn operator(self) -> Option<TokenIndex> {
+        first_own_token(self.0)
+    }
+
+    /// Returns the operand.
+    pub fn operand(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> UnaryOpType<'a> {
+    /// Returns the prefix operator token.
+    pub fn operator(self) -> Option<TokenIndex> {
+        first_own_token(self.0)
+    }
+
+    /// Returns the operand.
+    pub fn operand(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+// ---------------------------------------------------------------------
+// Calls and remote references.
+// ---------------------------------------------------------------------
+
+impl<'a> CallExpr<'a> {
+    /// Returns the call target (an atom, a [`RemoteExpr`], a fun
+    /// value, ...).
+    pub fn target(self) -> Option<Node<'a>> {
+        child_before(self.0, self.arguments()?.range().start())
+    }
+
+    /// Returns the argument list.
+    pub fn arguments(self) -> Option<ArgumentList<'a>> {
+        self.0.children().filter_map(ArgumentList::cast).last()
+    }
+}
+
+impl<'a> RemoteExpr<'a> {
+    /// Returns the module expression before `:`.
+    pub fn module(self) -> Option<Node<'a>> {
+        lhs(self.0)
+    }
+
+    /// Returns the function expression after `:`.
+    pub fn function(self) -> Option<Node<'a>> {
+        rhs(self.0)
+    }
+}
+
+impl<'a> ArgumentList<'a> {
+    /// Returns the arguments.
+    pub fn args(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Blocks.
+// ---------------------------------------------------------------------
+
+impl<'a> BeginExpr<'a> {
+    /// Returns the block body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> CatchExpr<'a> {
+    /// Returns the protected expression.
+    pub fn expr(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> CaseExpr<'a> {
+    /// Returns the expression between `case` and `of`.
+    pub fn subject(self) -> Option<Node<'a>> {
+        let of = own_keyword(self.0, erl_tokenize::Keyword::Of)?;
+        child_before(self.0, of)
+    }
+
+    /// Returns the `case` clauses.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> IfExpr<'a> {
+    /// Returns the `if` clauses.
+    pub fn clauses(self) -> impl Iterator<Item = IfClause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> ReceiveExpr<'a> {
+    /// Returns the message clauses.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+
+    /// Returns the `after Timeout -> Body` section, if present.
+    pub fn after(self) -> Option<ReceiveAfterSection<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> ReceiveAfterSection<'a> {
+    /// Returns the timeout expression.
+    pub fn timeout(self) -> Option<Node<'a>> {
+        nodes(self.0).next().filter(|n| !matches!(n, Node::Body(_)))
+    }
+
+    /// Returns the body run on timeout.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> TryExpr<'a> {
+    /// Returns the protected body between `try` and the first section.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `of` section, if present.
+    pub fn of_section(self) -> Option<TryOfSection<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `catch` section, if present.
+    pub fn catch_section(self) -> Option<TryCatchSection<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `after` section, if present.
+    pub fn after_section(self) -> Option<TryAfterSection<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> TryOfSection<'a> {
+    /// Returns the `of` clauses.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> TryCatchSection<'a> {
+    /// Returns the `catch` clauses: [`Node::Clause`] for a
+    /// pattern-only clause and [`Node::CatchClause`] for a
+    /// class-qualified one.
+    pub fn clauses(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0).filter(|n| matches!(n, Node::Clause(_) | Node::CatchClause(_)))
+    }
+}
+
+impl<'a> TryAfterSection<'a> {
+    /// Returns the cleanup body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> MaybeExpr<'a> {
+    /// Returns the body between `maybe` and `else` / `end`.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `else` section, if present.
+    pub fn else_section(self) -> Option<MaybeElseSection<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> MaybeElseSection<'a> {
+    /// Returns the `else` clauses.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Funs.
+// ---------------------------------------------------------------------
+
+impl<'a> AnonymousFun<'a> {
+    /// Returns the fun clauses.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> NamedFun<'a> {
+    /// Returns the name token of the first clause.
+    pub fn name(self) -> Option<TokenIndex> {
+        self.clauses().next()?.name()
+    }
+
+    /// Returns the fun clauses; each carries its own name token.
+    pub fn clauses(self) -> impl Iterator<Item = Clause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl LocalFunRef<'_> {
+    /// Returns the function name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_name_after(self.0, self.0.range().start())
+            .filter(|&i| self.arity_slash().is_none_or(|s| i < s))
+    }
+
+    /// Returns the arity token (an integer or a variable).
+    pub fn arity(self) -> Option<TokenIndex> {
+        let slash = self.arity_slash()?;
+        own_tokens(self.0).find(|&(i, _)| i > slash).map(|(i, _)| i)
+    }
+
+    fn arity_slash(self) -> Option<TokenIndex> {
+        own_symbol(self.0, erl_tokenize::Symbol::Slash)
+    }
+}
+
+impl RemoteFunRef<'_> {
+    /// Returns the module token (an atom or a variable).
+    pub fn module(self) -> Option<TokenIndex> {
+        let colon = own_symbol(self.0, erl_tokenize::Symbol::Colon)?;
+        own_name_after(self.0, self.0.range().start()).filter(|&i| i < colon)
+    }
+
+    /// Returns the function name token (an atom or a variable).
+    pub fn name(self) -> Option<TokenIndex> {
+        own_name_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Colon)?)
+    }
+
+    /// Returns the arity token (an integer or a variable).
+    pub fn arity(self) -> Option<TokenIndex> {
+        let slash = own_symbol(self.0, erl_tokenize::Symbol::Slash)?;
+        own_tokens(self.0).find(|&(i, _)| i > slash).map(|(i, _)| i)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Comprehensions and qualifiers.
+// ---------------------------------------------------------------------
+
+macro_rules! comprehension_shape {
+    ($($name:ident),*) => {
+        $(
+            impl<'a> $name<'a> {
+                /// Returns the template before `||`.
+                pub fn template(self) -> Option<Node<'a>> {
+                    child_before(self.0, own_symbol(self.0, erl_tokenize::Symbol::DoubleVerticalBar)?)
+                }
+
+                /// Returns the generators and filters after `||`.
+                pub fn qualifiers(self) -> impl Iterator<Item = Node<'a>> {
+                    let bar = own_symbol(self.0, erl_tokenize::Symbol::DoubleVerticalBar);
+                    nodes(self.0)
+                        .filter(move |n| bar.is_some_and(|b| n.syntax().range().start() > b))
+                }
+            }
+        )*
+    };
+}
+
+comprehension_shape!(ListComprehension, MapComprehension, BinaryComprehension);
+
+macro_rules! generator_shape {
+    ($($name:ident),*) => {
+        $(
+            impl<'a> $name<'a> {
+                /// Returns the generator pattern.
+                pub fn pattern(self) -> Option<Node<'a>> {
+                    lhs(self.0)
+                }
+
+                /// Returns the generator's source expression.
+                pub fn expr(self) -> Option<Node<'a>> {
+                    rhs(self.0)
+                }
+            }
+        )*
+    };
+}
+
+generator_shape!(
+    Generator,
+    BitstringGenerator,
+    StrictGenerator,
+    StrictBitstringGenerator
+);
+
+macro_rules! map_generator_shape {
+    ($($name:ident),*) => {
+        $(
+            impl<'a> $name<'a> {
+                /// Returns the key pattern before `:=`.
+                pub fn key(self) -> Option<Node<'a>> {
+                    lhs(self.0)
+                }
+
+                /// Returns the value pattern between `:=` and the arrow.
+                pub fn value(self) -> Option<Node<'a>> {
+                    let arrow = own_tokens(self.0).nth(1)?.0;
+                    child_before(self.0, arrow).filter(|n| {
+                        self.key().is_none_or(|k| k.syntax().node_id() != n.syntax().node_id())
+                    })
+                }
+
+                /// Returns the generator's source expression.
+                pub fn expr(self) -> Option<Node<'a>> {
+                    child_after(self.0, own_tokens(self.0).nth(1)?.0)
+                }
+            }
+        )*
+    };
+}
+
+map_generator_shape!(MapGenerator, StrictMapGenerator);
+
+impl<'a> ZipQualifier<'a> {
+    /// Returns the generators joined by `&&`.
+    pub fn generators(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> Filter<'a> {
+    /// Returns the filter expression.
+    pub fn expr(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+// ---------------------------------------------------------------------
+// Clauses, bodies, and guards.
+// ---------------------------------------------------------------------
+
+impl<'a> Body<'a> {
+    /// Returns the comma-separated expressions.
+    pub fn exprs(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> Clause<'a> {
+    /// Returns the name token of a named-fun clause.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the argument list of a fun clause.
+    pub fn arguments(self) -> Option<ArgumentList<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the pattern of a `case` / `receive` / `try` /
+    /// `maybe else` clause.
+    pub fn pattern(self) -> Option<Node<'a>> {
+        nodes(self.0).next().filter(|n| {
+            !matches!(
+                n,
+                Node::ArgumentList(_) | Node::GuardSequence(_) | Node::Body(_)
+            )
+        })
+    }
+
+    /// Returns the `when` guard, if present.
+    pub fn guard(self) -> Option<GuardSequence<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the clause body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> IfClause<'a> {
+    /// Returns the clause guard.
+    pub fn guard(self) -> Option<GuardSequence<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the clause body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> CatchClause<'a> {
+    /// Returns the exception class token (an atom or a variable).
+    pub fn class(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the reason pattern.
+    pub fn pattern(self) -> Option<Node<'a>> {
+        nodes(self.0)
+            .next()
+            .filter(|n| !matches!(n, Node::GuardSequence(_) | Node::Body(_)))
+    }
+
+    /// Returns the stack-trace variable token, if present.
+    pub fn stacktrace(self) -> Option<TokenIndex> {
+        let after = self.pattern()?.syntax().range().end();
+        let colon = own_tokens(self.0)
+            .find(|&(i, t)| i >= after && is_symbol(t, erl_tokenize::Symbol::Colon))?
+            .0;
+        own_name_after(self.0, colon)
+    }
+
+    /// Returns the `when` guard, if present.
+    pub fn guard(self) -> Option<GuardSequence<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the clause body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> Guard<'a> {
+    /// Returns the comma-separated guard expressions.
+    pub fn exprs(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> GuardSequence<'a> {
+    /// Returns the `;`-separated guards.
+    pub fn guards(self) -> impl Iterator<Item = Guard<'a>> {
+        typed_children(self.0)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Types.
+// ---------------------------------------------------------------------
+
+impl<'a> TupleType<'a> {
+    /// Returns the element types.
+    pub fn elements(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> ListType<'a> {
+    /// Returns the element type; `None` for `[]`.
+    pub fn element(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> NonemptyListType<'a> {
+    /// Returns the element type.
+    pub fn element(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> MapType<'a> {
+    /// Returns the association types.
+    pub fn fields(self) -> impl Iterator<Item = MapTypeField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> MapTypeField<'a> {
+    /// Returns the key type.
+    pub fn key(self) -> Option<Node<'a>> {
+        child_before(self.0, self.operator()?)
+    }
+
+    /// Returns the `=>` or `:=` token.
+    pub fn operator(self) -> Option<TokenIndex> {
+        first_own_token(self.0)
+    }
+
+    /// Returns `true` for a mandatory (`:=`) association.
+    pub fn is_exact(self) -> bool {
+        own_symbol(self.0, erl_tokenize::Symbol::MapMatch).is_some()
+    }
+
+    /// Returns the value type.
+    pub fn value(self) -> Option<Node<'a>> {
+        child_after(self.0, self.operator()?)
+    }
+}
+
+impl<'a> RecordType<'a> {
+    /// Returns the `Module` of a native-record type.
+    pub fn module(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).0
+    }
+
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        record_head(self.0, self.0.range().start()).1
+    }
+
+    /// Returns the `Field :: Type` entries.
+    pub fn fields(self) -> impl Iterator<Item = RecordTypeField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> RecordTypeField<'a> {
+    /// Returns the field name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the field type.
+    pub fn ty(self) -> Option<Node<'a>> {
+        child_after(
+            self.0,
+            own_symbol(self.0, erl_tokenize::Symbol::DoubleColon)?,
+        )
+    }
+}
+
+impl<'a> BitstringType<'a> {
+    /// Returns the segment types.
+    pub fn segments(self) -> impl Iterator<Item = BitstringTypeSegment<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> BitstringTypeSegment<'a> {
+    /// Returns the segment head (usually `_`).
+    pub fn head(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+
+    /// Returns the type after `:`.
+    pub fn size(self) -> Option<Node<'a>> {
+        child_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Colon)?)
+    }
+
+    /// Returns the unit type after `*`.
+    pub fn unit(self) -> Option<Node<'a>> {
+        child_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Multiply)?)
+    }
+}
+
+impl<'a> FunctionType<'a> {
+    /// Returns the parameter list; `None` for the any-function type
+    /// `fun()`.
+    pub fn params(self) -> Option<FunctionTypeParams<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the return type; `None` for `fun()`.
+    pub fn return_type(self) -> Option<FunctionTypeReturn<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `when` constraints of a spec clause.
+    pub fn guard(self) -> Option<TypeGuard<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> FunctionTypeParams<'a> {
+    /// Returns the parameter types.
+    pub fn params(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+
+    /// Returns `true` for the any-arity form `(...)`.
+    pub fn is_any_arity(self) -> bool {
+        own_symbol(self.0, erl_tokenize::Symbol::TripleDot).is_some()
+    }
+}
+
+impl<'a> FunctionTypeReturn<'a> {
+    /// Returns the return type.
+    pub fn ty(self) -> Option<Node<'a>> {
+        nodes(self.0).next()
+    }
+}
+
+impl<'a> TypeCall<'a> {
+    /// Returns the `Module` of a remote type call `Module:Name(...)`.
+    pub fn module(self) -> Option<TokenIndex> {
+        let colon = own_symbol(self.0, erl_tokenize::Symbol::Colon)?;
+        first_own_token(self.0).filter(|&i| i < colon)
+    }
+
+    /// Returns the type name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        match own_symbol(self.0, erl_tokenize::Symbol::Colon) {
+            Some(colon) => own_name_after(self.0, colon),
+            None => own_name_after(self.0, self.0.range().start()),
+        }
+    }
+
+    /// Returns the type argument list.
+    pub fn arguments(self) -> Option<TypeArgumentList<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl RemoteType<'_> {
+    /// Returns the module token.
+    pub fn module(self) -> Option<TokenIndex> {
+        first_own_token(self.0)
+    }
+
+    /// Returns the type name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_name_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Colon)?)
+    }
+}
+
+impl<'a> UnionType<'a> {
+    /// Returns the alternatives. A chain `A | B | C` nests as
+    /// `(A | B) | C`, so the first member may itself be a
+    /// [`UnionType`].
+    pub fn members(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> TypeArgumentList<'a> {
+    /// Returns the argument types.
+    pub fn args(self) -> impl Iterator<Item = Node<'a>> {
+        nodes(self.0)
+    }
+}
+
+impl<'a> TypeConstraint<'a> {
+    /// Returns the constrained variable (or, in the compatibility
+    /// form `Name(Types)`, the constraint name).
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| is_name(t))
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the type after `::`.
+    pub fn ty(self) -> Option<Node<'a>> {
+        child_after(
+            self.0,
+            own_symbol(self.0, erl_tokenize::Symbol::DoubleColon)?,
+        )
+    }
+
+    /// Returns the argument list of the compatibility form.
+    pub fn arguments(self) -> Option<TypeArgumentList<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl<'a> TypeGuard<'a> {
+    /// Returns the constraints after `when`.
+    pub fn constraints(self) -> impl Iterator<Item = TypeConstraint<'a>> {
+        typed_children(self.0)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Forms.
+// ---------------------------------------------------------------------
+
+impl<'a> Attribute<'a> {
+    /// Returns the attribute name node.
+    pub fn name(self) -> Option<AttributeName<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the opaque payload node.
+    pub fn payload(self) -> Option<AttributePayload<'a>> {
+        first_child(self.0)
+    }
+}
+
+impl AttributeName<'_> {
+    /// Returns the name's `atom` token.
+    pub fn token(self) -> Option<TokenIndex> {
+        first_token(self.0)
+    }
+}
+
+impl<'a> FunctionDecl<'a> {
+    /// Returns the name token of the first clause.
+    pub fn name(self) -> Option<TokenIndex> {
+        self.clauses().next()?.name()
+    }
+
+    /// Returns the function clauses.
+    pub fn clauses(self) -> impl Iterator<Item = FunctionClause<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> FunctionClause<'a> {
+    /// Returns the clause name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| t.kind() == erl_tokenize::TokenKind::Atom)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the argument list.
+    pub fn arguments(self) -> Option<ArgumentList<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the `when` guard, if present.
+    pub fn guard(self) -> Option<GuardSequence<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the clause body.
+    pub fn body(self) -> Option<Body<'a>> {
+        first_child(self.0)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Structured attribute payloads (see
+// [`SyntaxTree::parse_attribute_payload`](crate::SyntaxTree::parse_attribute_payload)).
+// ---------------------------------------------------------------------
+
+impl<'a> SpecAttribute<'a> {
+    /// Returns the `Module` of a `-spec Module:Name ...` form.
+    pub fn module(self) -> Option<TokenIndex> {
+        let colon = own_symbol(self.0, erl_tokenize::Symbol::Colon)?;
+        own_name_after(self.0, self.0.range().start()).filter(|&i| i < colon)
+    }
+
+    /// Returns the function name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        match own_symbol(self.0, erl_tokenize::Symbol::Colon) {
+            Some(colon) => own_name_after(self.0, colon),
+            None => own_name_after(self.0, self.0.range().start()),
+        }
+    }
+
+    /// Returns the `;`-separated signature clauses.
+    pub fn clauses(self) -> impl Iterator<Item = FunctionType<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> TypeDecl<'a> {
+    /// Returns the type name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_name_after(self.0, self.0.range().start())
+    }
+
+    /// Returns the type parameter list.
+    pub fn params(self) -> Option<TypeArgumentList<'a>> {
+        first_child(self.0)
+    }
+
+    /// Returns the definition after `::`.
+    pub fn ty(self) -> Option<Node<'a>> {
+        child_after(
+            self.0,
+            own_symbol(self.0, erl_tokenize::Symbol::DoubleColon)?,
+        )
+    }
+}
+
+impl<'a> RecordDecl<'a> {
+    /// Returns the record name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_name_after(self.0, self.0.range().start())
+    }
+
+    /// Returns the field declarations.
+    pub fn fields(self) -> impl Iterator<Item = RecordDeclField<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl<'a> RecordDeclField<'a> {
+    /// Returns the field name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| t.kind() == erl_tokenize::TokenKind::Atom)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the default value after `=`.
+    pub fn default(self) -> Option<Node<'a>> {
+        child_after(self.0, own_symbol(self.0, erl_tokenize::Symbol::Match)?)
+    }
+
+    /// Returns the field type after `::`.
+    pub fn ty(self) -> Option<Node<'a>> {
+        child_after(
+            self.0,
+            own_symbol(self.0, erl_tokenize::Symbol::DoubleColon)?,
+        )
+    }
+}
+
+impl<'a> ExportList<'a> {
+    /// Returns the `Name/Arity` entries.
+    pub fn entries(self) -> impl Iterator<Item = NameArity<'a>> {
+        typed_children(self.0)
+    }
+}
+
+impl NameArity<'_> {
+    /// Returns the function (or type) name token.
+    pub fn name(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .next()
+            .filter(|&(_, t)| t.kind() == erl_tokenize::TokenKind::Atom)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the arity token.
+    pub fn arity(self) -> Option<TokenIndex> {
+        own_tokens(self.0)
+            .find(|&(_, t)| t.kind() == erl_tokenize::TokenKind::Integer)
+            .map(|(i, _)| i)
+    }
+}
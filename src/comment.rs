@@ -0,0 +1,201 @@
+//! Comment attachment.
+//!
+//! Comments are hidden tokens, so the grammar folds them into whichever
+//! node range they happen to fall in. [`attach`] assigns each comment
+//! to one node instead, by the rules on [`CommentPlacement`].
+
+use erl_tokenize::{Symbol, Token, TokenKind};
+
+use crate::node::{NodeView, root_views};
+use crate::syntax::{NodeId, SyntaxIndex};
+use crate::token_buffer::TokenBuffer;
+use crate::token_range::TokenIndex;
+
+/// Where a comment sits relative to the node it is attached to.
+///
+/// Each comment token is attached to exactly one node:
+///
+/// 1. The enclosing node is the innermost one whose lexical tokens
+///    surround the comment. At the top level there is none, and the
+///    roots are the candidates.
+/// 2. A comment on the same line as the end of the preceding child,
+///    with only `,`, `;`, or `.` between them, trails that child.
+/// 3. Otherwise it leads the following child, so `%%` comments
+///    directly above a function or an attribute belong to that
+///    [`FunctionDecl`](crate::SyntaxKind::FunctionDecl) or
+///    [`Attribute`](crate::SyntaxKind::Attribute).
+/// 4. With no following child it trails the preceding one, and with no
+///    child at all it dangles in the enclosing node (`[ % empty ]`).
+///
+/// See [`SyntaxTree::comment_attachment`](crate::SyntaxTree::comment_attachment)
+/// and [`NodeView::leading_comments`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentPlacement {
+    /// Before the node: on the lines above it, or earlier on its first
+    /// line.
+    Leading,
+    /// After the node: at the end of its last line (past a `,`, `;`, or
+    /// `.`), or below it when nothing follows in the same parent.
+    Trailing,
+    /// Inside a node that has no child to attach it to, such as an
+    /// empty list.
+    Dangling,
+}
+
+/// Every comment's attachment in one tree.
+///
+/// Built in one pass by [`CommentTable::new`] and cached on the
+/// [`SyntaxIndex`], so per-node queries do not revisit the whole tree.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommentTable {
+    /// `(comment, owner, placement)`, in token order.
+    by_comment: Vec<(TokenIndex, NodeId, CommentPlacement)>,
+    /// The same attachments ordered by owner, placement, and token.
+    by_node: Vec<(NodeId, CommentPlacement, TokenIndex)>,
+}
+
+impl CommentTable {
+    /// Attaches every comment token of `tokens` to a node of `index`.
+    pub(crate) fn new(tokens: &TokenBuffer, index: &SyntaxIndex) -> Self {
+        let comments: Vec<usize> = tokens
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| token.kind() == TokenKind::Comment)
+            .map(|(i, _)| i)
+            .collect();
+        let mut by_comment = Vec::with_capacity(comments.len());
+        let roots = spans(tokens, root_views(tokens, index));
+        assign(tokens, None, &roots, &comments, &mut by_comment);
+        let mut by_node: Vec<_> = by_comment
+            .iter()
+            .map(|&(comment, node, placement)| (node, placement, comment))
+            .collect();
+        by_node.sort_unstable_by_key(|&(node, placement, comment)| {
+            (node.get(), placement as u8, comment)
+        });
+        Self {
+            by_comment,
+            by_node,
+        }
+    }
+}
+
+/// Returns the node `comment` is attached to and where it sits, or
+/// `None` when `comment` is not a comment token or the buffer has no
+/// node to attach it to.
+pub(crate) fn attach<'a>(
+    tokens: &'a TokenBuffer,
+    index: &'a SyntaxIndex,
+    comment: TokenIndex,
+) -> Option<(NodeView<'a>, CommentPlacement)> {
+    let table = index.comments(tokens);
+    let at = table
+        .by_comment
+        .binary_search_by_key(&comment, |&(comment, _, _)| comment)
+        .ok()?;
+    let (_, node, placement) = table.by_comment[at];
+    Some((NodeView::new(tokens, index, node)?, placement))
+}
+
+/// Returns the comments attached to `node` with `placement`.
+pub(crate) fn attached<'a>(
+    tokens: &'a TokenBuffer,
+    index: &'a SyntaxIndex,
+    node: NodeView<'a>,
+    placement: CommentPlacement,
+) -> impl Iterator<Item = (TokenIndex, Token)> + 'a {
+    let by_node = &index.comments(tokens).by_node;
+    let key = (node.node_id().get(), placement as u8);
+    let start =
+        by_node.partition_point(|&(node, placement, _)| (node.get(), placement as u8) < key);
+    by_node[start..]
+        .iter()
+        .take_while(move |&&(node, placement, _)| (node.get(), placement as u8) == key)
+        .filter_map(move |&(_, _, comment)| Some((comment, tokens.get(comment)?)))
+}
+
+/// Attaches `comments`, which lie inside `enclosing` (or anywhere, at
+/// the top level), to the children `siblings` or to `enclosing`,
+/// descending into the child that surrounds a comment.
+fn assign<'a>(
+    tokens: &'a TokenBuffer,
+    enclosing: Option<NodeView<'a>>,
+    siblings: &[(NodeView<'a>, (usize, usize))],
+    mut comments: &[usize],
+    out: &mut Vec<(TokenIndex, NodeId, CommentPlacement)>,
+) {
+    let mut preceding = None;
+    for &(node, (first, last)) in siblings {
+        let before = comments.partition_point(|&at| at < first);
+        for &at in &comments[..before] {
+            out.extend(place(tokens, at, preceding, Some(node), enclosing));
+        }
+        comments = &comments[before..];
+        let inside = comments.partition_point(|&at| at < last);
+        if inside > 0 {
+            let children = spans(tokens, node.children());
+            assign(tokens, Some(node), &children, &comments[..inside], out);
+        }
+        comments = &comments[inside..];
+        preceding = Some((node, last));
+    }
+    for &at in comments {
+        out.extend(place(tokens, at, preceding, None, enclosing));
+    }
+}
+
+/// Applies the rules on [`CommentPlacement`] to the comment at `at`,
+/// given the siblings around it and the node enclosing them.
+fn place(
+    tokens: &TokenBuffer,
+    at: usize,
+    preceding: Option<(NodeView<'_>, usize)>,
+    following: Option<NodeView<'_>>,
+    enclosing: Option<NodeView<'_>>,
+) -> Option<(TokenIndex, NodeId, CommentPlacement)> {
+    let slice = tokens.as_slice();
+    let (node, placement) = match (preceding, following, enclosing) {
+        (Some((node, last)), _, _)
+            if slice[last].end().line() == slice[at].start().line()
+                && slice[last + 1..at].iter().all(|t| !is_blocking(*t)) =>
+        {
+            (node, CommentPlacement::Trailing)
+        }
+        (_, Some(node), _) => (node, CommentPlacement::Leading),
+        (Some((node, _)), None, _) => (node, CommentPlacement::Trailing),
+        (None, None, Some(node)) => (node, CommentPlacement::Dangling),
+        (None, None, None) => return None,
+    };
+    Some((TokenIndex::new(at), node.node_id(), placement))
+}
+
+/// Pairs each of `nodes` with its [`lexical_span`], dropping nodes
+/// without lexical tokens.
+fn spans<'a>(
+    tokens: &TokenBuffer,
+    nodes: impl Iterator<Item = NodeView<'a>>,
+) -> Vec<(NodeView<'a>, (usize, usize))> {
+    nodes
+        .filter_map(|node| Some((node, lexical_span(tokens, node)?)))
+        .collect()
+}
+
+/// Returns the indexes of the first and last lexical tokens in
+/// `node`'s range.
+fn lexical_span(tokens: &TokenBuffer, node: NodeView<'_>) -> Option<(usize, usize)> {
+    let range = node.range().as_range();
+    let slice = tokens.as_slice();
+    let first = range.clone().find(|&i| slice[i].kind().is_lexical())?;
+    let last = range.rev().find(|&i| slice[i].kind().is_lexical())?;
+    Some((first, last))
+}
+
+/// Returns whether `token` is lexical and not a `,`, `;`, or `.`.
+fn is_blocking(token: Token) -> bool {
+    token.kind().is_lexical()
+        && !matches!(
+            token.kind(),
+            TokenKind::Symbol(Symbol::Comma | Symbol::Semicolon | Symbol::Dot)
+        )
+}
@@ -7,11 +7,24 @@
 //! [`SyntaxTree::diagnostics`](crate::SyntaxTree::diagnostics) being empty
 //! as success.
 //!
-//! Every diagnostic currently produced is a syntax error. Warnings and
-//! informational notes are not emitted yet. How the grammar continues
-//! after a diagnostic is recorded is in [`docs::diagnostics`](crate::docs::diagnostics).
+//! Each diagnostic carries a stable [`DiagnosticCode`] and a
+//! [`Severity`]; [`DiagnosticCode::all`] lists the registry. Where the
+//! grammar knows what would repair the input, the diagnostic also
+//! carries a [`Fix`]. The parser reports syntax errors,
+//! [`ModuleModel`](crate::model::ModuleModel) reports function clauses
+//! `erlc` would reject, and [`lint::variables`](crate::lint::variables)
+//! reports variable warnings. Informational notes are not emitted yet.
+//! How the grammar continues after a diagnostic is recorded is in
+//! [`docs::diagnostics`](crate::docs::diagnostics).
 
-use crate::token_range::TokenRange;
+use core::ops::Range;
+
+use crate::line_index::{LineColumn, LineIndex};
+use crate::token_range::{TokenIndex, TokenRange};
+
+pub use self::code::{DiagnosticCode, Severity};
+
+pub(crate) mod code;
 
 /// A syntax diagnostic surfaced by the parser.
 ///
@@ -27,13 +40,16 @@ pub struct Diagnostic {
     range: TokenRange,
     expected: Expected,
     found: Option<erl_tokenize::Token>,
+    fix: Option<Fix>,
+    related: Option<TokenRange>,
+    code: &'static DiagnosticCode,
 }
 
 impl Diagnostic {
     /// Builds a `Diagnostic` from its components.
     // `pub(crate)`: the parser records diagnostics. Callers read
     // `SyntaxTree::diagnostics`.
-    pub(crate) const fn new(
+    pub(crate) fn new(
         kind: DiagnosticKind,
         range: TokenRange,
         expected: Expected,
@@ -44,9 +60,46 @@ impl Diagnostic {
             range,
             expected,
             found,
+            fix: None,
+            related: None,
+            code: DiagnosticCode::for_kind(kind),
+        }
+    }
+
+    /// Builds a `Diagnostic` that expected the grammar category `code`,
+    /// one of the category constants of the registry. Its
+    /// [`Expected::Category`] is the category's wording.
+    pub(crate) fn for_category(
+        kind: DiagnosticKind,
+        range: TokenRange,
+        code: &'static DiagnosticCode,
+        found: Option<erl_tokenize::Token>,
+    ) -> Self {
+        let category = code.category().expect("a grammar category code");
+        Self {
+            code,
+            ..Self::new(kind, range, Expected::Category(category), found)
         }
     }
 
+    /// Attaches a suggested repair.
+    pub(crate) const fn with_fix(mut self, fix: Option<Fix>) -> Self {
+        self.fix = fix;
+        self
+    }
+
+    /// Replaces the registry entry, keeping everything else.
+    pub(crate) const fn with_code(mut self, code: &'static DiagnosticCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Attaches a second range the report refers to.
+    pub(crate) const fn with_related(mut self, related: TokenRange) -> Self {
+        self.related = Some(related);
+        self
+    }
+
     /// Returns the diagnostic's kind.
     pub const fn kind(self) -> DiagnosticKind {
         self.kind
@@ -69,6 +122,43 @@ impl Diagnostic {
     pub const fn found(self) -> Option<erl_tokenize::Token> {
         self.found
     }
+
+    /// Returns the suggested repair, if the grammar knows one. See
+    /// [`Fix`] for the situations that carry one.
+    pub const fn fix(self) -> Option<Fix> {
+        self.fix
+    }
+
+    /// Returns a second range the report refers to, such as the
+    /// `case` keyword of an [`UnsafeVariable`](DiagnosticKind::UnsafeVariable).
+    pub const fn related(self) -> Option<TokenRange> {
+        self.related
+    }
+
+    /// Returns the diagnostic's registry entry: the code of its
+    /// [`Expected::Category`] when it has one, otherwise the code of its
+    /// [`DiagnosticKind`].
+    pub fn code(self) -> &'static DiagnosticCode {
+        self.code
+    }
+
+    /// Returns the diagnostic's severity, as set by its
+    /// [`Diagnostic::code`].
+    pub fn severity(self) -> Severity {
+        self.code().severity()
+    }
+
+    /// Returns the byte span of [`Diagnostic::range`] in the source
+    /// `index` was built from. See [`LineIndex::byte_range`].
+    pub fn byte_range(self, index: &LineIndex) -> Range<usize> {
+        index.byte_range(self.range)
+    }
+
+    /// Returns the line and column where [`Diagnostic::range`] starts;
+    /// boundary reports at EOF land just after the last token.
+    pub fn line_column(self, index: &LineIndex) -> LineColumn {
+        index.line_column(index.offset(self.range.start()))
+    }
 }
 
 /// Category of a [`Diagnostic`].
@@ -100,6 +190,113 @@ pub enum DiagnosticKind {
     /// the stack), unwinds to a bounded depth, and continues
     /// recovery.
     NestingDepthExceeded,
+    /// A function is defined by more than one declaration. Reported by
+    /// [`ModuleModel`](crate::model::ModuleModel) on the name of the
+    /// later declaration's first clause.
+    DuplicateFunction,
+    /// A function clause takes a different number of arguments from
+    /// the first clause of its declaration. Reported by
+    /// [`ModuleModel`](crate::model::ModuleModel) on the clause name.
+    ClauseArityMismatch,
+    /// A function clause has a different name from the first clause of
+    /// its declaration. Reported by
+    /// [`ModuleModel`](crate::model::ModuleModel) on the clause name.
+    ClauseNameMismatch,
+    /// A variable is bound but never used. Reported by
+    /// [`lint::variables`](crate::lint::variables) on the first binding.
+    UnusedVariable,
+    /// A variable bound in only some clauses of a `case`, `if`, or
+    /// `receive`, or bound inside a `try`, `catch`, or `maybe`, is used
+    /// after it. Reported by [`lint::variables`](crate::lint::variables)
+    /// on the use; [`Diagnostic::related`] is the block's keyword.
+    UnsafeVariable,
+    /// A variable whose name starts with `_`, marking it as unused, is
+    /// used after it is bound. Reported by
+    /// [`lint::variables`](crate::lint::variables) on the first use.
+    UnderscoreVariableUsed,
+}
+
+/// A suggested edit that repairs the input a [`Diagnostic`] reports on.
+///
+/// The parser attaches one where the repair is unambiguous:
+///
+/// - a missing fixed token (`end`, `)`, `->`, ...) is inserted;
+/// - a single stray token swept up by recovery, or a stray closing
+///   delimiter, is deleted;
+/// - a `;` after the last clause is deleted before a block terminator
+///   or the closing `.`, and replaced by `.` before an attribute;
+/// - a `,` that ends a clause body followed by another clause head
+///   (`X > 0 -> a, X < 0 -> b`) is replaced by `;`.
+///
+/// Positions are token indexes into the tree's buffer;
+/// [`Fix::byte_edit`] turns a fix into a source edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fix {
+    /// Insert `text` before the token at `at`, or at the end of the
+    /// last token when `at` is the EOF boundary.
+    Insert {
+        /// Token the text goes before.
+        at: TokenIndex,
+        /// Text to insert.
+        text: &'static str,
+    },
+    /// Delete the tokens in the range.
+    Delete(TokenRange),
+    /// Replace the tokens in `range` with `text`.
+    Replace {
+        /// Tokens to replace.
+        range: TokenRange,
+        /// Replacement text.
+        text: &'static str,
+    },
+}
+
+impl Fix {
+    /// Returns the byte span of the source to replace and the text to
+    /// put there. `index` must have been built from the same tree and
+    /// `source`.
+    ///
+    /// An insertion is padded with a space on either side where it
+    /// would otherwise run into a neighbouring name, so inserting
+    /// `end` after `ok` yields `ok end` rather than `okend`.
+    pub fn byte_edit(self, index: &LineIndex, source: &str) -> (Range<usize>, String) {
+        match self {
+            Self::Insert { at, text } => {
+                let offset = index.offset(at).min(source.len());
+                let joins = |a: Option<char>, b: Option<char>| {
+                    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+                    word(a) && word(b)
+                };
+                let mut edit = String::new();
+                if joins(source[..offset].chars().next_back(), text.chars().next()) {
+                    edit.push(' ');
+                }
+                edit.push_str(text);
+                if joins(text.chars().next_back(), source[offset..].chars().next()) {
+                    edit.push(' ');
+                }
+                (offset..offset, edit)
+            }
+            Self::Delete(range) => (index.byte_range(range), String::new()),
+            Self::Replace { range, text } => (index.byte_range(range), text.to_string()),
+        }
+    }
+
+    /// Returns the fix with every token index passed through `shift`.
+    pub(crate) fn map_indexes(self, shift: impl Fn(TokenIndex) -> TokenIndex) -> Self {
+        let range = |r: TokenRange| TokenRange::new(shift(r.start()), shift(r.end()));
+        match self {
+            Self::Insert { at, text } => Self::Insert {
+                at: shift(at),
+                text,
+            },
+            Self::Delete(r) => Self::Delete(range(r)),
+            Self::Replace { range: r, text } => Self::Replace {
+                range: range(r),
+                text,
+            },
+        }
+    }
 }
 
 /// Appends `diagnostic` unless the immediately preceding element already
@@ -126,16 +323,15 @@ pub enum Expected {
     Unspecified,
     /// A specific token kind was expected.
     TokenKind(erl_tokenize::TokenKind),
-    /// A grammar-level category was expected. The identifier is chosen by
-    /// the grammar site.
+    /// A grammar-level category was expected, in words. Each category
+    /// has its own [`DiagnosticCode`], which [`Diagnostic::code`]
+    /// returns.
     Category(&'static str),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token_range::{TokenIndex, TokenRange};
-
     fn diag(kind: DiagnosticKind, range: TokenRange) -> Diagnostic {
         Diagnostic::new(kind, range, Expected::Unspecified, None)
     }
@@ -0,0 +1,996 @@
+//! The registry of stable diagnostic codes.
+//!
+//! Every [`DiagnosticKind`] and every [`Expected::Category`] string a
+//! grammar site can emit has one entry. Codes are never renumbered or
+//! reused: a category whose wording changes keeps its code, and a
+//! retired category leaves a gap. Kinds use `EP0001`–`EP0099`; grammar
+//! categories are grouped by area from `EP0100` on.
+//!
+//! Each category entry is also a constant, such as `TUPLE_CLOSE`, that
+//! grammar sites pass when they report it, so a diagnostic's code never
+//! depends on its wording.
+//!
+//! [`Expected::Category`]: super::Expected::Category
+
+use core::fmt;
+
+use super::DiagnosticKind;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The input is not valid Erlang.
+    Error,
+    /// The input is valid but likely wrong.
+    Warning,
+    /// Extra information attached to another report.
+    Note,
+}
+
+impl Severity {
+    /// Returns the lowercase label used in rendered reports: `error`,
+    /// `warning`, or `note`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One entry of the diagnostic registry.
+///
+/// Obtain entries from [`Diagnostic::code`](super::Diagnostic::code),
+/// [`DiagnosticCode::all`], or [`DiagnosticCode::lookup`]; they are not
+/// constructed by callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DiagnosticCode {
+    code: &'static str,
+    severity: Severity,
+    subject: Subject,
+    explanation: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Subject {
+    Kind(DiagnosticKind),
+    Category(&'static str),
+}
+
+impl DiagnosticCode {
+    /// Returns every registered code, in code order.
+    pub fn all() -> &'static [DiagnosticCode] {
+        REGISTRY
+    }
+
+    /// Returns the entry for a code such as `"EP0200"`.
+    pub fn lookup(code: &str) -> Option<&'static DiagnosticCode> {
+        REGISTRY.iter().find(|entry| entry.code == code)
+    }
+
+    /// Returns the entry for a [`DiagnosticKind`].
+    pub(crate) fn for_kind(kind: DiagnosticKind) -> &'static DiagnosticCode {
+        REGISTRY
+            .iter()
+            .find(|entry| entry.subject == Subject::Kind(kind))
+            .expect("every kind is registered")
+    }
+
+    /// Returns the code, for example `"EP0200"`.
+    pub const fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Returns the severity diagnostics with this code are reported at.
+    pub const fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns a one-sentence explanation of the problem.
+    pub const fn explanation(&self) -> &'static str {
+        self.explanation
+    }
+
+    /// Returns the kind this code stands for, or `None` for a
+    /// category code.
+    pub const fn kind(&self) -> Option<DiagnosticKind> {
+        match self.subject {
+            Subject::Kind(kind) => Some(kind),
+            Subject::Category(_) => None,
+        }
+    }
+
+    /// Returns the [`Expected::Category`](super::Expected::Category)
+    /// string this code stands for, or `None` for a kind code.
+    pub const fn category(&self) -> Option<&'static str> {
+        match self.subject {
+            Subject::Kind(_) => None,
+            Subject::Category(category) => Some(category),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+const fn kind(
+    code: &'static str,
+    kind: DiagnosticKind,
+    explanation: &'static str,
+) -> DiagnosticCode {
+    DiagnosticCode {
+        code,
+        severity: Severity::Error,
+        subject: Subject::Kind(kind),
+        explanation,
+    }
+}
+
+const fn warning(
+    code: &'static str,
+    kind: DiagnosticKind,
+    explanation: &'static str,
+) -> DiagnosticCode {
+    DiagnosticCode {
+        severity: Severity::Warning,
+        ..self::kind(code, kind, explanation)
+    }
+}
+
+const fn category(
+    code: &'static str,
+    category: &'static str,
+    explanation: &'static str,
+) -> DiagnosticCode {
+    DiagnosticCode {
+        code,
+        severity: Severity::Error,
+        subject: Subject::Category(category),
+        explanation,
+    }
+}
+
+// Top-level units and forms.
+pub(crate) const FORM_DOT: DiagnosticCode = category(
+    "EP0100",
+    "`.` to close top-level form",
+    "A form must end with `.`.",
+);
+pub(crate) const EXPRESSION_DOT: DiagnosticCode = category(
+    "EP0101",
+    "`.` to close top-level expression",
+    "A top-level expression must end with `.`.",
+);
+pub(crate) const TERM_DOT: DiagnosticCode = category(
+    "EP0102",
+    "`.` to close top-level term",
+    "Each term in a term list must end with `.`.",
+);
+pub(crate) const TYPE_DOT: DiagnosticCode = category(
+    "EP0103",
+    "`.` to close top-level type",
+    "A top-level type must end with `.`.",
+);
+pub(crate) const FORM_START: DiagnosticCode = category(
+    "EP0104",
+    "start of a module-level form",
+    "A module-level form must start with `-` or a function name.",
+);
+pub(crate) const FORM_OPENER: DiagnosticCode = category(
+    "EP0105",
+    "`-` to open an attribute or an atom to open a function",
+    "A form must be an attribute starting with `-` or a function starting with its name.",
+);
+pub(crate) const ATTRIBUTE_DASH: DiagnosticCode = category(
+    "EP0106",
+    "`-` to open attribute form",
+    "An attribute must start with `-`.",
+);
+pub(crate) const ATTRIBUTE_NAME: DiagnosticCode = category(
+    "EP0107",
+    "attribute name (atom) after `-`",
+    "The `-` of an attribute must be followed by the attribute's name.",
+);
+pub(crate) const ATTRIBUTE_PAYLOAD_CLOSE: DiagnosticCode = category(
+    "EP0108",
+    "`)` to close attribute payload",
+    "A parenthesized attribute payload must end with `)`.",
+);
+pub(crate) const ATTRIBUTE_PAYLOAD_END: DiagnosticCode = category(
+    "EP0109",
+    "end of attribute payload",
+    "The attribute payload has extra tokens after its value.",
+);
+pub(crate) const FUNCTION_CLAUSE_NAME: DiagnosticCode = category(
+    "EP0110",
+    "function name (atom) at the start of a function clause",
+    "A function clause must start with the function's name.",
+);
+// Attribute payloads.
+pub(crate) const SPEC_NAME: DiagnosticCode = category(
+    "EP0120",
+    "spec function name (atom)",
+    "A `-spec` or `-callback` must name the function it describes.",
+);
+pub(crate) const SPEC_REMOTE_NAME: DiagnosticCode = category(
+    "EP0121",
+    "spec function name after `:`",
+    "A module-qualified spec must name the function after `:`.",
+);
+pub(crate) const SPEC_PAYLOAD_CLOSE: DiagnosticCode = category(
+    "EP0122",
+    "`)` to close spec payload",
+    "A parenthesized spec must end with `)`.",
+);
+pub(crate) const TYPE_DECL_NAME: DiagnosticCode = category(
+    "EP0123",
+    "type name (atom)",
+    "A type declaration must start with the type's name.",
+);
+pub(crate) const TYPE_DECL_COLONS: DiagnosticCode = category(
+    "EP0124",
+    "`::` in type declaration",
+    "A type declaration needs `::` between its head and its definition.",
+);
+pub(crate) const TYPE_DECL_CLOSE: DiagnosticCode = category(
+    "EP0125",
+    "`)` to close type declaration",
+    "A parenthesized type declaration must end with `)`.",
+);
+pub(crate) const RECORD_DECL_OPEN: DiagnosticCode = category(
+    "EP0126",
+    "`(` to open record declaration",
+    "A `-record` attribute needs its payload in parentheses.",
+);
+pub(crate) const RECORD_DECL_NAME: DiagnosticCode = category(
+    "EP0127",
+    "record name (atom)",
+    "A record declaration must start with the record's name.",
+);
+pub(crate) const RECORD_DECL_COMMA: DiagnosticCode = category(
+    "EP0128",
+    "`,` after record name",
+    "A record declaration needs `,` between the name and the fields.",
+);
+pub(crate) const RECORD_DECL_FIELDS_OPEN: DiagnosticCode = category(
+    "EP0129",
+    "`{` to open record fields",
+    "Record declaration fields must be enclosed in `{...}`.",
+);
+pub(crate) const RECORD_DECL_FIELD_SEPARATOR: DiagnosticCode = category(
+    "EP0130",
+    "`,` or `}` in record declaration",
+    "Record declaration fields must be separated by `,` and closed by `}`.",
+);
+pub(crate) const RECORD_DECL_FIELDS_CLOSE: DiagnosticCode = category(
+    "EP0131",
+    "`}` to close record fields",
+    "Record declaration fields must end with `}`.",
+);
+pub(crate) const RECORD_DECL_CLOSE: DiagnosticCode = category(
+    "EP0132",
+    "`)` to close record declaration",
+    "A `-record` attribute must end with `)`.",
+);
+pub(crate) const RECORD_DECL_FIELD_NAME: DiagnosticCode = category(
+    "EP0133",
+    "record field name (atom)",
+    "A record declaration field must start with its name.",
+);
+pub(crate) const NAME_ARITY_LIST_OPEN: DiagnosticCode = category(
+    "EP0134",
+    "`[` to open `Name/Arity` list",
+    "This attribute takes a list of `Name/Arity` pairs in `[...]`.",
+);
+pub(crate) const NAME_ARITY_LIST_SEPARATOR: DiagnosticCode = category(
+    "EP0135",
+    "`,` or `]` in `Name/Arity` list",
+    "`Name/Arity` pairs must be separated by `,` and closed by `]`.",
+);
+pub(crate) const NAME_ARITY_LIST_CLOSE: DiagnosticCode = category(
+    "EP0136",
+    "`]` to close `Name/Arity` list",
+    "A `Name/Arity` list must end with `]`.",
+);
+pub(crate) const NAME_ARITY_NAME: DiagnosticCode = category(
+    "EP0137",
+    "function name (atom)",
+    "A `Name/Arity` pair must start with an atom.",
+);
+pub(crate) const NAME_ARITY_SLASH: DiagnosticCode = category(
+    "EP0138",
+    "`/` in `Name/Arity`",
+    "A `Name/Arity` pair needs `/` between name and arity.",
+);
+pub(crate) const NAME_ARITY_ARITY: DiagnosticCode = category(
+    "EP0139",
+    "arity (integer)",
+    "A `Name/Arity` pair must end with an integer arity.",
+);
+// Clauses.
+pub(crate) const CLAUSE_ARROW: DiagnosticCode = category(
+    "EP0150",
+    "`->` before clause body",
+    "A clause needs `->` between its head and its body.",
+);
+pub(crate) const CATCH_CLASS: DiagnosticCode = category(
+    "EP0151",
+    "class name in catch clause",
+    "A `Class:Reason` catch pattern must start with an atom or variable.",
+);
+pub(crate) const STACKTRACE_VARIABLE: DiagnosticCode = category(
+    "EP0152",
+    "stack-trace variable",
+    "A catch pattern's stack trace must be bound to a variable.",
+);
+pub(crate) const CLAUSE_SEPARATOR: DiagnosticCode = category(
+    "EP0153",
+    "`;` or block terminator",
+    "Clauses must be separated by `;` and the block closed by its keyword.",
+);
+pub(crate) const ARGUMENTS_OPEN: DiagnosticCode = category(
+    "EP0154",
+    "`(` to open argument list",
+    "A function clause needs its parameters in parentheses.",
+);
+pub(crate) const ARGUMENTS_CLOSE: DiagnosticCode = category(
+    "EP0155",
+    "`)` to close argument list",
+    "An argument list must end with `)`.",
+);
+pub(crate) const CLAUSE_AFTER_SEMICOLON: DiagnosticCode = category(
+    "EP0156",
+    "clause after `;`",
+    "A `;` separates clauses, so it cannot come after the last one.",
+);
+pub(crate) const FUNCTION_CLAUSE_AFTER_SEMICOLON: DiagnosticCode = category(
+    "EP0157",
+    "function clause after `;`",
+    "A function's last clause ends with `.`, not `;`.",
+);
+// Expressions.
+pub(crate) const EXPRESSION: DiagnosticCode =
+    category("EP0200", "expression", "An expression is required here.");
+pub(crate) const EXPRESSION_AFTER_COMMA: DiagnosticCode = category(
+    "EP0201",
+    "expression after `,`",
+    "A `,` must be followed by another expression.",
+);
+pub(crate) const SEPARATOR_OR_CLOSE: DiagnosticCode = category(
+    "EP0202",
+    "`,` or closing delimiter",
+    "Elements must be separated by `,` and the sequence closed by its delimiter.",
+);
+pub(crate) const NON_ASSOCIATIVE_OPERATOR: DiagnosticCode = category(
+    "EP0203",
+    "non-associative operator used twice",
+    "Comparison and other non-associative operators cannot be chained without parentheses.",
+);
+pub(crate) const PAREN_EXPR_CLOSE: DiagnosticCode = category(
+    "EP0204",
+    "`)` to close parenthesized expression",
+    "A parenthesized expression must end with `)`.",
+);
+pub(crate) const TUPLE_CLOSE: DiagnosticCode =
+    category("EP0205", "`}` to close tuple", "A tuple must end with `}`.");
+pub(crate) const LIST_CLOSE: DiagnosticCode =
+    category("EP0206", "`]` to close list", "A list must end with `]`.");
+pub(crate) const LIST_COMPREHENSION_CLOSE: DiagnosticCode = category(
+    "EP0207",
+    "`]` to close list comprehension",
+    "A list comprehension must end with `]`.",
+);
+pub(crate) const BEGIN_END: DiagnosticCode = category(
+    "EP0208",
+    "`end` to close `begin`",
+    "A `begin` block must end with `end`.",
+);
+pub(crate) const CASE_OF: DiagnosticCode = category(
+    "EP0209",
+    "`of` in `case` expression",
+    "A `case` expression needs `of` before its clauses.",
+);
+pub(crate) const CASE_END: DiagnosticCode = category(
+    "EP0210",
+    "`end` to close `case`",
+    "A `case` expression must end with `end`.",
+);
+pub(crate) const IF_END: DiagnosticCode = category(
+    "EP0211",
+    "`end` to close `if`",
+    "An `if` expression must end with `end`.",
+);
+pub(crate) const RECEIVE_END: DiagnosticCode = category(
+    "EP0212",
+    "`end` to close `receive`",
+    "A `receive` expression must end with `end`.",
+);
+pub(crate) const TRY_END: DiagnosticCode = category(
+    "EP0213",
+    "`end` to close `try`",
+    "A `try` expression must end with `end`.",
+);
+pub(crate) const MAYBE_END: DiagnosticCode = category(
+    "EP0214",
+    "`end` to close `maybe`",
+    "A `maybe` block must end with `end`.",
+);
+pub(crate) const FUN_END: DiagnosticCode = category(
+    "EP0215",
+    "`end` to close `fun`",
+    "A `fun` expression must end with `end`.",
+);
+pub(crate) const NAMED_FUN_END: DiagnosticCode = category(
+    "EP0216",
+    "`end` to close named `fun`",
+    "A named `fun` expression must end with `end`.",
+);
+pub(crate) const FUN_BODY: DiagnosticCode = category(
+    "EP0217",
+    "`(`, fun reference, or named fun after `fun`",
+    "`fun` must be followed by clauses, a `Name/Arity` reference, or a named fun.",
+);
+pub(crate) const FUN_REF_NAME: DiagnosticCode = category(
+    "EP0218",
+    "fun name (atom or variable)",
+    "A fun reference must start with a function name.",
+);
+pub(crate) const FUN_REF_SLASH: DiagnosticCode = category(
+    "EP0219",
+    "`/` in fun reference",
+    "A fun reference needs `/` between name and arity.",
+);
+pub(crate) const FUN_REF_ARITY: DiagnosticCode = category(
+    "EP0220",
+    "arity (integer or variable)",
+    "A fun reference must end with an arity.",
+);
+pub(crate) const REMOTE_FUN_REF_MODULE: DiagnosticCode = category(
+    "EP0221",
+    "module name (atom or variable)",
+    "A remote fun reference must start with a module name.",
+);
+pub(crate) const REMOTE_FUN_REF_COLON: DiagnosticCode = category(
+    "EP0222",
+    "`:` in remote fun reference",
+    "A remote fun reference needs `:` between module and function.",
+);
+pub(crate) const REMOTE_FUN_REF_NAME: DiagnosticCode = category(
+    "EP0223",
+    "function name (atom or variable)",
+    "A remote fun reference must name the function after `:`.",
+);
+pub(crate) const REMOTE_FUN_REF_SLASH: DiagnosticCode = category(
+    "EP0224",
+    "`/` in remote fun reference",
+    "A remote fun reference needs `/` between function and arity.",
+);
+pub(crate) const NAMED_FUN_NAME: DiagnosticCode = category(
+    "EP0225",
+    "named-fun name (variable or atom)",
+    "A named fun must be named by a variable.",
+);
+pub(crate) const MAP_GENERATOR_ARROW: DiagnosticCode = category(
+    "EP0226",
+    "`<-` or `<:-` after `Key := Value`",
+    "A map generator needs `<-` or `<:-` after its `Key := Value` pattern.",
+);
+// Records, maps, and bitstrings.
+pub(crate) const RECORD_NAME: DiagnosticCode = category(
+    "EP0240",
+    "record name",
+    "`#` must be followed by a record name.",
+);
+pub(crate) const RECORD_FIELD_NAME: DiagnosticCode = category(
+    "EP0241",
+    "record field name",
+    "A record field must be named by an atom.",
+);
+pub(crate) const RECORD_BODY: DiagnosticCode = category(
+    "EP0242",
+    "`{` or `.` after record name",
+    "A record name must be followed by `{` to build or update, or `.` to access a field.",
+);
+pub(crate) const WILDCARD_RECORD_OPEN: DiagnosticCode = category(
+    "EP0243",
+    "`{` after `#_`",
+    "An anonymous native record `#_` must be followed by `{`.",
+);
+pub(crate) const RECORD_SUFFIX_BODY: DiagnosticCode = category(
+    "EP0244",
+    "`{` or `.` after `#`-suffix record name",
+    "A record name after an expression must be followed by `{` or `.`.",
+);
+pub(crate) const WILDCARD_RECORD_BODY: DiagnosticCode = category(
+    "EP0245",
+    "`{` or `.` after `#_`",
+    "`#_` after an expression must be followed by `{` or `.`.",
+);
+pub(crate) const RECORD_CLOSE: DiagnosticCode = category(
+    "EP0246",
+    "`}` to close record",
+    "A record must end with `}`.",
+);
+pub(crate) const RECORD_FIELD_EQUALS: DiagnosticCode = category(
+    "EP0247",
+    "`=` in record field",
+    "A record field needs `=` between its name and value.",
+);
+pub(crate) const MAP_CLOSE: DiagnosticCode =
+    category("EP0248", "`}` to close map", "A map must end with `}`.");
+pub(crate) const MAP_COMPREHENSION_CLOSE: DiagnosticCode = category(
+    "EP0249",
+    "`}` to close map comprehension",
+    "A map comprehension must end with `}`.",
+);
+pub(crate) const MAP_FIELD_ARROW: DiagnosticCode = category(
+    "EP0250",
+    "`=>` or `:=` in map field",
+    "A map field needs `=>` or `:=` between key and value.",
+);
+pub(crate) const BITSTRING_CLOSE: DiagnosticCode = category(
+    "EP0251",
+    "`>>` to close bitstring",
+    "A bitstring must end with `>>`.",
+);
+pub(crate) const BINARY_COMPREHENSION_CLOSE: DiagnosticCode = category(
+    "EP0252",
+    "`>>` to close binary comprehension",
+    "A binary comprehension must end with `>>`.",
+);
+pub(crate) const BIT_TYPE_NAME: DiagnosticCode = category(
+    "EP0253",
+    "bit type name",
+    "A bitstring type specifier must be a name such as `binary` or `unit`.",
+);
+pub(crate) const BIT_TYPE_UNIT: DiagnosticCode = category(
+    "EP0254",
+    "bit type unit size",
+    "`unit:` must be followed by an integer.",
+);
+// Constructs that patterns, guards, and terms reject.
+pub(crate) const MATCH_IN_TERM: DiagnosticCode = category(
+    "EP0270",
+    "match `=` not allowed in term position",
+    "Terms cannot contain `=` matches.",
+);
+pub(crate) const VARIABLE_IN_TERM: DiagnosticCode = category(
+    "EP0271",
+    "variable not allowed in term position",
+    "Terms cannot contain variables.",
+);
+pub(crate) const SEND_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0272",
+    "send / maybe-match not allowed here",
+    "`!` and `?=` are only allowed in expressions.",
+);
+pub(crate) const CALL_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0273",
+    "call not allowed here",
+    "Function calls are not allowed in patterns or terms.",
+);
+pub(crate) const REMOTE_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0274",
+    "remote qualifier `:` not allowed here",
+    "`Module:Function` is not allowed in patterns or terms.",
+);
+pub(crate) const CATCH_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0275",
+    "`catch` prefix not allowed here",
+    "`catch` is only allowed in expressions.",
+);
+pub(crate) const LIST_COMPREHENSION_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0276",
+    "list comprehension not allowed here",
+    "List comprehensions are only allowed in expressions.",
+);
+pub(crate) const MAP_COMPREHENSION_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0277",
+    "map comprehension not allowed here",
+    "Map comprehensions are only allowed in expressions.",
+);
+pub(crate) const BINARY_COMPREHENSION_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0278",
+    "binary comprehension not allowed here",
+    "Binary comprehensions are only allowed in expressions.",
+);
+pub(crate) const BEGIN_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0279",
+    "`begin` block not allowed here",
+    "`begin` blocks are only allowed in expressions.",
+);
+pub(crate) const CASE_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0280",
+    "`case` block not allowed here",
+    "`case` expressions are only allowed in expressions.",
+);
+pub(crate) const IF_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0281",
+    "`if` block not allowed here",
+    "`if` expressions are only allowed in expressions.",
+);
+pub(crate) const RECEIVE_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0282",
+    "`receive` block not allowed here",
+    "`receive` expressions are only allowed in expressions.",
+);
+pub(crate) const TRY_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0283",
+    "`try` block not allowed here",
+    "`try` expressions are only allowed in expressions.",
+);
+pub(crate) const MAYBE_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0284",
+    "`maybe` block not allowed here",
+    "`maybe` blocks are only allowed in expressions.",
+);
+pub(crate) const FUN_NOT_ALLOWED: DiagnosticCode = category(
+    "EP0285",
+    "`fun` expression / reference not allowed here",
+    "`fun` expressions and references are only allowed in expressions.",
+);
+// Types.
+pub(crate) const TYPE: DiagnosticCode =
+    category("EP0300", "type expression", "A type is required here.");
+pub(crate) const NON_ASSOCIATIVE_RANGE: DiagnosticCode = category(
+    "EP0301",
+    "non-associative range operator used twice",
+    "`..` ranges cannot be chained.",
+);
+pub(crate) const PAREN_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0302",
+    "`)` to close parenthesized type",
+    "A parenthesized type must end with `)`.",
+);
+pub(crate) const TUPLE_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0303",
+    "`}` to close tuple type",
+    "A tuple type must end with `}`.",
+);
+pub(crate) const NON_EMPTY_LIST_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0304",
+    "`]` to close non-empty list type",
+    "A non-empty list type `[T, ...]` must end with `]`.",
+);
+pub(crate) const LIST_TYPE_ELLIPSIS: DiagnosticCode = category(
+    "EP0305",
+    "`...]` after list type comma",
+    "A `,` in a list type must be followed by `...]`.",
+);
+pub(crate) const LIST_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0306",
+    "`]` to close list type",
+    "A list type must end with `]`.",
+);
+pub(crate) const RECORD_TYPE_NAME: DiagnosticCode = category(
+    "EP0307",
+    "record type name",
+    "A record type must be named by an atom after `#`.",
+);
+pub(crate) const RECORD_TYPE_OPEN: DiagnosticCode = category(
+    "EP0308",
+    "`{` after record type name",
+    "A record type name must be followed by `{`.",
+);
+pub(crate) const RECORD_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0309",
+    "`}` to close record type",
+    "A record type must end with `}`.",
+);
+pub(crate) const MAP_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0310",
+    "`}` to close map type",
+    "A map type must end with `}`.",
+);
+pub(crate) const MAP_TYPE_FIELD_ARROW: DiagnosticCode = category(
+    "EP0311",
+    "`=>` or `:=` in map type field",
+    "A map type field needs `=>` or `:=` between key and value types.",
+);
+pub(crate) const RECORD_TYPE_FIELD_NAME: DiagnosticCode = category(
+    "EP0312",
+    "record type field name",
+    "A record type field must be named by an atom.",
+);
+pub(crate) const RECORD_TYPE_FIELD_COLONS: DiagnosticCode = category(
+    "EP0313",
+    "`::` in record type field",
+    "A record type field needs `::` between its name and type.",
+);
+pub(crate) const BITSTRING_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0314",
+    "`>>` to close bitstring type",
+    "A bitstring type must end with `>>`.",
+);
+pub(crate) const FUN_TYPE_OPEN: DiagnosticCode = category(
+    "EP0315",
+    "`(` after `fun` in function type",
+    "A `fun` type must be followed by `(`.",
+);
+pub(crate) const FUN_TYPE_CLOSE: DiagnosticCode = category(
+    "EP0316",
+    "`)` to close `fun` type",
+    "A `fun(...)` type must end with `)`.",
+);
+pub(crate) const FUNCTION_TYPE_PARAMETERS_OPEN: DiagnosticCode = category(
+    "EP0317",
+    "`(` to open function type parameters",
+    "Function type parameters must be enclosed in `(...)`.",
+);
+pub(crate) const FUNCTION_TYPE_PARAMETERS_CLOSE: DiagnosticCode = category(
+    "EP0318",
+    "`)` to close function type parameters",
+    "Function type parameters must end with `)`.",
+);
+pub(crate) const FUNCTION_TYPE_ARROW: DiagnosticCode = category(
+    "EP0319",
+    "`->` in function type",
+    "A function type needs `->` before its return type.",
+);
+pub(crate) const TYPE_ARGUMENTS_OPEN: DiagnosticCode = category(
+    "EP0320",
+    "`(` to open type argument list",
+    "Type arguments must be enclosed in `(...)`.",
+);
+pub(crate) const TYPE_ARGUMENTS_CLOSE: DiagnosticCode = category(
+    "EP0321",
+    "`)` to close type argument list",
+    "Type arguments must end with `)`.",
+);
+pub(crate) const TYPE_LIST_SEPARATOR_OR_CLOSE: DiagnosticCode = category(
+    "EP0322",
+    "`,` or closing delimiter in type list",
+    "Types in a list must be separated by `,` and closed by their delimiter.",
+);
+pub(crate) const TYPE_GUARD_WHEN: DiagnosticCode = category(
+    "EP0323",
+    "`when` at start of type guard",
+    "Spec constraints must start with `when`.",
+);
+pub(crate) const CONSTRAINT_START: DiagnosticCode = category(
+    "EP0324",
+    "constraint variable or class",
+    "A spec constraint must start with a variable or a constraint name.",
+);
+pub(crate) const CONSTRAINT_BODY: DiagnosticCode = category(
+    "EP0325",
+    "`::` or `(...)` in type constraint",
+    "A spec constraint must be `Var :: Type` or `is_subtype(Var, Type)`.",
+);
+
+static REGISTRY: &[DiagnosticCode] = &[
+    // Kinds, for diagnostics without a grammar category.
+    kind(
+        "EP0001",
+        DiagnosticKind::UnexpectedToken,
+        "A token appeared where the grammar does not allow it.",
+    ),
+    kind(
+        "EP0002",
+        DiagnosticKind::UnexpectedEof,
+        "The input ended in the middle of a construct.",
+    ),
+    kind(
+        "EP0003",
+        DiagnosticKind::SkippedToken,
+        "Error recovery skipped tokens it could not place in the tree.",
+    ),
+    kind(
+        "EP0004",
+        DiagnosticKind::MissingToken,
+        "A required token is missing.",
+    ),
+    kind(
+        "EP0005",
+        DiagnosticKind::NestingDepthExceeded,
+        "Constructs are nested deeper than the parser's limit.",
+    ),
+    kind(
+        "EP0006",
+        DiagnosticKind::DuplicateFunction,
+        "A function with the same name and arity is already defined.",
+    ),
+    kind(
+        "EP0007",
+        DiagnosticKind::ClauseArityMismatch,
+        "All clauses of a function must take the same number of arguments.",
+    ),
+    kind(
+        "EP0008",
+        DiagnosticKind::ClauseNameMismatch,
+        "All clauses of a function must have the same name.",
+    ),
+    warning(
+        "EP0009",
+        DiagnosticKind::UnusedVariable,
+        "A variable is bound but never used; prefix its name with `_` if that is intended.",
+    ),
+    kind(
+        "EP0010",
+        DiagnosticKind::UnsafeVariable,
+        "A variable bound in only some clauses of a block, or inside `try`, `catch`, or \
+         `maybe`, is used after the block.",
+    ),
+    warning(
+        "EP0011",
+        DiagnosticKind::UnderscoreVariableUsed,
+        "A variable whose name starts with `_` is used after it is bound.",
+    ),
+    // Top-level units and forms.
+    FORM_DOT,
+    EXPRESSION_DOT,
+    TERM_DOT,
+    TYPE_DOT,
+    FORM_START,
+    FORM_OPENER,
+    ATTRIBUTE_DASH,
+    ATTRIBUTE_NAME,
+    ATTRIBUTE_PAYLOAD_CLOSE,
+    ATTRIBUTE_PAYLOAD_END,
+    FUNCTION_CLAUSE_NAME,
+    // Attribute payloads.
+    SPEC_NAME,
+    SPEC_REMOTE_NAME,
+    SPEC_PAYLOAD_CLOSE,
+    TYPE_DECL_NAME,
+    TYPE_DECL_COLONS,
+    TYPE_DECL_CLOSE,
+    RECORD_DECL_OPEN,
+    RECORD_DECL_NAME,
+    RECORD_DECL_COMMA,
+    RECORD_DECL_FIELDS_OPEN,
+    RECORD_DECL_FIELD_SEPARATOR,
+    RECORD_DECL_FIELDS_CLOSE,
+    RECORD_DECL_CLOSE,
+    RECORD_DECL_FIELD_NAME,
+    NAME_ARITY_LIST_OPEN,
+    NAME_ARITY_LIST_SEPARATOR,
+    NAME_ARITY_LIST_CLOSE,
+    NAME_ARITY_NAME,
+    NAME_ARITY_SLASH,
+    NAME_ARITY_ARITY,
+    // Clauses.
+    CLAUSE_ARROW,
+    CATCH_CLASS,
+    STACKTRACE_VARIABLE,
+    CLAUSE_SEPARATOR,
+    ARGUMENTS_OPEN,
+    ARGUMENTS_CLOSE,
+    CLAUSE_AFTER_SEMICOLON,
+    FUNCTION_CLAUSE_AFTER_SEMICOLON,
+    // Expressions.
+    EXPRESSION,
+    EXPRESSION_AFTER_COMMA,
+    SEPARATOR_OR_CLOSE,
+    NON_ASSOCIATIVE_OPERATOR,
+    PAREN_EXPR_CLOSE,
+    TUPLE_CLOSE,
+    LIST_CLOSE,
+    LIST_COMPREHENSION_CLOSE,
+    BEGIN_END,
+    CASE_OF,
+    CASE_END,
+    IF_END,
+    RECEIVE_END,
+    TRY_END,
+    MAYBE_END,
+    FUN_END,
+    NAMED_FUN_END,
+    FUN_BODY,
+    FUN_REF_NAME,
+    FUN_REF_SLASH,
+    FUN_REF_ARITY,
+    REMOTE_FUN_REF_MODULE,
+    REMOTE_FUN_REF_COLON,
+    REMOTE_FUN_REF_NAME,
+    REMOTE_FUN_REF_SLASH,
+    NAMED_FUN_NAME,
+    MAP_GENERATOR_ARROW,
+    // Records, maps, and bitstrings.
+    RECORD_NAME,
+    RECORD_FIELD_NAME,
+    RECORD_BODY,
+    WILDCARD_RECORD_OPEN,
+    RECORD_SUFFIX_BODY,
+    WILDCARD_RECORD_BODY,
+    RECORD_CLOSE,
+    RECORD_FIELD_EQUALS,
+    MAP_CLOSE,
+    MAP_COMPREHENSION_CLOSE,
+    MAP_FIELD_ARROW,
+    BITSTRING_CLOSE,
+    BINARY_COMPREHENSION_CLOSE,
+    BIT_TYPE_NAME,
+    BIT_TYPE_UNIT,
+    // Constructs that patterns, guards, and terms reject.
+    MATCH_IN_TERM,
+    VARIABLE_IN_TERM,
+    SEND_NOT_ALLOWED,
+    CALL_NOT_ALLOWED,
+    REMOTE_NOT_ALLOWED,
+    CATCH_NOT_ALLOWED,
+    LIST_COMPREHENSION_NOT_ALLOWED,
+    MAP_COMPREHENSION_NOT_ALLOWED,
+    BINARY_COMPREHENSION_NOT_ALLOWED,
+    BEGIN_NOT_ALLOWED,
+    CASE_NOT_ALLOWED,
+    IF_NOT_ALLOWED,
+    RECEIVE_NOT_ALLOWED,
+    TRY_NOT_ALLOWED,
+    MAYBE_NOT_ALLOWED,
+    FUN_NOT_ALLOWED,
+    // Types.
+    TYPE,
+    NON_ASSOCIATIVE_RANGE,
+    PAREN_TYPE_CLOSE,
+    TUPLE_TYPE_CLOSE,
+    NON_EMPTY_LIST_TYPE_CLOSE,
+    LIST_TYPE_ELLIPSIS,
+    LIST_TYPE_CLOSE,
+    RECORD_TYPE_NAME,
+    RECORD_TYPE_OPEN,
+    RECORD_TYPE_CLOSE,
+    MAP_TYPE_CLOSE,
+    MAP_TYPE_FIELD_ARROW,
+    RECORD_TYPE_FIELD_NAME,
+    RECORD_TYPE_FIELD_COLONS,
+    BITSTRING_TYPE_CLOSE,
+    FUN_TYPE_OPEN,
+    FUN_TYPE_CLOSE,
+    FUNCTION_TYPE_PARAMETERS_OPEN,
+    FUNCTION_TYPE_PARAMETERS_CLOSE,
+    FUNCTION_TYPE_ARROW,
+    TYPE_ARGUMENTS_OPEN,
+    TYPE_ARGUMENTS_CLOSE,
+    TYPE_LIST_SEPARATOR_OR_CLOSE,
+    TYPE_GUARD_WHEN,
+    CONSTRAINT_START,
+    CONSTRAINT_BODY,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_ordered() {
+        for pair in REGISTRY.windows(2) {
+            assert!(pair[0].code < pair[1].code, "{} >= {}", pair[0], pair[1]);
+        }
+        for (i, entry) in REGISTRY.iter().enumerate() {
+            assert!(
+                REGISTRY[i + 1..].iter().all(|e| e.subject != entry.subject),
+                "{entry} registered twice"
+            );
+        }
+    }
+
+    #[test]
+    fn every_kind_has_a_code() {
+        for kind in [
+            DiagnosticKind::UnexpectedToken,
+            DiagnosticKind::UnexpectedEof,
+            DiagnosticKind::SkippedToken,
+            DiagnosticKind::MissingToken,
+            DiagnosticKind::NestingDepthExceeded,
+            DiagnosticKind::DuplicateFunction,
+            DiagnosticKind::ClauseArityMismatch,
+            DiagnosticKind::ClauseNameMismatch,
+            DiagnosticKind::UnusedVariable,
+            DiagnosticKind::UnsafeVariable,
+            DiagnosticKind::UnderscoreVariableUsed,
+        ] {
+            assert_eq!(DiagnosticCode::for_kind(kind).kind(), Some(kind));
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! `-doc` and `-moduledoc` attributes, collected as an EEP 48 docs
+//! chunk.
+//!
+//! Since OTP 27, documentation is written in attributes:
+//!
+//! ```erlang
+//! -moduledoc "A counter.".
+//! -doc #{since => ~"1.0"}.
+//! -doc """
+//!     Returns a new counter.
+//!     """.
+//! new() -> 0.
+//! ```
+//!
+//! [`DocsChunk::new`] walks the Module-mode roots in order. A `-doc`
+//! applies to the next function, `-type` / `-opaque` / `-nominal`, or
+//! `-callback` form; several `-doc` attributes before one form combine,
+//! a string (plain, triple-quoted, or a `~"` sigil) setting the text
+//! and a map adding metadata. `-doc false` hides the form and
+//! `-doc {file, Path}` is recorded as [`Doc::File`], since this crate
+//! does not read files. `-moduledoc` works the same way for the module.
+//!
+//! The chunk lists every callback, and every function and type that
+//! is exported (by `-export`, `-export_type`, or
+//! `-compile(export_all)`) or has a `-doc`. [`DocsChunk::to_term`]
+//! builds the `docs_v1` tuple `code:get_doc/1` returns.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::docs_chunk::{Doc, DocsChunk, EntryKind};
+//!
+//! let source = "-module(m).\n-export([f/1]).\n-doc \"Doubles X.\".\nf(X) -> X * 2.\n";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let chunk = DocsChunk::new(&tree, source);
+//! let entry = &chunk.entries()[0];
+//! assert_eq!((entry.kind(), entry.name(), entry.arity()), (EntryKind::Function, "f", 1));
+//! assert_eq!(entry.signature(), "f(X)");
+//! assert_eq!(entry.doc(), &Doc::Text("Doubles X.".to_string()));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::abstract_format::Term;
+use crate::ast::{self, AstNode};
+use crate::model::{
+    NameArity, atom, attribute_name, attribute_value, declaration, exports_all, name_arities,
+};
+use crate::node::NodeView;
+use crate::syntax::NodeId;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// The documentation of a module or an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Doc {
+    /// No `-doc` or `-moduledoc` text.
+    None,
+    /// `-doc false`.
+    Hidden,
+    /// The text of a string payload.
+    Text(String),
+    /// `-doc {file, Path}`: the text is in the file at `Path`.
+    File(String),
+}
+
+/// The kind of a documented form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// A function declaration.
+    Function,
+    /// A `-type`, `-opaque`, or `-nominal` declaration.
+    Type,
+    /// A `-callback` declaration.
+    Callback,
+}
+
+impl EntryKind {
+    /// Returns the atom EEP 48 uses for the kind.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Type => "type",
+            Self::Callback => "callback",
+        }
+    }
+}
+
+/// One function, type, or callback in a [`DocsChunk`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    kind: EntryKind,
+    name: String,
+    arity: usize,
+    node: NodeId,
+    line: usize,
+    signature: String,
+    doc: Doc,
+    metadata: Vec<(Term, Term)>,
+}
+
+impl Entry {
+    /// Returns the kind of form.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Returns the function, type, or callback name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of arguments or type parameters.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Returns the root node of the form: a
+    /// [`FunctionDecl`](crate::SyntaxKind::FunctionDecl) or an
+    /// [`Attribute`](crate::SyntaxKind::Attribute).
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+
+    /// Returns the line of the name.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the head as written, such as `f(X)` or `t(A)`: the name
+    /// and the parameters of the first clause.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Returns the documentation.
+    pub fn doc(&self) -> &Doc {
+        &self.doc
+    }
+
+    /// Returns the metadata from `-doc` maps, in the order the keys
+    /// were first set.
+    pub fn metadata(&self) -> &[(Term, Term)] {
+        &self.metadata
+    }
+}
+
+/// The documentation of a module, in the shape of an EEP 48 docs
+/// chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsChunk {
+    line: usize,
+    doc: Doc,
+    metadata: Vec<(Term, Term)>,
+    entries: Vec<Entry>,
+}
+
+impl DocsChunk {
+    /// Collects the documentation of the Module-mode `tree`. `source` is
+    /// the text the tokens were scanned from.
+    ///
+    /// Roots that error recovery left a hole in are skipped, and so
+    /// are `-doc` attributes whose payload is not a literal.
+    pub fn new(tree: &SyntaxTree, source: &str) -> Self {
+        let mut chunk = Self {
+            line: 1,
+            doc: Doc::None,
+            metadata: Vec::new(),
+            entries: Vec::new(),
+        };
+        let mut exports = Vec::new();
+        let mut export_all = false;
+        let mut pending = (Doc::None, Vec::new());
+        for root in tree.roots() {
+            let entry = if let Some(decl) = ast::FunctionDecl::cast(root) {
+                function(tree, source, decl)
+            } else if let Some(attribute) = ast::Attribute::cast(root) {
+                let Some((name, line)) = attribute_name(tree, source, attribute) else {
+                    continue;
+                };
+                let value = || attribute_value(tree, source, root);
+                match name.as_str() {
+                    "module" => chunk.line = line,
+                    "moduledoc" => {
+                        if let Some(value) = value() {
+                            apply(&value, &mut chunk.doc, &mut chunk.metadata);
+                        }
+                    }
+                    "doc" => {
+                        if let Some(value) = value() {
+                            apply(&value, &mut pending.0, &mut pending.1);
+                        }
+                    }
+                    "export" | "export_type" => {
+                        let kind = match name.as_str() {
+                            "export" => EntryKind::Function,
+                            _ => EntryKind::Type,
+                        };
+                        let list = value();
+                        exports.extend(list.iter().flat_map(name_arities).map(|key| (kind, key)));
+                    }
+                    "compile" => export_all |= value().is_some_and(|options| exports_all(&options)),
+                    "type" | "opaque" | "nominal" | "callback" => {}
+                    _ => continue,
+                }
+                let kind = match name.as_str() {
+                    "callback" => EntryKind::Callback,
+                    _ => EntryKind::Type,
+                };
+                declaration(tree, source, root)
+                    .map(|(key, at, params)| entry(tree, source, kind, root, key, at, params))
+            } else {
+                None
+            };
+            if let Some(mut entry) = entry {
+                (entry.doc, entry.metadata) =
+                    std::mem::replace(&mut pending, (Doc::None, Vec::new()));
+                chunk.entries.push(entry);
+            }
+        }
+        chunk.entries.retain(|entry| {
+            let exported = || {
+                exports.iter().any(|(kind, key)| {
+                    *kind == entry.kind && key.name() == entry.name && key.arity() == entry.arity
+                })
+            };
+            entry.kind == EntryKind::Callback
+                || entry.doc != Doc::None
+                || !entry.metadata.is_empty()
+                || (export_all && entry.kind == EntryKind::Function)
+                || exported()
+        });
+        chunk
+    }
+
+    /// Returns the line of the `-module` attribute, or 1 without one.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the `-moduledoc` documentation.
+    pub fn doc(&self) -> &Doc {
+        &self.doc
+    }
+
+    /// Returns the metadata from `-moduledoc` maps.
+    pub fn metadata(&self) -> &[(Term, Term)] {
+        &self.metadata
+    }
+
+    /// Returns the documented forms in source order.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Builds the EEP 48 term
+    /// `{docs_v1, Anno, erlang, <<"text/markdown">>, ModuleDoc, Metadata, Docs}`,
+    /// with each entry as
+    /// `{{Kind, Name, Arity}, Anno, [Signature], Doc, Metadata}`.
+    ///
+    /// A doc is `none`, `hidden`, or `#{<<"en">> => Text}`. The chunk
+    /// does not read files, so a [`Doc::File`] is written as `none`.
+    pub fn to_term(&self) -> Term {
+        let entries = self.entries.iter().map(|entry| {
+            Term::tuple([
+                Term::tuple([
+                    Term::atom(entry.kind.as_str()),
+                    Term::atom(&entry.name),
+                    Term::from(entry.arity as i64),
+                ]),
+                Term::from(entry.line as i64),
+                Term::list([Term::Binary(entry.signature.as_bytes().to_vec())]),
+                doc_term(&entry.doc),
+                Term::Map(entry.metadata.clone()),
+            ])
+        });
+        Term::tuple([
+            Term::atom("docs_v1"),
+            Term::from(self.line as i64),
+            Term::atom("erlang"),
+            Term::Binary(b"text/markdown".to_vec()),
+            doc_term(&self.doc),
+            Term::Map(self.metadata.clone()),
+            Term::list(entries),
+        ])
+    }
+}
+
+fn doc_term(doc: &Doc) -> Term {
+    match doc {
+        Doc::None | Doc::File(_) => Term::atom("none"),
+        Doc::Hidden => Term::atom("hidden"),
+        Doc::Text(text) => Term::Map(vec![(
+            Term::Binary(b"en".to_vec()),
+            Term::Binary(text.as_bytes().to_vec()),
+        )]),
+    }
+}
+
+/// Folds one `-doc` / `-moduledoc` value into `doc` and `metadata`.
+fn apply(value: &Term, doc: &mut Doc, metadata: &mut Vec<(Term, Term)>) {
+    if let Some(text) = text(value) {
+        *doc = Doc::Text(text);
+        return;
+    }
+    match value {
+        Term::Atom(atom) if atom == "false" => *doc = Doc::Hidden,
+        Term::Tuple(pair) => {
+            if let [tag, path] = pair.as_slice()
+                && tag.as_atom() == Some("file")
+                && let Some(path) = text(path)
+            {
+                *doc = Doc::File(path);
+            }
+        }
+        Term::Map(fields) => {
+            for (key, value) in fields {
+                match metadata.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, old)) => *old = value.clone(),
+                    None => metadata.push((key.clone(), value.clone())),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Reads a string (a list of character codes) or a UTF-8 binary.
+fn text(term: &Term) -> Option<String> {
+    match term {
+        Term::Binary(bytes) => String::from_utf8(bytes.clone()).ok(),
+        Term::List(chars) => chars
+            .iter()
+            .map(|c| char::from_u32(u32::try_from(c.as_i64()?).ok()?))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn function(tree: &SyntaxTree, source: &str, decl: ast::FunctionDecl<'_>) -> Option<Entry> {
+    let clause = decl.clauses().next()?;
+    let at = clause.name()?;
+    let arguments = clause.arguments()?;
+    let key = NameArity::new(atom(tree, source, at)?, arguments.args().count());
+    Some(entry(
+        tree,
+        source,
+        EntryKind::Function,
+        decl.syntax(),
+        key,
+        at,
+        arguments.syntax().range(),
+    ))
+}
+
+fn entry(
+    tree: &SyntaxTree,
+    source: &str,
+    kind: EntryKind,
+    node: NodeView<'_>,
+    key: NameArity,
+    at: TokenIndex,
+    params: TokenRange,
+) -> Entry {
+    let tokens = tree.tokens();
+    let name = tokens[at.get()];
+    let end = params
+        .as_range()
+        .rev()
+        .find(|&i| tokens[i].kind().is_lexical())
+        .map_or(name.end().offset(), |i| tokens[i].end().offset());
+    Entry {
+        kind,
+        name: key.name().to_string(),
+        arity: key.arity(),
+        node: node.node_id(),
+        line: name.start().line().get(),
+        signature: source[name.start().offset()..end].to_string(),
+        doc: Doc::None,
+        metadata: Vec::new(),
+    }
+}
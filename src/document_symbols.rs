@@ -0,0 +1,292 @@
+//! Document symbols: the outline of a module.
+//!
+//! [`outline`] lists a Module-mode tree's declarations in source order
+//! as [`DocumentSymbol`]s. Functions hold one child per clause and
+//! records one per field. Each symbol has the range of the whole
+//! declaration and, for the cursor, the range of its name token.
+
+use erl_tokenize::{Symbol, TokenKind};
+
+use crate::ast::{self, AstNode};
+use crate::model::{ModuleModel, NameArity, atom, attribute_name, declaration};
+use crate::node::NodeView;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// What a [`DocumentSymbol`] declares.
+///
+/// [`SymbolKind::lsp`] maps each kind to the closest LSP `SymbolKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// The `-module` attribute.
+    Module,
+    /// A function declaration.
+    Function,
+    /// One clause of a function.
+    Clause,
+    /// A `-record` attribute.
+    Record,
+    /// One field of a record.
+    Field,
+    /// A `-type`, `-opaque`, or `-nominal` attribute.
+    Type,
+    /// A `-spec` attribute.
+    Spec,
+    /// A `-callback` attribute.
+    Callback,
+    /// A `-define` attribute.
+    Macro,
+}
+
+impl SymbolKind {
+    /// Returns the LSP `SymbolKind` number: `Module` (2) for modules,
+    /// `Function` (12) for functions and clauses, `Struct` (23) for
+    /// records, `Field` (8), `TypeParameter` (26) for types,
+    /// `Interface` (11) for specs and callbacks, and `Constant` (14) for
+    /// macros.
+    pub const fn lsp(self) -> u32 {
+        match self {
+            Self::Module => 2,
+            Self::Function | Self::Clause => 12,
+            Self::Record => 23,
+            Self::Field => 8,
+            Self::Type => 26,
+            Self::Spec | Self::Callback => 11,
+            Self::Macro => 14,
+        }
+    }
+}
+
+/// One entry of the outline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    name: String,
+    kind: SymbolKind,
+    range: TokenRange,
+    selection_range: TokenRange,
+    exported: bool,
+    children: Vec<DocumentSymbol>,
+}
+
+impl DocumentSymbol {
+    /// Returns the display name: `f/1` for functions, types, specs,
+    /// and callbacks; the head, such as `f(X, [])`, for clauses; the
+    /// bare name otherwise, with `/N` after a macro that takes
+    /// arguments.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns what the symbol declares.
+    pub fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    /// Returns the tokens of the whole declaration, from its first
+    /// lexical token through the closing `.` of a form.
+    pub fn range(&self) -> TokenRange {
+        self.range
+    }
+
+    /// Returns the name token, within [`DocumentSymbol::range`].
+    pub fn selection_range(&self) -> TokenRange {
+        self.selection_range
+    }
+
+    /// Returns `true` for a function listed in `-export` (or compiled
+    /// with `export_all`) and a type listed in `-export_type`.
+    pub fn is_exported(&self) -> bool {
+        self.exported
+    }
+
+    /// Returns the clauses of a function or the fields of a record.
+    pub fn children(&self) -> &[DocumentSymbol] {
+        &self.children
+    }
+}
+
+/// Returns the outline of `tree`'s forms, in source order.
+pub(crate) fn outline(tree: &SyntaxTree, source: &str) -> Vec<DocumentSymbol> {
+    let model = ModuleModel::new(tree, source);
+    let mut symbols = Vec::new();
+    for root in tree.roots() {
+        let range = root.form_range();
+        let symbol = |name: String, kind, at: TokenIndex, exported, children| DocumentSymbol {
+            name,
+            kind,
+            range,
+            selection_range: single(at),
+            exported,
+            children,
+        };
+        if let Some(decl) = ast::FunctionDecl::cast(root) {
+            let Some((key, at)) = head(tree, source, decl.clauses().next()) else {
+                continue;
+            };
+            let clauses = decl
+                .clauses()
+                .filter_map(|clause| {
+                    let at = clause.name()?;
+                    let end = clause.arguments()?.syntax().lexical_range().end();
+                    Some(DocumentSymbol {
+                        name: head_text(tree, source, TokenRange::new(at, end)),
+                        kind: SymbolKind::Clause,
+                        range: clause.syntax().lexical_range(),
+                        selection_range: single(at),
+                        exported: false,
+                        children: Vec::new(),
+                    })
+                })
+                .collect();
+            let exported = model
+                .function(key.name(), key.arity())
+                .is_some_and(|f| f.is_exported());
+            symbols.push(symbol(
+                key.to_string(),
+                SymbolKind::Function,
+                at,
+                exported,
+                clauses,
+            ));
+            continue;
+        }
+        let Some(attribute) = ast::Attribute::cast(root) else {
+            continue;
+        };
+        let Some((name, _)) = attribute_name(tree, source, attribute) else {
+            continue;
+        };
+        let kind = match name.as_str() {
+            "module" => SymbolKind::Module,
+            "record" => SymbolKind::Record,
+            "type" | "opaque" | "nominal" => SymbolKind::Type,
+            "spec" => SymbolKind::Spec,
+            "callback" => SymbolKind::Callback,
+            "define" => SymbolKind::Macro,
+            _ => continue,
+        };
+        let payload = attribute.payload().map(|p| p.syntax());
+        match kind {
+            SymbolKind::Module => {
+                let Some((at, _)) = payload
+                    .into_iter()
+                    .flat_map(|p| p.tokens_in_range())
+                    .find(|(_, t)| t.kind() == TokenKind::Atom)
+                else {
+                    continue;
+                };
+                let Some(name) = atom(tree, source, at) else {
+                    continue;
+                };
+                symbols.push(symbol(name, kind, at, false, Vec::new()));
+            }
+            SymbolKind::Record => {
+                let Some(payload) = tree.parse_attribute_payload(root, source) else {
+                    continue;
+                };
+                let Some(decl) = payload.roots().next().and_then(ast::RecordDecl::cast) else {
+                    continue;
+                };
+                let Some((at, name)) = decl
+                    .name()
+                    .and_then(|at| Some((at, atom(tree, source, at)?)))
+                else {
+                    continue;
+                };
+                let fields = decl
+                    .fields()
+                    .filter_map(|field| {
+                        let at = field.name()?;
+                        Some(DocumentSymbol {
+                            name: atom(tree, source, at)?,
+                            kind: SymbolKind::Field,
+                            range: field.syntax().lexical_range(),
+                            selection_range: single(at),
+                            exported: false,
+                            children: Vec::new(),
+                        })
+                    })
+                    .collect();
+                symbols.push(symbol(name, kind, at, false, fields));
+            }
+            SymbolKind::Macro => {
+                let Some((at, name)) = payload.and_then(|p| macro_name(source, p)) else {
+                    continue;
+                };
+                symbols.push(symbol(name, kind, at, false, Vec::new()));
+            }
+            _ => {
+                let Some((key, at, _)) = declaration(tree, source, root) else {
+                    continue;
+                };
+                let exported = kind == SymbolKind::Type
+                    && model
+                        .ty(key.name(), key.arity())
+                        .is_some_and(|ty| ty.is_exported());
+                symbols.push(symbol(key.to_string(), kind, at, exported, Vec::new()));
+            }
+        }
+    }
+    symbols
+}
+
+/// Returns the key and name token of a function clause.
+fn head(
+    tree: &SyntaxTree,
+    source: &str,
+    clause: Option<ast::FunctionClause<'_>>,
+) -> Option<(NameArity, TokenIndex)> {
+    let clause = clause?;
+    let at = clause.name()?;
+    let arity = clause.arguments()?.args().count();
+    Some((NameArity::new(atom(tree, source, at)?, arity), at))
+}
+
+/// Returns the source text of `range` with each run of whitespace and
+/// comments written as one space.
+fn head_text(tree: &SyntaxTree, source: &str, range: TokenRange) -> String {
+    let mut text = String::new();
+    for token in &tree.tokens()[range.as_range()] {
+        if token.kind().is_hidden() {
+            if !text.ends_with(' ') {
+                text.push(' ');
+            }
+        } else {
+            text.push_str(token.text(source));
+        }
+    }
+    text
+}
+
+/// Reads `Name` or `Name(Params)` from a `-define` payload, returning
+/// the name token and `Name` or `Name/N`.
+fn macro_name(source: &str, payload: NodeView<'_>) -> Option<(TokenIndex, String)> {
+    let mut tokens = payload
+        .tokens_in_range()
+        .filter(|(_, t)| t.kind().is_lexical())
+        .skip_while(|(_, t)| t.kind() == TokenKind::Symbol(Symbol::OpenParen));
+    let (at, token) = tokens.next()?;
+    if !matches!(token.kind(), TokenKind::Atom | TokenKind::Variable) {
+        return None;
+    }
+    let name = token.text(source).to_string();
+    if tokens.next().map(|(_, t)| t.kind()) != Some(TokenKind::Symbol(Symbol::OpenParen)) {
+        return Some((at, name));
+    }
+    let (mut depth, mut arity, mut empty) = (0usize, 1, true);
+    for (_, token) in tokens {
+        match token.kind() {
+            TokenKind::Symbol(Symbol::CloseParen) if depth == 0 => break,
+            TokenKind::Symbol(Symbol::OpenParen) => depth += 1,
+            TokenKind::Symbol(Symbol::CloseParen) => depth -= 1,
+            TokenKind::Symbol(Symbol::Comma) if depth == 0 => arity += 1,
+            _ => {}
+        }
+        empty = false;
+    }
+    Some((at, format!("{name}/{}", if empty { 0 } else { arity })))
+}
+
+fn single(at: TokenIndex) -> TokenRange {
+    TokenRange::new(at, TokenIndex::new(at.get() + 1))
+}
@@ -0,0 +1,196 @@
+//! EDoc tags in the comments above forms.
+//!
+//! EDoc documents a module in the comment before its `-module`
+//! attribute, a function in the comment before its declaration, and a
+//! type in the comment before its `-type` or `-opaque` attribute:
+//!
+//! ```erlang
+//! %% @doc Adds two numbers.
+//! %% @param X the first number
+//! %% @returns the sum
+//! add(X, Y) -> X + Y.
+//! ```
+//!
+//! [`Edoc::for_node`] reads the comments attached in front of a node
+//! (see [`NodeView::leading_comments`]) and [`entries`] does so for
+//! every root. Tags start at the beginning of a comment line; the
+//! text runs to the next tag, `@end`, or the end of the comment, with
+//! the comment markers removed. Text before the first tag is not
+//! documentation and is skipped. Tag contents are kept as written, so
+//! EDoc's XHTML and wiki markup are left to the caller.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! let source = "%% @doc Adds two numbers.\n%% @param X the first number\nadd(X, Y) -> X + Y.\n";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let (node, edoc) = erl_parse::edoc::entries(&tree, source).remove(0);
+//! assert_eq!(node.kind(), erl_parse::SyntaxKind::FunctionDecl);
+//! assert_eq!(edoc.doc(), Some("Adds two numbers."));
+//! assert_eq!(edoc.params().collect::<Vec<_>>(), [("X", "the first number")]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+
+/// One `@name content` tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tag {
+    name: String,
+    content: String,
+}
+
+impl Tag {
+    /// Returns the tag name without the `@`, such as `"doc"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the text after the tag name, with comment markers and
+    /// surrounding whitespace removed. Lines are joined with `\n`.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// The EDoc tags of one comment block, in source order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Edoc {
+    tags: Vec<Tag>,
+}
+
+impl Edoc {
+    /// Parses the tags in a comment block. Each item is the text of one
+    /// comment token, `%` markers included.
+    ///
+    /// Returns `None` when the block has no tag.
+    pub fn parse<'a>(comments: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut tags: Vec<Tag> = Vec::new();
+        let mut open = false;
+        for comment in comments {
+            let line = comment.trim_start_matches('%').trim();
+            match tag_start(line) {
+                Some(("end", _)) => open = false,
+                Some((name, rest)) => {
+                    tags.push(Tag {
+                        name: name.to_string(),
+                        content: rest.trim().to_string(),
+                    });
+                    open = true;
+                }
+                None if open => {
+                    let tag = tags.last_mut().expect("an open tag");
+                    if !tag.content.is_empty() {
+                        tag.content.push('\n');
+                    }
+                    tag.content.push_str(line);
+                }
+                None => {}
+            }
+        }
+        for tag in &mut tags {
+            tag.content.truncate(tag.content.trim_end().len());
+        }
+        (!tags.is_empty()).then_some(Self { tags })
+    }
+
+    /// Parses the comments attached in front of `node`. `source` is the
+    /// text the tokens were scanned from.
+    ///
+    /// Returns `None` when those comments have no tag.
+    pub fn for_node(node: NodeView<'_>, source: &str) -> Option<Self> {
+        Self::parse(node.leading_comments().map(|(_, token)| token.text(source)))
+    }
+
+    /// Returns every tag in source order, including ones without an
+    /// accessor here (`@author`, `@private`, `@throws`, ...).
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Returns the content of the first tag called `name`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.name == name)
+            .map(Tag::content)
+    }
+
+    /// Returns the `@doc` text.
+    pub fn doc(&self) -> Option<&str> {
+        self.tag("doc")
+    }
+
+    /// Returns each `@param` as the parameter name and its description.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tags
+            .iter()
+            .filter(|tag| tag.name == "param")
+            .map(|tag| match tag.content.split_once(char::is_whitespace) {
+                Some((name, description)) => (name, description.trim_start()),
+                None => (tag.content.as_str(), ""),
+            })
+    }
+
+    /// Returns the `@returns` text (`@return` is accepted too).
+    pub fn returns(&self) -> Option<&str> {
+        self.tag("returns").or_else(|| self.tag("return"))
+    }
+
+    /// Returns the `@spec` text, such as `add(X, Y) -> integer()`.
+    pub fn spec(&self) -> Option<&str> {
+        self.tag("spec")
+    }
+
+    /// Returns the `@deprecated` text, empty when the tag has none.
+    pub fn deprecated(&self) -> Option<&str> {
+        self.tag("deprecated")
+    }
+
+    /// Returns the `@since` text.
+    pub fn since(&self) -> Option<&str> {
+        self.tag("since")
+    }
+
+    /// Returns each `@see` reference.
+    pub fn see(&self) -> impl Iterator<Item = &str> {
+        self.tags
+            .iter()
+            .filter(|tag| tag.name == "see")
+            .map(Tag::content)
+    }
+}
+
+/// Returns every documented [`FunctionDecl`](SyntaxKind::FunctionDecl)
+/// and [`Attribute`](SyntaxKind::Attribute) root of `tree` with its
+/// tags, in source order. `source` is the text the tokens were scanned
+/// from.
+pub fn entries<'a>(tree: &'a SyntaxTree, source: &str) -> Vec<(NodeView<'a>, Edoc)> {
+    tree.roots()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::FunctionDecl | SyntaxKind::Attribute
+            )
+        })
+        .filter_map(|node| Some((node, Edoc::for_node(node, source)?)))
+        .collect()
+}
+
+/// Splits `@name rest` at the start of a comment line.
+fn tag_start(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('@')?;
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(end);
+    let starts_lower = name.starts_with(|c: char| c.is_ascii_lowercase());
+    (starts_lower && (rest.is_empty() || rest.starts_with(char::is_whitespace)))
+        .then_some((name, rest))
+}
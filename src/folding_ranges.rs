@@ -0,0 +1,173 @@
+//! Folding ranges.
+//!
+//! [`folding_ranges`] finds the regions an editor can collapse: blocks
+//! and containers that span lines, functions with several clauses, runs
+//! of comment lines, and runs of `-export` and `-import` attributes.
+
+use erl_tokenize::TokenKind;
+
+use crate::ast::{self, AstNode};
+use crate::model::attribute_name;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// What a [`FoldingRange`] folds, after the LSP `FoldingRangeKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoldingKind {
+    /// A block, container, or function.
+    Region,
+    /// Consecutive comment lines.
+    Comment,
+    /// Consecutive `-export` and `-import` attributes.
+    Imports,
+}
+
+/// A run of lines an editor can collapse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FoldingRange {
+    range: TokenRange,
+    start_line: usize,
+    end_line: usize,
+    kind: FoldingKind,
+}
+
+impl FoldingRange {
+    /// Returns the tokens the region covers, without leading or
+    /// trailing whitespace and comments (except for a comment run).
+    pub fn range(self) -> TokenRange {
+        self.range
+    }
+
+    /// Returns the 1-based line of the first token.
+    pub fn start_line(self) -> usize {
+        self.start_line
+    }
+
+    /// Returns the 1-based line of the last token, after
+    /// [`FoldingRange::start_line`].
+    pub fn end_line(self) -> usize {
+        self.end_line
+    }
+
+    /// Returns what the region holds.
+    pub fn kind(self) -> FoldingKind {
+        self.kind
+    }
+}
+
+/// Returns `tree`'s folding ranges ordered by start line, at most one
+/// per pair of lines.
+pub(crate) fn folding_ranges(tree: &SyntaxTree, source: &str) -> Vec<FoldingRange> {
+    let tokens = tree.tokens();
+    let mut ranges = Vec::new();
+    let mut push = |range: TokenRange, kind| {
+        if range.is_empty() {
+            return;
+        }
+        let start_line = tokens[range.start().get()].start().line().get();
+        let end_line = tokens[range.end().get() - 1].end().line().get();
+        if start_line < end_line {
+            ranges.push(FoldingRange {
+                range,
+                start_line,
+                end_line,
+                kind,
+            });
+        }
+    };
+
+    let mut imports: Option<TokenRange> = None;
+    for root in tree.roots() {
+        let is_import = ast::Attribute::cast(root)
+            .and_then(|attribute| attribute_name(tree, source, attribute))
+            .is_some_and(|(name, _)| matches!(name.as_str(), "export" | "import"));
+        let form = root.form_range();
+        if is_import && !form.is_empty() {
+            imports = Some(match imports {
+                Some(run) => TokenRange::new(run.start(), form.end()),
+                None => form,
+            });
+        } else if let Some(run) = imports.take() {
+            push(run, FoldingKind::Imports);
+        }
+        for node in core::iter::once(root).chain(root.descendants()) {
+            let fold = match node.kind() {
+                SyntaxKind::CaseExpr
+                | SyntaxKind::IfExpr
+                | SyntaxKind::TryExpr
+                | SyntaxKind::ReceiveExpr
+                | SyntaxKind::MaybeExpr
+                | SyntaxKind::BeginExpr
+                | SyntaxKind::AnonymousFun
+                | SyntaxKind::NamedFun
+                | SyntaxKind::ListExpr
+                | SyntaxKind::MapExpr
+                | SyntaxKind::RecordExpr
+                | SyntaxKind::TupleExpr => true,
+                SyntaxKind::FunctionDecl => node
+                    .children()
+                    .filter(|c| c.kind() == SyntaxKind::FunctionClause)
+                    .nth(1)
+                    .is_some(),
+                _ => false,
+            };
+            if fold {
+                let range = match node.kind() {
+                    SyntaxKind::FunctionDecl => node.form_range(),
+                    _ => node.lexical_range(),
+                };
+                push(range, FoldingKind::Region);
+            }
+        }
+    }
+    if let Some(run) = imports {
+        push(run, FoldingKind::Imports);
+    }
+
+    let mut comments: Option<TokenRange> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let at = TokenIndex::new(i);
+        match token.kind() {
+            TokenKind::Comment if starts_line(tree, source, at) => {
+                let line = token.start().line().get();
+                comments = Some(match comments {
+                    Some(run) if tokens[run.end().get() - 1].start().line().get() + 1 == line => {
+                        TokenRange::new(run.start(), TokenIndex::new(i + 1))
+                    }
+                    run => {
+                        if let Some(run) = run {
+                            push(run, FoldingKind::Comment);
+                        }
+                        TokenRange::new(at, TokenIndex::new(i + 1))
+                    }
+                });
+            }
+            TokenKind::Whitespace => {}
+            _ => {
+                if let Some(run) = comments.take() {
+                    push(run, FoldingKind::Comment);
+                }
+            }
+        }
+    }
+    if let Some(run) = comments {
+        push(run, FoldingKind::Comment);
+    }
+
+    ranges.sort_by_key(|r| (r.start_line, core::cmp::Reverse(r.end_line)));
+    ranges.dedup_by_key(|r| (r.start_line, r.end_line));
+    ranges
+}
+
+/// Returns `true` when only whitespace precedes the token at `at` on
+/// its line.
+fn starts_line(tree: &SyntaxTree, source: &str, at: TokenIndex) -> bool {
+    match at.get().checked_sub(1).map(|i| tree.tokens()[i]) {
+        None => true,
+        Some(prev) if prev.kind() == TokenKind::Whitespace => {
+            prev.text(source).contains('\n') || at.get() == 1
+        }
+        Some(_) => false,
+    }
+}
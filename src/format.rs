@@ -0,0 +1,121 @@
+//! Source formatting in the style of `erlfmt`.
+//!
+//! [`format()`] re-prints a parsed tree. Layout follows the tree: a
+//! clause's [`Body`](crate::SyntaxKind::Body) goes on the line after
+//! `->` unless the clause fits on one line with a single expression;
+//! `case`, `receive`, `try`, and the other blocks put their clauses one
+//! per line between the opening keyword and `end`; tuples, lists, maps,
+//! argument lists, and other bracketed containers stay flat when they
+//! fit in [`Options::line_width`] and otherwise put one element per
+//! line. Attribute payloads are read with
+//! [`SyntaxTree::parse_attribute_payload`].
+//!
+//! The output scans to the same lexical tokens as the input, and
+//! formatting it again returns it unchanged. Whitespace is the only
+//! thing that changes: comments stay next to the token they were
+//! written against, and a blank line is kept wherever the output
+//! breaks the line anyway. A `.`-terminated unit that error recovery
+//! left a hole in, or that a diagnostic points into, is kept exactly as
+//! written. Macros do not parse before preprocessing, so units that use
+//! them are kept as written too. [`format_range`] formats only a
+//! selected span and returns the edits instead of the whole text.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::format::{self, Options};
+//!
+//! let source = "f(X)->case X of {ok,V}->V;_->error end.";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! assert_eq!(
+//!     format::format(&tree, source, Options::new()),
+//!     "f(X) ->\n    case X of\n        {ok, V} -> V;\n        _ -> error\n    end.\n",
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+mod doc;
+mod layout;
+mod range;
+
+use std::ops::Range;
+
+use self::layout::Layout;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenRange;
+
+/// Formatting settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    line_width: usize,
+    indent: usize,
+}
+
+impl Options {
+    /// Returns the defaults: lines of up to 100 columns, indented by 4
+    /// spaces per level.
+    pub const fn new() -> Self {
+        Self {
+            line_width: 100,
+            indent: 4,
+        }
+    }
+
+    /// Sets the column lines should fit in. A token longer than the
+    /// width still goes on one line.
+    pub const fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Sets the number of spaces per indentation level.
+    pub const fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats the source `tree` was parsed from. `source` is the text the
+/// tokens were scanned from.
+///
+/// The result ends with a newline unless the input has no tokens
+/// other than whitespace.
+pub fn format(tree: &SyntaxTree, source: &str, options: Options) -> String {
+    let document = Layout::new(tree, source).document();
+    doc::print(&document, options.line_width, options.indent)
+}
+
+/// Formats the part of the source `tree` was parsed from that
+/// `selection` covers, and returns the edits to apply to `source`.
+///
+/// The selection is widened to the smallest node that contains it (or
+/// the `.`-terminated units it overlaps), that node is formatted as
+/// [`format()`] would at the indentation of the line it starts on, and
+/// only changes to whitespace and comments between tokens inside
+/// `selection` are returned. Each edit replaces a byte range of
+/// `source` with new text, like [`Fix::byte_edit`](crate::Fix::byte_edit);
+/// the edits are in source order and do not overlap.
+///
+/// Returns `None` when `selection` overlaps an
+/// [`Error`](crate::SyntaxKind::Error) node or a diagnostic's range.
+/// Elsewhere, a node that error recovery left a hole in is not
+/// formatted as a whole; its children inside the selection are
+/// formatted instead.
+pub fn format_range(
+    tree: &SyntaxTree,
+    source: &str,
+    selection: TokenRange,
+    options: Options,
+) -> Option<Vec<(Range<usize>, String)>> {
+    range::edits(tree, source, selection, options)
+}
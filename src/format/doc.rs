@@ -0,0 +1,299 @@
+//! Document algebra and line-fitting printer.
+//!
+//! The layout pass builds a [`Doc`] from the syntax tree; [`print`]
+//! renders it, breaking a [`Doc::Group`] only when its flat form does
+//! not fit in the remaining width. Newlines are emitted lazily, so two
+//! adjacent breaks collapse into one and a line never ends in
+//! trailing whitespace.
+
+use erl_tokenize::Position;
+
+/// A layout document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Doc<'a> {
+    /// Prints nothing.
+    Nil,
+    /// Source text printed as is: a comment or a unit kept verbatim.
+    Text(&'a str),
+    /// A lexical token. A space is put between two adjacent tokens
+    /// that would otherwise scan differently.
+    Token(&'a str),
+    /// A space, dropped at the start of a line.
+    Space,
+    /// A space in flat mode, a newline in break mode.
+    Line,
+    /// Nothing in flat mode, a newline in break mode.
+    SoftLine,
+    /// Always a newline; forces every enclosing group to break.
+    HardLine,
+    /// Turns the newline pending at this point, if any, into a blank
+    /// line.
+    Blank,
+    /// Indents the newlines inside by one level.
+    Indent(Box<Doc<'a>>),
+    /// Printed flat if it fits, otherwise in break mode. The flag
+    /// caches whether the content contains a forced break.
+    Group(Box<Doc<'a>>, bool),
+    /// A sequence.
+    Concat(Vec<Doc<'a>>),
+}
+
+impl<'a> Doc<'a> {
+    pub(crate) fn group(doc: Self) -> Self {
+        let hard = doc.is_hard();
+        Self::Group(Box::new(doc), hard)
+    }
+
+    pub(crate) fn indent(doc: Self) -> Self {
+        Self::Indent(Box::new(doc))
+    }
+
+    fn is_hard(&self) -> bool {
+        match self {
+            Self::HardLine => true,
+            Self::Text(text) | Self::Token(text) => text.contains('\n'),
+            Self::Group(_, hard) => *hard,
+            Self::Indent(doc) => doc.is_hard(),
+            Self::Concat(docs) => docs.iter().any(Self::is_hard),
+            Self::Nil | Self::Space | Self::Line | Self::SoftLine | Self::Blank => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Renders `doc` within `width` columns, indenting by `indent` spaces
+/// per level. Non-empty output ends with a newline.
+pub(crate) fn print(doc: &Doc<'_>, width: usize, indent: usize) -> String {
+    print_at(doc, width, indent, 0, 0)
+}
+
+/// Renders `doc` as [`print`] does, as if the output started at
+/// `column` of a line indented by `level` spaces.
+pub(crate) fn print_at(
+    doc: &Doc<'_>,
+    width: usize,
+    indent: usize,
+    level: usize,
+    column: usize,
+) -> String {
+    let mut printer = Printer {
+        width,
+        out: String::new(),
+        column,
+        pending: None,
+        blank: false,
+        last_token: None,
+    };
+    let mut stack = vec![(level, Mode::Break, doc)];
+    while let Some((level, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Nil => {}
+            Doc::Text(text) => printer.write(text),
+            Doc::Token(text) => printer.write_token(text),
+            Doc::Space => printer.space(),
+            Doc::Line if mode == Mode::Flat => printer.space(),
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => printer.newline(level),
+            Doc::Blank => printer.blank |= printer.pending.is_some(),
+            Doc::Indent(inner) => stack.push((level + indent, mode, inner)),
+            Doc::Group(inner, hard) => {
+                let mode = if mode == Mode::Flat || (!hard && printer.fits(inner, &stack)) {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.push((level, mode, inner));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (level, mode, doc))),
+        }
+    }
+    if !printer.out.is_empty() {
+        printer.out.push('\n');
+    }
+    printer.out
+}
+
+struct Printer<'a> {
+    width: usize,
+    out: String,
+    column: usize,
+    pending: Option<usize>,
+    blank: bool,
+    last_token: Option<&'a str>,
+}
+
+impl<'a> Printer<'a> {
+    /// Returns whether `doc` printed flat, followed by the rest of the
+    /// stack up to its next line break, fits in the current line.
+    fn fits(&self, doc: &Doc<'_>, rest: &[(usize, Mode, &Doc<'_>)]) -> bool {
+        let mut width = self.width as isize - self.pending.unwrap_or(self.column) as isize;
+        let mut stack = vec![(Mode::Flat, doc)];
+        let mut rest = rest.iter().rev();
+        loop {
+            let (mode, doc) = match stack.pop() {
+                Some(next) => next,
+                None => match rest.next() {
+                    Some(&(_, mode, doc)) => (mode, doc),
+                    None => return true,
+                },
+            };
+            match doc {
+                Doc::Nil | Doc::Blank => {}
+                Doc::Text(text) | Doc::Token(text) => match text.split_once('\n') {
+                    Some((first, _)) => return width >= first.chars().count() as isize,
+                    None => width -= text.chars().count() as isize,
+                },
+                Doc::Space => width -= 1,
+                Doc::Line if mode == Mode::Flat => width -= 1,
+                Doc::SoftLine if mode == Mode::Flat => {}
+                Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+                Doc::Indent(inner) => stack.push((mode, inner)),
+                Doc::Group(inner, hard) => {
+                    stack.push((if *hard { Mode::Break } else { mode }, inner));
+                }
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (mode, doc))),
+            }
+            if width < 0 {
+                return false;
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(level) = self.pending.take() {
+            if !self.out.is_empty() {
+                self.out.push('\n');
+                if self.blank {
+                    self.out.push('\n');
+                }
+                self.out.extend(std::iter::repeat_n(' ', level));
+                self.column = level;
+            }
+            self.blank = false;
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.flush();
+        self.out.push_str(text);
+        self.column = match text.rsplit_once('\n') {
+            Some((_, last)) => last.chars().count(),
+            None => self.column + text.chars().count(),
+        };
+        self.last_token = None;
+    }
+
+    fn write_token(&mut self, text: &'a str) {
+        if self.pending.is_none()
+            && let Some(last) = self.last_token
+            && !scans_apart(last, text)
+        {
+            self.write(" ");
+        }
+        self.write(text);
+        self.last_token = Some(text);
+    }
+
+    fn space(&mut self) {
+        if self.pending.is_none() && !self.out.is_empty() && !self.out.ends_with(' ') {
+            self.write(" ");
+        }
+    }
+
+    fn newline(&mut self, level: usize) {
+        self.pending = Some(level);
+        self.last_token = None;
+    }
+}
+
+/// Returns whether `left` immediately followed by `right` still scans
+/// as those two tokens (so `a` `b` or `-` `-` cannot be written
+/// together).
+fn scans_apart(left: &str, right: &str) -> bool {
+    let text = format!("{left}{right}");
+    let Ok(Some(first)) = erl_tokenize::scan_token(&text, Position::new()) else {
+        return false;
+    };
+    if first.text(&text) != left {
+        return false;
+    }
+    matches!(
+        erl_tokenize::scan_token(&text, first.end()),
+        Ok(Some(second)) if second.text(&text) == right
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&'static str]) -> Doc<'static> {
+        let mut inner = vec![Doc::SoftLine];
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                inner.push(Doc::Token(","));
+                inner.push(Doc::Line);
+            }
+            inner.push(Doc::Token(item));
+        }
+        Doc::group(Doc::Concat(vec![
+            Doc::Token("["),
+            Doc::indent(Doc::Concat(inner)),
+            Doc::SoftLine,
+            Doc::Token("]"),
+        ]))
+    }
+
+    #[test]
+    fn group_breaks_only_when_it_does_not_fit() {
+        let doc = list(&["aaa", "bbb"]);
+        assert_eq!(print(&doc, 10, 4), "[aaa, bbb]\n");
+        assert_eq!(print(&doc, 9, 4), "[\n    aaa,\n    bbb\n]\n");
+    }
+
+    #[test]
+    fn hard_line_breaks_enclosing_groups() {
+        let doc = Doc::group(Doc::Concat(vec![
+            Doc::Token("a"),
+            Doc::Line,
+            Doc::Text("% c"),
+            Doc::HardLine,
+            Doc::Token("b"),
+        ]));
+        assert_eq!(print(&doc, 80, 4), "a\n% c\nb\n");
+    }
+
+    #[test]
+    fn adjacent_breaks_collapse_and_blank_needs_a_break() {
+        let doc = Doc::Concat(vec![
+            Doc::Token("a"),
+            Doc::HardLine,
+            Doc::HardLine,
+            Doc::Blank,
+            Doc::Token("b"),
+            Doc::Blank,
+            Doc::Space,
+            Doc::Token("c"),
+        ]);
+        assert_eq!(print(&doc, 80, 4), "a\n\nb c\n");
+    }
+
+    #[test]
+    fn tokens_that_would_merge_are_kept_apart() {
+        let doc = Doc::Concat(vec![
+            Doc::Token("-"),
+            Doc::Token("-"),
+            Doc::Token("1"),
+            Doc::Token(","),
+            Doc::Token("a"),
+            Doc::Token("b"),
+            Doc::Token("("),
+        ]);
+        assert_eq!(print(&doc, 80, 4), "- -1,a b(\n");
+    }
+}
@@ -0,0 +1,832 @@
+//! Syntax tree to [`Doc`] layout.
+//!
+//! Every lexical token becomes a [`Doc::Token`]; the node kinds around
+//! it decide where lines may break. Whitespace is regenerated, and each
+//! comment is re-emitted next to the lexical token it was written
+//! against: a comment on the same line as the token before it stays
+//! at the end of that line, any other comment goes on its own line
+//! before the next token. A blank line before a token or an own-line
+//! comment is kept where the layout already breaks the line.
+
+use erl_tokenize::{Token, TokenKind};
+
+use super::doc::Doc;
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// The comments around one lexical token.
+#[derive(Debug, Default)]
+struct Trivia {
+    /// Own-line comments before the token, each with whether a blank
+    /// line precedes it.
+    leading: Vec<(usize, bool)>,
+    /// Whether a blank line precedes the token.
+    blank: bool,
+    /// A comment on the same line after the token.
+    trailing: Option<usize>,
+}
+
+/// A laid-out item together with the lexical tokens at its edges, so
+/// neighbouring items can decide on the space between them.
+struct Chunk<'a> {
+    first: Option<usize>,
+    last: Option<usize>,
+    /// Set when the chunk is a single token of the node being laid out
+    /// (punctuation, keywords, operators).
+    token: Option<&'a str>,
+    kind: Option<SyntaxKind>,
+    doc: Doc<'a>,
+}
+
+pub(crate) struct Layout<'a> {
+    tree: &'a SyntaxTree,
+    source: &'a str,
+    tokens: &'a [Token],
+    trivia: Vec<Trivia>,
+    /// Own-line comments after the last lexical token.
+    eof: Vec<(usize, bool)>,
+}
+
+impl<'a> Layout<'a> {
+    pub(crate) fn new(tree: &'a SyntaxTree, source: &'a str) -> Self {
+        let tokens = tree.tokens();
+        let mut trivia: Vec<Trivia> = tokens.iter().map(|_| Trivia::default()).collect();
+        let mut previous: Option<usize> = None;
+        let mut newlines = 0;
+        let mut own_line = false;
+        let mut pending = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            match token.kind() {
+                TokenKind::Whitespace => {
+                    let count = token.text(source).matches('\n').count();
+                    newlines += count;
+                    own_line |= count > 0;
+                }
+                TokenKind::Comment => {
+                    match previous {
+                        Some(previous) if !own_line && pending.is_empty() => {
+                            trivia[previous].trailing = Some(i);
+                        }
+                        _ => pending.push((i, newlines >= 2)),
+                    }
+                    newlines = 0;
+                }
+                _ => {
+                    trivia[i].leading = std::mem::take(&mut pending);
+                    trivia[i].blank = newlines >= 2;
+                    previous = Some(i);
+                    newlines = 0;
+                    own_line = false;
+                }
+            }
+        }
+        // In comma-first code (`a % note\n, b`) the comment moves past
+        // the separator rather than leaving it alone on the next line.
+        let lexical: Vec<usize> = (0..tokens.len())
+            .filter(|&i| tokens[i].kind().is_lexical())
+            .collect();
+        for pair in lexical.windows(2) {
+            let (token, next) = (pair[0], pair[1]);
+            let separator = |i: usize| matches!(tokens[i].text(source), "," | ";" | ".");
+            if trivia[token].trailing.is_some()
+                && !separator(token)
+                && separator(next)
+                && trivia[next].leading.is_empty()
+                && trivia[next].trailing.is_none()
+            {
+                trivia[next].trailing = trivia[token].trailing.take();
+            }
+        }
+        Self {
+            tree,
+            source,
+            tokens,
+            trivia,
+            eof: pending,
+        }
+    }
+
+    /// Lays out the whole tree: one unit per `.`-terminated form, term,
+    /// or expression, each starting on its own line.
+    ///
+    /// A unit that error recovery left a hole in, or that a diagnostic
+    /// points into, is kept exactly as written.
+    pub(crate) fn document(&self) -> Doc<'a> {
+        let all = TokenRange::new(TokenIndex::new(0), TokenIndex::new(self.tokens.len()));
+        let items = self.items(all, self.tree.roots());
+        let mut units = vec![Vec::new()];
+        for item in items {
+            // Recovery can fold the `.` into the unit's last node.
+            let ends_unit = self.edges(&item).1.is_some_and(|i| self.text(i) == ".");
+            units.last_mut().expect("non-empty").push(item);
+            if ends_unit {
+                units.push(Vec::new());
+            }
+        }
+        units.retain(|unit| unit.iter().any(|item| self.edges(item).0.is_some()));
+
+        let first = |unit: &[Item<'_>]| unit.iter().find_map(|item| self.edges(item).0);
+        let mut parts = Vec::new();
+        for (i, unit) in units.iter().enumerate() {
+            let start = first(unit).expect("lexical");
+            let end = match units.get(i + 1) {
+                Some(next) => first(next).expect("lexical"),
+                None => self.tokens.len(),
+            };
+            let last = unit.iter().rev().find_map(|item| self.edges(item).1);
+            parts.push(Doc::HardLine);
+            if self.is_clean(unit, start, end) {
+                let chunks = unit.iter().map(|item| self.chunk(item)).collect();
+                parts.push(self.inline(chunks, false));
+            } else {
+                parts.push(self.verbatim(start, last.expect("lexical")));
+            }
+        }
+        for &(comment, blank) in &self.eof {
+            parts.push(Doc::HardLine);
+            if blank {
+                parts.push(Doc::Blank);
+            }
+            parts.push(Doc::Text(self.text(comment)));
+        }
+        Doc::Concat(parts)
+    }
+
+    /// Lays out `node` on its own, or returns `None` when error recovery
+    /// left a hole in it or a diagnostic points into it.
+    pub(crate) fn fragment(&self, node: NodeView<'_>) -> Option<Doc<'a>> {
+        let range = node.range();
+        let clean = self.is_clean(&[Item::Node(node)], range.start().get(), range.end().get());
+        clean.then(|| self.node(node))
+    }
+
+    fn is_clean(&self, unit: &[Item<'_>], start: usize, end: usize) -> bool {
+        let has_error = unit.iter().any(|item| match item {
+            Item::Node(node) => std::iter::once(*node)
+                .chain(node.descendants())
+                .any(|node| node.kind() == SyntaxKind::Error),
+            Item::Token(_) => false,
+        });
+        let has_diagnostic = self.tree.diagnostics().iter().any(|diagnostic| {
+            let at = diagnostic.range().start().get();
+            (start..=end).contains(&at)
+        });
+        !has_error && !has_diagnostic
+    }
+
+    /// Lays out the tokens `first..=last` exactly as written.
+    fn verbatim(&self, first: usize, last: usize) -> Doc<'a> {
+        let start = self.tokens[first].start().offset();
+        let end = self.tokens[last].end().offset();
+        let mut parts = self.leading(first);
+        parts.push(Doc::Text(&self.source[start..end]));
+        parts.extend(self.trailing(last));
+        Doc::Concat(parts)
+    }
+
+    fn text(&self, i: usize) -> &'a str {
+        self.tokens[i].text(self.source)
+    }
+
+    fn leading(&self, i: usize) -> Vec<Doc<'a>> {
+        let trivia = &self.trivia[i];
+        let mut parts = Vec::new();
+        for &(comment, blank) in &trivia.leading {
+            parts.push(Doc::HardLine);
+            if blank {
+                parts.push(Doc::Blank);
+            }
+            parts.push(Doc::Text(self.text(comment)));
+            parts.push(Doc::HardLine);
+        }
+        if trivia.blank {
+            parts.push(Doc::Blank);
+        }
+        parts
+    }
+
+    fn trailing(&self, i: usize) -> Vec<Doc<'a>> {
+        match self.trivia[i].trailing {
+            Some(comment) => vec![Doc::Space, Doc::Text(self.text(comment)), Doc::HardLine],
+            None => Vec::new(),
+        }
+    }
+
+    /// Lays out a lexical token with its comments.
+    fn token(&self, i: usize) -> Doc<'a> {
+        let mut parts = self.leading(i);
+        parts.push(Doc::Token(self.text(i)));
+        parts.extend(self.trailing(i));
+        match parts.len() {
+            1 => parts.pop().expect("one part"),
+            _ => Doc::Concat(parts),
+        }
+    }
+
+    /// Splits `range` into the lexical tokens outside `children` and the
+    /// children themselves, in source order.
+    fn items<'t>(
+        &self,
+        range: TokenRange,
+        children: impl Iterator<Item = NodeView<'t>>,
+    ) -> Vec<Item<'t>> {
+        let mut items = Vec::new();
+        let mut cursor = range.start().get();
+        for child in children {
+            self.push_tokens(&mut items, cursor..child.range().start().get());
+            items.push(Item::Node(child));
+            cursor = cursor.max(child.range().end().get());
+        }
+        self.push_tokens(&mut items, cursor..range.end().get());
+        items
+    }
+
+    fn push_tokens(&self, items: &mut Vec<Item<'_>>, range: std::ops::Range<usize>) {
+        items.extend(
+            range
+                .filter(|&i| self.tokens[i].kind().is_lexical())
+                .map(Item::Token),
+        );
+    }
+
+    /// Returns the first and last lexical tokens of an item.
+    fn edges(&self, item: &Item<'_>) -> (Option<usize>, Option<usize>) {
+        match *item {
+            Item::Token(i) => (Some(i), Some(i)),
+            Item::Node(node) => {
+                let mut lexical = node
+                    .range()
+                    .as_range()
+                    .filter(|&i| self.tokens[i].kind().is_lexical());
+                let first = lexical.next();
+                (first, lexical.next_back().or(first))
+            }
+        }
+    }
+
+    fn chunk(&self, item: &Item<'_>) -> Chunk<'a> {
+        let (first, last) = self.edges(item);
+        match *item {
+            Item::Token(i) => Chunk {
+                first,
+                last,
+                token: Some(self.text(i)),
+                kind: None,
+                doc: self.token(i),
+            },
+            Item::Node(node) => Chunk {
+                first,
+                last,
+                token: None,
+                kind: Some(node.kind()),
+                doc: self.node(node),
+            },
+        }
+    }
+
+    fn chunks(&self, node: NodeView<'_>) -> Vec<Chunk<'a>> {
+        self.items(node.range(), node.children())
+            .iter()
+            .map(|item| self.chunk(item))
+            .collect()
+    }
+
+    fn node(&self, node: NodeView<'_>) -> Doc<'a> {
+        use SyntaxKind::*;
+        match node.kind() {
+            Body => self.separated(self.chunks(node), ",", Doc::HardLine),
+            Guard => self.separated(self.chunks(node), ",", Doc::Line),
+            GuardSequence => Doc::group(Doc::indent(self.separated(
+                self.chunks(node),
+                ";",
+                Doc::Line,
+            ))),
+            FunctionDecl => self.separated(self.chunks(node), ";", Doc::HardLine),
+            SpecAttribute => Doc::indent(self.separated(self.chunks(node), ";", Doc::HardLine)),
+            FunctionClause | Clause | IfClause | CatchClause => {
+                Doc::group(self.clause(self.chunks(node)))
+            }
+            CaseExpr | IfExpr | ReceiveExpr | BeginExpr | TryExpr | MaybeExpr
+            | ReceiveAfterSection | TryOfSection | TryCatchSection | TryAfterSection
+            | MaybeElseSection => self.block(self.chunks(node)),
+            AnonymousFun | NamedFun => self.fun(node),
+            BinaryOpExpr | BinaryOpType | MatchExpr | SendExpr | MaybeMatchExpr => {
+                self.operator(self.chunks(node))
+            }
+            UnionType => self.union(node),
+            TypeDecl => self.definition(self.chunks(node)),
+            StringExpr => {
+                let mut parts = Vec::new();
+                for chunk in self.chunks(node) {
+                    if !parts.is_empty() {
+                        parts.push(Doc::Line);
+                    }
+                    parts.push(chunk.doc);
+                }
+                Doc::group(Doc::indent(Doc::Concat(parts)))
+            }
+            Attribute => self.attribute(node),
+            AttributePayload => self.payload(self.chunks(node), true),
+            _ => self.inline(self.chunks(node), false),
+        }
+    }
+
+    /// Lays out an attribute, reading the payload with
+    /// [`SyntaxTree::parse_attribute_payload`] where that parses cleanly
+    /// and keeping the written spacing of its tokens otherwise.
+    fn attribute(&self, node: NodeView<'_>) -> Doc<'a> {
+        let payload = self
+            .tree
+            .parse_attribute_payload(node, self.source)
+            .filter(|payload| {
+                payload.diagnostics().is_empty()
+                    && payload
+                        .roots()
+                        .flat_map(|root| std::iter::once(root).chain(root.descendants()))
+                        .all(|node| node.kind() != SyntaxKind::Error)
+            });
+        let mut chunks = self.chunks(node);
+        if let Some(payload) = &payload
+            && let Some(chunk) = chunks
+                .iter_mut()
+                .find(|chunk| chunk.kind == Some(SyntaxKind::AttributePayload))
+            && let Some(range) = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::AttributePayload)
+                .map(|child| child.range())
+        {
+            let inner = self
+                .items(range, payload.roots())
+                .iter()
+                .map(|item| self.chunk(item))
+                .collect();
+            chunk.doc = self.payload(inner, false);
+        }
+        self.inline(chunks, false)
+    }
+
+    /// Lays out an attribute payload. The parentheses around it hug
+    /// their contents, so `-export([...])` breaks inside the list.
+    fn payload(&self, mut chunks: Vec<Chunk<'a>>, written: bool) -> Doc<'a> {
+        let tokens: Vec<_> = chunks.iter().map(|chunk| chunk.token).collect();
+        let wrapped =
+            tokens.first() == Some(&Some("(")) && matching(&tokens, 0) == Some(tokens.len() - 1);
+        if !wrapped {
+            return self.inline(chunks, written);
+        }
+        let close = chunks.pop().expect("`)`");
+        let open = chunks.remove(0);
+        Doc::Concat(vec![open.doc, self.inline(chunks, written), close.doc])
+    }
+
+    /// Lays out `Head -> Body`: the body goes on the next line, indented,
+    /// when the clause does not fit on one line or the body has more
+    /// than one expression.
+    fn clause(&self, mut chunks: Vec<Chunk<'a>>) -> Doc<'a> {
+        let Some(arrow) = chunks.iter().position(|chunk| chunk.token == Some("->")) else {
+            return self.inline(chunks, false);
+        };
+        let body = chunks.split_off(arrow + 1);
+        let arrow = chunks.pop().expect("arrow");
+        Doc::Concat(vec![
+            self.inline(chunks, false),
+            Doc::Space,
+            arrow.doc,
+            Doc::indent(Doc::Concat(vec![Doc::Line, self.inline(body, false)])),
+        ])
+    }
+
+    /// Lays out a `case`/`if`/`receive`/`try`/`begin`/`maybe` block or
+    /// one of its sections: the keyword that opens a section ends its
+    /// line, the section's clauses or body follow indented, and `end`
+    /// goes on its own line.
+    fn block(&self, chunks: Vec<Chunk<'a>>) -> Doc<'a> {
+        let mut parts = Vec::new();
+        let mut head = Vec::new();
+        let mut section: Option<Vec<Chunk<'a>>> = None;
+        let flush = |parts: &mut Vec<Doc<'a>>,
+                     head: &mut Vec<Chunk<'a>>,
+                     section: &mut Option<Vec<Chunk<'a>>>| {
+            if !head.is_empty() {
+                parts.push(self.inline(std::mem::take(head), false));
+            }
+            if let Some(section) = section.take()
+                && !section.is_empty()
+            {
+                let doc = if section.iter().any(|chunk| chunk.token == Some("->")) {
+                    Doc::group(self.clause(section))
+                } else {
+                    self.separated(section, ";", Doc::HardLine)
+                };
+                parts.push(Doc::indent(Doc::Concat(vec![Doc::HardLine, doc])));
+            }
+        };
+        for chunk in chunks {
+            let opens = matches!(
+                chunk.token,
+                Some(
+                    "of" | "if"
+                        | "receive"
+                        | "begin"
+                        | "try"
+                        | "maybe"
+                        | "after"
+                        | "catch"
+                        | "else"
+                )
+            );
+            if chunk.token == Some("end") || chunk.kind.is_some_and(is_section) {
+                flush(&mut parts, &mut head, &mut section);
+                parts.push(Doc::HardLine);
+                parts.push(chunk.doc);
+            } else if opens {
+                if section.is_some() {
+                    flush(&mut parts, &mut head, &mut section);
+                }
+                head.push(chunk);
+                flush(&mut parts, &mut head, &mut section);
+                section = Some(Vec::new());
+            } else if let Some(section) = &mut section {
+                section.push(chunk);
+            } else {
+                head.push(chunk);
+            }
+        }
+        flush(&mut parts, &mut head, &mut section);
+        Doc::Concat(parts)
+    }
+
+    /// Lays out a `fun ... end`: a single clause stays on one line when
+    /// it fits, several clauses go one per line.
+    fn fun(&self, node: NodeView<'_>) -> Doc<'a> {
+        let items = self.items(node.range(), node.children());
+        let clauses = items
+            .iter()
+            .filter(|item| matches!(item, Item::Node(node) if node.kind() == SyntaxKind::Clause))
+            .count();
+        let ends = matches!(items.last(), Some(&Item::Token(i)) if self.text(i) == "end");
+        if clauses != 1 || !ends {
+            return self.block_like_fun(&items);
+        }
+        let mut chunks: Vec<_> = items
+            .iter()
+            .map(|item| match item {
+                Item::Node(clause) if clause.kind() == SyntaxKind::Clause => {
+                    let (first, last) = self.edges(item);
+                    Chunk {
+                        first,
+                        last,
+                        token: None,
+                        kind: Some(SyntaxKind::Clause),
+                        doc: self.clause(self.chunks(*clause)),
+                    }
+                }
+                _ => self.chunk(item),
+            })
+            .collect();
+        let end = chunks.pop().expect("end");
+        Doc::group(Doc::Concat(vec![
+            self.inline(chunks, false),
+            Doc::Line,
+            end.doc,
+        ]))
+    }
+
+    fn block_like_fun(&self, items: &[Item<'_>]) -> Doc<'a> {
+        let mut chunks: Vec<_> = items.iter().map(|item| self.chunk(item)).collect();
+        if chunks.first().and_then(|chunk| chunk.token) != Some("fun") {
+            return self.inline(chunks, false);
+        }
+        let keyword = chunks.remove(0);
+        let end = match chunks.last().and_then(|chunk| chunk.token) {
+            Some("end") => chunks.pop(),
+            _ => None,
+        };
+        let mut parts = vec![
+            keyword.doc,
+            Doc::indent(Doc::Concat(vec![
+                Doc::HardLine,
+                self.separated(chunks, ";", Doc::HardLine),
+            ])),
+        ];
+        if let Some(end) = end {
+            parts.push(Doc::HardLine);
+            parts.push(end.doc);
+        }
+        Doc::Concat(parts)
+    }
+
+    /// Lays out `Left Op Right`, breaking after the operator. A right
+    /// operand that is a container, a call, or a block starts on the
+    /// operator's line and breaks inside instead.
+    fn operator(&self, mut chunks: Vec<Chunk<'a>>) -> Doc<'a> {
+        if chunks.len() != 3 || chunks[1].token.is_none() {
+            return self.inline(chunks, false);
+        }
+        let right = chunks.pop().expect("right");
+        let op = chunks.pop().expect("operator");
+        let left = chunks.pop().expect("left");
+        if right.kind.is_some_and(hugs) {
+            return Doc::Concat(vec![left.doc, Doc::Space, op.doc, Doc::Space, right.doc]);
+        }
+        Doc::group(Doc::Concat(vec![
+            left.doc,
+            Doc::Space,
+            op.doc,
+            Doc::indent(Doc::Concat(vec![Doc::Line, right.doc])),
+        ]))
+    }
+
+    /// Lays out `Name(Vars) :: Type`, breaking after `::` when the type
+    /// does not fit on the line.
+    fn definition(&self, mut chunks: Vec<Chunk<'a>>) -> Doc<'a> {
+        let Some(colons) = chunks.iter().position(|chunk| chunk.token == Some("::")) else {
+            return self.inline(chunks, false);
+        };
+        let body = chunks.split_off(colons + 1);
+        let colons = chunks.pop().expect("`::`");
+        let head = self.inline(chunks, false);
+        if let [chunk] = body.as_slice()
+            && chunk.kind.is_some_and(hugs)
+        {
+            let body = self.inline(body, false);
+            return Doc::Concat(vec![head, Doc::Space, colons.doc, Doc::Space, body]);
+        }
+        Doc::group(Doc::Concat(vec![
+            head,
+            Doc::Space,
+            colons.doc,
+            Doc::indent(Doc::Concat(vec![Doc::Line, self.inline(body, false)])),
+        ]))
+    }
+
+    /// Lays out `A | B | ...`, one alternative per line when broken.
+    /// The grammar nests unions to the left; they are laid out as one.
+    fn union(&self, node: NodeView<'_>) -> Doc<'a> {
+        let mut parts = Vec::new();
+        self.alternatives(node, &mut parts);
+        Doc::group(Doc::Concat(parts))
+    }
+
+    fn alternatives(&self, node: NodeView<'_>, parts: &mut Vec<Doc<'a>>) {
+        for item in self.items(node.range(), node.children()) {
+            match item {
+                Item::Node(child) if child.kind() == SyntaxKind::UnionType => {
+                    self.alternatives(child, parts);
+                }
+                Item::Token(i) if self.text(i) == "|" => {
+                    parts.push(Doc::Line);
+                    parts.push(self.token(i));
+                    parts.push(Doc::Space);
+                }
+                item => parts.push(self.chunk(&item).doc),
+            }
+        }
+    }
+
+    /// Lays out chunks split at the `separator` tokens, with `line`
+    /// after each separator.
+    fn separated(&self, chunks: Vec<Chunk<'a>>, separator: &str, line: Doc<'a>) -> Doc<'a> {
+        let mut parts = Vec::new();
+        let mut segment = Vec::new();
+        for chunk in chunks {
+            if chunk.token == Some(separator) {
+                parts.push(self.inline(std::mem::take(&mut segment), false));
+                parts.push(chunk.doc);
+                parts.push(line.clone());
+            } else {
+                segment.push(chunk);
+            }
+        }
+        parts.push(self.inline(segment, false));
+        Doc::Concat(parts)
+    }
+
+    /// Lays out chunks on one line, turning each bracketed run of the
+    /// node's own tokens (`(...)`, `[...]`, `{...}`, `<<...>>`) into a
+    /// [`Self::sequence`]. `written` keeps the source's choice of space
+    /// between tokens, for attribute payloads the grammar does not read.
+    fn inline(&self, chunks: Vec<Chunk<'a>>, written: bool) -> Doc<'a> {
+        let tokens: Vec<_> = chunks.iter().map(|chunk| chunk.token).collect();
+        let mut chunks: Vec<_> = chunks.into_iter().map(Some).collect();
+        let mut merged: Vec<Chunk<'a>> = Vec::new();
+        let mut i = 0;
+        while i < chunks.len() {
+            if chunks[i].as_ref().is_some_and(|chunk| opens(chunk.token))
+                && let Some(close) = matching(&tokens, i)
+            {
+                let open = chunks[i].take().expect("open");
+                let close_chunk = chunks[close].take().expect("close");
+                let inner = chunks[i + 1..close]
+                    .iter_mut()
+                    .map(|chunk| chunk.take().expect("inner"))
+                    .collect();
+                merged.push(Chunk {
+                    first: open.first,
+                    last: close_chunk.last,
+                    token: None,
+                    kind: None,
+                    doc: self.sequence(open.doc, inner, close_chunk.doc, written),
+                });
+                i = close + 1;
+            } else {
+                merged.push(chunks[i].take().expect("chunk"));
+                i += 1;
+            }
+        }
+        self.join(merged, written)
+    }
+
+    /// Lays out `Open Element, ... Close`: flat when it fits, otherwise
+    /// one element per line, indented, with the closing delimiter on a
+    /// line of its own. A sole element that is itself a container or a
+    /// block hugs the delimiters instead.
+    fn sequence(
+        &self,
+        open: Doc<'a>,
+        inner: Vec<Chunk<'a>>,
+        close: Doc<'a>,
+        written: bool,
+    ) -> Doc<'a> {
+        if inner.is_empty() {
+            return Doc::Concat(vec![open, close]);
+        }
+        let mut elements = vec![Vec::new()];
+        let mut separators = Vec::new();
+        let mut depth = 0usize;
+        for chunk in inner {
+            match chunk.token {
+                Some("," | "|" | "||") if depth == 0 => {
+                    separators.push(chunk);
+                    elements.push(Vec::new());
+                    continue;
+                }
+                token if opens(token) => depth += 1,
+                token if closes(token) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            elements.last_mut().expect("element").push(chunk);
+        }
+        if let [element] = elements.as_slice()
+            && let [chunk] = element.as_slice()
+            && chunk
+                .kind
+                .is_some_and(|kind| hugs(kind) || kind == SyntaxKind::AttributePayload)
+        {
+            let element = elements.pop().expect("element");
+            return Doc::Concat(vec![open, self.inline(element, written), close]);
+        }
+        let mut parts = vec![Doc::SoftLine];
+        let mut separators = separators.into_iter();
+        for (i, element) in elements.into_iter().enumerate() {
+            if i > 0 {
+                let separator = separators.next().expect("separator");
+                if separator.token != Some(",") {
+                    parts.push(Doc::Space);
+                }
+                parts.push(separator.doc);
+                parts.push(Doc::Line);
+            }
+            parts.push(self.inline(element, written));
+        }
+        Doc::group(Doc::Concat(vec![
+            open,
+            Doc::indent(Doc::Concat(parts)),
+            Doc::SoftLine,
+            close,
+        ]))
+    }
+
+    /// Joins chunks with a space wherever [`Self::spaced`] asks for one.
+    fn join(&self, chunks: Vec<Chunk<'a>>, written: bool) -> Doc<'a> {
+        let mut parts = Vec::new();
+        let mut previous: Option<usize> = None;
+        for chunk in chunks {
+            if let (Some(left), Some(right)) = (previous, chunk.first)
+                && self.spaced(left, right, written)
+            {
+                parts.push(Doc::Space);
+            }
+            previous = chunk.last.or(previous);
+            parts.push(chunk.doc);
+        }
+        match parts.len() {
+            0 => Doc::Nil,
+            1 => parts.pop().expect("one part"),
+            _ => Doc::Concat(parts),
+        }
+    }
+
+    /// Returns whether a space goes between the lexical tokens `left`
+    /// and `right` when nothing else separates them.
+    fn spaced(&self, left: usize, right: usize, written: bool) -> bool {
+        if written {
+            return left + 1 < right;
+        }
+        let (a, b) = (self.text(left), self.text(right));
+        if matches!(a, "," | ";") {
+            return true;
+        }
+        if matches!(
+            b,
+            ")" | "]" | "}" | ">>" | "," | "." | ";" | ":" | "/" | ".."
+        ) || matches!(
+            a,
+            "(" | "[" | "{" | "<<" | "#" | "?" | ":" | "/" | "." | ".." | "-" | "+" | "*"
+        ) {
+            return false;
+        }
+        let operand = matches!(
+            self.tokens[left].kind(),
+            TokenKind::Atom | TokenKind::Variable | TokenKind::Integer
+        ) || matches!(a, ")" | "]" | "}" | ">>");
+        match b {
+            "(" => {
+                !(matches!(
+                    self.tokens[left].kind(),
+                    TokenKind::Atom | TokenKind::Variable
+                ) || matches!(a, ")" | "fun"))
+            }
+            "{" => self.tokens[left].kind() != TokenKind::Atom,
+            "#" | "-" | "+" | "*" => !operand,
+            _ => true,
+        }
+    }
+}
+
+/// A direct child of a node being laid out.
+#[derive(Debug, Clone, Copy)]
+enum Item<'t> {
+    Token(usize),
+    Node(NodeView<'t>),
+}
+
+fn opens(token: Option<&str>) -> bool {
+    matches!(token, Some("(" | "[" | "{" | "<<"))
+}
+
+fn closes(token: Option<&str>) -> bool {
+    matches!(token, Some(")" | "]" | "}" | ">>"))
+}
+
+/// Returns the index of the token closing the bracket opened at `open`,
+/// given the token (if any) of each chunk.
+fn matching(tokens: &[Option<&str>], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, &token) in tokens.iter().enumerate().skip(open) {
+        if opens(token) {
+            depth += 1;
+        } else if closes(token) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn is_section(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::ReceiveAfterSection
+            | SyntaxKind::TryOfSection
+            | SyntaxKind::TryCatchSection
+            | SyntaxKind::TryAfterSection
+            | SyntaxKind::MaybeElseSection
+    )
+}
+
+/// Kinds that open with a delimiter or keyword and can break inside,
+/// so they may start on the line of whatever precedes them.
+fn hugs(kind: SyntaxKind) -> bool {
+    use SyntaxKind::*;
+    matches!(
+        kind,
+        TupleExpr
+            | ListExpr
+            | ConsExpr
+            | BitstringExpr
+            | MapExpr
+            | RecordExpr
+            | CallExpr
+            | CaseExpr
+            | IfExpr
+            | ReceiveExpr
+            | TryExpr
+            | BeginExpr
+            | MaybeExpr
+            | AnonymousFun
+            | NamedFun
+            | ListComprehension
+            | MapComprehension
+            | BinaryComprehension
+            | TupleType
+            | ListType
+            | MapType
+            | RecordType
+            | TypeCall
+    )
+}
@@ -0,0 +1,201 @@
+//! Formatting a selected token span.
+//!
+//! The selection is widened to the smallest node that contains it, or
+//! to the roots it overlaps when it crosses a unit boundary. A node that
+//! error recovery left a hole in is replaced by its children that
+//! overlap the selection, recursively, so only clean nodes are laid
+//! out. Each node is printed at the indentation of the line it starts
+//! on, and the result is compared with the source one gap (the
+//! whitespace and comments between two lexical tokens) at a time.
+//! Only gaps inside the selection become edits.
+
+use std::ops::Range;
+
+use erl_tokenize::{Position, Token, TokenKind};
+
+use super::Options;
+use super::doc;
+use super::layout::Layout;
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+pub(crate) fn edits(
+    tree: &SyntaxTree,
+    source: &str,
+    selection: TokenRange,
+    options: Options,
+) -> Option<Vec<(Range<usize>, String)>> {
+    let tokens = tree.tokens();
+    let lexical: Vec<usize> = (selection.start().get()..selection.end().get().min(tokens.len()))
+        .filter(|&i| tokens[i].kind().is_lexical())
+        .collect();
+    let (Some(&first), Some(&last)) = (lexical.first(), lexical.last()) else {
+        return Some(Vec::new());
+    };
+    let broken = tree.roots().any(|root| {
+        std::iter::once(root)
+            .chain(root.descendants())
+            .any(|node| node.kind() == SyntaxKind::Error && overlaps(node.range(), selection))
+    });
+    if broken
+        || tree
+            .diagnostics()
+            .iter()
+            .any(|diagnostic| overlaps(diagnostic.range(), selection))
+    {
+        return None;
+    }
+
+    let layout = Layout::new(tree, source);
+    let mut targets = Vec::new();
+    match enclosing(tree, first, last) {
+        Some(node) => collect(&layout, node, selection, &mut targets),
+        None => {
+            for root in tree
+                .roots()
+                .filter(|root| overlaps(root.range(), selection))
+            {
+                collect(&layout, root, selection, &mut targets);
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    for (node, document) in targets {
+        let old: Vec<usize> = node
+            .range()
+            .as_range()
+            .filter(|&i| tokens[i].kind().is_lexical())
+            .collect();
+        let Some(&start) = old.first() else {
+            continue;
+        };
+        let offset = tokens[start].start().offset();
+        let line = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let level = source[line..offset]
+            .chars()
+            .take_while(|&c| c == ' ')
+            .count();
+        let column = source[line..offset].chars().count();
+        let text = doc::print_at(&document, options.line_width, options.indent, level, column);
+        let Some(new) = scan_all(&text) else {
+            continue;
+        };
+        let new: Vec<&Token> = new.iter().filter(|t| t.kind().is_lexical()).collect();
+        if new.len() != old.len() {
+            continue;
+        }
+        let inside = |gap: usize| {
+            selection.start().get() <= old[gap] && old[gap + 1] < selection.end().get()
+        };
+        let count = old.len();
+        let old = Gaps {
+            source,
+            lexical: old.iter().map(|&i| &tokens[i]).collect(),
+        };
+        let new = Gaps {
+            source: &text,
+            lexical: new,
+        };
+        let mut gap = 0;
+        while gap + 1 < count {
+            if old.text(gap, gap) == new.text(gap, gap) {
+                gap += 1;
+                continue;
+            }
+            // A comment the layout moved past a token changes two gaps;
+            // they are replaced together.
+            let mut end = gap;
+            while old.comments(gap, end) != new.comments(gap, end) && end + 2 < count {
+                end += 1;
+            }
+            if old.comments(gap, end) == new.comments(gap, end) && inside(gap) && inside(end) {
+                edits.push((old.span(gap, end), new.text(gap, end).to_string()));
+            }
+            gap = end + 1;
+        }
+    }
+    Some(edits)
+}
+
+/// Returns whether `range` and `selection` share a token; an empty
+/// range shares one with a selection it touches.
+fn overlaps(range: TokenRange, selection: TokenRange) -> bool {
+    let (start, end) = (range.start().get(), range.end().get());
+    if range.is_empty() {
+        (selection.start().get()..=selection.end().get()).contains(&start)
+    } else {
+        start < selection.end().get() && selection.start().get() < end
+    }
+}
+
+/// Returns the innermost node that contains both `first` and `last`.
+fn enclosing(tree: &SyntaxTree, first: usize, last: usize) -> Option<NodeView<'_>> {
+    let node = tree.innermost_containing(TokenIndex::new(first))?;
+    let mut ancestors: Vec<_> = node.ancestors().collect();
+    ancestors.reverse();
+    std::iter::once(node)
+        .chain(ancestors)
+        .find(|node| node.range().as_range().contains(&last))
+}
+
+/// Adds `node` to `targets` when it lays out cleanly, otherwise its
+/// children that overlap `selection`.
+fn collect<'t, 'a>(
+    layout: &Layout<'a>,
+    node: NodeView<'t>,
+    selection: TokenRange,
+    targets: &mut Vec<(NodeView<'t>, doc::Doc<'a>)>,
+) {
+    match layout.fragment(node) {
+        Some(document) => targets.push((node, document)),
+        None => {
+            for child in node
+                .children()
+                .filter(|child| overlaps(child.range(), selection))
+            {
+                collect(layout, child, selection, targets);
+            }
+        }
+    }
+}
+
+/// The lexical tokens of a node in one text.
+struct Gaps<'s> {
+    source: &'s str,
+    lexical: Vec<&'s Token>,
+}
+
+impl<'s> Gaps<'s> {
+    /// Returns the byte span from the start of gap `first` to the end
+    /// of gap `last`; gap `i` follows lexical token `i`.
+    fn span(&self, first: usize, last: usize) -> Range<usize> {
+        self.lexical[first].end().offset()..self.lexical[last + 1].start().offset()
+    }
+
+    fn text(&self, first: usize, last: usize) -> &'s str {
+        &self.source[self.span(first, last)]
+    }
+
+    fn comments(&self, first: usize, last: usize) -> Vec<&'s str> {
+        let text = self.text(first, last);
+        scan_all(text)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|t| t.kind() == TokenKind::Comment)
+            .map(|t| t.text(text))
+            .collect()
+    }
+}
+
+fn scan_all(text: &str) -> Option<Vec<Token>> {
+    let mut out = Vec::new();
+    let mut position = Position::new();
+    while let Some(token) = erl_tokenize::scan_token(text, position).ok()? {
+        position = token.end();
+        out.push(token);
+    }
+    Some(out)
+}
@@ -15,12 +15,15 @@
 //! - [`attribute`], [`function`], and [`form`] parse module-level
 //!   forms; [`module`] and [`term_list`] wrap them as the top-level
 //!   driver entry points for their respective parse modes.
+//! - [`attribute_payload`] gives well-known attribute payloads a
+//!   structured reading on request.
 //!
 //! Grammar structure and precedence values track OTP 29's
 //! `lib/stdlib/src/erl_parse.yrl`; the productions this crate accepts
 //! may lag or lead a specific OTP release as the language evolves.
 
 pub(crate) mod attribute;
+pub(crate) mod attribute_payload;
 pub(crate) mod clause;
 pub(crate) mod expr;
 pub(crate) mod form;
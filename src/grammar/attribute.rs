@@ -23,9 +23,12 @@
 //! unpreprocessed `-define` / `-include` / conditional directives all
 //! flow through the same code path. The name atom's spelling is
 //! available to the caller by reading the [`AttributeName`] child's
-//! range from the token buffer.
+//! range from the token buffer. Callers that hold the source text can
+//! re-read a well-known payload (`-spec`, `-type`, `-record`,
+//! `-export`, ...) into structured nodes with
+//! [`SyntaxTree::parse_attribute_payload`](crate::SyntaxTree::parse_attribute_payload).
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, code};
 use crate::grammar::util::expect_symbol;
 use crate::parser::{CompletedMarker, Parser};
 use crate::syntax::SyntaxKind;
@@ -35,11 +38,7 @@ use crate::token_range::TokenRange;
 pub(crate) fn parse_attribute(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
 
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::Hyphen,
-        "`-` to open attribute form",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::Hyphen, &code::ATTRIBUTE_DASH);
     parse_attribute_name(p);
     parse_attribute_payload(p);
 
@@ -55,14 +54,14 @@ fn parse_attribute_name(p: &mut Parser) {
         }
         _ => {
             let found = p.peek_lexical(0).map(|(_, t)| t);
-            p.push_diagnostic(Diagnostic::new(
+            p.push_diagnostic(Diagnostic::for_category(
                 if found.is_some() {
                     DiagnosticKind::UnexpectedToken
                 } else {
                     DiagnosticKind::UnexpectedEof
                 },
                 TokenRange::empty_at(start_at),
-                Expected::Category("attribute name (atom) after `-`"),
+                &code::ATTRIBUTE_NAME,
                 found,
             ));
         }
@@ -103,7 +102,7 @@ fn parse_attribute_payload(p: &mut Parser) {
         expect_symbol(
             p,
             erl_tokenize::Symbol::CloseParen,
-            "`)` to close attribute payload",
+            &code::ATTRIBUTE_PAYLOAD_CLOSE,
         );
     }
 }
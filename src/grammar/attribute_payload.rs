@@ -0,0 +1,270 @@
+//! Structured grammar for well-known attribute payloads.
+//!
+//! The module-mode driver keeps every attribute payload opaque (see
+//! [`crate::grammar::attribute`]). This module is the opt-in second
+//! pass behind [`crate::SyntaxTree::parse_attribute_payload`]: the
+//! caller (who has the source text) resolves the attribute name to a
+//! [`PayloadShape`], and [`parse_payload`] runs the matching
+//! production over the payload tokens.
+//!
+//! Productions follow the yrl's `attribute` rules:
+//!
+//! ```text
+//! -spec / -callback        type_spec   -> spec_fun type_sigs | '(' spec_fun type_sigs ')'
+//! -type / -opaque / ...    typed_attr  -> expr '::' top_type   (optionally parenthesized)
+//! -record                  '(' atom ',' '{' typed_record_fields '}' ')'
+//! -export / ...            '(' '[' atom '/' integer, ... ']' ')'
+//! anything else            attr_val    -> expr | '(' exprs ')'
+//! ```
+
+use crate::diagnostic::{DiagnosticCode, code};
+use crate::grammar::clause::parse_argument_list;
+use crate::grammar::expr::parse_expr;
+use crate::grammar::recovery::{push_missing_token, skip_until_sync};
+use crate::grammar::ty::{parse_spec_clause, parse_type, parse_type_argument_list};
+use crate::grammar::util::{at_symbol, expect_symbol, is_symbol};
+use crate::parser::{CompletedMarker, Parser, RecoveryContext};
+use crate::syntax::SyntaxKind;
+
+/// Which production a payload is read with, chosen from the
+/// attribute name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PayloadShape {
+    /// `-spec`, `-callback`.
+    Spec,
+    /// `-type`, `-opaque`, `-nominal`.
+    TypeDecl,
+    /// `-record`.
+    Record,
+    /// `-export`, `-export_type`, `-optional_callbacks`.
+    NameArityList,
+    /// Any other attribute: a bare expression or a parenthesized
+    /// expression list.
+    Generic,
+}
+
+impl PayloadShape {
+    /// Maps an (unquoted) attribute name to its payload shape.
+    /// Returns `None` for preprocessor directives, whose payloads are
+    /// macro bodies or conditions rather than attribute values.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "define" | "undef" | "include" | "include_lib" | "ifdef" | "ifndef" | "else"
+            | "endif" | "if" | "elif" => None,
+            "spec" | "callback" => Some(Self::Spec),
+            "type" | "opaque" | "nominal" => Some(Self::TypeDecl),
+            "record" => Some(Self::Record),
+            "export" | "export_type" | "optional_callbacks" => Some(Self::NameArityList),
+            _ => Some(Self::Generic),
+        }
+    }
+}
+
+/// Parses one payload with `shape`, starting at the cursor and
+/// running to the end of the buffer. Tokens the production leaves
+/// unconsumed are swept into a trailing [`SyntaxKind::Error`] node.
+pub(crate) fn parse_payload(p: &mut Parser, shape: PayloadShape) -> CompletedMarker {
+    let completed = match shape {
+        PayloadShape::Spec => parse_spec(p),
+        PayloadShape::TypeDecl => parse_type_decl(p),
+        PayloadShape::Record => parse_record_decl(p),
+        PayloadShape::NameArityList => parse_name_arity_payload(p),
+        PayloadShape::Generic if at_symbol(p, erl_tokenize::Symbol::OpenParen) => {
+            parse_argument_list(p)
+        }
+        PayloadShape::Generic => parse_expr(p),
+    };
+    let _ = skip_until_sync(
+        p,
+        RecoveryContext::Form,
+        |_| false,
+        &code::ATTRIBUTE_PAYLOAD_END,
+    );
+    completed
+}
+
+/// Consumes an optional outer `(`; returns whether it was present so
+/// the caller can expect the matching `)`.
+fn open_optional_paren(p: &mut Parser) -> bool {
+    if at_symbol(p, erl_tokenize::Symbol::OpenParen) {
+        p.consume_lexical();
+        true
+    } else {
+        false
+    }
+}
+
+fn consume_atom(p: &mut Parser, category: &'static DiagnosticCode) {
+    match p.peek_lexical(0).map(|(_, t)| t.kind()) {
+        Some(erl_tokenize::TokenKind::Atom) => {
+            p.consume_lexical();
+        }
+        _ => push_missing_token(p, category),
+    }
+}
+
+fn parse_spec(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    let parenthesized = open_optional_paren(p);
+    consume_atom(p, &code::SPEC_NAME);
+    if at_symbol(p, erl_tokenize::Symbol::Colon) {
+        p.consume_lexical();
+        consume_atom(p, &code::SPEC_REMOTE_NAME);
+    }
+    parse_spec_clause(p);
+    while at_symbol(p, erl_tokenize::Symbol::Semicolon) {
+        p.consume_lexical();
+        parse_spec_clause(p);
+    }
+    if parenthesized {
+        expect_symbol(
+            p,
+            erl_tokenize::Symbol::CloseParen,
+            &code::SPEC_PAYLOAD_CLOSE,
+        );
+    }
+    m.complete(p, SyntaxKind::SpecAttribute)
+}
+
+fn parse_type_decl(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    let parenthesized = open_optional_paren(p);
+    consume_atom(p, &code::TYPE_DECL_NAME);
+    parse_type_argument_list(p);
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::DoubleColon,
+        &code::TYPE_DECL_COLONS,
+    );
+    parse_type(p);
+    if parenthesized {
+        expect_symbol(p, erl_tokenize::Symbol::CloseParen, &code::TYPE_DECL_CLOSE);
+    }
+    m.complete(p, SyntaxKind::TypeDecl)
+}
+
+fn parse_record_decl(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    expect_symbol(p, erl_tokenize::Symbol::OpenParen, &code::RECORD_DECL_OPEN);
+    consume_atom(p, &code::RECORD_DECL_NAME);
+    expect_symbol(p, erl_tokenize::Symbol::Comma, &code::RECORD_DECL_COMMA);
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::OpenBrace,
+        &code::RECORD_DECL_FIELDS_OPEN,
+    );
+    if !at_symbol(p, erl_tokenize::Symbol::CloseBrace) {
+        parse_record_decl_field(p);
+        loop {
+            if !at_symbol(p, erl_tokenize::Symbol::Comma)
+                && !at_symbol(p, erl_tokenize::Symbol::CloseBrace)
+            {
+                let _ = skip_until_sync(
+                    p,
+                    RecoveryContext::Container,
+                    |t| {
+                        is_symbol(t, erl_tokenize::Symbol::Comma)
+                            || is_symbol(t, erl_tokenize::Symbol::CloseBrace)
+                    },
+                    &code::RECORD_DECL_FIELD_SEPARATOR,
+                );
+            }
+            if !at_symbol(p, erl_tokenize::Symbol::Comma) {
+                break;
+            }
+            p.consume_lexical();
+            parse_record_decl_field(p);
+        }
+    }
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::CloseBrace,
+        &code::RECORD_DECL_FIELDS_CLOSE,
+    );
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::CloseParen,
+        &code::RECORD_DECL_CLOSE,
+    );
+    m.complete(p, SyntaxKind::RecordDecl)
+}
+
+fn parse_record_decl_field(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    consume_atom(p, &code::RECORD_DECL_FIELD_NAME);
+    if at_symbol(p, erl_tokenize::Symbol::Match) {
+        p.consume_lexical();
+        parse_expr(p);
+    }
+    if at_symbol(p, erl_tokenize::Symbol::DoubleColon) {
+        p.consume_lexical();
+        parse_type(p);
+    }
+    m.complete(p, SyntaxKind::RecordDeclField)
+}
+
+/// `( [Name/Arity, ...] )`: the outer parentheses belong to the
+/// payload, the list is the [`SyntaxKind::ExportList`] node.
+fn parse_name_arity_payload(p: &mut Parser) -> CompletedMarker {
+    let parenthesized = open_optional_paren(p);
+    let list = parse_export_list(p);
+    if parenthesized {
+        expect_symbol(
+            p,
+            erl_tokenize::Symbol::CloseParen,
+            &code::ATTRIBUTE_PAYLOAD_CLOSE,
+        );
+    }
+    list
+}
+
+fn parse_export_list(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::OpenSquare,
+        &code::NAME_ARITY_LIST_OPEN,
+    );
+    if !at_symbol(p, erl_tokenize::Symbol::CloseSquare) {
+        parse_name_arity(p);
+        loop {
+            if !at_symbol(p, erl_tokenize::Symbol::Comma)
+                && !at_symbol(p, erl_tokenize::Symbol::CloseSquare)
+            {
+                let _ = skip_until_sync(
+                    p,
+                    RecoveryContext::Container,
+                    |t| {
+                        is_symbol(t, erl_tokenize::Symbol::Comma)
+                            || is_symbol(t, erl_tokenize::Symbol::CloseSquare)
+                    },
+                    &code::NAME_ARITY_LIST_SEPARATOR,
+                );
+            }
+            if !at_symbol(p, erl_tokenize::Symbol::Comma) {
+                break;
+            }
+            p.consume_lexical();
+            parse_name_arity(p);
+        }
+    }
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::CloseSquare,
+        &code::NAME_ARITY_LIST_CLOSE,
+    );
+    m.complete(p, SyntaxKind::ExportList)
+}
+
+fn parse_name_arity(p: &mut Parser) -> CompletedMarker {
+    let m = p.start();
+    consume_atom(p, &code::NAME_ARITY_NAME);
+    expect_symbol(p, erl_tokenize::Symbol::Slash, &code::NAME_ARITY_SLASH);
+    match p.peek_lexical(0).map(|(_, t)| t.kind()) {
+        Some(erl_tokenize::TokenKind::Integer) => {
+            p.consume_lexical();
+        }
+        _ => push_missing_token(p, &code::NAME_ARITY_ARITY),
+    }
+    m.complete(p, SyntaxKind::NameArity)
+}
@@ -14,28 +14,27 @@
 //! forms (calls, blocks, and other general expressions) are rejected
 //! in pattern position while sharing the same node shape.
 
+use crate::diagnostic::{Diagnostic, DiagnosticCode, DiagnosticKind, Fix, code};
 use crate::grammar::expr::{parse_comma_separated_exprs, parse_expr, parse_expr_max};
 use crate::grammar::pattern::parse_pattern;
-use crate::grammar::util::{at_keyword, at_symbol, consume_atom_or_var, expect_symbol};
+use crate::grammar::util::{at_keyword, at_symbol, consume_atom_or_var, expect_symbol, is_symbol};
 use crate::parser::{CompletedMarker, Parser};
 use crate::syntax::SyntaxKind;
+use crate::token_range::{TokenIndex, TokenRange};
 
 /// Parses a comma-separated list of expressions as a
 /// [`SyntaxKind::Body`] node. Corresponds to `exprs` in OTP 29's yrl.
 pub(crate) fn parse_body(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    parse_exprs_comma(p);
+    let comma = parse_exprs_comma(p);
+    p.set_body_comma(comma);
     m.complete(p, SyntaxKind::Body)
 }
 
 /// Parses a `-> Body` sequence: consumes the arrow, then parses a
 /// body. Used by every clause form.
 pub(crate) fn parse_arrow_body(p: &mut Parser) -> CompletedMarker {
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::RightArrow,
-        "`->` before clause body",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::RightArrow, &code::CLAUSE_ARROW);
     parse_body(p)
 }
 
@@ -108,7 +107,7 @@ pub(crate) fn parse_try_clause(p: &mut Parser) -> CompletedMarker {
         // the full Pratt loop here would swallow the optional stack
         // `:` as a remote qualifier, so match is consumed explicitly
         // and `:` is left for the stacktrace production.
-        consume_atom_or_var(p, "class name in catch clause");
+        consume_atom_or_var(p, &code::CATCH_CLASS);
         p.consume_lexical(); // `:`
         let prev = p.set_context(crate::parser::ParseContext::Pattern);
         parse_expr_max(p);
@@ -119,7 +118,7 @@ pub(crate) fn parse_try_clause(p: &mut Parser) -> CompletedMarker {
         p.set_context(prev);
         if at_symbol(p, erl_tokenize::Symbol::Colon) {
             p.consume_lexical();
-            consume_atom_or_var(p, "stack-trace variable");
+            consume_atom_or_var(p, &code::STACKTRACE_VARIABLE);
         }
         parse_clause_guard_opt(p);
         parse_arrow_body(p);
@@ -152,7 +151,12 @@ fn is_class_qualified_try_clause_head(p: &Parser) -> bool {
 /// becomes a [`SyntaxKind::Error`] node with a matching
 /// [`crate::diagnostic::DiagnosticKind::SkippedToken`] diagnostic and
 /// the loop resumes from the sync token. This keeps a garbled
-/// clause head from swallowing the rest of the block.
+/// clause head from swallowing the rest of the block. When the
+/// garbage starts with `->`, the last body probably ended with `,`
+/// where `;` was meant, and the diagnostic suggests that swap.
+///
+/// A `;` directly followed by a terminator is reported on its own,
+/// with a fix deleting it, instead of parsing an empty clause.
 pub(crate) fn parse_semicolon_separated<F>(p: &mut Parser, mut production: F)
 where
     F: FnMut(&mut Parser) -> CompletedMarker,
@@ -160,21 +164,66 @@ where
     production(p);
     loop {
         if !at_symbol(p, erl_tokenize::Symbol::Semicolon) && !at_clause_boundary(p) {
-            let _ = crate::grammar::recovery::skip_until_sync(
+            let fix = p
+                .peek_lexical(0)
+                .filter(|&(_, t)| is_symbol(t, erl_tokenize::Symbol::RightArrow))
+                .and(p.body_comma())
+                .map(|comma| Fix::Replace {
+                    range: TokenRange::new(comma, TokenIndex::new(comma.get() + 1)),
+                    text: ";",
+                });
+            let _ = crate::grammar::recovery::skip_until_sync_with_fix(
                 p,
                 crate::parser::RecoveryContext::Clause,
                 is_clause_boundary,
-                "`;` or block terminator",
+                &code::CLAUSE_SEPARATOR,
+                fix,
             );
         }
-        if !at_symbol(p, erl_tokenize::Symbol::Semicolon) {
+        let Some((semicolon, _)) = p
+            .peek_lexical(0)
+            .filter(|&(_, t)| is_symbol(t, erl_tokenize::Symbol::Semicolon))
+        else {
             break;
-        }
+        };
         p.consume_lexical();
+        if at_clause_boundary(p) {
+            push_stray_semicolon(p, semicolon, None, &code::CLAUSE_AFTER_SEMICOLON);
+            continue;
+        }
         production(p);
     }
 }
 
+/// Reports a `;` that is not followed by another clause. The fix
+/// replaces it with `replacement`, or deletes it when that is `None`.
+pub(crate) fn push_stray_semicolon(
+    p: &mut Parser,
+    semicolon: TokenIndex,
+    replacement: Option<&'static str>,
+    category: &'static DiagnosticCode,
+) {
+    let found = p.peek_lexical(0).map(|(_, t)| t);
+    let range = TokenRange::new(semicolon, TokenIndex::new(semicolon.get() + 1));
+    let fix = match replacement {
+        Some(text) => Fix::Replace { range, text },
+        None => Fix::Delete(range),
+    };
+    p.push_diagnostic(
+        Diagnostic::for_category(
+            if found.is_some() {
+                DiagnosticKind::UnexpectedToken
+            } else {
+                DiagnosticKind::UnexpectedEof
+            },
+            TokenRange::empty_at(p.cursor_position()),
+            category,
+            found,
+        )
+        .with_fix(Some(fix)),
+    );
+}
+
 fn at_clause_boundary(p: &Parser) -> bool {
     p.peek_lexical(0).is_none_or(|(_, t)| is_clause_boundary(t))
 }
@@ -197,28 +246,27 @@ fn is_clause_boundary(token: erl_tokenize::Token) -> bool {
 /// reused by function declarations in the form / module grammar.
 pub(crate) fn parse_argument_list(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::OpenParen,
-        "`(` to open argument list",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::OpenParen, &code::ARGUMENTS_OPEN);
     if at_symbol(p, erl_tokenize::Symbol::CloseParen) {
         p.consume_lexical();
         return m.complete(p, SyntaxKind::ArgumentList);
     }
     parse_comma_separated_exprs(p, erl_tokenize::Symbol::CloseParen);
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseParen,
-        "`)` to close argument list",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseParen, &code::ARGUMENTS_CLOSE);
     m.complete(p, SyntaxKind::ArgumentList)
 }
 
-fn parse_exprs_comma(p: &mut Parser) {
+/// Parses `Expr, Expr, ...` and returns the index of the last `,`.
+fn parse_exprs_comma(p: &mut Parser) -> Option<TokenIndex> {
+    let mut comma = None;
     parse_expr(p);
-    while at_symbol(p, erl_tokenize::Symbol::Comma) {
+    while let Some((index, _)) = p
+        .peek_lexical(0)
+        .filter(|&(_, t)| is_symbol(t, erl_tokenize::Symbol::Comma))
+    {
         p.consume_lexical();
+        comma = Some(index);
         parse_expr(p);
     }
+    comma
 }
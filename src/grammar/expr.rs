@@ -14,7 +14,7 @@
 //!
 //! Grammar shape follows OTP 29's `lib/stdlib/src/erl_parse.yrl`.
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::diagnostic::{Diagnostic, DiagnosticCode, DiagnosticKind, code};
 use crate::grammar::clause::{
     parse_argument_list, parse_arrow_body, parse_body, parse_case_clause, parse_clause_guard_opt,
     parse_if_clause, parse_semicolon_separated, parse_try_clause,
@@ -76,10 +76,10 @@ fn parse_expr_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
                 // Two non-associative operators of equal precedence in a
                 // row (for example `1 == 2 == 3`); record the error and
                 // keep parsing so downstream consumers still see a tree.
-                p.push_diagnostic(Diagnostic::new(
+                p.push_diagnostic(Diagnostic::for_category(
                     DiagnosticKind::UnexpectedToken,
                     TokenRange::empty_at(p.cursor_position()),
-                    Expected::Category("non-associative operator used twice"),
+                    &code::NON_ASSOCIATIVE_OPERATOR,
                     Some(token),
                 ));
             }
@@ -88,10 +88,10 @@ fn parse_expr_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
             // may appear in pattern / term position.
             match kind {
                 SyntaxKind::SendExpr | SyntaxKind::MaybeMatchExpr => {
-                    reject_in_restricted(p, "send / maybe-match not allowed here");
+                    reject_in_restricted(p, &code::SEND_NOT_ALLOWED);
                 }
                 SyntaxKind::MatchExpr if p.context() == ParseContext::Term => {
-                    push_context_error(p, "match `=` not allowed in term position");
+                    push_context_error(p, &code::MATCH_IN_TERM);
                 }
                 _ => {}
             }
@@ -105,7 +105,7 @@ fn parse_expr_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
 
         // Call suffix `(...)`. `Left 750 '('` in the yrl.
         if is_symbol(token, erl_tokenize::Symbol::OpenParen) && CALL_LBP > min_bp {
-            reject_in_restricted(p, "call not allowed here");
+            reject_in_restricted(p, &code::CALL_NOT_ALLOWED);
             let m = lhs.precede(p);
             parse_argument_list(p);
             lhs = m.complete(p, SyntaxKind::CallExpr);
@@ -115,7 +115,7 @@ fn parse_expr_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
 
         // Remote qualifier `Mod : Fun`. `Nonassoc 800 ':'` in the yrl.
         if is_symbol(token, erl_tokenize::Symbol::Colon) && REMOTE_LBP > min_bp {
-            reject_in_restricted(p, "remote qualifier `:` not allowed here");
+            reject_in_restricted(p, &code::REMOTE_NOT_ALLOWED);
             let m = lhs.precede(p);
             p.consume_lexical();
             parse_expr_max(p);
@@ -163,10 +163,10 @@ fn parse_expr_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
 pub(crate) fn parse_expr_max(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
     let Some((idx, token)) = p.peek_lexical(0) else {
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             DiagnosticKind::UnexpectedEof,
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("expression"),
+            &code::EXPRESSION,
             None,
         ));
         return m.complete(p, SyntaxKind::Error);
@@ -176,7 +176,7 @@ pub(crate) fn parse_expr_max(p: &mut Parser) -> CompletedMarker {
     if let Some(rbp) = operator::prefix_binding_power(token) {
         let is_catch = is_keyword(token, erl_tokenize::Keyword::Catch);
         if is_catch {
-            reject_in_restricted(p, "`catch` prefix not allowed here");
+            reject_in_restricted(p, &code::CATCH_NOT_ALLOWED);
         }
         p.consume_lexical();
         parse_expr_bp(p, rbp);
@@ -192,7 +192,7 @@ pub(crate) fn parse_expr_max(p: &mut Parser) -> CompletedMarker {
         erl_tokenize::TokenKind::Atom => atomic(p, m, SyntaxKind::AtomExpr),
         erl_tokenize::TokenKind::Variable => {
             if p.context() == ParseContext::Term {
-                push_context_error(p, "variable not allowed in term position");
+                push_context_error(p, &code::VARIABLE_IN_TERM);
             }
             atomic(p, m, SyntaxKind::VarExpr)
         }
@@ -241,7 +241,7 @@ pub(crate) fn parse_expr_max(p: &mut Parser) -> CompletedMarker {
             // token and its `TokenRange` matches the
             // `SkippedToken` diagnostic's `range()`.
             m.abandon(p);
-            crate::grammar::recovery::skip_one_token(p, "expression")
+            crate::grammar::recovery::skip_one_token(p, &code::EXPRESSION)
         }
     }
 }
@@ -254,11 +254,7 @@ fn atomic(p: &mut Parser, m: crate::parser::Marker, kind: SyntaxKind) -> Complet
 fn parse_paren(p: &mut Parser, m: crate::parser::Marker) -> CompletedMarker {
     p.consume_lexical(); // `(`
     parse_expr_bp(p, 0);
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseParen,
-        "`)` to close parenthesized expression",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseParen, &code::PAREN_EXPR_CLOSE);
     m.complete(p, SyntaxKind::ParenExpr)
 }
 
@@ -269,7 +265,7 @@ fn parse_tuple(p: &mut Parser, m: crate::parser::Marker) -> CompletedMarker {
         return m.complete(p, SyntaxKind::TupleExpr);
     }
     parse_comma_separated_exprs(p, erl_tokenize::Symbol::CloseBrace);
-    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, "`}` to close tuple");
+    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, &code::TUPLE_CLOSE);
     m.complete(p, SyntaxKind::TupleExpr)
 }
 
@@ -286,20 +282,20 @@ fn parse_list(p: &mut Parser, m: crate::parser::Marker) -> CompletedMarker {
     parse_expr_bp(p, 0);
     loop {
         if at_symbol(p, erl_tokenize::Symbol::DoubleVerticalBar) {
-            reject_in_restricted(p, "list comprehension not allowed here");
+            reject_in_restricted(p, &code::LIST_COMPREHENSION_NOT_ALLOWED);
             p.consume_lexical();
             parse_comprehension_qualifiers(p);
             expect_symbol(
                 p,
                 erl_tokenize::Symbol::CloseSquare,
-                "`]` to close list comprehension",
+                &code::LIST_COMPREHENSION_CLOSE,
             );
             return m.complete(p, SyntaxKind::ListComprehension);
         }
         if at_symbol(p, erl_tokenize::Symbol::VerticalBar) {
             p.consume_lexical();
             parse_expr_bp(p, 0);
-            expect_symbol(p, erl_tokenize::Symbol::CloseSquare, "`]` to close list");
+            expect_symbol(p, erl_tokenize::Symbol::CloseSquare, &code::LIST_CLOSE);
             return m.complete(p, SyntaxKind::ConsExpr);
         }
         if at_symbol(p, erl_tokenize::Symbol::Comma) {
@@ -309,7 +305,7 @@ fn parse_list(p: &mut Parser, m: crate::parser::Marker) -> CompletedMarker {
         }
         break;
     }
-    expect_symbol(p, erl_tokenize::Symbol::CloseSquare, "`]` to close list");
+    expect_symbol(p, erl_tokenize::Symbol::CloseSquare, &code::LIST_CLOSE);
     m.complete(p, SyntaxKind::ListExpr)
 }
 
@@ -335,7 +331,7 @@ pub(crate) fn parse_comma_separated_exprs(p: &mut Parser, close: erl_tokenize::S
                     crate::grammar::util::is_symbol(t, erl_tokenize::Symbol::Comma)
                         || crate::grammar::util::is_symbol(t, close)
                 },
-                "`,` or closing delimiter",
+                &code::SEPARATOR_OR_CLOSE,
             );
         }
         if !at_symbol(p, erl_tokenize::Symbol::Comma) {
@@ -346,10 +342,10 @@ pub(crate) fn parse_comma_separated_exprs(p: &mut Parser, close: erl_tokenize::S
             // Trailing comma before the closing delimiter is a syntax
             // error at the yrl level (Erlang does not accept it); record
             // and let the caller close the group.
-            p.push_diagnostic(Diagnostic::new(
+            p.push_diagnostic(Diagnostic::for_category(
                 DiagnosticKind::UnexpectedToken,
                 TokenRange::empty_at(p.cursor_position()),
-                Expected::Category("expression after `,`"),
+                &code::EXPRESSION_AFTER_COMMA,
                 p.peek_lexical(0).map(|(_, t)| t),
             ));
             break;
@@ -374,35 +370,35 @@ fn binary_op_kind(token: erl_tokenize::Token) -> SyntaxKind {
 
 /// `begin Exprs end`.
 fn parse_begin(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`begin` block not allowed here");
+    reject_in_restricted(p, &code::BEGIN_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `begin`
     parse_body(p);
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `begin`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::BEGIN_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::BeginExpr)
 }
 
 /// `case Expr of Clause; Clause; ... end`.
 fn parse_case(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`case` block not allowed here");
+    reject_in_restricted(p, &code::CASE_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `case`
     parse_expr(p);
-    expect_keyword(p, erl_tokenize::Keyword::Of, "`of` in `case` expression");
+    expect_keyword(p, erl_tokenize::Keyword::Of, &code::CASE_OF);
     parse_semicolon_separated(p, parse_case_clause);
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `case`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::CASE_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::CaseExpr)
 }
 
 /// `if Guard -> Body ; Guard -> Body ; ... end`.
 fn parse_if(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`if` block not allowed here");
+    reject_in_restricted(p, &code::IF_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `if`
     parse_semicolon_separated(p, parse_if_clause);
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `if`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::IF_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::IfExpr)
 }
@@ -413,7 +409,7 @@ fn parse_if(p: &mut Parser, m: Marker) -> CompletedMarker {
 /// [`SyntaxKind::ReceiveAfterSection`] so callers can find it via
 /// child kind without scanning terminal keywords.
 fn parse_receive(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`receive` block not allowed here");
+    reject_in_restricted(p, &code::RECEIVE_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `receive`
     if !at_keyword(p, erl_tokenize::Keyword::After) && !at_keyword(p, erl_tokenize::Keyword::End) {
@@ -426,7 +422,7 @@ fn parse_receive(p: &mut Parser, m: Marker) -> CompletedMarker {
         parse_arrow_body(p);
         section.complete(p, SyntaxKind::ReceiveAfterSection);
     }
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `receive`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::RECEIVE_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::ReceiveExpr)
 }
@@ -438,7 +434,7 @@ fn parse_receive(p: &mut Parser, m: Marker) -> CompletedMarker {
 /// accepts either or both without enforcing that either is present;
 /// error-recovery contracts tighten this in a later change.
 fn parse_try(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`try` block not allowed here");
+    reject_in_restricted(p, &code::TRY_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `try`
     parse_body(p);
@@ -465,7 +461,7 @@ fn parse_try(p: &mut Parser, m: Marker) -> CompletedMarker {
         parse_body(p);
         section.complete(p, SyntaxKind::TryAfterSection);
     }
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `try`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::TRY_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::TryExpr)
 }
@@ -474,7 +470,7 @@ fn parse_try(p: &mut Parser, m: Marker) -> CompletedMarker {
 /// the shared infix table (see [`crate::grammar::operator`]) and
 /// materialises as [`SyntaxKind::MaybeMatchExpr`].
 fn parse_maybe(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`maybe` block not allowed here");
+    reject_in_restricted(p, &code::MAYBE_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     p.consume_lexical(); // `maybe`
     parse_body(p);
@@ -484,7 +480,7 @@ fn parse_maybe(p: &mut Parser, m: Marker) -> CompletedMarker {
         parse_semicolon_separated(p, parse_case_clause);
         section.complete(p, SyntaxKind::MaybeElseSection);
     }
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `maybe`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::MAYBE_END);
     p.set_context(prev);
     m.complete(p, SyntaxKind::MaybeExpr)
 }
@@ -504,7 +500,7 @@ fn parse_maybe(p: &mut Parser, m: Marker) -> CompletedMarker {
 /// - `fun Name (` → named fun (`Name` is a variable in valid Erlang;
 ///   atom accepted at the syntax layer for the same reason).
 fn parse_fun(p: &mut Parser, m: Marker) -> CompletedMarker {
-    reject_in_restricted(p, "`fun` expression / reference not allowed here");
+    reject_in_restricted(p, &code::FUN_NOT_ALLOWED);
     let prev = p.set_context(ParseContext::Expression);
     let result = parse_fun_inner(p, m);
     p.set_context(prev);
@@ -516,7 +512,7 @@ fn parse_fun_inner(p: &mut Parser, m: Marker) -> CompletedMarker {
 
     if at_symbol(p, erl_tokenize::Symbol::OpenParen) {
         parse_semicolon_separated(p, parse_fun_clause);
-        expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close `fun`");
+        expect_keyword(p, erl_tokenize::Keyword::End, &code::FUN_END);
         return m.complete(p, SyntaxKind::AnonymousFun);
     }
 
@@ -539,14 +535,14 @@ fn parse_fun_inner(p: &mut Parser, m: Marker) -> CompletedMarker {
         ) => parse_named_fun(p, m),
         _ => {
             let found = first.map(|(_, t)| t);
-            p.push_diagnostic(Diagnostic::new(
+            p.push_diagnostic(Diagnostic::for_category(
                 if found.is_some() {
                     DiagnosticKind::UnexpectedToken
                 } else {
                     DiagnosticKind::UnexpectedEof
                 },
                 TokenRange::empty_at(p.cursor_position()),
-                Expected::Category("`(`, fun reference, or named fun after `fun`"),
+                &code::FUN_BODY,
                 found,
             ));
             m.complete(p, SyntaxKind::Error)
@@ -555,32 +551,24 @@ fn parse_fun_inner(p: &mut Parser, m: Marker) -> CompletedMarker {
 }
 
 fn parse_local_fun_ref(p: &mut Parser, m: Marker) -> CompletedMarker {
-    consume_atom_or_var(p, "fun name (atom or variable)");
-    expect_symbol(p, erl_tokenize::Symbol::Slash, "`/` in fun reference");
-    consume_integer_or_var(p, "arity (integer or variable)");
+    consume_atom_or_var(p, &code::FUN_REF_NAME);
+    expect_symbol(p, erl_tokenize::Symbol::Slash, &code::FUN_REF_SLASH);
+    consume_integer_or_var(p, &code::FUN_REF_ARITY);
     m.complete(p, SyntaxKind::LocalFunRef)
 }
 
 fn parse_remote_fun_ref(p: &mut Parser, m: Marker) -> CompletedMarker {
-    consume_atom_or_var(p, "module name (atom or variable)");
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::Colon,
-        "`:` in remote fun reference",
-    );
-    consume_atom_or_var(p, "function name (atom or variable)");
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::Slash,
-        "`/` in remote fun reference",
-    );
-    consume_integer_or_var(p, "arity (integer or variable)");
+    consume_atom_or_var(p, &code::REMOTE_FUN_REF_MODULE);
+    expect_symbol(p, erl_tokenize::Symbol::Colon, &code::REMOTE_FUN_REF_COLON);
+    consume_atom_or_var(p, &code::REMOTE_FUN_REF_NAME);
+    expect_symbol(p, erl_tokenize::Symbol::Slash, &code::REMOTE_FUN_REF_SLASH);
+    consume_integer_or_var(p, &code::FUN_REF_ARITY);
     m.complete(p, SyntaxKind::RemoteFunRef)
 }
 
 fn parse_named_fun(p: &mut Parser, m: Marker) -> CompletedMarker {
     parse_semicolon_separated(p, parse_named_fun_clause);
-    expect_keyword(p, erl_tokenize::Keyword::End, "`end` to close named `fun`");
+    expect_keyword(p, erl_tokenize::Keyword::End, &code::NAMED_FUN_END);
     m.complete(p, SyntaxKind::NamedFun)
 }
 
@@ -604,7 +592,7 @@ fn parse_fun_clause(p: &mut Parser) -> CompletedMarker {
 /// and left for a semantic pass to reject).
 fn parse_named_fun_clause(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    consume_atom_or_var(p, "named-fun name (variable or atom)");
+    consume_atom_or_var(p, &code::NAMED_FUN_NAME);
     let prev = p.set_context(ParseContext::Pattern);
     parse_argument_list(p);
     p.set_context(prev);
@@ -633,7 +621,7 @@ fn parse_record_or_map_prefix(p: &mut Parser, m: Marker) -> CompletedMarker {
         };
         return m.complete(p, kind);
     }
-    consume_atom_or_var(p, "record name");
+    consume_atom_or_var(p, &code::RECORD_NAME);
     try_consume_native_record_qualifier(p);
     if at_symbol(p, erl_tokenize::Symbol::OpenBrace) {
         parse_record_body(p);
@@ -641,18 +629,18 @@ fn parse_record_or_map_prefix(p: &mut Parser, m: Marker) -> CompletedMarker {
     }
     if at_symbol(p, erl_tokenize::Symbol::Dot) {
         p.consume_lexical();
-        consume_atom_or_var(p, "record field name");
+        consume_atom_or_var(p, &code::RECORD_FIELD_NAME);
         return m.complete(p, SyntaxKind::RecordIndexExpr);
     }
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         if found.is_some() {
             DiagnosticKind::UnexpectedToken
         } else {
             DiagnosticKind::UnexpectedEof
         },
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category("`{` or `.` after record name"),
+        &code::RECORD_BODY,
         found,
     ));
     m.complete(p, SyntaxKind::Error)
@@ -667,14 +655,14 @@ fn parse_anon_record_prefix(p: &mut Parser, m: Marker) -> CompletedMarker {
         return m.complete(p, SyntaxKind::RecordExpr);
     }
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         if found.is_some() {
             DiagnosticKind::UnexpectedToken
         } else {
             DiagnosticKind::UnexpectedEof
         },
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category("`{` after `#_`"),
+        &code::WILDCARD_RECORD_OPEN,
         found,
     ));
     m.complete(p, SyntaxKind::Error)
@@ -688,7 +676,7 @@ fn complete_record_or_map_suffix(p: &mut Parser, m: Marker) -> CompletedMarker {
         let _ = parse_map_body(p);
         return m.complete(p, SyntaxKind::MapUpdateExpr);
     }
-    consume_atom_or_var(p, "record name");
+    consume_atom_or_var(p, &code::RECORD_NAME);
     try_consume_native_record_qualifier(p);
     if at_symbol(p, erl_tokenize::Symbol::OpenBrace) {
         parse_record_body(p);
@@ -696,18 +684,18 @@ fn complete_record_or_map_suffix(p: &mut Parser, m: Marker) -> CompletedMarker {
     }
     if at_symbol(p, erl_tokenize::Symbol::Dot) {
         p.consume_lexical();
-        consume_atom_or_var(p, "record field name");
+        consume_atom_or_var(p, &code::RECORD_FIELD_NAME);
         return m.complete(p, SyntaxKind::RecordFieldAccessExpr);
     }
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         if found.is_some() {
             DiagnosticKind::UnexpectedToken
         } else {
             DiagnosticKind::UnexpectedEof
         },
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category("`{` or `.` after `#`-suffix record name"),
+        &code::RECORD_SUFFIX_BODY,
         found,
     ));
     m.complete(p, SyntaxKind::Error)
@@ -724,7 +712,7 @@ fn try_consume_native_record_qualifier(p: &mut Parser) {
         )
     {
         p.consume_lexical(); // `:`
-        consume_atom_or_var(p, "record name");
+        consume_atom_or_var(p, &code::RECORD_NAME);
     }
 }
 
@@ -738,18 +726,18 @@ fn complete_anon_record_suffix(p: &mut Parser, m: Marker) -> CompletedMarker {
     }
     if at_symbol(p, erl_tokenize::Symbol::Dot) {
         p.consume_lexical();
-        consume_atom_or_var(p, "record field name");
+        consume_atom_or_var(p, &code::RECORD_FIELD_NAME);
         return m.complete(p, SyntaxKind::RecordFieldAccessExpr);
     }
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         if found.is_some() {
             DiagnosticKind::UnexpectedToken
         } else {
             DiagnosticKind::UnexpectedEof
         },
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category("`{` or `.` after `#_`"),
+        &code::WILDCARD_RECORD_BODY,
         found,
     ));
     m.complete(p, SyntaxKind::Error)
@@ -772,13 +760,13 @@ fn parse_map_body(p: &mut Parser) -> bool {
     parse_map_field(p);
     loop {
         if at_symbol(p, erl_tokenize::Symbol::DoubleVerticalBar) {
-            reject_in_restricted(p, "map comprehension not allowed here");
+            reject_in_restricted(p, &code::MAP_COMPREHENSION_NOT_ALLOWED);
             p.consume_lexical();
             parse_comprehension_qualifiers(p);
             expect_symbol(
                 p,
                 erl_tokenize::Symbol::CloseBrace,
-                "`}` to close map comprehension",
+                &code::MAP_COMPREHENSION_CLOSE,
             );
             return true;
         }
@@ -789,7 +777,7 @@ fn parse_map_body(p: &mut Parser) -> bool {
         }
         break;
     }
-    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, "`}` to close map");
+    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, &code::MAP_CLOSE);
     false
 }
 
@@ -803,14 +791,14 @@ fn parse_map_field(p: &mut Parser) -> CompletedMarker {
         parse_expr(p);
     } else {
         let found = p.peek_lexical(0).map(|(_, t)| t);
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             if found.is_some() {
                 DiagnosticKind::UnexpectedToken
             } else {
                 DiagnosticKind::UnexpectedEof
             },
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("`=>` or `:=` in map field"),
+            &code::MAP_FIELD_ARROW,
             found,
         ));
     }
@@ -831,13 +819,13 @@ fn parse_record_body(p: &mut Parser) {
         }
         p.consume_lexical();
     }
-    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, "`}` to close record");
+    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, &code::RECORD_CLOSE);
 }
 
 fn parse_record_field(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    consume_atom_or_var(p, "record field name");
-    expect_symbol(p, erl_tokenize::Symbol::Match, "`=` in record field");
+    consume_atom_or_var(p, &code::RECORD_FIELD_NAME);
+    expect_symbol(p, erl_tokenize::Symbol::Match, &code::RECORD_FIELD_EQUALS);
     parse_expr(p);
     m.complete(p, SyntaxKind::RecordField)
 }
@@ -857,13 +845,13 @@ fn parse_bitstring(p: &mut Parser, m: Marker) -> CompletedMarker {
     }
     parse_bitstring_element(p);
     if at_symbol(p, erl_tokenize::Symbol::DoubleVerticalBar) {
-        reject_in_restricted(p, "binary comprehension not allowed here");
+        reject_in_restricted(p, &code::BINARY_COMPREHENSION_NOT_ALLOWED);
         p.consume_lexical();
         parse_comprehension_qualifiers(p);
         expect_symbol(
             p,
             erl_tokenize::Symbol::DoubleRightAngle,
-            "`>>` to close binary comprehension",
+            &code::BINARY_COMPREHENSION_CLOSE,
         );
         return m.complete(p, SyntaxKind::BinaryComprehension);
     }
@@ -874,7 +862,7 @@ fn parse_bitstring(p: &mut Parser, m: Marker) -> CompletedMarker {
     expect_symbol(
         p,
         erl_tokenize::Symbol::DoubleRightAngle,
-        "`>>` to close bitstring",
+        &code::BITSTRING_CLOSE,
     );
     m.complete(p, SyntaxKind::BitstringExpr)
 }
@@ -904,10 +892,10 @@ fn parse_bit_type_list(p: &mut Parser) {
 }
 
 fn parse_bit_type(p: &mut Parser) {
-    consume_atom_or_var(p, "bit type name");
+    consume_atom_or_var(p, &code::BIT_TYPE_NAME);
     if at_symbol(p, erl_tokenize::Symbol::Colon) {
         p.consume_lexical();
-        consume_integer_or_var(p, "bit type unit size");
+        consume_integer_or_var(p, &code::BIT_TYPE_UNIT);
     }
 }
 
@@ -953,7 +941,7 @@ fn parse_qualifier(p: &mut Parser) -> CompletedMarker {
             parse_expr(p);
             SyntaxKind::StrictMapGenerator
         } else {
-            expect_generator_arrow_error(p, "`<-` or `<:-` after `Key := Value`");
+            expect_generator_arrow_error(p, &code::MAP_GENERATOR_ARROW);
             SyntaxKind::Error
         }
     } else if at_symbol(p, erl_tokenize::Symbol::LeftArrow) {
@@ -987,16 +975,16 @@ fn parse_qualifier(p: &mut Parser) -> CompletedMarker {
     result
 }
 
-fn expect_generator_arrow_error(p: &mut Parser, msg: &'static str) {
+fn expect_generator_arrow_error(p: &mut Parser, category: &'static DiagnosticCode) {
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         if found.is_some() {
             DiagnosticKind::UnexpectedToken
         } else {
             DiagnosticKind::UnexpectedEof
         },
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category(msg),
+        category,
         found,
     ));
 }
@@ -1010,18 +998,18 @@ fn expect_generator_arrow_error(p: &mut Parser, msg: &'static str) {
 /// term positions do not accept. The cursor is NOT rewound; the
 /// grammar continues to consume so that downstream navigation still
 /// sees a structural node.
-fn reject_in_restricted(p: &mut Parser, msg: &'static str) {
+fn reject_in_restricted(p: &mut Parser, category: &'static DiagnosticCode) {
     if p.context() != ParseContext::Expression {
-        push_context_error(p, msg);
+        push_context_error(p, category);
     }
 }
 
-fn push_context_error(p: &mut Parser, msg: &'static str) {
+fn push_context_error(p: &mut Parser, category: &'static DiagnosticCode) {
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         DiagnosticKind::UnexpectedToken,
         TokenRange::empty_at(p.cursor_position()),
-        Expected::Category(msg),
+        category,
         found,
     ));
 }
@@ -13,7 +13,7 @@
 //! bad token; the driver runs the shared unexpected-token loop
 //! afterwards, so the form still terminates at the next `.`.
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, code};
 use crate::grammar::attribute::parse_attribute;
 use crate::grammar::function::parse_function_decl;
 use crate::parser::{CompletedMarker, Parser};
@@ -28,20 +28,20 @@ pub(crate) fn parse_form(p: &mut Parser) -> CompletedMarker {
         Some(_) => {
             let m = p.start();
             let found = p.peek_lexical(0).map(|(_, t)| t);
-            p.push_diagnostic(Diagnostic::new(
+            p.push_diagnostic(Diagnostic::for_category(
                 DiagnosticKind::UnexpectedToken,
                 TokenRange::empty_at(p.cursor_position()),
-                Expected::Category("`-` to open an attribute or an atom to open a function"),
+                &code::FORM_OPENER,
                 found,
             ));
             m.complete(p, SyntaxKind::Error)
         }
         None => {
             let m = p.start();
-            p.push_diagnostic(Diagnostic::new(
+            p.push_diagnostic(Diagnostic::for_category(
                 DiagnosticKind::UnexpectedEof,
                 TokenRange::empty_at(p.cursor_position()),
-                Expected::Category("start of a module-level form"),
+                &code::FORM_START,
                 None,
             ));
             m.complete(p, SyntaxKind::Error)
@@ -15,9 +15,11 @@
 //! Same-name / same-arity checks and cross-form same-name grouping
 //! belong to a later semantic phase.
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
-use crate::grammar::clause::{parse_argument_list, parse_arrow_body, parse_clause_guard_opt};
-use crate::grammar::util::at_symbol;
+use crate::diagnostic::{Diagnostic, DiagnosticKind, code};
+use crate::grammar::clause::{
+    parse_argument_list, parse_arrow_body, parse_clause_guard_opt, push_stray_semicolon,
+};
+use crate::grammar::util::is_symbol;
 use crate::parser::{CompletedMarker, Parser};
 use crate::syntax::SyntaxKind;
 use crate::token_range::TokenRange;
@@ -27,8 +29,27 @@ pub(crate) fn parse_function_decl(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
 
     parse_function_clause(p);
-    while at_symbol(p, erl_tokenize::Symbol::Semicolon) {
+    while let Some((semicolon, _)) = p
+        .peek_lexical(0)
+        .filter(|&(_, t)| is_symbol(t, erl_tokenize::Symbol::Semicolon))
+    {
         p.consume_lexical();
+        // A `;` after the last clause: drop it before the form's `.`,
+        // or turn it into the `.` the next attribute needs.
+        let next = p.peek_lexical(0).map(|(_, t)| t);
+        if next.is_none_or(|t| is_symbol(t, erl_tokenize::Symbol::Dot)) {
+            push_stray_semicolon(p, semicolon, None, &code::FUNCTION_CLAUSE_AFTER_SEMICOLON);
+            break;
+        }
+        if next.is_some_and(|t| is_symbol(t, erl_tokenize::Symbol::Hyphen)) {
+            push_stray_semicolon(
+                p,
+                semicolon,
+                Some("."),
+                &code::FUNCTION_CLAUSE_AFTER_SEMICOLON,
+            );
+            break;
+        }
         parse_function_clause(p);
     }
 
@@ -46,14 +67,14 @@ fn parse_function_clause(p: &mut Parser) -> CompletedMarker {
         p.consume_lexical();
     } else {
         let found = p.peek_lexical(0).map(|(_, t)| t);
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             if found.is_some() {
                 DiagnosticKind::UnexpectedToken
             } else {
                 DiagnosticKind::UnexpectedEof
             },
             TokenRange::empty_at(name_start),
-            Expected::Category("function name (atom) at the start of a function clause"),
+            &code::FUNCTION_CLAUSE_NAME,
             found,
         ));
     }
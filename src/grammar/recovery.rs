@@ -23,14 +23,22 @@
 //!   tokens and does not emit any [`SyntaxKind::Error`] node — the
 //!   parser never synthesizes a fake [`erl_tokenize::Token`].
 //!
+//! Where the repair is obvious the diagnostic carries a [`Fix`]: a
+//! missing fixed token is inserted ([`push_missing_token_with_fix`]),
+//! and a single skipped token is deleted when it is a stray closing
+//! delimiter or the only token [`skip_until_sync`] swept up.
+//!
 //! All three helpers deduplicate at the append site through
 //! [`Parser::push_diagnostic`], so recovery loops that revisit the same
 //! cursor position do not surface the same diagnostic twice.
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use erl_tokenize::{Symbol, TokenKind};
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode, DiagnosticKind, Fix};
+use crate::parser::SkippedRun;
 use crate::parser::{CompletedMarker, Parser, RecoveryContext};
 use crate::syntax::SyntaxKind;
-use crate::token_range::TokenRange;
+use crate::token_range::{TokenIndex, TokenRange};
 
 /// Consumes a single lexical token at the current cursor as a
 /// [`SyntaxKind::Error`] node and emits a
@@ -40,7 +48,7 @@ use crate::token_range::TokenRange;
 /// appended at the boundary — the caller receives a zero-width
 /// [`SyntaxKind::Error`] node anchored at the cursor to keep the
 /// call site's signature uniform.
-pub(crate) fn skip_one_token(p: &mut Parser, category: &'static str) -> CompletedMarker {
+pub(crate) fn skip_one_token(p: &mut Parser, category: &'static DiagnosticCode) -> CompletedMarker {
     let start = p.cursor_position();
     let found = p.peek_lexical(0).map(|(_, t)| t);
     let m = p.start();
@@ -49,17 +57,26 @@ pub(crate) fn skip_one_token(p: &mut Parser, category: &'static str) -> Complete
     if consumed {
         let end = p.cursor_position();
         let node_range = TokenRange::new(start, end);
-        p.push_diagnostic(Diagnostic::new(
-            DiagnosticKind::SkippedToken,
-            node_range,
-            Expected::Category(category),
-            found,
-        ));
+        let stray_closer = found.is_some_and(|t| {
+            matches!(
+                t.kind(),
+                erl_tokenize::TokenKind::Symbol(
+                    erl_tokenize::Symbol::CloseParen
+                        | erl_tokenize::Symbol::CloseSquare
+                        | erl_tokenize::Symbol::CloseBrace
+                        | erl_tokenize::Symbol::DoubleRightAngle
+                )
+            )
+        });
+        p.push_diagnostic(
+            Diagnostic::for_category(DiagnosticKind::SkippedToken, node_range, category, found)
+                .with_fix(stray_closer.then_some(Fix::Delete(node_range))),
+        );
     } else {
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             DiagnosticKind::UnexpectedEof,
             TokenRange::empty_at(start),
-            Expected::Category(category),
+            category,
             None,
         ));
     }
@@ -74,11 +91,30 @@ pub(crate) fn skip_one_token(p: &mut Parser, category: &'static str) -> Complete
 /// — when the cursor is already at a sync token / EOF, or when the
 /// same `(context, cursor position)` recovery pair has already fired
 /// (see [`Parser::begin_recovery_attempt`]).
+///
+/// When exactly one lexical token other than `.` was skipped, the
+/// diagnostic suggests deleting it.
 pub(crate) fn skip_until_sync<F>(
     p: &mut Parser,
     context: RecoveryContext,
     is_sync: F,
-    category: &'static str,
+    category: &'static DiagnosticCode,
+) -> Option<CompletedMarker>
+where
+    F: Fn(erl_tokenize::Token) -> bool,
+{
+    skip_until_sync_with_fix(p, context, is_sync, category, None)
+}
+
+/// [`skip_until_sync`] with a caller-chosen [`Fix`] for the diagnostic
+/// in place of the default single-token deletion. `fix` is ignored
+/// when nothing is skipped.
+pub(crate) fn skip_until_sync_with_fix<F>(
+    p: &mut Parser,
+    context: RecoveryContext,
+    is_sync: F,
+    category: &'static DiagnosticCode,
+    fix: Option<Fix>,
 ) -> Option<CompletedMarker>
 where
     F: Fn(erl_tokenize::Token) -> bool,
@@ -93,24 +129,27 @@ where
     }
     let start = p.cursor_position();
     let m = p.start();
-    let mut consumed = false;
+    let mut consumed = 0;
+    let mut skipped_dot = false;
     while let Some((_, t)) = p.peek_lexical(0) {
         if is_sync(t) {
             break;
         }
         p.consume_lexical();
-        consumed = true;
+        consumed += 1;
+        skipped_dot |= crate::grammar::util::is_symbol(t, erl_tokenize::Symbol::Dot);
     }
     let completed = m.complete(p, SyntaxKind::Error);
-    if consumed {
+    if consumed > 0 {
         let end = p.cursor_position();
         let node_range = TokenRange::new(start, end);
-        p.push_diagnostic(Diagnostic::new(
-            DiagnosticKind::SkippedToken,
-            node_range,
-            Expected::Category(category),
-            None,
-        ));
+        // Deleting a form's `.` never helps, even when it is all that
+        // was skipped.
+        let fix = fix.or((consumed == 1 && !skipped_dot).then_some(Fix::Delete(node_range)));
+        p.push_diagnostic(
+            Diagnostic::for_category(DiagnosticKind::SkippedToken, node_range, category, None)
+                .with_fix(fix),
+        );
     }
     Some(completed)
 }
@@ -119,20 +158,67 @@ where
 /// current cursor position (zero-width [`TokenRange`]). Never
 /// consumes tokens and never emits a [`SyntaxKind::Error`] node —
 /// the parser refuses to synthesize a fake [`Token`].
-pub(crate) fn push_missing_token(p: &mut Parser, category: &'static str) {
+pub(crate) fn push_missing_token(p: &mut Parser, category: &'static DiagnosticCode) {
     let at = p.cursor_position();
     let found = p.peek_lexical(0).map(|(_, t)| t);
-    p.push_diagnostic(Diagnostic::new(
+    p.push_diagnostic(Diagnostic::for_category(
         DiagnosticKind::MissingToken,
         TokenRange::empty_at(at),
-        Expected::Category(category),
+        category,
         found,
     ));
 }
 
+/// [`push_missing_token`] for a token whose text is fixed, such as a
+/// closing delimiter or keyword: the diagnostic suggests inserting
+/// `text` at the cursor, or before the form's `.` when recovery has
+/// already swept it up (see [`insert_anchor`]).
+pub(crate) fn push_missing_token_with_fix(
+    p: &mut Parser,
+    text: &'static str,
+    category: &'static DiagnosticCode,
+) {
+    let at = p.cursor_position();
+    let found = p.peek_lexical(0).map(|(_, t)| t);
+    let fix = insert_anchor(p, at).map(|at| Fix::Insert { at, text });
+    p.push_diagnostic(
+        Diagnostic::for_category(
+            DiagnosticKind::MissingToken,
+            TokenRange::empty_at(at),
+            category,
+            found,
+        )
+        .with_fix(fix),
+    );
+}
+
+/// Returns where a missing token reported at `at` goes.
+///
+/// That is `at` itself unless the tokens recovery skipped right before
+/// it include a `.`: inserting after the form's terminator leaves the
+/// form broken (`f() -> {a, b.}`), so the token goes right after the
+/// last lexical token the grammar consumed instead (`f() -> {a, b}.`).
+/// Returns `None` when that token is the previous form's `.`, or there
+/// is none.
+fn insert_anchor(p: &Parser, at: TokenIndex) -> Option<TokenIndex> {
+    let prev = p.lexical_before(at)?;
+    let in_run = |run: &SkippedRun| prev < run.end && run.before.is_none_or(|b| b < prev);
+    let Some(run) = p.skipped_run().filter(|run| run.swept_dot && in_run(run)) else {
+        return Some(at);
+    };
+    let before = run.before?;
+    let tokens = p.syntax_tree().tokens();
+    if tokens[before.get()].kind() == TokenKind::Symbol(Symbol::Dot) {
+        None
+    } else {
+        Some(TokenIndex::new(before.get() + 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostic::code;
     use crate::{ParseMode, Parser};
     use core::assert_matches;
 
@@ -159,7 +245,7 @@ mod tests {
     fn skip_one_token_emits_error_node_and_matching_diagnostic() {
         let mut p = load("foo bar");
         let outer = p.start();
-        let completed = skip_one_token(&mut p, "test");
+        let completed = skip_one_token(&mut p, &code::EXPRESSION);
         outer.complete(&mut p, SyntaxKind::Error);
         p.finalize_pending_units_for_test();
         let _ = completed;
@@ -189,7 +275,7 @@ mod tests {
                     erl_tokenize::TokenKind::Symbol(erl_tokenize::Symbol::Dot)
                 )
             },
-            "test-sync",
+            &code::FORM_DOT,
         )
         .expect("skipped");
         outer.complete(&mut p, SyntaxKind::Error);
@@ -224,7 +310,7 @@ mod tests {
                     erl_tokenize::TokenKind::Symbol(erl_tokenize::Symbol::Dot)
                 )
             },
-            "test-sync",
+            &code::FORM_DOT,
         );
         assert!(result.is_none());
         outer.complete(&mut p, SyntaxKind::Error);
@@ -258,7 +344,7 @@ mod tests {
         let mut p = load("foo");
         let outer = p.start();
         let before_entries = p.syntax_tree().syntax().len();
-        push_missing_token(&mut p, "missing thing");
+        push_missing_token(&mut p, &code::ARGUMENTS_CLOSE);
         let after_entries = p.syntax_tree().syntax().len();
         outer.complete(&mut p, SyntaxKind::Error);
         p.finalize_pending_units_for_test();
@@ -277,10 +363,10 @@ mod tests {
     #[test]
     fn push_diagnostic_deduplicates_same_kind_at_same_cursor() {
         let mut p = load("foo");
-        push_missing_token(&mut p, "a");
+        push_missing_token(&mut p, &code::LIST_CLOSE);
         // Second push at the same cursor with the same kind is
         // collapsed.
-        push_missing_token(&mut p, "b");
+        push_missing_token(&mut p, &code::TUPLE_CLOSE);
         assert_eq!(p.syntax_tree().diagnostics().len(), 1);
     }
 }
@@ -20,10 +20,10 @@
 //! Integer arithmetic operators reuse the expression-side
 //! `infix_binding_power` table.
 
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, code};
 use crate::grammar::operator;
 use crate::grammar::util::{
-    at_symbol, consume_atom_or_var, expect_keyword, expect_symbol, is_symbol,
+    at_keyword, at_symbol, consume_atom_or_var, expect_keyword, expect_symbol, is_symbol,
 };
 use crate::parser::{CompletedMarker, Marker, ParseContext, Parser};
 use crate::syntax::SyntaxKind;
@@ -100,10 +100,10 @@ fn parse_type_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
         // Range `..` (Nonassoc 200).
         if is_symbol(token, erl_tokenize::Symbol::DoubleDot) && RANGE_LBP > min_bp {
             if last_nonassoc_bp == Some(RANGE_LBP) {
-                p.push_diagnostic(Diagnostic::new(
+                p.push_diagnostic(Diagnostic::for_category(
                     DiagnosticKind::UnexpectedToken,
                     TokenRange::empty_at(p.cursor_position()),
-                    Expected::Category("non-associative range operator used twice"),
+                    &code::NON_ASSOCIATIVE_RANGE,
                     Some(token),
                 ));
             }
@@ -169,10 +169,10 @@ fn parse_type_bp_inner(p: &mut Parser, min_bp: u16) -> CompletedMarker {
 fn parse_type_max(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
     let Some((_, token)) = p.peek_lexical(0) else {
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             DiagnosticKind::UnexpectedEof,
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("type expression"),
+            &code::TYPE,
             None,
         ));
         return m.complete(p, SyntaxKind::Error);
@@ -212,7 +212,7 @@ fn parse_type_max(p: &mut Parser) -> CompletedMarker {
             // node covers only the one skipped token, giving
             // `Diagnostic::range() == Error node's TokenRange`.
             m.abandon(p);
-            crate::grammar::recovery::skip_one_token(p, "type expression")
+            crate::grammar::recovery::skip_one_token(p, &code::TYPE)
         }
     }
 }
@@ -253,11 +253,7 @@ fn parse_atom_head(p: &mut Parser, m: Marker) -> CompletedMarker {
 fn parse_paren_type(p: &mut Parser, m: Marker) -> CompletedMarker {
     p.consume_lexical(); // `(`
     parse_top_type(p);
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseParen,
-        "`)` to close parenthesized type",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseParen, &code::PAREN_TYPE_CLOSE);
     m.complete(p, SyntaxKind::ParenExpr)
 }
 
@@ -268,11 +264,7 @@ fn parse_tuple_type(p: &mut Parser, m: Marker) -> CompletedMarker {
         return m.complete(p, SyntaxKind::TupleType);
     }
     parse_top_types_comma(p, erl_tokenize::Symbol::CloseBrace);
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseBrace,
-        "`}` to close tuple type",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, &code::TUPLE_TYPE_CLOSE);
     m.complete(p, SyntaxKind::TupleType)
 }
 
@@ -291,26 +283,22 @@ fn parse_list_type(p: &mut Parser, m: Marker) -> CompletedMarker {
             expect_symbol(
                 p,
                 erl_tokenize::Symbol::CloseSquare,
-                "`]` to close non-empty list type",
+                &code::NON_EMPTY_LIST_TYPE_CLOSE,
             );
             return m.complete(p, SyntaxKind::NonemptyListType);
         }
         // A comma without a trailing `...` is not part of the yrl list-
         // type production; keep consuming the extra element as a plain
         // type so the tree stays navigable and record an error.
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             DiagnosticKind::UnexpectedToken,
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("`...]` after list type comma"),
+            &code::LIST_TYPE_ELLIPSIS,
             p.peek_lexical(0).map(|(_, t)| t),
         ));
         parse_top_type(p);
     }
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseSquare,
-        "`]` to close list type",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseSquare, &code::LIST_TYPE_CLOSE);
     m.complete(p, SyntaxKind::ListType)
 }
 
@@ -323,7 +311,7 @@ fn parse_hash_type(p: &mut Parser, m: Marker) -> CompletedMarker {
     }
     // `#Name` — record type. Consume the name, then optionally the
     // `:remote` qualifier per the yrl's `#atom ':' record_name`.
-    consume_atom_or_var(p, "record type name");
+    consume_atom_or_var(p, &code::RECORD_TYPE_NAME);
     if at_symbol(p, erl_tokenize::Symbol::Colon)
         && matches!(
             p.peek_lexical(1).map(|(_, t)| t.kind()),
@@ -333,11 +321,7 @@ fn parse_hash_type(p: &mut Parser, m: Marker) -> CompletedMarker {
         p.consume_lexical(); // `:`
         p.consume_lexical(); // second atom (record_name)
     }
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::OpenBrace,
-        "`{` after record type name",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::OpenBrace, &code::RECORD_TYPE_OPEN);
     if at_symbol(p, erl_tokenize::Symbol::CloseBrace) {
         p.consume_lexical();
         return m.complete(p, SyntaxKind::RecordType);
@@ -346,7 +330,7 @@ fn parse_hash_type(p: &mut Parser, m: Marker) -> CompletedMarker {
     expect_symbol(
         p,
         erl_tokenize::Symbol::CloseBrace,
-        "`}` to close record type",
+        &code::RECORD_TYPE_CLOSE,
     );
     m.complete(p, SyntaxKind::RecordType)
 }
@@ -365,7 +349,7 @@ fn parse_map_type_body(p: &mut Parser) {
         }
         p.consume_lexical();
     }
-    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, "`}` to close map type");
+    expect_symbol(p, erl_tokenize::Symbol::CloseBrace, &code::MAP_TYPE_CLOSE);
 }
 
 fn parse_map_type_field(p: &mut Parser) -> CompletedMarker {
@@ -378,14 +362,14 @@ fn parse_map_type_field(p: &mut Parser) -> CompletedMarker {
         parse_top_type(p);
     } else {
         let found = p.peek_lexical(0).map(|(_, t)| t);
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             if found.is_some() {
                 DiagnosticKind::UnexpectedToken
             } else {
                 DiagnosticKind::UnexpectedEof
             },
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("`=>` or `:=` in map type field"),
+            &code::MAP_TYPE_FIELD_ARROW,
             found,
         ));
     }
@@ -404,11 +388,11 @@ fn parse_record_type_fields(p: &mut Parser) {
 
 fn parse_record_type_field(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    consume_atom_or_var(p, "record type field name");
+    consume_atom_or_var(p, &code::RECORD_TYPE_FIELD_NAME);
     expect_symbol(
         p,
         erl_tokenize::Symbol::DoubleColon,
-        "`::` in record type field",
+        &code::RECORD_TYPE_FIELD_COLONS,
     );
     parse_top_type(p);
     m.complete(p, SyntaxKind::RecordTypeField)
@@ -435,7 +419,7 @@ fn parse_bitstring_type(p: &mut Parser, m: Marker) -> CompletedMarker {
     expect_symbol(
         p,
         erl_tokenize::Symbol::DoubleRightAngle,
-        "`>>` to close bitstring type",
+        &code::BITSTRING_TYPE_CLOSE,
     );
     m.complete(p, SyntaxKind::BitstringType)
 }
@@ -462,21 +446,13 @@ fn parse_bitstring_type_segment(p: &mut Parser) -> CompletedMarker {
 /// `fun ( ( T, T ) -> Return )` — explicit-args form.
 fn parse_fun_type(p: &mut Parser, m: Marker) -> CompletedMarker {
     p.consume_lexical(); // `fun`
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::OpenParen,
-        "`(` after `fun` in function type",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::OpenParen, &code::FUN_TYPE_OPEN);
     if at_symbol(p, erl_tokenize::Symbol::CloseParen) {
         p.consume_lexical();
         return m.complete(p, SyntaxKind::FunctionType);
     }
     parse_fun_type_signature(p);
-    expect_symbol(
-        p,
-        erl_tokenize::Symbol::CloseParen,
-        "`)` to close `fun` type",
-    );
+    expect_symbol(p, erl_tokenize::Symbol::CloseParen, &code::FUN_TYPE_CLOSE);
     m.complete(p, SyntaxKind::FunctionType)
 }
 
@@ -485,7 +461,7 @@ fn parse_fun_type_signature(p: &mut Parser) {
     expect_symbol(
         p,
         erl_tokenize::Symbol::OpenParen,
-        "`(` to open function type parameters",
+        &code::FUNCTION_TYPE_PARAMETERS_OPEN,
     );
     if at_symbol(p, erl_tokenize::Symbol::TripleDot) {
         p.consume_lexical();
@@ -495,11 +471,15 @@ fn parse_fun_type_signature(p: &mut Parser) {
     expect_symbol(
         p,
         erl_tokenize::Symbol::CloseParen,
-        "`)` to close function type parameters",
+        &code::FUNCTION_TYPE_PARAMETERS_CLOSE,
     );
     params.complete(p, SyntaxKind::FunctionTypeParams);
 
-    expect_symbol(p, erl_tokenize::Symbol::RightArrow, "`->` in function type");
+    expect_symbol(
+        p,
+        erl_tokenize::Symbol::RightArrow,
+        &code::FUNCTION_TYPE_ARROW,
+    );
 
     let ret = p.start();
     parse_top_type(p);
@@ -507,13 +487,14 @@ fn parse_fun_type_signature(p: &mut Parser) {
 }
 
 /// `( T, T, ... )` — used by [`parse_atom_head`] for local / remote
-/// type calls; consumes the parentheses.
-fn parse_type_argument_list(p: &mut Parser) -> CompletedMarker {
+/// type calls and by `-type` declaration heads; consumes the
+/// parentheses.
+pub(crate) fn parse_type_argument_list(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
     expect_symbol(
         p,
         erl_tokenize::Symbol::OpenParen,
-        "`(` to open type argument list",
+        &code::TYPE_ARGUMENTS_OPEN,
     );
     if at_symbol(p, erl_tokenize::Symbol::CloseParen) {
         p.consume_lexical();
@@ -523,7 +504,7 @@ fn parse_type_argument_list(p: &mut Parser) -> CompletedMarker {
     expect_symbol(
         p,
         erl_tokenize::Symbol::CloseParen,
-        "`)` to close type argument list",
+        &code::TYPE_ARGUMENTS_CLOSE,
     );
     m.complete(p, SyntaxKind::TypeArgumentList)
 }
@@ -547,7 +528,7 @@ fn parse_top_types_comma(p: &mut Parser, close: erl_tokenize::Symbol) {
                     crate::grammar::util::is_symbol(t, erl_tokenize::Symbol::Comma)
                         || crate::grammar::util::is_symbol(t, close)
                 },
-                "`,` or closing delimiter in type list",
+                &code::TYPE_LIST_SEPARATOR_OR_CLOSE,
             );
         }
         if !at_symbol(p, erl_tokenize::Symbol::Comma) {
@@ -558,6 +539,28 @@ fn parse_top_types_comma(p: &mut Parser, close: erl_tokenize::Symbol) {
     }
 }
 
+// -------------------------------------------------------------------
+// Spec clauses.
+// -------------------------------------------------------------------
+
+/// Parses one `-spec` / `-callback` signature, the yrl's
+/// `type_sig -> fun_type | fun_type 'when' type_guards`, as a
+/// [`SyntaxKind::FunctionType`] holding
+/// [`SyntaxKind::FunctionTypeParams`],
+/// [`SyntaxKind::FunctionTypeReturn`], and, when a `when` clause
+/// follows, a [`SyntaxKind::TypeGuard`].
+pub(crate) fn parse_spec_clause(p: &mut Parser) -> CompletedMarker {
+    let prev = p.set_context(ParseContext::Type);
+    let m = p.start();
+    parse_fun_type_signature(p);
+    if at_keyword(p, erl_tokenize::Keyword::When) {
+        parse_type_guard(p);
+    }
+    let completed = m.complete(p, SyntaxKind::FunctionType);
+    p.set_context(prev);
+    completed
+}
+
 // -------------------------------------------------------------------
 // Constraint / when clause.
 // -------------------------------------------------------------------
@@ -568,22 +571,11 @@ fn parse_top_types_comma(p: &mut Parser, close: erl_tokenize::Symbol) {
 /// `type_guard -> var '::' top_type` shape (annotated) or the
 /// compatibility form `type_guard -> atom '(' top_types ')'`.
 ///
-/// Left as a `pub(crate)` helper for the form / module grammar's
-/// spec handling; not exposed as a public entry point.
-#[cfg_attr(
-    not(test),
-    expect(
-        dead_code,
-        reason = "Called by the form / module grammar when it lands; only in-module tests currently exercise it"
-    )
-)]
+/// Used by [`parse_spec_clause`]; not exposed as a public entry
+/// point.
 pub(crate) fn parse_type_guard(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    expect_keyword(
-        p,
-        erl_tokenize::Keyword::When,
-        "`when` at start of type guard",
-    );
+    expect_keyword(p, erl_tokenize::Keyword::When, &code::TYPE_GUARD_WHEN);
     parse_type_constraint(p);
     while at_symbol(p, erl_tokenize::Symbol::Comma) {
         p.consume_lexical();
@@ -594,7 +586,7 @@ pub(crate) fn parse_type_guard(p: &mut Parser) -> CompletedMarker {
 
 fn parse_type_constraint(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
-    consume_atom_or_var(p, "constraint variable or class");
+    consume_atom_or_var(p, &code::CONSTRAINT_START);
     if at_symbol(p, erl_tokenize::Symbol::DoubleColon) {
         p.consume_lexical();
         parse_top_type(p);
@@ -602,14 +594,14 @@ fn parse_type_constraint(p: &mut Parser) -> CompletedMarker {
         parse_type_argument_list(p);
     } else {
         let found = p.peek_lexical(0).map(|(_, t)| t);
-        p.push_diagnostic(Diagnostic::new(
+        p.push_diagnostic(Diagnostic::for_category(
             if found.is_some() {
                 DiagnosticKind::UnexpectedToken
             } else {
                 DiagnosticKind::UnexpectedEof
             },
             TokenRange::empty_at(p.cursor_position()),
-            Expected::Category("`::` or `(...)` in type constraint"),
+            &code::CONSTRAINT_BODY,
             found,
         ));
     }
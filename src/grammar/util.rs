@@ -5,6 +5,7 @@
 //! `push_diagnostic` primitives with the "peek for a specific token /
 //! consume-or-error" pattern that grammar productions repeat.
 
+use crate::diagnostic::DiagnosticCode;
 use crate::parser::Parser;
 
 /// Returns `true` when the next lexical token is the given [`Symbol`].
@@ -35,50 +36,59 @@ pub(crate) fn is_keyword(token: erl_tokenize::Token, kw: erl_tokenize::Keyword)
 
 /// Consumes the next lexical token if it is [`Symbol`] `sym`.
 /// Otherwise emits a [`DiagnosticKind::MissingToken`] diagnostic
-/// (zero-width `TokenRange` at the cursor) via
-/// [`crate::grammar::recovery::push_missing_token`] and does not
+/// (zero-width `TokenRange` at the cursor) suggesting `sym` be
+/// inserted, via
+/// [`crate::grammar::recovery::push_missing_token_with_fix`], and does not
 /// advance — the parser refuses to synthesize a fake `Token`, so
 /// the caller either recovers or fails locally.
-pub(crate) fn expect_symbol(p: &mut Parser, sym: erl_tokenize::Symbol, msg: &'static str) {
+pub(crate) fn expect_symbol(
+    p: &mut Parser,
+    sym: erl_tokenize::Symbol,
+    category: &'static DiagnosticCode,
+) {
     if at_symbol(p, sym) {
         p.consume_lexical();
         return;
     }
-    crate::grammar::recovery::push_missing_token(p, msg);
+    crate::grammar::recovery::push_missing_token_with_fix(p, sym.as_str(), category);
 }
 
 /// Consumes the next lexical token if it is [`Keyword`] `kw`.
 /// Otherwise behaves as for [`expect_symbol`]: emits a
 /// [`DiagnosticKind::MissingToken`] diagnostic and does not
 /// advance.
-pub(crate) fn expect_keyword(p: &mut Parser, kw: erl_tokenize::Keyword, msg: &'static str) {
+pub(crate) fn expect_keyword(
+    p: &mut Parser,
+    kw: erl_tokenize::Keyword,
+    category: &'static DiagnosticCode,
+) {
     if at_keyword(p, kw) {
         p.consume_lexical();
         return;
     }
-    crate::grammar::recovery::push_missing_token(p, msg);
+    crate::grammar::recovery::push_missing_token_with_fix(p, kw.as_str(), category);
 }
 
 /// Consumes the next lexical token if it is an atom or a variable;
 /// on mismatch emits a [`DiagnosticKind::MissingToken`] diagnostic
 /// (zero-width [`TokenRange`] at the cursor) and does not advance.
-pub(crate) fn consume_atom_or_var(p: &mut Parser, msg: &'static str) {
+pub(crate) fn consume_atom_or_var(p: &mut Parser, category: &'static DiagnosticCode) {
     match p.peek_lexical(0).map(|(_, t)| t.kind()) {
         Some(erl_tokenize::TokenKind::Atom | erl_tokenize::TokenKind::Variable) => {
             p.consume_lexical();
         }
-        _ => crate::grammar::recovery::push_missing_token(p, msg),
+        _ => crate::grammar::recovery::push_missing_token(p, category),
     }
 }
 
 /// Consumes the next lexical token if it is an integer or a
 /// variable; on mismatch emits a [`DiagnosticKind::MissingToken`]
 /// diagnostic and does not advance.
-pub(crate) fn consume_integer_or_var(p: &mut Parser, msg: &'static str) {
+pub(crate) fn consume_integer_or_var(p: &mut Parser, category: &'static DiagnosticCode) {
     match p.peek_lexical(0).map(|(_, t)| t.kind()) {
         Some(erl_tokenize::TokenKind::Integer | erl_tokenize::TokenKind::Variable) => {
             p.consume_lexical();
         }
-        _ => crate::grammar::recovery::push_missing_token(p, msg),
+        _ => crate::grammar::recovery::push_missing_token(p, category),
     }
 }
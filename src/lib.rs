@@ -11,7 +11,20 @@
 //! includes, and conditionals, preprocess first with
 //! [erl_pp](https://docs.rs/erl_pp).
 //! [`ParseMode`] selects the top-level construct; recovery and tree
-//! walking are in [`docs::diagnostics`] and [`docs::navigation`].
+//! walking are in [`docs::diagnostics`] and [`docs::navigation`]; typed
+//! per-kind accessors are in [`ast`]; conversion to OTP's abstract
+//! format is in [`abstract_format`]. [`LineIndex`] maps token indexes
+//! to byte offsets and line/column positions,
+//! [`Diagnostic::render`] formats diagnostics for people,
+//! [`format`] re-prints source in a consistent layout, [`edoc`] reads
+//! EDoc tags from the comments above forms, and [`docs_chunk`] collects
+//! `-doc` attributes into an EEP 48 docs chunk. [`model`] groups a
+//! module's forms by what they declare, and [`scope`] resolves each
+//! variable token to the variable it binds or uses; [`lint`] turns
+//! those bindings into unused and unsafe variable diagnostics. [`xref`]
+//! collects the calls and fun references each function makes, and
+//! [`query`] finds nodes by S-expression patterns over their kinds.
+//! [`visit`] walks a tree with per-kind [`visit::Visitor`] hooks.
 //!
 //! # Minimal loop
 //!
@@ -43,26 +56,56 @@
 //! Construct a [`Parser`] for a [`ParseMode`], feed tokens, pull completed
 //! `.`-terminated units with [`Parser::next_node`], then
 //! [`Parser::finish`]. Strict success is
-//! [`SyntaxTree::diagnostics`] being empty.
+//! [`SyntaxTree::diagnostics`] being empty. After an edit,
+//! [`SyntaxTree::reparse`] re-runs the grammar only for the units the
+//! edit touched.
+//!
+//! [`format`]: mod@crate::format
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+mod comment;
 mod cursor;
 mod diagnostic;
+mod document_symbols;
 mod event;
+mod folding_ranges;
 mod grammar;
+mod line_index;
 mod node;
 mod parser;
+mod render;
+mod reparse;
+mod selection_ranges;
+mod semantic_tokens;
 mod syntax;
 mod syntax_tree;
 mod token_buffer;
 mod token_range;
 
-pub use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+pub use crate::comment::CommentPlacement;
+pub use crate::diagnostic::{Diagnostic, DiagnosticCode, DiagnosticKind, Expected, Fix, Severity};
+pub use crate::document_symbols::{DocumentSymbol, SymbolKind};
+pub use crate::folding_ranges::{FoldingKind, FoldingRange};
+pub use crate::line_index::{LineColumn, LineIndex, Utf16Position};
 pub use crate::node::NodeView;
 pub use crate::parser::{ParseMode, Parser};
+pub use crate::render::Render;
+pub use crate::reparse::TokenEdit;
+pub use crate::semantic_tokens::{SemanticToken, SemanticTokenModifiers, SemanticTokenType};
 pub use crate::syntax::{NodeId, SyntaxKind};
 pub use crate::syntax_tree::SyntaxTree;
 pub use crate::token_range::{TokenIndex, TokenRange};
 
+pub mod abstract_format;
+pub mod ast;
 pub mod docs;
+pub mod docs_chunk;
+pub mod edoc;
+pub mod format;
+pub mod lint;
+pub mod model;
+pub mod query;
+pub mod scope;
+pub mod visit;
+pub mod xref;
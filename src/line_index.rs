@@ -0,0 +1,302 @@
+//! Mapping between token positions, byte offsets, and line/column
+//! positions.
+//!
+//! [`LineIndex`] is built once per tree from
+//! [`SyntaxTree::tokens`](crate::SyntaxTree::tokens) and the source they
+//! were scanned from, and answers position questions without rescanning.
+//! It owns its tables, so it can be cached next to the tree.
+
+use core::ops::Range;
+
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// A 1-based line and column.
+///
+/// `column` counts Unicode scalar values from the start of the line, as
+/// `erl_scan` and most editors do. This differs from
+/// [`erl_tokenize::Position::column`], which counts bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineColumn {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+}
+
+/// A 0-based line and UTF-16 code-unit column, the shape of an LSP
+/// `Position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Utf16Position {
+    /// 0-based line number.
+    pub line: u32,
+    /// 0-based column, in UTF-16 code units.
+    pub character: u32,
+}
+
+/// Position tables for one tree and its source text.
+///
+/// Token-based lookups assume every token in the tree was scanned from
+/// the same `source`; trees fed from a preprocessor that splices in
+/// other files need one index per file. Offsets past the end of the
+/// source are clamped to its length.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte span of each token, in buffer order.
+    tokens: Vec<Range<usize>>,
+    /// Byte offset at which each line starts; `line_starts[0] == 0`.
+    line_starts: Vec<usize>,
+    /// Byte offset and UTF-8 length of every non-ASCII character.
+    wide_chars: Vec<(usize, u8)>,
+    len: usize,
+}
+
+impl LineIndex {
+    /// Builds the index for `tree`, whose tokens were scanned from
+    /// `source`.
+    pub fn new(tree: &SyntaxTree, source: &str) -> Self {
+        let tokens = tree
+            .tokens()
+            .iter()
+            .map(|t| t.start().offset()..t.end().offset())
+            .collect();
+        let mut line_starts = vec![0];
+        let mut wide_chars = Vec::new();
+        for (offset, c) in source.char_indices() {
+            if c == '\n' {
+                line_starts.push(offset + 1);
+            } else if !c.is_ascii() {
+                wide_chars.push((offset, c.len_utf8() as u8));
+            }
+        }
+        Self {
+            tokens,
+            line_starts,
+            wide_chars,
+            len: source.len(),
+        }
+    }
+
+    /// Returns the byte offset of the boundary before `index`.
+    ///
+    /// For an existing token that is its start. For the EOF boundary
+    /// (`index == tokens.len()`) it is the end of the last token, so
+    /// end-of-input reports land just after the last thing written.
+    /// Indexes beyond that are clamped to the same boundary.
+    pub fn offset(&self, index: TokenIndex) -> usize {
+        match self.tokens.get(index.get()) {
+            Some(span) => span.start,
+            None => self.tokens.last().map_or(0, |span| span.end),
+        }
+    }
+
+    /// Returns the byte span a token range covers: from the first
+    /// token's start to the last token's end. Empty ranges yield the
+    /// empty span at [`LineIndex::offset`] of their start.
+    pub fn byte_range(&self, range: TokenRange) -> Range<usize> {
+        let start = self.offset(range.start());
+        if range.is_empty() {
+            return start..start;
+        }
+        let end = self
+            .tokens
+            .get(range.end().get() - 1)
+            .map_or(start, |span| span.end);
+        start..end.max(start)
+    }
+
+    /// Returns the 1-based line and character column of a byte offset.
+    pub fn line_column(&self, offset: usize) -> LineColumn {
+        let offset = offset.min(self.len);
+        let line = self.line_of(offset);
+        let start = self.line_starts[line];
+        let extra: usize = self
+            .wide_chars_in(start..offset)
+            .iter()
+            .map(|&(_, len)| usize::from(len) - 1)
+            .sum();
+        LineColumn {
+            line: line + 1,
+            column: offset - start - extra + 1,
+        }
+    }
+
+    /// Returns the LSP position of a byte offset: 0-based line and
+    /// UTF-16 code-unit column.
+    pub fn utf16_position(&self, offset: usize) -> Utf16Position {
+        let offset = offset.min(self.len);
+        let line = self.line_of(offset);
+        let start = self.line_starts[line];
+        let extra: usize = self
+            .wide_chars_in(start..offset)
+            .iter()
+            .map(|&(_, len)| {
+                // Four-byte characters take two UTF-16 units.
+                let units = if len == 4 { 2 } else { 1 };
+                usize::from(len) - units
+            })
+            .sum();
+        Utf16Position {
+            line: line as u32,
+            character: (offset - start - extra) as u32,
+        }
+    }
+
+    /// Returns the byte offset of a 1-based line and character column,
+    /// or `None` when the line does not exist or the column runs past
+    /// its end (the newline itself counts as the last column).
+    pub fn line_column_to_offset(&self, position: LineColumn) -> Option<usize> {
+        let start = *self.line_starts.get(position.line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(position.line)
+            .map_or(self.len, |next| next - 1);
+        if position.column == 0 {
+            return None;
+        }
+        let mut offset = start;
+        for _ in 1..position.column {
+            if offset >= end {
+                return None;
+            }
+            offset += self.char_len_at(offset);
+        }
+        Some(offset)
+    }
+
+    /// Returns the byte offset of an LSP position, or `None` when the
+    /// line does not exist or the column runs past its end.
+    pub fn utf16_position_to_offset(&self, position: Utf16Position) -> Option<usize> {
+        let line = position.line as usize;
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.len, |next| next - 1);
+        let mut offset = start;
+        let mut units = 0;
+        while units < position.character as usize {
+            if offset >= end {
+                return None;
+            }
+            let len = self.char_len_at(offset);
+            units += if len == 4 { 2 } else { 1 };
+            offset += len;
+        }
+        Some(offset)
+    }
+
+    /// Returns the token at a byte offset.
+    ///
+    /// That is the first token that ends after `offset`: the token
+    /// containing it, or the next one when `offset` falls between
+    /// tokens. Returns the EOF boundary (`tokens.len()`) past the last
+    /// token.
+    pub fn token_at(&self, offset: usize) -> TokenIndex {
+        TokenIndex::new(self.tokens.partition_point(|span| span.end <= offset))
+    }
+
+    /// The 0-based line containing `offset`.
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    fn wide_chars_in(&self, range: Range<usize>) -> &[(usize, u8)] {
+        let lo = self.wide_chars.partition_point(|&(o, _)| o < range.start);
+        let hi = self.wide_chars.partition_point(|&(o, _)| o < range.end);
+        &self.wide_chars[lo..hi]
+    }
+
+    fn char_len_at(&self, offset: usize) -> usize {
+        match self.wide_chars.binary_search_by_key(&offset, |&(o, _)| o) {
+            Ok(i) => usize::from(self.wide_chars[i].1),
+            Err(_) => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ParseMode, Parser};
+
+    fn index(source: &str) -> LineIndex {
+        let mut parser = Parser::new(ParseMode::Module);
+        for t in erl_tokenize::scan_tokens(source).expect("valid source") {
+            parser.feed_token(t);
+        }
+        LineIndex::new(&parser.finish(), source)
+    }
+
+    #[test]
+    fn columns_count_characters_and_utf16_units() {
+        let source = "f() ->\n  \"é😀\" ++ x.";
+        let index = index(source);
+        let x = source.find('x').unwrap();
+        assert_eq!(
+            index.line_column(x),
+            LineColumn {
+                line: 2,
+                column: 11
+            }
+        );
+        assert_eq!(
+            index.utf16_position(x),
+            Utf16Position {
+                line: 1,
+                character: 11
+            }
+        );
+        assert_eq!(
+            index.line_column_to_offset(LineColumn {
+                line: 2,
+                column: 11
+            }),
+            Some(x)
+        );
+        assert_eq!(
+            index.utf16_position_to_offset(Utf16Position {
+                line: 1,
+                character: 11
+            }),
+            Some(x)
+        );
+        assert_eq!(
+            index.line_column_to_offset(LineColumn { line: 1, column: 8 }),
+            None
+        );
+    }
+
+    #[test]
+    fn token_lookups_round_trip() {
+        let source = "f() -> ok.";
+        let index = index(source);
+        let ok = source.find("ok").unwrap();
+        let token = index.token_at(ok + 1);
+        assert_eq!(index.offset(token), ok);
+        assert_eq!(
+            index.byte_range(TokenRange::new(token, TokenIndex::new(token.get() + 2))),
+            ok..source.len()
+        );
+        assert_eq!(index.token_at(source.len()), TokenIndex::new(8));
+        assert_eq!(index.offset(TokenIndex::new(8)), source.len());
+    }
+
+    #[test]
+    fn eof_diagnostics_land_after_the_last_token() {
+        let source = "f() ->\n  ok +";
+        let mut parser = Parser::new(ParseMode::Module);
+        for t in erl_tokenize::scan_tokens(source).expect("valid source") {
+            parser.feed_token(t);
+        }
+        let tree = parser.finish();
+        let index = LineIndex::new(&tree, source);
+        let diagnostic = *tree.diagnostics().last().expect("missing operand");
+        assert_eq!(
+            diagnostic.line_column(&index),
+            LineColumn { line: 2, column: 7 }
+        );
+        let root = tree.roots().next().expect("function");
+        assert_eq!(root.byte_range(&index), 0..source.len());
+    }
+}
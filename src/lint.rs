@@ -0,0 +1,85 @@
+//! Variable problems `erl_lint` reports in code that parses.
+//!
+//! The parser only reports syntax errors. [`variables`] is an optional
+//! pass over a parsed tree that reports the variable problems `erl_lint`
+//! finds, using the bindings [`Scopes`] resolves:
+//!
+//! - [`UnusedVariable`](DiagnosticKind::UnusedVariable): a variable is
+//!   bound but never used, and its name does not start with `_`;
+//! - [`UnsafeVariable`](DiagnosticKind::UnsafeVariable): a variable
+//!   bound in only some clauses of a `case`, `if`, or `receive`, or
+//!   inside a `try`, `catch`, or `maybe`, is used after it;
+//! - [`UnderscoreVariableUsed`](DiagnosticKind::UnderscoreVariableUsed):
+//!   a `_Name` variable is used after it is bound.
+//!
+//! [`UnsafeVariable`](DiagnosticKind::UnsafeVariable) has
+//! [`Severity::Error`](crate::Severity::Error), since `erl_lint` rejects
+//! the code; the others have
+//! [`Severity::Warning`](crate::Severity::Warning). All of them render
+//! like syntax errors.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! let source = "f(X, Y) -> Y.";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let found = erl_parse::lint::variables(&tree, source);
+//! assert_eq!(found.len(), 1);
+//! assert_eq!(found[0].message(&tree, source), "variable `X` is unused");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::scope::{Role, Scopes};
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// Returns the variable diagnostics for `tree`, ordered by position.
+/// `source` is the text the tokens were scanned from.
+///
+/// Module-mode and Expression-mode trees are checked; see
+/// [`Scopes::new`] for what is analysed.
+pub fn variables(tree: &SyntaxTree, source: &str) -> Vec<Diagnostic> {
+    let scopes = Scopes::new(tree, source);
+    let report = |kind, at: TokenIndex| {
+        Diagnostic::new(
+            kind,
+            single(at),
+            Expected::Unspecified,
+            Some(tree.tokens()[at.get()]),
+        )
+    };
+    let mut diagnostics = Vec::new();
+    for variable in scopes.variables() {
+        let Some(&binding) = variable.bindings().first() else {
+            continue;
+        };
+        let ignored = variable.name().starts_with('_');
+        match variable.uses().first() {
+            None if !ignored => diagnostics.push(report(DiagnosticKind::UnusedVariable, binding)),
+            Some(&at) if ignored => {
+                diagnostics.push(report(DiagnosticKind::UnderscoreVariableUsed, at));
+            }
+            _ => {}
+        }
+    }
+    for &(at, occurrence) in scopes.occurrences() {
+        let Some(keyword) = occurrence.unsafe_in() else {
+            continue;
+        };
+        if occurrence.role() != Role::Use {
+            continue;
+        }
+        diagnostics.push(report(DiagnosticKind::UnsafeVariable, at).with_related(single(keyword)));
+    }
+    diagnostics.sort_by_key(|d| d.range().start());
+    diagnostics
+}
+
+fn single(at: TokenIndex) -> TokenRange {
+    TokenRange::new(at, TokenIndex::new(at.get() + 1))
+}
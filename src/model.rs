@@ -0,0 +1,552 @@
+//! A module's forms, grouped by what they declare.
+//!
+//! The parser keeps every form separate: each function declaration is
+//! its own [`FunctionDecl`](crate::SyntaxKind::FunctionDecl) root and
+//! each attribute an opaque [`Attribute`](crate::SyntaxKind::Attribute).
+//! [`ModuleModel::new`] reads the Module-mode roots once and collects
+//! the module name, exports, imports, records, types, specs, callbacks,
+//! and functions, the latter keyed by [`NameArity`] and paired with
+//! their `-spec`.
+//!
+//! It also reports what `erlc` rejects when it groups clauses:
+//!
+//! - a function defined by two declarations
+//!   ([`DuplicateFunction`](DiagnosticKind::DuplicateFunction));
+//! - a clause with a different arity from the first clause of its
+//!   declaration
+//!   ([`ClauseArityMismatch`](DiagnosticKind::ClauseArityMismatch));
+//! - a clause with a different name
+//!   ([`ClauseNameMismatch`](DiagnosticKind::ClauseNameMismatch)).
+//!
+//! A function takes its name and arity from its first clause.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::model::ModuleModel;
+//!
+//! let source = "-module(m).\n-export([f/1]).\n-spec f(integer()) -> integer().\nf(X) -> X.\n";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let model = ModuleModel::new(&tree, source);
+//! assert_eq!(model.name(), Some("m"));
+//! let f = model.function("f", 1).expect("f/1");
+//! assert!(f.is_exported());
+//! assert!(f.spec().is_some());
+//! assert!(model.diagnostics().is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+use erl_tokenize::TokenValue;
+
+use crate::abstract_format::{self, Location, Term};
+use crate::ast::{self, AstNode};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::node::NodeView;
+use crate::syntax::NodeId;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// A `Name/Arity` pair naming a function or a type.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NameArity {
+    name: String,
+    arity: usize,
+}
+
+impl NameArity {
+    /// Makes a pair.
+    pub fn new(name: impl Into<String>, arity: usize) -> Self {
+        Self {
+            name: name.into(),
+            arity,
+        }
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the arity.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn is(&self, name: &str, arity: usize) -> bool {
+        self.name == name && self.arity == arity
+    }
+}
+
+impl fmt::Display for NameArity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.name, self.arity)
+    }
+}
+
+/// A function: its first [`FunctionDecl`](crate::SyntaxKind::FunctionDecl)
+/// and the `-spec` for the same `Name/Arity`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    key: NameArity,
+    node: NodeId,
+    spec: Option<NodeId>,
+    exported: bool,
+}
+
+impl Function {
+    /// Returns the name and arity of the first clause.
+    pub fn key(&self) -> &NameArity {
+        &self.key
+    }
+
+    /// Returns the `FunctionDecl` root. A duplicate declaration is
+    /// reported rather than recorded, so this is the first one.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+
+    /// Returns the `-spec` attribute root, if any.
+    pub fn spec(&self) -> Option<NodeId> {
+        self.spec
+    }
+
+    /// Returns `true` when an `-export` lists the function or the
+    /// module is compiled with `export_all`.
+    pub fn is_exported(&self) -> bool {
+        self.exported
+    }
+}
+
+/// A `-type`, `-opaque`, or `-nominal` declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Type {
+    key: NameArity,
+    node: NodeId,
+    opaque: bool,
+    exported: bool,
+}
+
+impl Type {
+    /// Returns the type name and the number of parameters.
+    pub fn key(&self) -> &NameArity {
+        &self.key
+    }
+
+    /// Returns the `Attribute` root.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+
+    /// Returns `true` for `-opaque`.
+    pub fn is_opaque(&self) -> bool {
+        self.opaque
+    }
+
+    /// Returns `true` when an `-export_type` lists the type.
+    pub fn is_exported(&self) -> bool {
+        self.exported
+    }
+}
+
+/// A `-spec` or `-callback` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    key: NameArity,
+    node: NodeId,
+}
+
+impl Spec {
+    /// Returns the function name and the arity of the first signature.
+    pub fn key(&self) -> &NameArity {
+        &self.key
+    }
+
+    /// Returns the `Attribute` root.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+}
+
+/// A `-record` declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    name: String,
+    node: NodeId,
+    fields: Vec<String>,
+}
+
+impl Record {
+    /// Returns the record name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the `Attribute` root.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+
+    /// Returns the field names in declaration order.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+}
+
+/// A function named by an `-import` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    module: String,
+    key: NameArity,
+    node: NodeId,
+}
+
+impl Import {
+    /// Returns the module the function comes from.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Returns the imported function.
+    pub fn key(&self) -> &NameArity {
+        &self.key
+    }
+
+    /// Returns the `Attribute` root.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+}
+
+/// The declarations of one module. See the [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleModel {
+    name: Option<String>,
+    exports: Vec<NameArity>,
+    export_types: Vec<NameArity>,
+    imports: Vec<Import>,
+    records: Vec<Record>,
+    types: Vec<Type>,
+    specs: Vec<Spec>,
+    callbacks: Vec<Spec>,
+    functions: Vec<Function>,
+    /// The position of each function in `functions`.
+    function_index: HashMap<NameArity, usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ModuleModel {
+    /// Reads the Module-mode `tree`. `source` is the text the tokens
+    /// were scanned from.
+    ///
+    /// Forms that error recovery left a hole in are skipped where their
+    /// name or arity cannot be read.
+    pub fn new(tree: &SyntaxTree, source: &str) -> Self {
+        let mut model = Self::default();
+        let mut export_all = false;
+        for root in tree.roots() {
+            if let Some(decl) = ast::FunctionDecl::cast(root) {
+                model.function_decl(tree, source, decl);
+                continue;
+            }
+            let Some(attribute) = ast::Attribute::cast(root) else {
+                continue;
+            };
+            let Some((name, _)) = attribute_name(tree, source, attribute) else {
+                continue;
+            };
+            let node = root.node_id();
+            match name.as_str() {
+                "module" => {
+                    let value = attribute_value(tree, source, root);
+                    let module = match &value {
+                        Some(Term::Tuple(parts)) => parts.first(),
+                        value => value.as_ref(),
+                    };
+                    model.name = module.and_then(Term::as_atom).map(str::to_string);
+                }
+                "export" => {
+                    let value = attribute_value(tree, source, root);
+                    model.exports.extend(value.iter().flat_map(name_arities));
+                }
+                "export_type" => {
+                    let value = attribute_value(tree, source, root);
+                    model
+                        .export_types
+                        .extend(value.iter().flat_map(name_arities));
+                }
+                "import" => {
+                    if let Some(Term::Tuple(parts)) = attribute_value(tree, source, root)
+                        && let [module, list] = parts.as_slice()
+                        && let Some(module) = module.as_atom()
+                    {
+                        model.imports.extend(name_arities(list).map(|key| Import {
+                            module: module.to_string(),
+                            key,
+                            node,
+                        }));
+                    }
+                }
+                "compile" => {
+                    let value = attribute_value(tree, source, root);
+                    export_all |= value.is_some_and(|options| exports_all(&options));
+                }
+                "record" => {
+                    if let Some(record) = record(tree, source, root) {
+                        model.records.push(record);
+                    }
+                }
+                "type" | "opaque" | "nominal" => {
+                    if let Some((key, _, _)) = declaration(tree, source, root) {
+                        model.types.push(Type {
+                            key,
+                            node,
+                            opaque: name == "opaque",
+                            exported: false,
+                        });
+                    }
+                }
+                "spec" | "callback" => {
+                    if let Some((key, _, _)) = declaration(tree, source, root) {
+                        let spec = Spec { key, node };
+                        match name.as_str() {
+                            "spec" => model.specs.push(spec),
+                            _ => model.callbacks.push(spec),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let exports: HashSet<&NameArity> = model.exports.iter().collect();
+        let mut specs = HashMap::new();
+        for spec in &model.specs {
+            specs.entry(&spec.key).or_insert(spec.node);
+        }
+        for function in &mut model.functions {
+            function.exported = export_all || exports.contains(&function.key);
+            function.spec = specs.get(&function.key).copied();
+        }
+        let export_types: HashSet<&NameArity> = model.export_types.iter().collect();
+        for ty in &mut model.types {
+            ty.exported = export_types.contains(&ty.key);
+        }
+        model
+    }
+
+    /// Records a function declaration and reports its head mismatches,
+    /// or reports it as a duplicate.
+    fn function_decl(&mut self, tree: &SyntaxTree, source: &str, decl: ast::FunctionDecl<'_>) {
+        let mut clauses = decl.clauses().filter_map(|clause| {
+            let name = clause.name()?;
+            Some((
+                name,
+                atom(tree, source, name)?,
+                clause.arguments()?.args().count(),
+            ))
+        });
+        let Some((first, name, arity)) = clauses.next() else {
+            return;
+        };
+        let report = |kind, at: TokenIndex| {
+            Diagnostic::new(
+                kind,
+                TokenRange::new(at, TokenIndex::new(at.get() + 1)),
+                Expected::Unspecified,
+                Some(tree.tokens()[at.get()]),
+            )
+        };
+        for (at, other, other_arity) in clauses {
+            if other != name {
+                self.diagnostics
+                    .push(report(DiagnosticKind::ClauseNameMismatch, at));
+            } else if other_arity != arity {
+                self.diagnostics
+                    .push(report(DiagnosticKind::ClauseArityMismatch, at));
+            }
+        }
+        let key = NameArity::new(name, arity);
+        if self.function_index.contains_key(&key) {
+            self.diagnostics
+                .push(report(DiagnosticKind::DuplicateFunction, first));
+            return;
+        }
+        self.function_index
+            .insert(key.clone(), self.functions.len());
+        self.functions.push(Function {
+            key,
+            node: decl.syntax().node_id(),
+            spec: None,
+            exported: false,
+        });
+    }
+
+    /// Returns the name given by `-module`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the `-export` entries in source order.
+    pub fn exports(&self) -> &[NameArity] {
+        &self.exports
+    }
+
+    /// Returns the `-export_type` entries in source order.
+    pub fn export_types(&self) -> &[NameArity] {
+        &self.export_types
+    }
+
+    /// Returns the imported functions in source order.
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    /// Returns the records in source order.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Returns the types in source order.
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+
+    /// Returns the `-spec` attributes in source order.
+    pub fn specs(&self) -> &[Spec] {
+        &self.specs
+    }
+
+    /// Returns the `-callback` attributes in source order.
+    pub fn callbacks(&self) -> &[Spec] {
+        &self.callbacks
+    }
+
+    /// Returns the functions in the order they are first defined.
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// Returns the function `name/arity`.
+    pub fn function(&self, name: &str, arity: usize) -> Option<&Function> {
+        let at = self.function_index.get(&NameArity::new(name, arity))?;
+        Some(&self.functions[*at])
+    }
+
+    /// Returns the type `name/arity`.
+    pub fn ty(&self, name: &str, arity: usize) -> Option<&Type> {
+        self.types.iter().find(|t| t.key.is(name, arity))
+    }
+
+    /// Returns the record called `name`.
+    pub fn record(&self, name: &str) -> Option<&Record> {
+        self.records.iter().find(|r| r.name == name)
+    }
+
+    /// Returns the duplicate definitions and head mismatches, in source
+    /// order within each declaration. Each one points at a clause name.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+}
+
+/// Returns an attribute's name and the line it is on.
+pub(crate) fn attribute_name(
+    tree: &SyntaxTree,
+    source: &str,
+    attribute: ast::Attribute<'_>,
+) -> Option<(String, usize)> {
+    let at = attribute.name()?.token()?;
+    Some((
+        atom(tree, source, at)?,
+        tree.tokens()[at.get()].start().line().get(),
+    ))
+}
+
+/// Returns `Value` of the attribute's `{attribute, Anno, Name, Value}`
+/// form.
+pub(crate) fn attribute_value(tree: &SyntaxTree, source: &str, root: NodeView<'_>) -> Option<Term> {
+    match abstract_format::form(tree, source, root, Location::Line)? {
+        Term::Tuple(mut elements) if elements.len() == 4 => elements.pop(),
+        _ => None,
+    }
+}
+
+/// Returns the name of an `atom` token.
+pub(crate) fn atom(tree: &SyntaxTree, source: &str, at: TokenIndex) -> Option<String> {
+    match tree.tokens().get(at.get())?.value(source) {
+        TokenValue::Atom(name) => Some(name.into_owned()),
+        _ => None,
+    }
+}
+
+/// Reads a `[{Name, Arity}, ...]` list.
+pub(crate) fn name_arities(list: &Term) -> impl Iterator<Item = NameArity> + '_ {
+    list.as_list().into_iter().flatten().filter_map(|pair| {
+        let [name, arity] = pair.as_tuple()? else {
+            return None;
+        };
+        Some(NameArity::new(
+            name.as_atom()?,
+            usize::try_from(arity.as_i64()?).ok()?,
+        ))
+    })
+}
+
+/// Returns `true` when `-compile` options include `export_all`.
+pub(crate) fn exports_all(options: &Term) -> bool {
+    let export_all = |option: &Term| option.as_atom() == Some("export_all");
+    export_all(options)
+        || options
+            .as_list()
+            .is_some_and(|list| list.iter().any(export_all))
+}
+
+/// Reads the name and arity of a `-type`-like or `-spec`-like
+/// attribute, with the name token and the parameter list it was read
+/// from.
+pub(crate) fn declaration(
+    tree: &SyntaxTree,
+    source: &str,
+    root: NodeView<'_>,
+) -> Option<(NameArity, TokenIndex, TokenRange)> {
+    let payload = tree.parse_attribute_payload(root, source)?;
+    if !payload.diagnostics().is_empty() {
+        return None;
+    }
+    let node = payload.roots().next()?;
+    let (name, arity, params) = if let Some(spec) = ast::SpecAttribute::cast(node) {
+        let params = spec.clauses().next()?.params()?;
+        if params.is_any_arity() {
+            return None;
+        }
+        (spec.name()?, params.params().count(), params.syntax())
+    } else {
+        let decl = ast::TypeDecl::cast(node)?;
+        let params = decl.params()?;
+        (decl.name()?, params.args().count(), params.syntax())
+    };
+    Some((
+        NameArity::new(atom(tree, source, name)?, arity),
+        name,
+        params.range(),
+    ))
+}
+
+fn record(tree: &SyntaxTree, source: &str, root: NodeView<'_>) -> Option<Record> {
+    let payload = tree.parse_attribute_payload(root, source)?;
+    let decl = ast::RecordDecl::cast(payload.roots().next()?)?;
+    Some(Record {
+        name: atom(tree, source, decl.name()?)?,
+        node: root.node_id(),
+        fields: decl
+            .fields()
+            .filter_map(|field| atom(tree, source, field.name()?))
+            .collect(),
+    })
+}
@@ -10,6 +10,10 @@
 //! navigation is a concrete value type rather than an abstraction. All
 //! borrows share a single lifetime.
 
+use core::ops::Range;
+
+use crate::comment::CommentPlacement;
+use crate::line_index::{LineColumn, LineIndex};
 use crate::syntax::{NodeId, SyntaxIndex, SyntaxKind};
 use crate::token_buffer::TokenBuffer;
 use crate::token_range::{TokenIndex, TokenRange};
@@ -63,10 +67,54 @@ impl<'a> NodeView<'a> {
         self.entry_ref().range()
     }
 
+    /// Returns [`NodeView::range`] without the hidden tokens at either
+    /// end, or an empty range at its start when every token is hidden.
+    pub(crate) fn lexical_range(self) -> TokenRange {
+        let range = self.range();
+        let slice = self.tokens.as_slice();
+        let lexical = |&i: &usize| slice[i].kind().is_lexical();
+        match (
+            range.as_range().find(lexical),
+            range.as_range().rev().find(lexical),
+        ) {
+            (Some(first), Some(last)) => {
+                TokenRange::new(TokenIndex::new(first), TokenIndex::new(last + 1))
+            }
+            _ => TokenRange::new(range.start(), range.start()),
+        }
+    }
+
+    /// Returns [`NodeView::lexical_range`] extended over the `.` right
+    /// after the node, which ends a top-level form.
+    pub(crate) fn form_range(self) -> TokenRange {
+        let range = self.lexical_range();
+        let end = self.range().end();
+        let dot = self.tokens.get(end).is_some_and(|t| {
+            t.kind() == erl_tokenize::TokenKind::Symbol(erl_tokenize::Symbol::Dot)
+        });
+        if dot && !range.is_empty() {
+            TokenRange::new(range.start(), TokenIndex::new(end.get() + 1))
+        } else {
+            range
+        }
+    }
+
     fn subtree_fence(self) -> usize {
         self.entry_ref().subtree_end().get()
     }
 
+    /// Returns the id just past this node's subtree in preorder: its
+    /// next sibling, or whatever follows its last descendant.
+    pub(crate) fn subtree_end(self) -> NodeId {
+        NodeId::new(self.subtree_fence())
+    }
+
+    /// Returns the view of another entry of the same index, for walks
+    /// that step through the preorder array directly.
+    pub(crate) fn entry_view(self, node_id: NodeId) -> Option<Self> {
+        Self::new(self.tokens, self.index, node_id)
+    }
+
     /// Returns an iterator that walks direct children in preorder.
     pub fn children(self) -> impl Iterator<Item = NodeView<'a>> {
         Children {
@@ -95,6 +143,60 @@ impl<'a> NodeView<'a> {
         self.tokens.iter_range(self.range())
     }
 
+    /// Returns the comments attached in front of this node, in source
+    /// order: the `%%` lines directly above a function, or a comment
+    /// between a parent's opening token and this child. See
+    /// [`CommentPlacement`](crate::CommentPlacement) for the rules.
+    pub fn leading_comments(self) -> impl Iterator<Item = (TokenIndex, erl_tokenize::Token)> {
+        crate::comment::attached(self.tokens, self.index, self, CommentPlacement::Leading)
+    }
+
+    /// Returns the comments attached after this node, in source order:
+    /// a comment at the end of its last line, or below it when nothing
+    /// else follows in the parent.
+    pub fn trailing_comments(self) -> impl Iterator<Item = (TokenIndex, erl_tokenize::Token)> {
+        crate::comment::attached(self.tokens, self.index, self, CommentPlacement::Trailing)
+    }
+
+    /// Returns the comments inside this node that no child takes, in
+    /// source order, such as the comment in `[ % none ]`.
+    pub fn dangling_comments(self) -> impl Iterator<Item = (TokenIndex, erl_tokenize::Token)> {
+        crate::comment::attached(self.tokens, self.index, self, CommentPlacement::Dangling)
+    }
+
+    /// Returns the source text this node spans, hidden tokens included,
+    /// as a slice of `source` (the text the tokens were scanned from).
+    ///
+    /// The slice runs from the first token's start to the last token's
+    /// end, so whitespace, comments, and string escapes come back
+    /// exactly as written. Empty ranges yield `""`.
+    pub fn text(self, source: &str) -> &str {
+        let range = self.range();
+        let first = self.tokens.get(range.start());
+        let last = range
+            .end()
+            .get()
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(TokenIndex::new(i)));
+        match (first, last) {
+            (Some(first), Some(last)) if !range.is_empty() => {
+                &source[first.start().offset()..last.end().offset()]
+            }
+            _ => "",
+        }
+    }
+
+    /// Returns the byte span of this node's [`TokenRange`] in the
+    /// source `index` was built from. See [`LineIndex::byte_range`].
+    pub fn byte_range(self, index: &LineIndex) -> Range<usize> {
+        index.byte_range(self.range())
+    }
+
+    /// Returns the line and column of this node's first token.
+    pub fn line_column(self, index: &LineIndex) -> LineColumn {
+        index.line_column(index.offset(self.range().start()))
+    }
+
     /// Returns an iterator over ancestors starting from the root, moving
     /// toward the direct parent. The node itself is not included.
     pub fn ancestors(self) -> impl Iterator<Item = NodeView<'a>> {
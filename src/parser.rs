@@ -16,8 +16,10 @@
 //! [`CompletedMarker`]; the parser drains completed top-level units into
 //! the syntax index at boundaries.
 
+use erl_tokenize::{Symbol, TokenKind};
+
 use crate::cursor::{CursorCheckpoint, TokenCursor};
-use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected};
+use crate::diagnostic::{Diagnostic, DiagnosticCode, DiagnosticKind, Expected, code};
 use crate::event::Event;
 use crate::grammar::expr::parse_expr;
 use crate::grammar::ty::parse_type;
@@ -164,6 +166,13 @@ pub struct Parser {
     /// Which grammar sub-language is currently accepted (see
     /// [`ParseContext`]).
     context: ParseContext,
+    /// The last `,` between the expressions of the most recently
+    /// completed clause body. Clause-list recovery reads it to suggest
+    /// `;` when a body runs into the next clause's `->`.
+    body_comma: Option<TokenIndex>,
+    /// The most recent run of tokens recovery skipped. Missing-token
+    /// fixes read it to avoid inserting after a swept-up `.`.
+    skipped: Option<SkippedRun>,
 }
 
 impl Parser {
@@ -194,7 +203,7 @@ impl Parser {
     pub fn new(mode: ParseMode) -> Self {
         Self {
             mode,
-            tree: SyntaxTree::new(),
+            tree: SyntaxTree::new(mode),
             events: Vec::new(),
             at: 0,
             depth: 0,
@@ -204,9 +213,29 @@ impl Parser {
             unit_events_cursor: 0,
             pending_pull: std::collections::VecDeque::new(),
             context: ParseContext::Expression,
+            body_comma: None,
+            skipped: None,
         }
     }
 
+    /// Creates a parser that continues after `tree`: its tokens, nodes,
+    /// diagnostics, and unit boundaries are kept, and the cursor sits
+    /// at the end of its buffer as if every token had just been fed.
+    /// Used by [`SyntaxTree::reparse`] around the units it reuses.
+    pub(crate) fn resume(tree: SyntaxTree) -> Self {
+        let at = tree.token_buffer().end_index().get();
+        let mut parser = Self::new(tree.mode());
+        parser.tree = tree;
+        parser.at = at;
+        parser
+    }
+
+    /// Consumes the parser and returns its tree as is, without the
+    /// end-of-input handling of [`Parser::finish`].
+    pub(crate) fn into_syntax_tree(self) -> SyntaxTree {
+        self.tree
+    }
+
     /// Returns the mode this parser was constructed with.
     pub fn mode(&self) -> ParseMode {
         self.mode
@@ -363,9 +392,47 @@ impl Parser {
     /// recovery loop that tries several alternatives at the same
     /// cursor position does not surface the same diagnostic twice.
     pub(crate) fn push_diagnostic(&mut self, diagnostic: Diagnostic) {
+        if diagnostic.kind() == DiagnosticKind::SkippedToken {
+            self.note_skipped(diagnostic.range());
+        }
         crate::diagnostic::push_unique_at_cursor(self.tree.diagnostics_mut(), diagnostic);
     }
 
+    /// Extends the current [`SkippedRun`] with `range`, or starts a new
+    /// one when a consumed lexical token separates the two.
+    fn note_skipped(&mut self, range: TokenRange) {
+        let before = self.lexical_before(range.start());
+        let tokens = self.tree.tokens();
+        let swept_dot = (range.start().get()..range.end().get().min(tokens.len()))
+            .any(|i| tokens[i].kind() == TokenKind::Symbol(Symbol::Dot));
+        self.skipped = Some(match self.skipped {
+            Some(run) if before.is_some_and(|b| b < run.end) => SkippedRun {
+                end: range.end(),
+                swept_dot: run.swept_dot || swept_dot,
+                ..run
+            },
+            _ => SkippedRun {
+                end: range.end(),
+                before,
+                swept_dot,
+            },
+        });
+    }
+
+    /// Returns the last lexical token before `at`.
+    pub(crate) fn lexical_before(&self, at: TokenIndex) -> Option<TokenIndex> {
+        let tokens = self.tree.tokens();
+        (0..at.get().min(tokens.len()))
+            .rev()
+            .find(|&i| tokens[i].kind().is_lexical())
+            .map(TokenIndex::new)
+    }
+
+    /// Returns the most recent run of skipped tokens.
+    pub(crate) fn skipped_run(&self) -> Option<SkippedRun> {
+        self.skipped
+    }
+
     /// Enters a nested grammar site and increments the depth
     /// counter. Returns `true` when the site may recurse; returns
     /// `false` when the site would exceed
@@ -436,6 +503,16 @@ impl Parser {
         std::mem::replace(&mut self.context, context)
     }
 
+    /// Returns the last `,` of the most recently completed clause body.
+    pub(crate) fn body_comma(&self) -> Option<TokenIndex> {
+        self.body_comma
+    }
+
+    /// Records the last `,` of the clause body just completed.
+    pub(crate) fn set_body_comma(&mut self, comma: Option<TokenIndex>) {
+        self.body_comma = comma;
+    }
+
     /// Resets driver state so a grammar module's tests can drive the
     /// cursor manually from position 0. Never called in production
     /// paths.
@@ -497,6 +574,7 @@ impl Parser {
             cursor: TokenCursor::new(self.tree.token_buffer(), self.at).save(),
             events_len: self.events.len(),
             diagnostics_len: self.tree.diagnostics().len(),
+            skipped: self.skipped,
         }
     }
 
@@ -515,6 +593,7 @@ impl Parser {
         self.tree
             .diagnostics_mut()
             .truncate(checkpoint.diagnostics_len);
+        self.skipped = checkpoint.skipped;
     }
 
     /// Runs `body` inside a nesting-depth-tracked block. `body` returns the
@@ -574,23 +653,21 @@ impl Parser {
             ParseMode::Expression => self.advance_dot_driven_grammar(
                 parse_expr,
                 RecoveryContext::Expression,
-                "`.` to close top-level expression",
+                &code::EXPRESSION_DOT,
             ),
             ParseMode::Module => self.advance_dot_driven_grammar(
                 crate::grammar::module::parse_top_form,
                 RecoveryContext::Form,
-                "`.` to close top-level form",
+                &code::FORM_DOT,
             ),
             ParseMode::TermList => self.advance_dot_driven_grammar(
                 crate::grammar::term_list::parse_top_term,
                 RecoveryContext::Term,
-                "`.` to close top-level term",
-            ),
-            ParseMode::Type => self.advance_dot_driven_grammar(
-                parse_type,
-                RecoveryContext::Type,
-                "`.` to close top-level type",
+                &code::TERM_DOT,
             ),
+            ParseMode::Type => {
+                self.advance_dot_driven_grammar(parse_type, RecoveryContext::Type, &code::TYPE_DOT)
+            }
         }
     }
 
@@ -609,7 +686,7 @@ impl Parser {
         &mut self,
         parse_one: F,
         context: RecoveryContext,
-        unexpected_msg: &'static str,
+        category: &'static DiagnosticCode,
     ) where
         F: Fn(&mut Parser) -> CompletedMarker,
     {
@@ -619,8 +696,7 @@ impl Parser {
             // Recovery: wrap any leftover tokens before the boundary
             // dot into a single Error node with a matching
             // SkippedToken diagnostic.
-            let _ =
-                crate::grammar::recovery::skip_until_sync(self, context, is_dot, unexpected_msg);
+            let _ = crate::grammar::recovery::skip_until_sync(self, context, is_dot, category);
             // Consume the boundary dot itself.
             if let Some((_, token)) = self.peek_lexical(0)
                 && is_dot(token)
@@ -628,6 +704,8 @@ impl Parser {
                 self.consume_lexical();
             }
             self.finalize_pending_units();
+            let end = TokenIndex::new(self.at);
+            self.tree.push_unit(end);
         }
     }
 
@@ -662,6 +740,23 @@ impl Parser {
     }
 }
 
+/// Runs the structured attribute-payload grammar over `tokens` from
+/// `start` to the end of the buffer and returns the resulting tree.
+/// The buffer is the caller's, so every [`TokenIndex`] in the result
+/// names the same token as in the tree the payload came from.
+pub(crate) fn parse_attribute_payload(
+    tokens: TokenBuffer,
+    start: TokenIndex,
+    shape: crate::grammar::attribute_payload::PayloadShape,
+) -> SyntaxTree {
+    let mut p = Parser::new(ParseMode::Module);
+    *p.tree.tokens_mut() = tokens;
+    p.at = start.get();
+    let _completed = crate::grammar::attribute_payload::parse_payload(&mut p, shape);
+    p.finalize_pending_units();
+    p.tree
+}
+
 fn is_dot(token: erl_tokenize::Token) -> bool {
     matches!(
         token.kind(),
@@ -783,6 +878,19 @@ pub(crate) struct Checkpoint {
     cursor: CursorCheckpoint,
     events_len: usize,
     diagnostics_len: usize,
+    skipped: Option<SkippedRun>,
+}
+
+/// Adjacent `SkippedToken` spans with only skipped tokens between
+/// them, as recovery reported them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SkippedRun {
+    /// One past the last skipped token.
+    pub(crate) end: TokenIndex,
+    /// The last lexical token before the run.
+    pub(crate) before: Option<TokenIndex>,
+    /// Whether the run includes a `.`.
+    pub(crate) swept_dot: bool,
 }
 
 /// Zero-sized proof returned by parser-loop bodies that at least one token
@@ -0,0 +1,706 @@
+//! S-expression queries over syntax trees.
+//!
+//! A [`Query`] is a list of patterns in the style of tree-sitter
+//! queries, written with [`SyntaxKind`] names. [`Query::matches`] runs
+//! every pattern against every node of a tree, in preorder, and returns
+//! the nodes each pattern captured:
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use erl_parse::query::Query;
+//!
+//! let source = "f(L) -> lists:reverse(L), io:format(L).";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let query = Query::new(
+//!     r#"((CallExpr (RemoteExpr (AtomExpr) @mod (AtomExpr) @fun) (ArgumentList))
+//!         (#eq? @mod "lists"))"#,
+//! )?;
+//! let fun = query.capture_index("fun").expect("@fun");
+//! let found: Vec<_> = query
+//!     .matches(&tree, source)
+//!     .iter()
+//!     .flat_map(|m| m.nodes(fun))
+//!     .map(|node| node.text(source))
+//!     .collect();
+//! assert_eq!(found, ["reverse"]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Syntax
+//!
+//! - `(Kind child ...)` matches a `Kind` node whose children include
+//!   nodes matching each child pattern, in order but not necessarily
+//!   adjacent. `(_ child ...)` matches a node of any kind.
+//! - `_` matches any node.
+//! - `[pattern ...]` matches a node that any alternative matches.
+//! - `@name` after a pattern captures the node it matched. Right after
+//!   the kind, `(Kind @name ...)` is shorthand for `(Kind ...) @name`.
+//! - `.` between two child patterns makes them adjacent siblings; before
+//!   the first it requires the first child, and after the last the
+//!   last child.
+//! - `(#eq? @name "text")` and `(#not-eq? @name "text")` compare a
+//!   capture's text with a string or, given `@other`, with another
+//!   capture's text. `(#any-of? @name "a" "b" ...)` and
+//!   `(#not-any-of? ...)` compare it with several strings. A predicate
+//!   goes inside the pattern it filters, or after it at the top level;
+//!   `((Kind) @name (#eq? @name "text"))` groups a pattern with its
+//!   predicates.
+//! - `;` starts a comment that runs to the end of the line.
+//!
+//! A capture's text is the source from its first to its last lexical
+//! token, as written: the atom `'lists'` is not equal to `"lists"`.
+//! Each pattern reports at most one match per node, the first in
+//! child order that satisfies its predicates.
+
+use core::fmt;
+
+use crate::ast::kind_from_name;
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+
+/// A compiled query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    patterns: Vec<TopPattern>,
+    capture_names: Vec<String>,
+}
+
+/// Why [`Query::new`] rejected a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    offset: usize,
+    message: String,
+}
+
+impl QueryError {
+    /// Returns the byte offset in the query text where the problem is.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns a description such as ``unknown node kind `Foo` ``.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl core::error::Error for QueryError {}
+
+/// One match of one pattern.
+#[derive(Debug, Clone)]
+pub struct QueryMatch<'a> {
+    pattern: usize,
+    node: NodeView<'a>,
+    captures: Vec<(usize, NodeView<'a>)>,
+}
+
+impl<'a> QueryMatch<'a> {
+    /// Returns the index of the pattern that matched, in query order.
+    pub fn pattern_index(&self) -> usize {
+        self.pattern
+    }
+
+    /// Returns the node the pattern matched.
+    pub fn node(&self) -> NodeView<'a> {
+        self.node
+    }
+
+    /// Returns the captures as `(capture index, node)` pairs, in the
+    /// order the pattern captured them. See [`Query::capture_names`].
+    pub fn captures(&self) -> &[(usize, NodeView<'a>)] {
+        &self.captures
+    }
+
+    /// Returns the nodes captured as `capture`.
+    pub fn nodes(&self, capture: usize) -> impl Iterator<Item = NodeView<'a>> + '_ {
+        self.captures
+            .iter()
+            .filter(move |&&(index, _)| index == capture)
+            .map(|&(_, node)| node)
+    }
+}
+
+impl Query {
+    /// Compiles `query`.
+    pub fn new(query: &str) -> Result<Self, QueryError> {
+        let mut compiler = Compiler {
+            lexer: Lexer {
+                text: query,
+                offset: 0,
+            },
+            capture_names: Vec::new(),
+        };
+        let mut patterns = Vec::new();
+        loop {
+            let (offset, token) = compiler.lexer.peek()?;
+            match token {
+                None => break,
+                Some(Token::Open) if compiler.lexer.peek_predicate() => {
+                    let Some(TopPattern { predicates, .. }) = patterns.last_mut() else {
+                        return Err(error(offset, "a predicate must follow a pattern"));
+                    };
+                    let predicate = compiler.predicate()?;
+                    predicates.push(predicate);
+                }
+                Some(_) => {
+                    let mut predicates = Vec::new();
+                    let pattern = compiler.pattern(&mut predicates)?;
+                    patterns.push(TopPattern {
+                        pattern,
+                        predicates,
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            patterns,
+            capture_names: compiler.capture_names,
+        })
+    }
+
+    /// Returns the capture names, without `@`, indexed by capture.
+    pub fn capture_names(&self) -> &[String] {
+        &self.capture_names
+    }
+
+    /// Returns the index of the capture named `name` (without `@`).
+    pub fn capture_index(&self, name: &str) -> Option<usize> {
+        self.capture_names.iter().position(|n| n == name)
+    }
+
+    /// Returns the number of top-level patterns.
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Runs the query over every node of `tree`. `source` is the text
+    /// the tokens were scanned from, for text predicates.
+    ///
+    /// Matches are ordered by node in preorder, then by pattern.
+    pub fn matches<'a>(&self, tree: &'a SyntaxTree, source: &str) -> Vec<QueryMatch<'a>> {
+        let matcher = Matcher { tree, source };
+        let mut matches = Vec::new();
+        for root in tree.roots() {
+            for node in core::iter::once(root).chain(root.descendants()) {
+                for (index, top) in self.patterns.iter().enumerate() {
+                    let mut found = None;
+                    let mut captures = Vec::new();
+                    matcher.pattern(&top.pattern, node, &mut captures, &mut |captures| {
+                        let accepted = top
+                            .predicates
+                            .iter()
+                            .all(|predicate| matcher.holds(predicate, captures));
+                        if accepted {
+                            found = Some(captures.clone());
+                        }
+                        accepted
+                    });
+                    if let Some(captures) = found {
+                        matches.push(QueryMatch {
+                            pattern: index,
+                            node,
+                            captures,
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TopPattern {
+    pattern: Pattern,
+    predicates: Vec<Predicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    /// `(Kind ...)`, `(_ ...)`, or `_`; `kind` is `None` for a wildcard.
+    Node {
+        kind: Option<SyntaxKind>,
+        children: Vec<Child>,
+        anchor_end: bool,
+        captures: Vec<usize>,
+    },
+    /// `[...]`.
+    Alternation {
+        alternatives: Vec<Pattern>,
+        captures: Vec<usize>,
+    },
+}
+
+impl Pattern {
+    fn captures_mut(&mut self) -> &mut Vec<usize> {
+        match self {
+            Self::Node { captures, .. } | Self::Alternation { captures, .. } => captures,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Child {
+    pattern: Pattern,
+    /// Preceded by `.`: adjacent to the previous child pattern's node,
+    /// or the first child.
+    anchored: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Eq {
+        capture: usize,
+        value: Value,
+        negated: bool,
+    },
+    AnyOf {
+        capture: usize,
+        values: Vec<String>,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Capture(usize),
+    Text(String),
+}
+
+// ---------------------------------------------------------------------
+// Compiling.
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'q> {
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Dot,
+    Capture(&'q str),
+    Predicate(&'q str),
+    String(String),
+    Name(&'q str),
+}
+
+fn error(offset: usize, message: impl Into<String>) -> QueryError {
+    QueryError {
+        offset,
+        message: message.into(),
+    }
+}
+
+struct Lexer<'q> {
+    text: &'q str,
+    offset: usize,
+}
+
+impl<'q> Lexer<'q> {
+    /// Returns the next token and its offset without consuming it.
+    fn peek(&self) -> Result<(usize, Option<Token<'q>>), QueryError> {
+        let mut copy = Lexer {
+            text: self.text,
+            offset: self.offset,
+        };
+        copy.next()
+    }
+
+    /// Returns `true` when the next tokens are `(` and a `#predicate`.
+    fn peek_predicate(&self) -> bool {
+        let mut copy = Lexer {
+            text: self.text,
+            offset: self.offset,
+        };
+        matches!(copy.next(), Ok((_, Some(Token::Open))))
+            && matches!(copy.next(), Ok((_, Some(Token::Predicate(_)))))
+    }
+
+    fn next(&mut self) -> Result<(usize, Option<Token<'q>>), QueryError> {
+        loop {
+            let rest = &self.text[self.offset..];
+            let trimmed = rest.trim_start();
+            self.offset += rest.len() - trimmed.len();
+            if trimmed.starts_with(';') {
+                self.offset += trimmed.find('\n').unwrap_or(trimmed.len());
+                continue;
+            }
+            break;
+        }
+        let start = self.offset;
+        let rest = &self.text[start..];
+        let Some(c) = rest.chars().next() else {
+            return Ok((start, None));
+        };
+        let punctuation = match c {
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            '[' => Some(Token::OpenBracket),
+            ']' => Some(Token::CloseBracket),
+            '.' => Some(Token::Dot),
+            _ => None,
+        };
+        if let Some(token) = punctuation {
+            self.offset += 1;
+            return Ok((start, Some(token)));
+        }
+        if c == '"' {
+            let mut value = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.offset += i + 1;
+                        return Ok((start, Some(Token::String(value))));
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, c)) => value.push(c),
+                        None => break,
+                    },
+                    c => value.push(c),
+                }
+            }
+            return Err(error(start, "unterminated string"));
+        }
+        let word_len = |s: &str| {
+            s.find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '?' | '!')))
+                .unwrap_or(s.len())
+        };
+        let (prefix, body) = match c {
+            '@' | '#' => (1, &rest[1..]),
+            _ => (0, rest),
+        };
+        let len = word_len(body);
+        if len == 0 {
+            return Err(error(start, format!("unexpected `{c}`")));
+        }
+        self.offset += prefix + len;
+        let word = &body[..len];
+        Ok((
+            start,
+            Some(match c {
+                '@' => Token::Capture(word),
+                '#' => Token::Predicate(word),
+                _ => Token::Name(word),
+            }),
+        ))
+    }
+
+    /// Consumes the next token, which must be `expected`.
+    fn expect(&mut self, expected: Token<'q>, what: &str) -> Result<(), QueryError> {
+        match self.next()? {
+            (_, Some(token)) if token == expected => Ok(()),
+            (offset, _) => Err(error(offset, format!("expected {what}"))),
+        }
+    }
+}
+
+struct Compiler<'q> {
+    lexer: Lexer<'q>,
+    capture_names: Vec<String>,
+}
+
+impl<'q> Compiler<'q> {
+    /// Parses one pattern and the captures after it. Predicates inside
+    /// it are added to `predicates`.
+    fn pattern(&mut self, predicates: &mut Vec<Predicate>) -> Result<Pattern, QueryError> {
+        let (offset, token) = self.lexer.next()?;
+        let mut pattern = match token {
+            Some(Token::Name("_")) => Pattern::Node {
+                kind: None,
+                children: Vec::new(),
+                anchor_end: false,
+                captures: Vec::new(),
+            },
+            Some(Token::OpenBracket) => {
+                let mut alternatives = Vec::new();
+                while !matches!(self.lexer.peek()?.1, Some(Token::CloseBracket)) {
+                    alternatives.push(self.pattern(predicates)?);
+                }
+                self.lexer.next()?;
+                if alternatives.is_empty() {
+                    return Err(error(offset, "empty alternation"));
+                }
+                Pattern::Alternation {
+                    alternatives,
+                    captures: Vec::new(),
+                }
+            }
+            Some(Token::Open) => match self.lexer.peek()? {
+                (_, Some(Token::Open | Token::OpenBracket)) => {
+                    // A group: one pattern followed by its predicates.
+                    let pattern = self.pattern(predicates)?;
+                    while self.lexer.peek_predicate() {
+                        let predicate = self.predicate()?;
+                        predicates.push(predicate);
+                    }
+                    self.lexer.expect(Token::Close, "`)` to close the group")?;
+                    pattern
+                }
+                (offset, Some(Token::Name(name))) => {
+                    self.lexer.next()?;
+                    let kind =
+                        match name {
+                            "_" => None,
+                            name => Some(kind_from_name(name).ok_or_else(|| {
+                                error(offset, format!("unknown node kind `{name}`"))
+                            })?),
+                        };
+                    self.children(kind, predicates)?
+                }
+                (offset, _) => return Err(error(offset, "expected a node kind")),
+            },
+            _ => return Err(error(offset, "expected a pattern")),
+        };
+        while let (_, Some(Token::Capture(name))) = self.lexer.peek()? {
+            self.lexer.next()?;
+            let index = self.capture(name);
+            pattern.captures_mut().push(index);
+        }
+        Ok(pattern)
+    }
+
+    /// Parses the children of `(Kind ...` through the closing `)`.
+    fn children(
+        &mut self,
+        kind: Option<SyntaxKind>,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Pattern, QueryError> {
+        let mut children = Vec::new();
+        let mut captures = Vec::new();
+        let mut anchored = false;
+        loop {
+            let (offset, token) = self.lexer.peek()?;
+            match token {
+                Some(Token::Close) => {
+                    self.lexer.next()?;
+                    return Ok(Pattern::Node {
+                        kind,
+                        anchor_end: anchored && !children.is_empty(),
+                        children,
+                        captures,
+                    });
+                }
+                Some(Token::Capture(name)) if children.is_empty() && !anchored => {
+                    self.lexer.next()?;
+                    captures.push(self.capture(name));
+                }
+                Some(Token::Dot) => {
+                    self.lexer.next()?;
+                    anchored = true;
+                }
+                Some(Token::Open) if self.lexer.peek_predicate() => {
+                    let predicate = self.predicate()?;
+                    predicates.push(predicate);
+                }
+                None => return Err(error(offset, "expected `)` to close the pattern")),
+                Some(_) => {
+                    let pattern = self.pattern(predicates)?;
+                    children.push(Child { pattern, anchored });
+                    anchored = false;
+                }
+            }
+        }
+    }
+
+    /// Parses `(#name? args...)`.
+    fn predicate(&mut self) -> Result<Predicate, QueryError> {
+        self.lexer.expect(Token::Open, "`(`")?;
+        let (offset, Some(Token::Predicate(name))) = self.lexer.next()? else {
+            unreachable!("peek_predicate checked for a predicate name");
+        };
+        let mut args = Vec::new();
+        loop {
+            let (at, token) = self.lexer.next()?;
+            match token {
+                Some(Token::Close) => break,
+                Some(Token::Capture(capture)) => match self.capture_index(capture) {
+                    Some(index) => args.push(Value::Capture(index)),
+                    None => return Err(error(at, format!("undefined capture `@{capture}`"))),
+                },
+                Some(Token::String(text)) => args.push(Value::Text(text)),
+                _ => return Err(error(at, "expected a capture, a string, or `)`")),
+            }
+        }
+        let negated = name.starts_with("not-");
+        let mut args = args.into_iter();
+        let capture = match args.next() {
+            Some(Value::Capture(index)) => index,
+            _ => return Err(error(offset, format!("`#{name}` takes a capture first"))),
+        };
+        match name.trim_start_matches("not-") {
+            "eq?" => match (args.next(), args.next()) {
+                (Some(value), None) => Ok(Predicate::Eq {
+                    capture,
+                    value,
+                    negated,
+                }),
+                _ => Err(error(offset, format!("`#{name}` takes two arguments"))),
+            },
+            "any-of?" => {
+                let values = args
+                    .map(|value| match value {
+                        Value::Text(text) => Some(text),
+                        Value::Capture(_) => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| error(offset, format!("`#{name}` takes strings")))?;
+                Ok(Predicate::AnyOf {
+                    capture,
+                    values,
+                    negated,
+                })
+            }
+            _ => Err(error(offset, format!("unknown predicate `#{name}`"))),
+        }
+    }
+
+    fn capture(&mut self, name: &str) -> usize {
+        self.capture_index(name).unwrap_or_else(|| {
+            self.capture_names.push(name.to_string());
+            self.capture_names.len() - 1
+        })
+    }
+
+    fn capture_index(&self, name: &str) -> Option<usize> {
+        self.capture_names.iter().position(|n| n == name)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Matching.
+// ---------------------------------------------------------------------
+
+type Captures<'a> = Vec<(usize, NodeView<'a>)>;
+
+struct Matcher<'a, 's> {
+    tree: &'a SyntaxTree,
+    source: &'s str,
+}
+
+impl<'a> Matcher<'a, '_> {
+    /// Tries to match `pattern` at `node`, calling `accept` with the
+    /// captures of each way it matches until `accept` returns `true`.
+    fn pattern(
+        &self,
+        pattern: &Pattern,
+        node: NodeView<'a>,
+        captures: &mut Captures<'a>,
+        accept: &mut dyn FnMut(&Captures<'a>) -> bool,
+    ) -> bool {
+        let mark = captures.len();
+        let matched = match pattern {
+            Pattern::Node {
+                kind,
+                children,
+                anchor_end,
+                captures: names,
+            } => {
+                if kind.is_some_and(|kind| kind != node.kind()) {
+                    return false;
+                }
+                captures.extend(names.iter().map(|&index| (index, node)));
+                let kids: Vec<_> = node.children().collect();
+                self.children(children, &kids, None, *anchor_end, captures, accept)
+            }
+            Pattern::Alternation {
+                alternatives,
+                captures: names,
+            } => {
+                captures.extend(names.iter().map(|&index| (index, node)));
+                alternatives
+                    .iter()
+                    .any(|alternative| self.pattern(alternative, node, captures, accept))
+            }
+        };
+        captures.truncate(mark);
+        matched
+    }
+
+    /// Matches `patterns` against the children after `last`, the one
+    /// the previous pattern matched.
+    fn children(
+        &self,
+        patterns: &[Child],
+        kids: &[NodeView<'a>],
+        last: Option<usize>,
+        anchor_end: bool,
+        captures: &mut Captures<'a>,
+        accept: &mut dyn FnMut(&Captures<'a>) -> bool,
+    ) -> bool {
+        let Some((first, rest)) = patterns.split_first() else {
+            if anchor_end && last.is_some_and(|last| last + 1 != kids.len()) {
+                return false;
+            }
+            return accept(captures);
+        };
+        let next = last.map_or(0, |last| last + 1);
+        let candidates = if first.anchored {
+            next..(next + 1).min(kids.len())
+        } else {
+            next..kids.len()
+        };
+        candidates.into_iter().any(|at| {
+            self.pattern(&first.pattern, kids[at], captures, &mut |captures| {
+                let mut captures = captures.clone();
+                self.children(rest, kids, Some(at), anchor_end, &mut captures, accept)
+            })
+        })
+    }
+
+    fn holds(&self, predicate: &Predicate, captures: &Captures<'a>) -> bool {
+        let texts = |capture: usize| {
+            captures
+                .iter()
+                .filter(move |&&(index, _)| index == capture)
+                .map(|&(_, node)| self.text(node))
+        };
+        match predicate {
+            Predicate::Eq {
+                capture,
+                value,
+                negated,
+            } => {
+                let value = match value {
+                    Value::Text(text) => Some(text.as_str()),
+                    Value::Capture(other) => texts(*other).next(),
+                };
+                match value {
+                    Some(value) => texts(*capture).all(|text| (text == value) != *negated),
+                    None => true,
+                }
+            }
+            Predicate::AnyOf {
+                capture,
+                values,
+                negated,
+            } => texts(*capture).all(|text| values.iter().any(|v| v == text) != *negated),
+        }
+    }
+
+    /// Returns the source from `node`'s first to its last lexical token.
+    fn text(&self, node: NodeView<'_>) -> &str {
+        let range = node.lexical_range();
+        if range.is_empty() {
+            return "";
+        }
+        let tokens = self.tree.tokens();
+        let start = tokens[range.start().get()].start().offset();
+        let end = tokens[range.end().get() - 1].end().offset();
+        &self.source[start..end]
+    }
+}
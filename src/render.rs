@@ -0,0 +1,389 @@
+//! Human-readable diagnostic text.
+//!
+//! [`Diagnostic::message`] turns a diagnostic's kind, expectation, and
+//! offending token into a one-line sentence. [`Render`] lays that out
+//! either as a rustc-style report with a source snippet or as the
+//! single line `erlc` prints, so tools can pick whichever their users
+//! already read.
+
+use core::fmt;
+
+use erl_tokenize::{Token, TokenKind, TokenValue};
+
+use crate::abstract_format::{Integer, Term, pretty};
+use crate::ast::{self, AstNode};
+use crate::diagnostic::{Diagnostic, DiagnosticKind, Expected, Fix, Severity};
+use crate::line_index::LineIndex;
+use crate::model::{NameArity, atom};
+use crate::parser::Parser;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenRange;
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Longest token text quoted verbatim in a message; longer or
+/// multi-line tokens are described by kind instead.
+const MAX_QUOTED_TOKEN: usize = 24;
+
+impl Diagnostic {
+    /// Returns a one-line description such as ``expected expression,
+    /// found `->` `` or `` missing `)` to close argument list ``.
+    ///
+    /// `source` is the text the tree's tokens were scanned from.
+    pub fn message(self, tree: &SyntaxTree, source: &str) -> String {
+        let expected = describe_expected(self.expected());
+        let found = self.blamed_token(tree).map(|t| describe_token(t, source));
+        match (self.kind(), expected, found) {
+            (DiagnosticKind::DuplicateFunction, _, _) => match self.heads(tree, source) {
+                Some((head, _)) => format!("function `{head}` is already defined"),
+                None => "function is already defined".to_string(),
+            },
+            (DiagnosticKind::ClauseArityMismatch | DiagnosticKind::ClauseNameMismatch, _, _) => {
+                match self.heads(tree, source) {
+                    Some((head, first)) => {
+                        format!("head mismatch: `{head}` among the clauses of `{first}`")
+                    }
+                    None => "head mismatch".to_string(),
+                }
+            }
+            (DiagnosticKind::UnusedVariable, _, _) => {
+                format!("variable `{}` is unused", self.variable(tree, source))
+            }
+            (DiagnosticKind::UnsafeVariable, _, _) => format!(
+                "variable `{}` unsafe in `{}`",
+                self.variable(tree, source),
+                self.related_keyword(tree, source)
+            ),
+            (DiagnosticKind::UnderscoreVariableUsed, _, _) => format!(
+                "variable `{}` is used, but its leading `_` marks it as unused",
+                self.variable(tree, source)
+            ),
+            (DiagnosticKind::NestingDepthExceeded, _, _) => {
+                format!("nesting too deep (limit is {})", Parser::MAX_NESTING_DEPTH)
+            }
+            (DiagnosticKind::MissingToken, Some(expected), _) => format!("missing {expected}"),
+            (DiagnosticKind::MissingToken, None, _) => "missing token".to_string(),
+            (DiagnosticKind::UnexpectedEof, Some(expected), _) => {
+                format!("expected {expected}, found end of input")
+            }
+            (DiagnosticKind::UnexpectedEof, None, _) => "unexpected end of input".to_string(),
+            (_, Some(expected), Some(found)) => format!("expected {expected}, found {found}"),
+            (_, Some(expected), None) => format!("expected {expected}"),
+            (_, None, Some(found)) => format!("unexpected {found}"),
+            (_, None, None) => "syntax error".to_string(),
+        }
+    }
+
+    /// Returns a [`Render`] of this diagnostic over `tree` and its
+    /// `source`, ready to [`Display`](fmt::Display) as a snippet report
+    /// or to format as an `erlc` line.
+    pub fn render<'a>(self, tree: &'a SyntaxTree, source: &'a str) -> Render<'a> {
+        Render {
+            diagnostic: self,
+            tree,
+            source,
+            file: "nofile",
+            color: false,
+        }
+    }
+
+    /// The `name/arity` of the clause a [`ModuleModel`] diagnostic
+    /// points at, and of the first clause of its declaration.
+    ///
+    /// [`ModuleModel`]: crate::model::ModuleModel
+    fn heads(self, tree: &SyntaxTree, source: &str) -> Option<(NameArity, NameArity)> {
+        let head = |clause: ast::FunctionClause<'_>| {
+            let name = atom(tree, source, clause.name()?)?;
+            Some(NameArity::new(name, clause.arguments()?.args().count()))
+        };
+        let clause = ast::FunctionClause::cast(tree.innermost_containing(self.range().start())?)?;
+        let decl = ast::FunctionDecl::cast(clause.syntax().ancestors().last()?)?;
+        Some((head(clause)?, head(decl.clauses().next()?)?))
+    }
+
+    /// The variable name a [`lint::variables`] diagnostic points at.
+    ///
+    /// [`lint::variables`]: crate::lint::variables
+    fn variable<'a>(self, tree: &SyntaxTree, source: &'a str) -> &'a str {
+        self.blamed_token(tree).map_or("_", |t| t.text(source))
+    }
+
+    /// The keyword text of the related range, such as `case` or `try`.
+    fn related_keyword<'a>(self, tree: &SyntaxTree, source: &'a str) -> &'a str {
+        self.related()
+            .and_then(|range| tree.tokens().get(range.start().get()))
+            .map_or("", |t| t.text(source))
+    }
+
+    /// The token a report points at: the one the parser found, or else
+    /// the first token of a non-empty range.
+    fn blamed_token(self, tree: &SyntaxTree) -> Option<Token> {
+        self.found().or_else(|| {
+            let range = self.range();
+            tree.tokens()[range.as_range()]
+                .iter()
+                .find(|t| !t.kind().is_hidden())
+                .copied()
+        })
+    }
+}
+
+/// A [`Diagnostic`] ready for display.
+///
+/// The [`Display`](fmt::Display) form is a rustc-style report:
+///
+/// ```text
+/// error[EP0200]: expected expression, found `->`
+///  --> foo.erl:1:4
+///   |
+/// 1 | f( -> ok.
+///   |    ^^
+/// ```
+///
+/// [`Render::erlc`] gives the one-line form `erlc` prints instead. The
+/// file name defaults to `nofile`, as in OTP, and colors are off.
+#[derive(Debug, Clone, Copy)]
+pub struct Render<'a> {
+    diagnostic: Diagnostic,
+    tree: &'a SyntaxTree,
+    source: &'a str,
+    file: &'a str,
+    color: bool,
+}
+
+impl<'a> Render<'a> {
+    /// Sets the file name shown in the location.
+    pub fn file(mut self, file: &'a str) -> Self {
+        self.file = file;
+        self
+    }
+
+    /// Turns ANSI colors on or off.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Formats the diagnostic the way `erlc` reports a syntax error:
+    /// `file:Line:Col: syntax error before: Token`.
+    ///
+    /// The token is written as `erl_parse:format_error/1` writes it:
+    /// symbols and reserved words as quoted atoms, atoms quoted when
+    /// needed, strings in double quotes, integers in decimal. At end of
+    /// input the token part is empty. The column counts characters.
+    ///
+    /// [`ModuleModel`](crate::model::ModuleModel) diagnostics use the
+    /// wording `erlc` reports those problems with instead, such as
+    /// `function f/1 already defined`, and
+    /// [`lint::variables`](crate::lint::variables) warnings are written
+    /// as `Warning: variable 'X' is unused`. A use of an unsafe variable,
+    /// whether from `case` or from `try`, is written as `variable 'X'
+    /// unsafe in 'try' (line L, column C)` without the `Warning:`
+    /// prefix, since `erl_lint` rejects it as an error.
+    pub fn erlc(&self) -> String {
+        let index = LineIndex::new(self.tree, self.source);
+        let position = self.diagnostic.line_column(&index);
+        let token = match self.diagnostic.kind() {
+            DiagnosticKind::UnexpectedEof => None,
+            _ => self.diagnostic.blamed_token(self.tree).or_else(|| {
+                self.tree
+                    .tokens()
+                    .get(self.diagnostic.range().start().get())
+                    .copied()
+            }),
+        };
+        let heads = self.diagnostic.heads(self.tree, self.source);
+        let text = match (self.diagnostic.kind(), heads) {
+            (DiagnosticKind::DuplicateFunction, Some((head, _))) => {
+                format!("function {head} already defined")
+            }
+            (DiagnosticKind::ClauseArityMismatch, Some((head, first))) => format!(
+                "head mismatch: function {} with arities {} and {} is regarded as two distinct \
+                 functions. Is the number of arguments incorrect or is the semicolon in {first} \
+                 unwanted?",
+                head.name(),
+                first.arity(),
+                head.arity()
+            ),
+            (DiagnosticKind::ClauseNameMismatch, Some((head, first))) => format!(
+                "head mismatch: previous function {first} is distinct from {head}. \
+                 Is the semicolon in {first} unwanted?"
+            ),
+            (DiagnosticKind::UnusedVariable, _) => {
+                format!("Warning: variable {} is unused", self.erlc_variable())
+            }
+            (DiagnosticKind::UnsafeVariable, _) => format!(
+                "variable {} unsafe in '{}' {}",
+                self.erlc_variable(),
+                self.diagnostic.related_keyword(self.tree, self.source),
+                self.erlc_where(&index)
+            ),
+            (DiagnosticKind::UnderscoreVariableUsed, _) => format!(
+                "Warning: variable {} is used but its name starts with '_'",
+                self.erlc_variable()
+            ),
+            _ => format!(
+                "syntax error before: {}",
+                token.map_or(String::new(), |t| erlc_token(t, self.source))
+            ),
+        };
+        format!(
+            "{}:{}:{}: {text}",
+            self.file, position.line, position.column
+        )
+    }
+
+    /// The variable name quoted as an atom, as `erl_lint` writes it.
+    fn erlc_variable(&self) -> String {
+        format!("'{}'", self.diagnostic.variable(self.tree, self.source))
+    }
+
+    /// `(line L, column C)` for the start of the related range.
+    fn erlc_where(&self, index: &LineIndex) -> String {
+        self.diagnostic.related().map_or(String::new(), |range| {
+            let at = index.line_column(index.offset(range.start()));
+            format!("(line {}, column {})", at.line, at.column)
+        })
+    }
+
+    fn paint(&self, color: &'static str) -> (&'static str, &'static str) {
+        if self.color { (color, RESET) } else { ("", "") }
+    }
+}
+
+impl fmt::Display for Render<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let index = LineIndex::new(self.tree, self.source);
+        let span = match self.diagnostic.found() {
+            Some(token) => token.start().offset()..token.end().offset(),
+            None => self.diagnostic.byte_range(&index),
+        };
+        let start = index.line_column(span.start);
+        let line_no = start.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        let (blue, blue_end) = self.paint(BLUE);
+        let (bold, bold_end) = self.paint(BOLD);
+
+        let severity = self.diagnostic.severity();
+        let (label, label_end) = self.paint(match severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+            Severity::Note => GREEN,
+        });
+        writeln!(
+            f,
+            "{label}{severity}[{}]{label_end}{bold}: {}{bold_end}",
+            self.diagnostic.code(),
+            self.diagnostic.message(self.tree, self.source)
+        )?;
+        writeln!(
+            f,
+            "{gutter}{blue}-->{blue_end} {}:{}:{}",
+            self.file, start.line, start.column
+        )?;
+        writeln!(f, "{gutter} {blue}|{blue_end}")?;
+
+        let line_start = self.source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[span.start..]
+            .find('\n')
+            .map_or(self.source.len(), |i| span.start + i);
+        let line = self.source[line_start..line_end].trim_end_matches('\r');
+        let indent: String = self.source[line_start..span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        // Multi-line spans are underlined to the end of their first line.
+        let underlined = span.start..span.end.clamp(span.start, line_start + line.len());
+        let width = self.source[underlined].chars().count().max(1);
+        writeln!(f, "{blue}{line_no} |{blue_end} {line}")?;
+        write!(
+            f,
+            "{gutter} {blue}|{blue_end} {indent}{label}{}{label_end}",
+            "^".repeat(width)
+        )?;
+        if let Some(fix) = self.diagnostic.fix() {
+            write!(
+                f,
+                "\n{gutter} {blue}={blue_end} {bold}help{bold_end}: {}",
+                describe_fix(fix, &index, self.source)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// `insert `end``, `remove `)``, or `replace `,` with `;``.
+fn describe_fix(fix: Fix, index: &LineIndex, source: &str) -> String {
+    let quote = |range: TokenRange| {
+        let text = source[index.byte_range(range)].trim();
+        if text.len() <= MAX_QUOTED_TOKEN && !text.contains('\n') {
+            format!("`{text}`")
+        } else {
+            "this".to_string()
+        }
+    };
+    match fix {
+        Fix::Insert { text, .. } => format!("insert `{text}`"),
+        Fix::Delete(range) => format!("remove {}", quote(range)),
+        Fix::Replace { range, text } => format!("replace {} with `{text}`", quote(range)),
+    }
+}
+
+fn describe_expected(expected: Expected) -> Option<String> {
+    match expected {
+        Expected::Unspecified => None,
+        Expected::Category(category) => Some(category.to_string()),
+        Expected::TokenKind(kind) => Some(describe_kind(kind)),
+    }
+}
+
+fn describe_kind(kind: TokenKind) -> String {
+    match kind {
+        TokenKind::Symbol(symbol) => format!("`{}`", symbol.as_str()),
+        TokenKind::Keyword(keyword) => format!("`{}`", keyword.as_str()),
+        TokenKind::Atom => "an atom".to_string(),
+        TokenKind::Char => "a character literal".to_string(),
+        TokenKind::Comment => "a comment".to_string(),
+        TokenKind::Float => "a float".to_string(),
+        TokenKind::Integer => "an integer".to_string(),
+        TokenKind::SigilString => "a sigil string".to_string(),
+        TokenKind::String => "a string".to_string(),
+        TokenKind::Variable => "a variable".to_string(),
+        TokenKind::Whitespace => "whitespace".to_string(),
+    }
+}
+
+/// The token's text in backticks, or its kind when the text is long or
+/// spans lines.
+fn describe_token(token: Token, source: &str) -> String {
+    let text = token.text(source);
+    if text.len() <= MAX_QUOTED_TOKEN && !text.contains('\n') {
+        format!("`{text}`")
+    } else {
+        describe_kind(token.kind())
+    }
+}
+
+/// `erl_parse`'s `yecctoken2string/1`.
+fn erlc_token(token: Token, source: &str) -> String {
+    let text = token.text(source);
+    match token.value(source) {
+        TokenValue::Atom(name) => Term::Atom(name.into_owned()).to_string(),
+        TokenValue::Integer(_) => {
+            Integer::parse_literal(text).map_or_else(|| text.to_string(), |n| n.to_string())
+        }
+        TokenValue::Float(value) => Term::Float(value).to_string(),
+        TokenValue::String(value) => {
+            let mut out = String::new();
+            pretty::write_string(&value, '"', &mut out);
+            out
+        }
+        TokenValue::Keyword(_) | TokenValue::Symbol(_) => Term::atom(text).to_string(),
+        _ => text.to_string(),
+    }
+}
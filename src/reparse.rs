@@ -0,0 +1,232 @@
+//! Incremental reparse over an edited token sequence.
+//!
+//! Each `.`-terminated unit is parsed while the token buffer ends at
+//! its dot, so its nodes and diagnostics are a function of the tokens
+//! between the previous unit's end and its own dot. [`reparse`] keeps
+//! the units that end before an edit, feeds the new tokens from there
+//! into a resumed [`Parser`] until a unit boundary lines up with an old
+//! one past the edit, and then carries the remaining old units over
+//! with their token indexes shifted by the edit's length change.
+
+use crate::diagnostic::{Diagnostic, push_unique_at_cursor};
+use crate::parser::Parser;
+use crate::syntax::{EntryIndex, NodeId, SyntaxEntry};
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// A replacement of a token span, stated against the tokens of an
+/// existing [`SyntaxTree`]: the tokens in [`TokenEdit::range`] were
+/// replaced by [`TokenEdit::new_len`] new tokens.
+///
+/// Pass it to [`SyntaxTree::reparse`] together with the full token
+/// sequence after the edit. Tokens outside the range are expected to
+/// be the same lexemes as before; only their source positions may
+/// have moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenEdit {
+    range: TokenRange,
+    new_len: usize,
+}
+
+impl TokenEdit {
+    /// Builds an edit that replaces `range` of the old tokens with
+    /// `new_len` new tokens. An insertion has an empty `range`; a
+    /// deletion has `new_len == 0`.
+    pub const fn new(range: TokenRange, new_len: usize) -> Self {
+        Self { range, new_len }
+    }
+
+    /// Computes the smallest edit that turns `old_tokens` (scanned from
+    /// `old_source`) into `new_tokens` (scanned from `new_source`):
+    /// the longest common prefix and suffix of tokens with the same
+    /// kind and text are left out of the range.
+    pub fn diff(
+        old_tokens: &[erl_tokenize::Token],
+        old_source: &str,
+        new_tokens: &[erl_tokenize::Token],
+        new_source: &str,
+    ) -> Self {
+        let same = |a: &erl_tokenize::Token, b: &erl_tokenize::Token| {
+            a.kind() == b.kind() && a.text(old_source) == b.text(new_source)
+        };
+        let prefix = old_tokens
+            .iter()
+            .zip(new_tokens)
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        let suffix = old_tokens[prefix..]
+            .iter()
+            .rev()
+            .zip(new_tokens[prefix..].iter().rev())
+            .take_while(|(a, b)| same(a, b))
+            .count();
+        Self {
+            range: TokenRange::new(
+                TokenIndex::new(prefix),
+                TokenIndex::new(old_tokens.len() - suffix),
+            ),
+            new_len: new_tokens.len() - suffix - prefix,
+        }
+    }
+
+    /// Returns the replaced span of the old tokens.
+    pub const fn range(self) -> TokenRange {
+        self.range
+    }
+
+    /// Returns how many new tokens took the place of
+    /// [`TokenEdit::range`].
+    pub const fn new_len(self) -> usize {
+        self.new_len
+    }
+}
+
+/// Implements [`SyntaxTree::reparse`].
+pub(crate) fn reparse(
+    old: &SyntaxTree,
+    edit: TokenEdit,
+    new_tokens: &[erl_tokenize::Token],
+) -> (SyntaxTree, Vec<NodeId>) {
+    let old_len = old.tokens().len();
+    let range = edit.range();
+    assert!(
+        range.end().get() <= old_len,
+        "TokenEdit range {range:?} extends past the {old_len} old tokens"
+    );
+    assert_eq!(
+        new_tokens.len(),
+        old_len - range.len() + edit.new_len(),
+        "new token count disagrees with the TokenEdit"
+    );
+    // Old indexes at or after `old_end` map to `index - old_end + new_end`.
+    let old_end = range.end().get();
+    let new_end = range.start().get() + edit.new_len();
+
+    let units = old.units();
+    let kept = units.partition_point(|u| u.tokens() <= range.start());
+    let mut parser = Parser::resume(prefix_tree(old, kept, new_tokens));
+    let mut next = parser.syntax_tree().tokens().len();
+    let mut changed = Vec::new();
+    let mut changed_from = parser.syntax_tree().syntax().len();
+    let mut spliced = false;
+    while next < new_tokens.len() {
+        let units_before = parser.syntax_tree().units().len();
+        parser.feed_token(new_tokens[next]);
+        next += 1;
+        if spliced || parser.syntax_tree().units().len() == units_before {
+            continue;
+        }
+        let Some(end) = parser
+            .syntax_tree()
+            .units()
+            .last()
+            .map(|u| u.tokens().get())
+        else {
+            continue;
+        };
+        if end < new_end {
+            continue;
+        }
+        let old_boundary = TokenIndex::new(end - new_end + old_end);
+        let Ok(aligned) = units.binary_search_by_key(&old_boundary, |u| u.tokens()) else {
+            continue;
+        };
+        let mut tree = parser.into_syntax_tree();
+        push_roots(&tree, changed_from, &mut changed);
+        append_units(&mut tree, old, aligned, new_tokens, old_end, new_end);
+        next = tree.tokens().len();
+        changed_from = tree.syntax().len();
+        parser = Parser::resume(tree);
+        spliced = true;
+    }
+    let tree = parser.finish();
+    push_roots(&tree, changed_from, &mut changed);
+    (tree, changed)
+}
+
+/// The first `kept` units of `old`, over the matching prefix of
+/// `new_tokens` (which the edit leaves unchanged).
+fn prefix_tree(old: &SyntaxTree, kept: usize, new_tokens: &[erl_tokenize::Token]) -> SyntaxTree {
+    let mut tree = SyntaxTree::new(old.mode());
+    for unit in &old.units()[..kept] {
+        for &token in &new_tokens[tree.tokens().len()..unit.tokens().get()] {
+            tree.tokens_mut().push(token);
+        }
+        for &entry in &old.syntax().entries()[tree.syntax().len()..unit.entries()] {
+            tree.syntax_mut().push(entry);
+        }
+        let from = tree.diagnostics().len();
+        tree.diagnostics_mut()
+            .extend_from_slice(&old.diagnostics()[from..unit.diagnostics()]);
+        tree.push_unit(unit.tokens());
+    }
+    tree
+}
+
+/// Appends the old units after `aligned` to `tree`, shifting their
+/// token indexes into `new_tokens` and their entry fences into the
+/// new syntax index. `tree` ends at the (shifted) end of unit
+/// `aligned`.
+fn append_units(
+    tree: &mut SyntaxTree,
+    old: &SyntaxTree,
+    aligned: usize,
+    new_tokens: &[erl_tokenize::Token],
+    old_end: usize,
+    new_end: usize,
+) {
+    let shift = |index: TokenIndex| TokenIndex::new(index.get() - old_end + new_end);
+    let shift_range = |range: TokenRange| TokenRange::new(shift(range.start()), shift(range.end()));
+    let units = old.units();
+    for pair in units[aligned..].windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        for &token in &new_tokens[shift(start.tokens()).get()..shift(end.tokens()).get()] {
+            tree.tokens_mut().push(token);
+        }
+        let offset = tree.syntax().len();
+        for entry in &old.syntax().entries()[start.entries()..end.entries()] {
+            let subtree_end = entry.subtree_end().get() - start.entries() + offset;
+            tree.syntax_mut().push(SyntaxEntry::new(
+                entry.kind(),
+                shift_range(entry.range()),
+                EntryIndex::new(subtree_end),
+            ));
+        }
+        let old_span = start.tokens().get()..end.tokens().get();
+        for diagnostic in &old.diagnostics()[start.diagnostics()..end.diagnostics()] {
+            // `found` carries source positions; take the token from
+            // the new sequence so it matches a fresh parse.
+            let found = diagnostic.found().map(|token| {
+                old.tokens()[old_span.clone()]
+                    .iter()
+                    .position(|t| *t == token)
+                    .map_or(token, |i| {
+                        new_tokens[shift(TokenIndex::new(old_span.start + i)).get()]
+                    })
+            });
+            push_unique_at_cursor(
+                tree.diagnostics_mut(),
+                Diagnostic::new(
+                    diagnostic.kind(),
+                    shift_range(diagnostic.range()),
+                    diagnostic.expected(),
+                    found,
+                )
+                .with_fix(diagnostic.fix().map(|fix| fix.map_indexes(shift)))
+                .with_code(diagnostic.code()),
+            );
+        }
+        tree.push_unit(shift(end.tokens()));
+    }
+}
+
+/// Pushes the ids of the roots in `tree`'s syntax index from entry
+/// `from` onward.
+fn push_roots(tree: &SyntaxTree, from: usize, out: &mut Vec<NodeId>) {
+    let entries = tree.syntax().entries();
+    let mut i = from;
+    while i < entries.len() {
+        out.push(NodeId::new(i));
+        i = entries[i].subtree_end().get();
+    }
+}
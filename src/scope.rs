@@ -0,0 +1,715 @@
+//! Variable binding and use.
+//!
+//! [`Scopes::new`] follows Erlang's scoping rules over the function
+//! clauses of a Module-mode tree, or the expressions of an
+//! Expression-mode tree, and records every variable token as a
+//! [`Binding`](Role::Binding) or a [`Use`](Role::Use) of a
+//! [`Variable`]:
+//!
+//! - A variable in a pattern (a function or `case` clause head, the
+//!   left of `=` or `?=`, a `catch` clause's class, reason, and stack
+//!   trace) binds it unless it is already bound, in which case the
+//!   pattern matches against it and the token is a use. Map keys and
+//!   bit-string sizes inside patterns are uses.
+//! - `fun` clause heads and comprehension generator patterns always
+//!   bind: their variables shadow ones of the same name outside, and
+//!   nothing bound inside a `fun` or a comprehension is visible after
+//!   it. A named fun's name is bound inside the fun.
+//! - A variable bound in every clause of a `case`, `if`, or `receive`
+//!   (the `after` section counts as a clause) is bound after the
+//!   block, as one variable with a binding in each clause. One bound in
+//!   only some clauses is *unsafe* after the block.
+//! - Everything bound inside `try`, `catch`, and `maybe` is unsafe
+//!   after them, and a `try` body's bindings are unsafe in its `catch`
+//!   and `after` sections.
+//! - `_` is neither bound nor used and gets no entry; `_Name`
+//!   variables are ordinary variables.
+//!
+//! Uses of unsafe variables carry [`Occurrence::unsafe_in`]. A use of
+//! a variable that is never bound belongs to a variable without
+//! bindings; all such uses of one name in a function clause share it.
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::scope::{Role, Scopes};
+//!
+//! let source = "f(X) -> Y = X + 1, Y.";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let scopes = Scopes::new(&tree, source);
+//! // `X` in the head binds; `X` in the body uses the same variable.
+//! let x = scopes.occurrence(erl_parse::TokenIndex::new(2)).expect("X");
+//! assert_eq!(x.role(), Role::Binding);
+//! let variable = scopes.variable(x.variable());
+//! assert_eq!(variable.name(), "X");
+//! assert_eq!(variable.uses().len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use erl_tokenize::TokenKind;
+
+use crate::ast::{self, AstNode};
+use crate::node::NodeView;
+use crate::parser::ParseMode;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenIndex;
+
+/// Identifies a [`Variable`] within one [`Scopes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VariableId(usize);
+
+impl VariableId {
+    /// Returns the index into [`Scopes::variables`].
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+/// Whether a variable token binds its variable or uses it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The token gives the variable its value.
+    Binding,
+    /// The token reads the variable, or matches against it in a
+    /// pattern.
+    Use,
+}
+
+/// One variable token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Occurrence {
+    variable: VariableId,
+    role: Role,
+    unsafe_in: Option<TokenIndex>,
+}
+
+impl Occurrence {
+    /// Returns the variable the token belongs to.
+    pub fn variable(self) -> VariableId {
+        self.variable
+    }
+
+    /// Returns whether the token binds or uses the variable.
+    pub fn role(self) -> Role {
+        self.role
+    }
+
+    /// For a use of a variable that is not safely bound, returns the
+    /// keyword token of the block it was bound in: `case`, `if`,
+    /// `receive`, `try`, `catch`, or `maybe`.
+    pub fn unsafe_in(self) -> Option<TokenIndex> {
+        self.unsafe_in
+    }
+}
+
+/// A variable: the tokens that bind it and the tokens that use it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    name: String,
+    bindings: Vec<TokenIndex>,
+    uses: Vec<TokenIndex>,
+}
+
+impl Variable {
+    /// Returns the variable name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the binding tokens in source order. A variable bound in
+    /// each clause of a block has one per clause; an unbound one has
+    /// none.
+    pub fn bindings(&self) -> &[TokenIndex] {
+        &self.bindings
+    }
+
+    /// Returns the use tokens in source order.
+    pub fn uses(&self) -> &[TokenIndex] {
+        &self.uses
+    }
+}
+
+/// The variables of a tree, with every variable token's role. See the
+/// [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scopes {
+    occurrences: Vec<(TokenIndex, Occurrence)>,
+    variables: Vec<Variable>,
+}
+
+impl Scopes {
+    /// Analyses `tree`. `source` is the text the tokens were scanned
+    /// from.
+    ///
+    /// Module-mode trees are analysed one function clause at a time and
+    /// Expression-mode trees one top-level expression at a time. Other
+    /// modes have no variables to bind.
+    pub fn new(tree: &SyntaxTree, source: &str) -> Self {
+        let mut walker = Walker {
+            tree,
+            source,
+            names: Vec::new(),
+            parent: Vec::new(),
+            occurrences: Vec::new(),
+            unbound: Vec::new(),
+        };
+        for root in tree.roots() {
+            match (tree.mode(), root.kind()) {
+                (ParseMode::Module, SyntaxKind::FunctionDecl) => {
+                    for clause in root.children() {
+                        walker.unbound.clear();
+                        walker.function_clause(&mut Vec::new(), clause);
+                    }
+                }
+                (ParseMode::Expression, _) => {
+                    walker.unbound.clear();
+                    walker.expr(&mut Vec::new(), root);
+                }
+                _ => {}
+            }
+        }
+        walker.finish()
+    }
+
+    /// Returns every variable token in source order.
+    pub fn occurrences(&self) -> &[(TokenIndex, Occurrence)] {
+        &self.occurrences
+    }
+
+    /// Returns the role of the variable token at `at`, or `None` for
+    /// other tokens and `_`.
+    pub fn occurrence(&self, at: TokenIndex) -> Option<Occurrence> {
+        let i = self
+            .occurrences
+            .binary_search_by_key(&at, |&(i, _)| i)
+            .ok()?;
+        Some(self.occurrences[i].1)
+    }
+
+    /// Returns the variables in the order they first appear.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Returns the variable `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` comes from another `Scopes`.
+    pub fn variable(&self, id: VariableId) -> &Variable {
+        &self.variables[id.0]
+    }
+
+    /// Returns the tokens binding the variable at `at`; empty when `at`
+    /// is not a variable token.
+    pub fn definitions(&self, at: TokenIndex) -> &[TokenIndex] {
+        self.occurrence(at)
+            .map_or(&[], |o| self.variable(o.variable).bindings())
+    }
+
+    /// Returns the tokens using the variable at `at`; empty when `at`
+    /// is not a variable token.
+    pub fn references(&self, at: TokenIndex) -> &[TokenIndex] {
+        self.occurrence(at)
+            .map_or(&[], |o| self.variable(o.variable).uses())
+    }
+}
+
+/// A variable in scope.
+#[derive(Debug, Clone, Copy)]
+struct Bound<'a> {
+    name: &'a str,
+    id: usize,
+    unsafe_in: Option<TokenIndex>,
+}
+
+/// The variables in scope, innermost last.
+type Env<'a> = Vec<Bound<'a>>;
+
+struct Walker<'a> {
+    tree: &'a SyntaxTree,
+    source: &'a str,
+    /// Raw variable names; ids of branch bindings are merged through
+    /// `parent`.
+    names: Vec<&'a str>,
+    parent: Vec<usize>,
+    occurrences: Vec<(TokenIndex, usize, Role, Option<TokenIndex>)>,
+    /// Variables used without a binding in the current function clause.
+    unbound: Vec<(&'a str, usize)>,
+}
+
+impl<'a> Walker<'a> {
+    fn name(&self, at: TokenIndex) -> &'a str {
+        self.tree.tokens()[at.get()].text(self.source)
+    }
+
+    fn fresh(&mut self, name: &'a str) -> usize {
+        self.names.push(name);
+        self.parent.push(self.parent.len());
+        self.parent.len() - 1
+    }
+
+    fn root(&mut self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            self.parent[id] = self.parent[self.parent[id]];
+            id = self.parent[id];
+        }
+        id
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        let (a, b) = (self.root(a), self.root(b));
+        self.parent[b] = a;
+        a
+    }
+
+    /// Records a use of the variable token `at`.
+    fn use_var(&mut self, env: &Env<'a>, at: TokenIndex) {
+        let name = self.name(at);
+        if name == "_" {
+            return;
+        }
+        if let Some(bound) = env.iter().rev().find(|b| b.name == name) {
+            self.occurrences
+                .push((at, bound.id, Role::Use, bound.unsafe_in));
+            return;
+        }
+        let id = match self.unbound.iter().find(|(n, _)| *n == name) {
+            Some(&(_, id)) => id,
+            None => {
+                let id = self.fresh(name);
+                self.unbound.push((name, id));
+                id
+            }
+        };
+        self.occurrences.push((at, id, Role::Use, None));
+    }
+
+    /// Records the variable token `at` in a pattern: a use when the
+    /// name is bound in `env[floor..]`, otherwise a new binding.
+    fn bind_var(&mut self, env: &mut Env<'a>, at: TokenIndex, floor: usize) {
+        let name = self.name(at);
+        if name == "_" {
+            return;
+        }
+        if let Some(bound) = env[floor..].iter().rev().find(|b| b.name == name) {
+            self.occurrences
+                .push((at, bound.id, Role::Use, bound.unsafe_in));
+            return;
+        }
+        let id = self.fresh(name);
+        self.occurrences.push((at, id, Role::Binding, None));
+        env.push(Bound {
+            name,
+            id,
+            unsafe_in: None,
+        });
+    }
+
+    fn function_clause(&mut self, env: &mut Env<'a>, clause: NodeView<'a>) {
+        let Some(clause) = ast::FunctionClause::cast(clause) else {
+            return;
+        };
+        if let Some(arguments) = clause.arguments() {
+            for arg in arguments.syntax().children() {
+                self.pattern(env, arg, 0);
+            }
+        }
+        self.guard_and_body(env, clause.guard(), clause.body());
+    }
+
+    fn guard_and_body(
+        &mut self,
+        env: &mut Env<'a>,
+        guard: Option<ast::GuardSequence<'a>>,
+        body: Option<ast::Body<'a>>,
+    ) {
+        if let Some(guard) = guard {
+            self.expr(env, guard.syntax());
+        }
+        if let Some(body) = body {
+            self.expr(env, body.syntax());
+        }
+    }
+
+    fn pattern(&mut self, env: &mut Env<'a>, node: NodeView<'a>, floor: usize) {
+        match node.kind() {
+            SyntaxKind::VarExpr => {
+                if let Some(at) = ast::VarExpr::cast(node).and_then(|v| v.token()) {
+                    self.bind_var(env, at, floor);
+                }
+            }
+            SyntaxKind::MapField => {
+                let field = ast::MapField::cast(node).expect("a map field");
+                if let Some(key) = field.key() {
+                    self.expr(env, key.syntax());
+                }
+                if let Some(value) = field.value() {
+                    self.pattern(env, value.syntax(), floor);
+                }
+            }
+            SyntaxKind::BitstringElement => {
+                let element = ast::BitstringElement::cast(node).expect("a bit-string element");
+                if let Some(value) = element.value() {
+                    self.pattern(env, value.syntax(), floor);
+                }
+                if let Some(size) = element.size() {
+                    self.expr(env, size.syntax());
+                }
+            }
+            _ => {
+                for child in node.children() {
+                    self.pattern(env, child, floor);
+                }
+                for at in self.own_variables(node) {
+                    self.use_var(env, at);
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, env: &mut Env<'a>, node: NodeView<'a>) {
+        let keyword = || {
+            node.tokens_in_range()
+                .find(|(_, t)| !t.kind().is_hidden())
+                .map(|(i, _)| i)
+        };
+        match node.kind() {
+            SyntaxKind::VarExpr => {
+                if let Some(at) = ast::VarExpr::cast(node).and_then(|v| v.token()) {
+                    self.use_var(env, at);
+                }
+            }
+            SyntaxKind::MatchExpr | SyntaxKind::MaybeMatchExpr => {
+                let mut children = node.children();
+                let (pattern, value) = (children.next(), children.next());
+                if let Some(value) = value {
+                    self.expr(env, value);
+                }
+                if let Some(pattern) = pattern {
+                    self.pattern(env, pattern, 0);
+                }
+            }
+            SyntaxKind::CaseExpr | SyntaxKind::IfExpr | SyntaxKind::ReceiveExpr => {
+                let arms = node.children().filter(|c| {
+                    matches!(
+                        c.kind(),
+                        SyntaxKind::Clause | SyntaxKind::IfClause | SyntaxKind::ReceiveAfterSection
+                    )
+                });
+                let arms: Vec<_> = arms.collect();
+                if let Some(subject) = ast::CaseExpr::cast(node).and_then(|c| c.subject()) {
+                    self.expr(env, subject.syntax());
+                }
+                let mut exports = Vec::new();
+                for &arm in &arms {
+                    let mut inner = env.clone();
+                    self.arm(&mut inner, arm);
+                    exports.push(inner.split_off(env.len()));
+                }
+                self.export(env, exports, keyword(), false);
+            }
+            SyntaxKind::TryExpr => self.try_expr(env, node, keyword()),
+            SyntaxKind::MaybeExpr => {
+                let maybe = ast::MaybeExpr::cast(node).expect("a maybe block");
+                let mut inner = env.clone();
+                if let Some(body) = maybe.body() {
+                    self.expr(&mut inner, body.syntax());
+                }
+                let mut exports = vec![inner.split_off(env.len())];
+                let clauses = maybe.else_section().into_iter().flat_map(|s| s.clauses());
+                self.unsafe_arms(env, &mut exports, clauses.map(|c| c.syntax()), keyword());
+                self.export(env, exports, keyword(), true);
+            }
+            SyntaxKind::CatchExpr => {
+                let mut inner = env.clone();
+                for child in node.children() {
+                    self.expr(&mut inner, child);
+                }
+                let exports = vec![inner.split_off(env.len())];
+                self.export(env, exports, keyword(), true);
+            }
+            SyntaxKind::AnonymousFun => {
+                for clause in node.children() {
+                    self.fun_clause(&mut env.clone(), clause);
+                }
+            }
+            SyntaxKind::NamedFun => {
+                // Every clause repeats the fun's name; each is a binding
+                // of the one variable visible in the clauses.
+                let mut inner = env.clone();
+                let names = node
+                    .children()
+                    .filter_map(|clause| ast::Clause::cast(clause)?.name());
+                for at in names {
+                    let name = self.name(at);
+                    let id = match inner[env.len()..].first() {
+                        Some(bound) => bound.id,
+                        None => {
+                            let id = self.fresh(name);
+                            inner.push(Bound {
+                                name,
+                                id,
+                                unsafe_in: None,
+                            });
+                            id
+                        }
+                    };
+                    self.occurrences.push((at, id, Role::Binding, None));
+                }
+                for clause in node.children() {
+                    self.fun_clause(&mut inner.clone(), clause);
+                }
+            }
+            SyntaxKind::ListComprehension
+            | SyntaxKind::BinaryComprehension
+            | SyntaxKind::MapComprehension => {
+                let mut inner = env.clone();
+                let (qualifiers, template): (Vec<_>, Vec<_>) =
+                    node.children().partition(|c| is_qualifier(c.kind()));
+                for qualifier in qualifiers {
+                    self.qualifier(&mut inner, qualifier);
+                }
+                for child in template {
+                    self.expr(&mut inner, child);
+                }
+            }
+            _ => {
+                for child in node.children() {
+                    self.expr(env, child);
+                }
+                // Bare variable tokens, such as the arms of `fun M:F/A`.
+                for at in self.own_variables(node) {
+                    self.use_var(env, at);
+                }
+            }
+        }
+    }
+
+    /// Walks one clause of a block.
+    fn arm(&mut self, env: &mut Env<'a>, arm: NodeView<'a>) {
+        match arm.kind() {
+            SyntaxKind::Clause => {
+                let clause = ast::Clause::cast(arm).expect("a clause");
+                if let Some(pattern) = clause.pattern() {
+                    self.pattern(env, pattern.syntax(), 0);
+                }
+                self.guard_and_body(env, clause.guard(), clause.body());
+            }
+            SyntaxKind::IfClause => {
+                let clause = ast::IfClause::cast(arm).expect("an if clause");
+                self.guard_and_body(env, clause.guard(), clause.body());
+            }
+            SyntaxKind::CatchClause => {
+                let clause = ast::CatchClause::cast(arm).expect("a catch clause");
+                let class = clause.class().filter(|&at| self.is_variable(at));
+                if let Some(class) = class {
+                    self.bind_var(env, class, 0);
+                }
+                if let Some(pattern) = clause.pattern() {
+                    self.pattern(env, pattern.syntax(), 0);
+                }
+                if let Some(stacktrace) = clause.stacktrace() {
+                    self.bind_var(env, stacktrace, 0);
+                }
+                self.guard_and_body(env, clause.guard(), clause.body());
+            }
+            _ => {
+                for child in arm.children() {
+                    self.expr(env, child);
+                }
+            }
+        }
+    }
+
+    fn fun_clause(&mut self, env: &mut Env<'a>, clause: NodeView<'a>) {
+        let Some(clause) = ast::Clause::cast(clause) else {
+            return;
+        };
+        let floor = env.len();
+        if let Some(arguments) = clause.arguments() {
+            for arg in arguments.syntax().children() {
+                self.pattern(env, arg, floor);
+            }
+        }
+        self.guard_and_body(env, clause.guard(), clause.body());
+    }
+
+    fn try_expr(&mut self, env: &mut Env<'a>, node: NodeView<'a>, keyword: Option<TokenIndex>) {
+        let try_expr = ast::TryExpr::cast(node).expect("a try block");
+        let mut inner = env.clone();
+        if let Some(body) = try_expr.body() {
+            self.expr(&mut inner, body.syntax());
+        }
+        let mut exports = Vec::new();
+        for clause in try_expr.of_section().into_iter().flat_map(|s| s.clauses()) {
+            let mut arm = inner.clone();
+            self.arm(&mut arm, clause.syntax());
+            exports.push(arm.split_off(inner.len()));
+        }
+        exports.push(inner.split_off(env.len()));
+        let handlers = try_expr
+            .catch_section()
+            .into_iter()
+            .flat_map(|s| s.syntax().children())
+            .chain(try_expr.after_section().map(|s| s.syntax()));
+        self.unsafe_arms(env, &mut exports, handlers, keyword);
+        self.export(env, exports, keyword, true);
+    }
+
+    /// Walks `arms` with the bindings so far in `exports` visible but
+    /// unsafe, adding what each arm binds to `exports`.
+    fn unsafe_arms(
+        &mut self,
+        env: &Env<'a>,
+        exports: &mut Vec<Env<'a>>,
+        arms: impl Iterator<Item = NodeView<'a>>,
+        keyword: Option<TokenIndex>,
+    ) {
+        let mut base = env.clone();
+        base.extend(exports.iter().flatten().map(|b| Bound {
+            unsafe_in: b.unsafe_in.or(keyword),
+            ..*b
+        }));
+        for arm in arms {
+            let mut inner = base.clone();
+            self.arm(&mut inner, arm);
+            exports.push(inner.split_off(base.len()));
+        }
+    }
+
+    /// Adds the bindings of a block's clauses to `env`. A name bound in
+    /// every clause stays safe unless `always_unsafe`; the bindings of
+    /// one name across clauses become one variable.
+    fn export(
+        &mut self,
+        env: &mut Env<'a>,
+        arms: Vec<Env<'a>>,
+        keyword: Option<TokenIndex>,
+        always_unsafe: bool,
+    ) {
+        let mut merged: Env<'a> = Vec::new();
+        for bound in arms.iter().flatten() {
+            match merged.iter_mut().find(|b| b.name == bound.name) {
+                Some(b) => {
+                    b.id = self.merge(b.id, bound.id);
+                    b.unsafe_in = b.unsafe_in.or(bound.unsafe_in);
+                }
+                None => merged.push(*bound),
+            }
+        }
+        for bound in &mut merged {
+            let everywhere = arms
+                .iter()
+                .all(|arm| arm.iter().any(|b| b.name == bound.name));
+            if always_unsafe || !everywhere {
+                bound.unsafe_in = bound.unsafe_in.or(keyword);
+            }
+        }
+        env.extend(merged);
+    }
+
+    fn qualifier(&mut self, env: &mut Env<'a>, qualifier: NodeView<'a>) {
+        match qualifier.kind() {
+            SyntaxKind::ZipQualifier => {
+                let generators: Vec<_> = qualifier.children().collect();
+                for &generator in &generators {
+                    if let Some(source) = generator.children().last() {
+                        self.expr(env, source);
+                    }
+                }
+                let floor = env.len();
+                for generator in generators {
+                    self.generator_patterns(env, generator, floor);
+                }
+            }
+            SyntaxKind::Filter => self.expr(env, qualifier),
+            _ => {
+                if let Some(source) = qualifier.children().last() {
+                    self.expr(env, source);
+                }
+                let floor = env.len();
+                self.generator_patterns(env, qualifier, floor);
+            }
+        }
+    }
+
+    /// Binds the patterns of a generator: every child but the last.
+    fn generator_patterns(&mut self, env: &mut Env<'a>, generator: NodeView<'a>, floor: usize) {
+        let count = generator.children().count();
+        for pattern in generator.children().take(count.saturating_sub(1)) {
+            self.pattern(env, pattern, floor);
+        }
+    }
+
+    fn is_variable(&self, at: TokenIndex) -> bool {
+        self.tree.tokens()[at.get()].kind() == TokenKind::Variable
+    }
+
+    /// The variable tokens of `node` outside its children.
+    fn own_variables(&self, node: NodeView<'a>) -> Vec<TokenIndex> {
+        let children: Vec<_> = node.children().map(|c| c.range()).collect();
+        node.tokens_in_range()
+            .filter(|&(at, t)| {
+                t.kind() == TokenKind::Variable
+                    && !children.iter().any(|r| r.start() <= at && at < r.end())
+            })
+            .map(|(at, _)| at)
+            .collect()
+    }
+
+    fn finish(mut self) -> Scopes {
+        let mut dense = vec![None; self.names.len()];
+        let mut variables: Vec<Variable> = Vec::new();
+        let mut occurrences = std::mem::take(&mut self.occurrences);
+        occurrences.sort_by_key(|&(at, ..)| at);
+        let occurrences = occurrences
+            .into_iter()
+            .map(|(at, id, role, unsafe_in)| {
+                let root = self.root(id);
+                let variable = *dense[root].get_or_insert_with(|| {
+                    variables.push(Variable {
+                        name: self.names[root].to_string(),
+                        bindings: Vec::new(),
+                        uses: Vec::new(),
+                    });
+                    VariableId(variables.len() - 1)
+                });
+                let entry = &mut variables[variable.0];
+                match role {
+                    Role::Binding => entry.bindings.push(at),
+                    Role::Use => entry.uses.push(at),
+                }
+                let occurrence = Occurrence {
+                    variable,
+                    role,
+                    unsafe_in,
+                };
+                (at, occurrence)
+            })
+            .collect();
+        Scopes {
+            occurrences,
+            variables,
+        }
+    }
+}
+
+fn is_qualifier(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Generator
+            | SyntaxKind::BitstringGenerator
+            | SyntaxKind::MapGenerator
+            | SyntaxKind::StrictGenerator
+            | SyntaxKind::StrictBitstringGenerator
+            | SyntaxKind::StrictMapGenerator
+            | SyntaxKind::ZipQualifier
+            | SyntaxKind::Filter
+    )
+}
@@ -0,0 +1,99 @@
+//! Smart selection: the ranges an editor steps through when it expands
+//! a selection.
+//!
+//! [`selection_ranges`] starts at one token and walks out through its
+//! enclosing nodes. Between two nodes it adds the steps the index has no
+//! node for: an element together with the `,` or `;` after it, and all
+//! the elements of a container without its brackets.
+
+use erl_tokenize::{Symbol, TokenKind};
+
+use crate::node::NodeView;
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::{TokenIndex, TokenRange};
+
+/// Returns the chain of ranges around the token at `at`, innermost
+/// first, each strictly containing the one before.
+pub(crate) fn selection_ranges(tree: &SyntaxTree, at: TokenIndex) -> Vec<TokenRange> {
+    let mut ranges: Vec<TokenRange> = Vec::new();
+    let mut push = |range: TokenRange| {
+        if range.is_empty() {
+            return;
+        }
+        match ranges.last() {
+            Some(&last) if last == range || !range.contains_range(last) => {}
+            _ => ranges.push(range),
+        }
+    };
+    if at.get() >= tree.tokens().len() {
+        return ranges;
+    }
+    push(TokenRange::new(at, TokenIndex::new(at.get() + 1)));
+    let Some(node) = tree.innermost_containing(at) else {
+        return ranges;
+    };
+    let mut chain: Vec<NodeView<'_>> = node.ancestors().collect();
+    chain.push(node);
+    chain.reverse();
+    for (i, node) in chain.iter().enumerate() {
+        let range = node.lexical_range();
+        push(range);
+        let Some(&parent) = chain.get(i + 1) else {
+            push(node.form_range());
+            break;
+        };
+        if let Some(separator) = separator_after(tree, range, parent.lexical_range()) {
+            push(TokenRange::new(
+                range.start(),
+                TokenIndex::new(separator.get() + 1),
+            ));
+        }
+        if let Some(elements) = elements(parent) {
+            push(elements);
+        }
+    }
+    ranges
+}
+
+/// Returns the `,` or `;` that directly follows `range` inside
+/// `parent`.
+fn separator_after(tree: &SyntaxTree, range: TokenRange, parent: TokenRange) -> Option<TokenIndex> {
+    let tokens = tree.tokens();
+    let at = (range.end().get()..parent.end().get()).find(|&i| tokens[i].kind().is_lexical())?;
+    matches!(
+        tokens[at].kind(),
+        TokenKind::Symbol(Symbol::Comma | Symbol::Semicolon)
+    )
+    .then_some(TokenIndex::new(at))
+}
+
+/// Returns the span from the first to the last element of a bracketed
+/// container, or `None` for other nodes and empty containers.
+fn elements(node: NodeView<'_>) -> Option<TokenRange> {
+    let element = match node.kind() {
+        SyntaxKind::MapExpr | SyntaxKind::MapUpdateExpr => Some(SyntaxKind::MapField),
+        SyntaxKind::RecordExpr | SyntaxKind::RecordUpdateExpr => Some(SyntaxKind::RecordField),
+        SyntaxKind::MapType => Some(SyntaxKind::MapTypeField),
+        SyntaxKind::RecordType => Some(SyntaxKind::RecordTypeField),
+        SyntaxKind::RecordDecl => Some(SyntaxKind::RecordDeclField),
+        SyntaxKind::ArgumentList
+        | SyntaxKind::TypeArgumentList
+        | SyntaxKind::FunctionTypeParams
+        | SyntaxKind::TupleExpr
+        | SyntaxKind::ListExpr
+        | SyntaxKind::BitstringExpr
+        | SyntaxKind::TupleType
+        | SyntaxKind::BitstringType
+        | SyntaxKind::ExportList => None,
+        _ => return None,
+    };
+    let mut children = node
+        .children()
+        .filter(|child| element.is_none_or(|kind| child.kind() == kind))
+        .map(NodeView::lexical_range)
+        .filter(|range| !range.is_empty());
+    let first = children.next()?;
+    let last = children.last().unwrap_or(first);
+    Some(TokenRange::new(first.start(), last.end()))
+}
@@ -0,0 +1,374 @@
+//! Semantic token classification.
+//!
+//! [`classify`] gives each lexical token and comment an LSP semantic
+//! token type and modifier set. A token's [`TokenKind`] decides the
+//! default, and the node around it refines atoms and variables: the
+//! atom before `(` in a clause head is a function, the atom after `#`
+//! a record, and so on. Attribute payloads are read with
+//! [`SyntaxTree::parse_attribute_payload`], so names in `-spec`,
+//! `-type`, `-record`, and `-export` are classified too, and
+//! [`Scopes`] tells bound variables from used ones.
+//! [`SemanticToken::encode`] lays the result out as LSP expects it.
+
+use core::ops::BitOr;
+
+use erl_tokenize::{Token, TokenKind, TokenValue};
+
+use crate::ast::{self, AstNode};
+use crate::line_index::{LineIndex, Utf16Position};
+use crate::node::NodeView;
+use crate::scope::{Role, Scopes};
+use crate::syntax::SyntaxKind;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenIndex;
+
+/// A semantic token type from the LSP specification.
+///
+/// [`SemanticTokenType::ALL`] is a legend in the order of
+/// [`SemanticTokenType::index`], ready for a server's capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTokenType {
+    /// A module name: `lists` in `lists:map/2` or `-module(lists)`.
+    Namespace,
+    /// A type name in a type call or `-type` declaration.
+    Type,
+    /// A record name.
+    Struct,
+    /// A variable in a `-spec`, `-type`, or `-record` attribute.
+    TypeParameter,
+    /// A variable in an expression or pattern.
+    Variable,
+    /// A record field name.
+    Property,
+    /// Any other atom.
+    EnumMember,
+    /// A function name in a clause head, call, fun reference, `-spec`,
+    /// or `-export`.
+    Function,
+    /// An attribute name, such as `module` in `-module(m)`.
+    Macro,
+    /// A reserved word.
+    Keyword,
+    /// A `%` comment.
+    Comment,
+    /// A string, sigil string, or character literal.
+    String,
+    /// An integer or float literal.
+    Number,
+    /// A symbol: an operator or punctuation.
+    Operator,
+}
+
+impl SemanticTokenType {
+    /// Every type, in [`SemanticTokenType::index`] order.
+    pub const ALL: [Self; 14] = [
+        Self::Namespace,
+        Self::Type,
+        Self::Struct,
+        Self::TypeParameter,
+        Self::Variable,
+        Self::Property,
+        Self::EnumMember,
+        Self::Function,
+        Self::Macro,
+        Self::Keyword,
+        Self::Comment,
+        Self::String,
+        Self::Number,
+        Self::Operator,
+    ];
+
+    /// Returns the LSP name, such as `"typeParameter"`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Namespace => "namespace",
+            Self::Type => "type",
+            Self::Struct => "struct",
+            Self::TypeParameter => "typeParameter",
+            Self::Variable => "variable",
+            Self::Property => "property",
+            Self::EnumMember => "enumMember",
+            Self::Function => "function",
+            Self::Macro => "macro",
+            Self::Keyword => "keyword",
+            Self::Comment => "comment",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Operator => "operator",
+        }
+    }
+
+    /// Returns the position of this type in [`SemanticTokenType::ALL`].
+    pub const fn index(self) -> u32 {
+        self as u32
+    }
+}
+
+/// A set of LSP semantic token modifiers.
+///
+/// [`SemanticTokenModifiers::bits`] numbers the modifiers in the order
+/// of [`SemanticTokenModifiers::LEGEND`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SemanticTokenModifiers(u32);
+
+impl SemanticTokenModifiers {
+    /// No modifiers.
+    pub const NONE: Self = Self(0);
+    /// A name being declared: a variable binding, a clause head, or the
+    /// name in a `-type` or `-record` attribute.
+    pub const DECLARATION: Self = Self(1);
+    /// Documentation: the text of a `-doc` or `-moduledoc` attribute.
+    pub const DOCUMENTATION: Self = Self(1 << 1);
+
+    /// The LSP modifier names, one per bit from the lowest.
+    pub const LEGEND: [&'static str; 2] = ["declaration", "documentation"];
+
+    /// Returns the LSP bit set.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` when every modifier of `other` is in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for SemanticTokenModifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A token with its semantic classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SemanticToken {
+    index: TokenIndex,
+    token_type: SemanticTokenType,
+    modifiers: SemanticTokenModifiers,
+}
+
+impl SemanticToken {
+    /// Returns the token's index in [`SyntaxTree::tokens`].
+    pub fn index(self) -> TokenIndex {
+        self.index
+    }
+
+    /// Returns the token type.
+    pub fn token_type(self) -> SemanticTokenType {
+        self.token_type
+    }
+
+    /// Returns the modifiers.
+    pub fn modifiers(self) -> SemanticTokenModifiers {
+        self.modifiers
+    }
+
+    /// Encodes `tokens` as the `data` of an LSP `SemanticTokens`
+    /// result: five integers per token, holding the line delta, the
+    /// start delta, the length, the [`SemanticTokenType::index`], and
+    /// the [`SemanticTokenModifiers::bits`].
+    ///
+    /// `tokens` come from [`SyntaxTree::semantic_tokens`] on `tree`, and
+    /// `source` is the text its tokens were scanned from. Columns and
+    /// lengths count UTF-16 code units. A token that spans several
+    /// lines, such as a multi-line string, is split into one entry per
+    /// line, as clients without `multilineTokenSupport` expect.
+    pub fn encode(tokens: &[SemanticToken], tree: &SyntaxTree, source: &str) -> Vec<u32> {
+        let index = LineIndex::new(tree, source);
+        let mut data = Vec::with_capacity(tokens.len() * 5);
+        let (mut last_line, mut last_character) = (0, 0);
+        for token in tokens {
+            let at = tree.tokens()[token.index.get()];
+            let Utf16Position {
+                mut line,
+                mut character,
+            } = index.utf16_position(index.offset(token.index));
+            for text in at.text(source).split('\n') {
+                let text = text.strip_suffix('\r').unwrap_or(text);
+                let length = text.encode_utf16().count() as u32;
+                if length > 0 {
+                    let delta_start = if line == last_line {
+                        character - last_character
+                    } else {
+                        character
+                    };
+                    data.extend([
+                        line - last_line,
+                        delta_start,
+                        length,
+                        token.token_type.index(),
+                        token.modifiers.bits(),
+                    ]);
+                    (last_line, last_character) = (line, character);
+                }
+                line += 1;
+                character = 0;
+            }
+        }
+        data
+    }
+}
+
+type Class = (SemanticTokenType, SemanticTokenModifiers);
+
+/// Returns a [`SemanticToken`] for every token of `tree` but
+/// whitespace, in token order.
+pub(crate) fn classify(tree: &SyntaxTree, source: &str) -> Vec<SemanticToken> {
+    let mut classes: Vec<Option<Class>> = tree.tokens().iter().map(default_class).collect();
+    for root in tree.roots() {
+        refine(&mut classes, tree, root, "");
+        let Some(attribute) = ast::Attribute::cast(root) else {
+            continue;
+        };
+        let name = attribute
+            .name()
+            .and_then(|name| name.token())
+            .and_then(|at| match tree.tokens()[at.get()].value(source) {
+                TokenValue::Atom(name) => Some(name.into_owned()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let Some(payload) = tree.parse_attribute_payload(root, source) else {
+            continue;
+        };
+        for node in payload.roots() {
+            refine(&mut classes, tree, node, &name);
+            let in_types = matches!(
+                node.kind(),
+                SyntaxKind::SpecAttribute | SyntaxKind::TypeDecl | SyntaxKind::RecordDecl
+            );
+            for (at, token) in node.tokens_in_range() {
+                let none = SemanticTokenModifiers::NONE;
+                classes[at.get()] = Some(match (token.kind(), name.as_str()) {
+                    (TokenKind::Variable, _) if in_types => {
+                        (SemanticTokenType::TypeParameter, none)
+                    }
+                    (TokenKind::Atom, "module") => (SemanticTokenType::Namespace, none),
+                    (TokenKind::String | TokenKind::SigilString, "doc" | "moduledoc") => (
+                        SemanticTokenType::String,
+                        SemanticTokenModifiers::DOCUMENTATION,
+                    ),
+                    _ => continue,
+                });
+            }
+        }
+    }
+    let scopes = Scopes::new(tree, source);
+    for &(at, occurrence) in scopes.occurrences() {
+        let modifiers = match occurrence.role() {
+            Role::Binding => SemanticTokenModifiers::DECLARATION,
+            Role::Use => SemanticTokenModifiers::NONE,
+        };
+        classes[at.get()] = Some((SemanticTokenType::Variable, modifiers));
+    }
+    classes
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, class)| {
+            let (token_type, modifiers) = class?;
+            Some(SemanticToken {
+                index: TokenIndex::new(i),
+                token_type,
+                modifiers,
+            })
+        })
+        .collect()
+}
+
+fn default_class(token: &Token) -> Option<Class> {
+    let token_type = match token.kind() {
+        TokenKind::Whitespace => return None,
+        TokenKind::Atom => SemanticTokenType::EnumMember,
+        TokenKind::Variable => SemanticTokenType::Variable,
+        TokenKind::Keyword(_) => SemanticTokenType::Keyword,
+        TokenKind::Comment => SemanticTokenType::Comment,
+        TokenKind::String | TokenKind::SigilString | TokenKind::Char => SemanticTokenType::String,
+        TokenKind::Integer | TokenKind::Float => SemanticTokenType::Number,
+        TokenKind::Symbol(_) => SemanticTokenType::Operator,
+    };
+    Some((token_type, SemanticTokenModifiers::NONE))
+}
+
+/// Classifies the names under `root` (inclusive) by their node. The
+/// view may come from an attribute payload tree, which shares `tree`'s
+/// token numbering; `attribute` names the attribute it is the payload
+/// of, or is empty.
+fn refine(classes: &mut [Option<Class>], tree: &SyntaxTree, root: NodeView<'_>, attribute: &str) {
+    use SemanticTokenType::*;
+    let none = SemanticTokenModifiers::NONE;
+    let declaration = SemanticTokenModifiers::DECLARATION;
+    let mut mark = |at: Option<TokenIndex>, token_type, modifiers| {
+        // Only atoms are renamed here; variables in name positions
+        // (`M:f()`, `fun M:F/A`) stay variables.
+        if let Some(at) = at
+            && tree.tokens()[at.get()].kind() == TokenKind::Atom
+        {
+            classes[at.get()] = Some((token_type, modifiers));
+        }
+    };
+    let atom = |node: Option<ast::Node<'_>>| match node? {
+        ast::Node::AtomExpr(n) => n.token(),
+        _ => None,
+    };
+    for view in core::iter::once(root).chain(root.descendants()) {
+        match ast::Node::new(view) {
+            ast::Node::AttributeName(n) => mark(n.token(), Macro, none),
+            ast::Node::FunctionClause(n) => mark(n.name(), Function, declaration),
+            ast::Node::CallExpr(n) => mark(atom(n.target()), Function, none),
+            ast::Node::RemoteExpr(n) => {
+                mark(atom(n.module()), Namespace, none);
+                mark(atom(n.function()), Function, none);
+            }
+            ast::Node::LocalFunRef(n) => mark(n.name(), Function, none),
+            ast::Node::RemoteFunRef(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Function, none);
+            }
+            ast::Node::RecordExpr(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Struct, none);
+            }
+            ast::Node::RecordUpdateExpr(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Struct, none);
+            }
+            ast::Node::RecordFieldAccessExpr(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Struct, none);
+                mark(n.field(), Property, none);
+            }
+            ast::Node::RecordIndexExpr(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Struct, none);
+                mark(n.field(), Property, none);
+            }
+            ast::Node::RecordField(n) => mark(n.name(), Property, none),
+            ast::Node::RecordType(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Struct, none);
+            }
+            ast::Node::RecordTypeField(n) => mark(n.name(), Property, none),
+            ast::Node::TypeCall(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Type, none);
+            }
+            ast::Node::RemoteType(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Type, none);
+            }
+            ast::Node::SpecAttribute(n) => {
+                mark(n.module(), Namespace, none);
+                mark(n.name(), Function, none);
+            }
+            ast::Node::TypeDecl(n) => mark(n.name(), Type, declaration),
+            ast::Node::RecordDecl(n) => mark(n.name(), Struct, declaration),
+            ast::Node::RecordDeclField(n) => mark(n.name(), Property, declaration),
+            ast::Node::NameArity(n) if attribute == "export_type" => mark(n.name(), Type, none),
+            ast::Node::NameArity(n) => mark(n.name(), Function, none),
+            _ => {}
+        }
+    }
+}
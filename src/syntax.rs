@@ -8,6 +8,10 @@
 //! See [`SyntaxIndex`]'s type documentation for the preorder-array
 //! invariants that the builder must preserve.
 
+use std::sync::OnceLock;
+
+use crate::comment::CommentTable;
+use crate::token_buffer::TokenBuffer;
 use crate::token_range::TokenRange;
 
 /// Grammar-level nonterminal kind assigned to a syntax entry.
@@ -307,11 +311,9 @@ pub enum SyntaxKind {
     // with an [`AttributeName`][Self::AttributeName] child and, when a
     // parenthesized payload is present, an
     // [`AttributePayload`][Self::AttributePayload] child. Callers pull
-    // the attribute name from the token buffer and, when needed, feed
-    // the payload's `TokenRange` back through a fresh parser in
-    // [`crate::ParseMode::Type`], [`crate::ParseMode::TermList`], or
-    // [`crate::ParseMode::Expression`] when they want a structured
-    // reading of the payload.
+    // the attribute name from the token buffer and, when they want a
+    // structured reading of the payload, call
+    // [`crate::SyntaxTree::parse_attribute_payload`].
     // ---------------------------------------------------------------------
     /// A top-level attribute form, `-Name.` or `-Name(Payload).`. The
     /// terminating `.` is folded into the node's `TokenRange` by the
@@ -330,6 +332,8 @@ pub enum SyntaxKind {
     /// terminated by `.`. Same-name grouping across forms and clause
     /// name / arity consistency are semantic concerns; the parser
     /// does not enforce them.
+    /// [`ModuleModel`](crate::model::ModuleModel) groups functions and
+    /// reports both.
     FunctionDecl,
     /// A single clause of a [`FunctionDecl`][Self::FunctionDecl] of
     /// the form `Name(Args) [when Guard] -> Body`. The clause name is
@@ -338,6 +342,41 @@ pub enum SyntaxKind {
     /// [`GuardSequence`][Self::GuardSequence] and the body is a
     /// [`Body`][Self::Body].
     FunctionClause,
+
+    // ---------------------------------------------------------------------
+    // Structured attribute payloads.
+    //
+    // Never emitted by the module-mode driver, which keeps every
+    // payload opaque. [`SyntaxTree::parse_attribute_payload`] emits
+    // them when a caller asks for a structured reading of a
+    // well-known attribute's payload.
+    //
+    // [`SyntaxTree::parse_attribute_payload`]: crate::SyntaxTree::parse_attribute_payload
+    // ---------------------------------------------------------------------
+    /// A `-spec` / `-callback` payload, `[Module :] Name Sig; Sig; ...`.
+    /// The name pieces are bare tokens; each signature is a
+    /// [`FunctionType`][Self::FunctionType] whose optional `when`
+    /// clause is a trailing [`TypeGuard`][Self::TypeGuard] child.
+    SpecAttribute,
+    /// A `-type` / `-opaque` / `-nominal` payload, `Name(Vars) ::
+    /// Type`. The name is a bare token followed by a
+    /// [`TypeArgumentList`][Self::TypeArgumentList] and the defining
+    /// type.
+    TypeDecl,
+    /// A `-record` payload, `(Name, {Field, ...})`. The record name is
+    /// a bare token; each field is a
+    /// [`RecordDeclField`][Self::RecordDeclField].
+    RecordDecl,
+    /// A single `Name [= Default] [:: Type]` field inside a
+    /// [`RecordDecl`][Self::RecordDecl].
+    RecordDeclField,
+    /// A `[Name/Arity, ...]` list of [`NameArity`][Self::NameArity]
+    /// pairs, as in `-export`, `-export_type`, and
+    /// `-optional_callbacks` payloads.
+    ExportList,
+    /// A single `Name/Arity` pair inside an
+    /// [`ExportList`][Self::ExportList]; both parts are bare tokens.
+    NameArity,
 }
 
 /// Index into the entry array that identifies a boundary (values in
@@ -459,6 +498,8 @@ impl SyntaxEntry {
 #[derive(Debug, Clone)]
 pub(crate) struct SyntaxIndex {
     entries: Vec<SyntaxEntry>,
+    /// Comment attachment, built on first use and dropped on mutation.
+    comments: OnceLock<CommentTable>,
 }
 
 impl SyntaxIndex {
@@ -467,6 +508,7 @@ impl SyntaxIndex {
     pub(crate) const fn new() -> Self {
         Self {
             entries: Vec::new(),
+            comments: OnceLock::new(),
         }
     }
 
@@ -492,8 +534,22 @@ impl SyntaxIndex {
     pub(crate) fn push(&mut self, entry: SyntaxEntry) -> NodeId {
         let id = NodeId::new(self.entries.len());
         self.entries.push(entry);
+        self.forget_comments();
         id
     }
+
+    /// Returns the comment attachment of this index over `tokens`,
+    /// building it on first use.
+    pub(crate) fn comments(&self, tokens: &TokenBuffer) -> &CommentTable {
+        self.comments
+            .get_or_init(|| CommentTable::new(tokens, self))
+    }
+
+    /// Drops the cached comment attachment, for when the tokens or
+    /// entries it was built from change.
+    pub(crate) fn forget_comments(&mut self) {
+        self.comments.take();
+    }
 }
 
 #[cfg(test)]
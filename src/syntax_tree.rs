@@ -17,10 +17,16 @@
 //! if they want to decouple the snapshot from the running parser.
 
 use crate::diagnostic::Diagnostic;
+use crate::document_symbols::DocumentSymbol;
+use crate::folding_ranges::FoldingRange;
+use crate::grammar::attribute_payload::PayloadShape;
 use crate::node::NodeView;
-use crate::syntax::{NodeId, SyntaxIndex};
+use crate::parser::ParseMode;
+use crate::reparse::TokenEdit;
+use crate::semantic_tokens::SemanticToken;
+use crate::syntax::{NodeId, SyntaxIndex, SyntaxKind};
 use crate::token_buffer::TokenBuffer;
-use crate::token_range::TokenIndex;
+use crate::token_range::{TokenIndex, TokenRange};
 
 /// The full result of a parse: input tokens, syntax nodes, and
 /// accumulated diagnostics.
@@ -36,23 +42,74 @@ use crate::token_range::TokenIndex;
 /// tree.
 #[derive(Debug, Clone)]
 pub struct SyntaxTree {
+    mode: ParseMode,
     tokens: TokenBuffer,
     syntax: SyntaxIndex,
     diagnostics: Vec<Diagnostic>,
+    units: Vec<UnitEnd>,
+}
+
+/// Where one `.`-terminated top-level unit ends in each of the tree's
+/// append-only sequences.
+///
+/// The parser records one per unit it closes at a boundary dot (the
+/// unterminated tail flushed by [`Parser::finish`](crate::Parser::finish)
+/// gets none). A unit is parsed while the buffer ends at its dot, so
+/// its nodes and diagnostics depend only on the tokens since the
+/// previous unit's end; [`SyntaxTree::reparse`] relies on that to
+/// reuse units an edit does not touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UnitEnd {
+    tokens: TokenIndex,
+    entries: usize,
+    diagnostics: usize,
+}
+
+impl UnitEnd {
+    /// Builds a `UnitEnd` from the three sequence lengths.
+    pub(crate) const fn new(tokens: TokenIndex, entries: usize, diagnostics: usize) -> Self {
+        Self {
+            tokens,
+            entries,
+            diagnostics,
+        }
+    }
+
+    /// Token boundary just past the unit's terminating `.`.
+    pub(crate) const fn tokens(self) -> TokenIndex {
+        self.tokens
+    }
+
+    /// Syntax-index length once the unit was finalized.
+    pub(crate) const fn entries(self) -> usize {
+        self.entries
+    }
+
+    /// Diagnostic count once the unit was finalized.
+    pub(crate) const fn diagnostics(self) -> usize {
+        self.diagnostics
+    }
 }
 
 impl SyntaxTree {
-    /// Creates an empty tree.
+    /// Creates an empty tree for `mode`.
     // `pub(crate)`: callers receive a tree from `Parser::finish` /
     // `Parser::syntax_tree`. An empty tree has no tokens to pair with.
-    pub(crate) const fn new() -> Self {
+    pub(crate) fn new(mode: ParseMode) -> Self {
         Self {
+            mode,
             tokens: TokenBuffer::new(),
             syntax: SyntaxIndex::new(),
             diagnostics: Vec::new(),
+            units: Vec::new(),
         }
     }
 
+    /// Returns the mode the tree was parsed in.
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
     /// Tokens the caller fed, in feed order.
     ///
     /// The sequence is append-only: a [`TokenIndex`] obtained earlier
@@ -69,11 +126,21 @@ impl SyntaxTree {
     }
 
     /// Borrows the crate-internal syntax index.
-    #[cfg(test)]
     pub(crate) fn syntax(&self) -> &SyntaxIndex {
         &self.syntax
     }
 
+    /// Reconstructs the source from the token buffer by concatenating
+    /// every token's text in feed order. `source` is the text the tokens
+    /// were scanned from.
+    ///
+    /// Hidden tokens are stored alongside lexical ones, so when every
+    /// scanned token was fed the result equals `source` byte for byte,
+    /// whether or not the parse recorded diagnostics.
+    pub fn to_source(&self, source: &str) -> String {
+        self.tokens().iter().map(|t| t.text(source)).collect()
+    }
+
     /// Returns an iterator over root-level nodes (each `.`-terminated
     /// unit in the preorder array).
     pub fn roots(&self) -> impl Iterator<Item = NodeView<'_>> {
@@ -89,6 +156,20 @@ impl SyntaxTree {
         crate::node::innermost_containing(&self.tokens, &self.syntax, target)
     }
 
+    /// Returns the node the comment token at `comment` is attached to,
+    /// and whether it leads, trails, or dangles in that node. Returns
+    /// `None` when `comment` is not a comment or there is no node to
+    /// attach it to.
+    ///
+    /// See [`CommentPlacement`](crate::CommentPlacement) for the rules
+    /// and [`NodeView::leading_comments`] for the per-node view.
+    pub fn comment_attachment(
+        &self,
+        comment: TokenIndex,
+    ) -> Option<(NodeView<'_>, crate::CommentPlacement)> {
+        crate::comment::attach(&self.tokens, &self.syntax, comment)
+    }
+
     /// Returns a [`NodeView`] for `node_id` in this tree, or `None`
     /// when the id does not refer to an existing entry.
     ///
@@ -100,13 +181,167 @@ impl SyntaxTree {
         NodeView::new(&self.tokens, &self.syntax, node_id)
     }
 
+    /// Re-parses the payload of a Module-mode [`SyntaxKind::Attribute`]
+    /// node with the production its name calls for, returning a
+    /// separate tree whose root is the structured payload.
+    ///
+    /// `-spec` / `-callback` yield a [`SyntaxKind::SpecAttribute`] with
+    /// one [`SyntaxKind::FunctionType`] per clause; `-type` / `-opaque`
+    /// / `-nominal` a [`SyntaxKind::TypeDecl`]; `-record` a
+    /// [`SyntaxKind::RecordDecl`]; `-export` / `-export_type` /
+    /// `-optional_callbacks` an [`SyntaxKind::ExportList`] of
+    /// [`SyntaxKind::NameArity`] entries. Any other attribute is read
+    /// as an [`SyntaxKind::ArgumentList`] (parenthesized payload) or a
+    /// single expression (bare payload).
+    ///
+    /// The returned tree shares this tree's token numbering: every
+    /// [`TokenIndex`] in it refers to the same token here, so ranges
+    /// can be mapped back without adjustment. The tokens are shared
+    /// rather than copied, so the cost is that of parsing the payload.
+    /// Tokens the production does not consume become a trailing
+    /// [`SyntaxKind::Error`] root, and the returned tree carries its own
+    /// diagnostics.
+    ///
+    /// Returns `None` when `attribute` is not an `Attribute` node, has
+    /// no name or an empty payload, or names a preprocessor directive
+    /// (`-define`, `-include`, `-ifdef`, ...). `source` is the text the
+    /// tokens were scanned from; it is needed to read the name.
+    pub fn parse_attribute_payload(
+        &self,
+        attribute: NodeView<'_>,
+        source: &str,
+    ) -> Option<SyntaxTree> {
+        if attribute.kind() != SyntaxKind::Attribute {
+            return None;
+        }
+        let name = attribute
+            .children()
+            .find(|child| child.kind() == SyntaxKind::AttributeName)?;
+        let payload = attribute
+            .children()
+            .find(|child| child.kind() == SyntaxKind::AttributePayload)?;
+        if payload.range().is_empty() {
+            return None;
+        }
+        let shape = name
+            .tokens_in_range()
+            .find_map(|(_, token)| match token.value(source) {
+                erl_tokenize::TokenValue::Atom(name) => PayloadShape::from_name(&name),
+                _ => None,
+            })?;
+        let tokens = self.tokens.prefix(payload.range().end());
+        Some(crate::parser::parse_attribute_payload(
+            tokens,
+            payload.range().start(),
+            shape,
+        ))
+    }
+
+    /// Classifies every token but whitespace for LSP semantic
+    /// highlighting, in token order. `source` is the text the tokens
+    /// were scanned from.
+    ///
+    /// The token's [`TokenKind`](erl_tokenize::TokenKind) gives the
+    /// default type; the enclosing node refines atoms into functions,
+    /// modules, records, fields, types, and attribute names, and
+    /// variables into bindings (with
+    /// [`SemanticTokenModifiers::DECLARATION`](crate::SemanticTokenModifiers::DECLARATION))
+    /// and uses. Names inside `-spec`, `-type`, `-record`, and
+    /// `-export` payloads are classified as well.
+    /// [`SemanticToken::encode`] turns the result into the positions
+    /// and lengths of the LSP delta encoding.
+    pub fn semantic_tokens(&self, source: &str) -> Vec<SemanticToken> {
+        crate::semantic_tokens::classify(self, source)
+    }
+
+    /// Returns the outline of a Module-mode tree: the `-module`
+    /// attribute, functions with one child per clause, records with one
+    /// child per field, types, specs, callbacks, and `-define`d macros,
+    /// in source order. `source` is the text the tokens were scanned
+    /// from.
+    ///
+    /// Each [`DocumentSymbol`] carries the range of the whole form or
+    /// clause and a selection range on its name token. Forms whose name
+    /// cannot be read, such as those error recovery cut short, are
+    /// left out.
+    pub fn document_symbols(&self, source: &str) -> Vec<DocumentSymbol> {
+        crate::document_symbols::outline(self, source)
+    }
+
+    /// Returns the regions an editor can fold, ordered by start line:
+    /// `case`, `if`, `try`, `receive`, `maybe`, and `begin` blocks,
+    /// funs, lists, maps, records, and tuples that span lines;
+    /// function declarations with more than one clause; runs of
+    /// comments that each start a line; and runs of consecutive
+    /// `-export` and `-import` attributes. `source` is the text the
+    /// tokens were scanned from.
+    ///
+    /// Lines are 1-based, as in [`LineColumn`](crate::LineColumn);
+    /// subtract one for LSP. Where two regions cover the same lines,
+    /// only the outer one is kept.
+    pub fn folding_ranges(&self, source: &str) -> Vec<FoldingRange> {
+        crate::folding_ranges::folding_ranges(self, source)
+    }
+
+    /// Returns the ranges an editor's "expand selection" steps through
+    /// from the token at `at`, innermost first, each strictly containing
+    /// the one before.
+    ///
+    /// The chain starts with the token itself, then follows
+    /// [`SyntaxTree::innermost_containing`] and its
+    /// [`NodeView::ancestors`] out to the form and its closing `.`.
+    /// Node ranges leave out leading and trailing whitespace and
+    /// comments. Between a node and its parent come two steps the index
+    /// does not materialize: the node with the `,` or `;` after it, and
+    /// all the elements of an argument list, tuple, list, map, record,
+    /// or binary without the brackets. A step equal to the one before is
+    /// dropped. Returns an empty chain when `at` is past the last token.
+    pub fn selection_ranges(&self, at: TokenIndex) -> Vec<TokenRange> {
+        crate::selection_ranges::selection_ranges(self, at)
+    }
+
     /// Borrows the accumulated diagnostics.
     pub fn diagnostics(&self) -> &[Diagnostic] {
         &self.diagnostics
     }
 
+    /// Parses `new_tokens` (the full token sequence after a text edit)
+    /// by reusing every `.`-terminated unit of this tree that `edit`
+    /// leaves untouched, and returns the new tree together with the
+    /// [`NodeId`]s of the roots that were re-parsed.
+    ///
+    /// `edit` is stated against this tree's tokens: [`TokenEdit::range`]
+    /// was replaced by [`TokenEdit::new_len`] tokens of `new_tokens`.
+    /// [`TokenEdit::diff`] computes it from the old and new sources.
+    /// Units that end before the edit keep their nodes, [`NodeId`]s,
+    /// and diagnostics; the grammar re-runs from the edited unit until
+    /// a unit boundary lines up with an old one past the edit, and the
+    /// units after that are carried over with their token indexes
+    /// shifted. The result is the tree a fresh [`Parser`] would build
+    /// from `new_tokens` in the same mode.
+    ///
+    /// The returned ids are in preorder and include every root of a
+    /// re-parsed unit, including the unterminated tail that
+    /// [`Parser::finish`] flushes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edit.range()` extends past this tree's tokens, or if
+    /// `new_tokens.len()` disagrees with the edit.
+    ///
+    /// [`Parser`]: crate::Parser
+    /// [`Parser::finish`]: crate::Parser::finish
+    pub fn reparse(
+        &self,
+        edit: TokenEdit,
+        new_tokens: &[erl_tokenize::Token],
+    ) -> (SyntaxTree, Vec<NodeId>) {
+        crate::reparse::reparse(self, edit, new_tokens)
+    }
+
     /// Mutable access to the token buffer, for the in-crate parser core.
     pub(crate) fn tokens_mut(&mut self) -> &mut TokenBuffer {
+        self.syntax.forget_comments();
         &mut self.tokens
     }
 
@@ -119,4 +354,16 @@ impl SyntaxTree {
     pub(crate) fn diagnostics_mut(&mut self) -> &mut Vec<Diagnostic> {
         &mut self.diagnostics
     }
+
+    /// Borrows the recorded unit boundaries, in unit order.
+    pub(crate) fn units(&self) -> &[UnitEnd] {
+        &self.units
+    }
+
+    /// Records that a `.`-terminated unit ended at `tokens`, capturing
+    /// the current syntax-index and diagnostic lengths.
+    pub(crate) fn push_unit(&mut self, tokens: TokenIndex) {
+        let unit = UnitEnd::new(tokens, self.syntax.len(), self.diagnostics.len());
+        self.units.push(unit);
+    }
 }
@@ -4,6 +4,8 @@
 //! append-only: a [`TokenIndex`] obtained earlier still names the same
 //! token after later feeds.
 
+use std::sync::Arc;
+
 use crate::token_range::{TokenIndex, TokenRange};
 
 /// Append-only buffer of tokens the caller fed.
@@ -14,31 +16,49 @@ use crate::token_range::{TokenIndex, TokenRange};
 /// Already-stored tokens are never removed, reordered, or mutated, so a
 /// [`TokenIndex`] stays valid for the rest of the parse and on the
 /// finished tree.
+///
+/// The storage is shared between a buffer and its clones and
+/// [prefixes](Self::prefix), and copied only when one of them grows.
 #[derive(Debug, Clone)]
 pub(crate) struct TokenBuffer {
-    tokens: Vec<erl_tokenize::Token>,
+    tokens: Arc<Vec<erl_tokenize::Token>>,
+    /// How many tokens of `tokens` belong to this buffer; a prefix
+    /// shares a longer vector.
+    len: usize,
 }
 
 impl TokenBuffer {
     /// Creates an empty buffer.
-    pub(crate) const fn new() -> Self {
-        Self { tokens: Vec::new() }
+    pub(crate) fn new() -> Self {
+        Self {
+            tokens: Arc::new(Vec::new()),
+            len: 0,
+        }
     }
 
     /// Returns the token at `index`, or `None` if the index is out of range.
     pub(crate) fn get(&self, index: TokenIndex) -> Option<erl_tokenize::Token> {
-        self.tokens.get(index.get()).copied()
+        self.as_slice().get(index.get()).copied()
     }
 
     /// Borrows the buffer as a slice.
     pub(crate) fn as_slice(&self) -> &[erl_tokenize::Token] {
-        &self.tokens
+        &self.tokens[..self.len]
+    }
+
+    /// Returns a buffer of the tokens before `end`, without copying
+    /// them. Indexes below `end` name the same tokens in both buffers.
+    pub(crate) fn prefix(&self, end: TokenIndex) -> Self {
+        Self {
+            tokens: Arc::clone(&self.tokens),
+            len: end.get().min(self.len),
+        }
     }
 
     /// Returns the trailing boundary index (the upper end of the
     /// `0..=len()` domain).
     pub(crate) fn end_index(&self) -> TokenIndex {
-        TokenIndex::new(self.tokens.len())
+        TokenIndex::new(self.len)
     }
 
     /// Returns an iterator that yields `(TokenIndex, Token)` pairs inside
@@ -48,7 +68,7 @@ impl TokenBuffer {
         range: TokenRange,
     ) -> impl Iterator<Item = (TokenIndex, erl_tokenize::Token)> {
         BufferRange {
-            tokens: &self.tokens,
+            tokens: self.as_slice(),
             cursor: range.start().get(),
             end: range.end().get(),
         }
@@ -60,8 +80,11 @@ impl TokenBuffer {
     // `pub(crate)`: only the parser core and in-crate tests call this.
     // External callers feed tokens through `Parser::feed_token`.
     pub(crate) fn push(&mut self, token: erl_tokenize::Token) -> TokenIndex {
-        let index = TokenIndex::new(self.tokens.len());
-        self.tokens.push(token);
+        let index = TokenIndex::new(self.len);
+        let tokens = Arc::make_mut(&mut self.tokens);
+        tokens.truncate(self.len);
+        tokens.push(token);
+        self.len += 1;
         index
     }
 }
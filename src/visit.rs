@@ -0,0 +1,226 @@
+//! Visitors and rewriters over syntax trees.
+//!
+//! [`walk`] drives a [`Visitor`] through a subtree in preorder. It steps
+//! through the tree's flat preorder array and jumps over a skipped
+//! subtree in one step, so there is no recursion and no allocation
+//! beyond a stack of the nodes still open. Each node gets
+//! [`Visitor::enter`], then the hook for its kind
+//! ([`Visitor::visit_case_expr`], [`Visitor::visit_function_clause`],
+//! ...) with the typed [`ast`] wrapper, and [`Visitor::leave`] once its
+//! children are done. Either call may return [`Control::SkipChildren`]
+//! or [`Control::Stop`].
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::ast;
+//! use erl_parse::visit::{self, Control, Visitor};
+//!
+//! /// Counts the `case` expressions outside funs.
+//! struct Cases(usize);
+//!
+//! impl<'a> Visitor<'a> for Cases {
+//!     fn visit_case_expr(&mut self, _: ast::CaseExpr<'a>) -> Control {
+//!         self.0 += 1;
+//!         Control::Continue
+//!     }
+//!
+//!     fn visit_anonymous_fun(&mut self, _: ast::AnonymousFun<'a>) -> Control {
+//!         Control::SkipChildren
+//!     }
+//! }
+//!
+//! let source = "f(X) -> case X of 1 -> fun() -> case X of _ -> b end end; _ -> c end.";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let mut cases = Cases(0);
+//! visit::walk_tree(&tree, &mut cases);
+//! assert_eq!(cases.0, 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A [`Rewriter`] returns replacement text for the nodes it wants to
+//! change, and [`rewrite`] turns that into source edits.
+
+use core::ops::Range;
+
+use crate::ast::{self, for_each_ast_node};
+use crate::node::NodeView;
+use crate::syntax::NodeId;
+use crate::syntax_tree::SyntaxTree;
+
+/// What a walk does after a [`Visitor`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Control {
+    /// Go on into the node's children.
+    Continue,
+    /// Leave the node's children out and go on with its next sibling.
+    SkipChildren,
+    /// End the walk; no further calls, [`Visitor::leave`] included, are
+    /// made.
+    Stop,
+}
+
+impl Control {
+    /// Returns whichever of `self` and `other` cuts the walk shorter.
+    fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Stop, _) | (_, Self::Stop) => Self::Stop,
+            (Self::SkipChildren, _) | (_, Self::SkipChildren) => Self::SkipChildren,
+            _ => Self::Continue,
+        }
+    }
+}
+
+macro_rules! visitor {
+    ($($name:ident => $visit:ident),* $(,)?) => {
+        /// Callbacks for [`walk`].
+        ///
+        /// Every method has a default that does nothing and returns
+        /// [`Control::Continue`]; implement the ones you need. For each
+        /// node, [`Visitor::enter`] is called first and, unless it
+        /// returns [`Control::Stop`], then the hook for the node's kind.
+        /// The walk follows whichever of the two results cuts it
+        /// shorter.
+        pub trait Visitor<'a> {
+            /// Called for every node before its kind's hook.
+            fn enter(&mut self, node: NodeView<'a>) -> Control {
+                let _ = node;
+                Control::Continue
+            }
+
+            /// Called for every entered node after its children, or
+            /// right away when they are skipped.
+            fn leave(&mut self, node: NodeView<'a>) {
+                let _ = node;
+            }
+
+            $(
+                #[doc = concat!(
+                    "Called for each [`SyntaxKind::", stringify!($name),
+                    "`](crate::SyntaxKind::", stringify!($name), ") node."
+                )]
+                fn $visit(&mut self, node: ast::$name<'a>) -> Control {
+                    let _ = node;
+                    Control::Continue
+                }
+            )*
+        }
+
+        /// Calls the hook of `visitor` for `node`'s kind.
+        fn dispatch<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, node: ast::Node<'a>) -> Control {
+            match node {
+                $(ast::Node::$name(n) => visitor.$visit(n),)*
+            }
+        }
+    };
+}
+
+for_each_ast_node!(visitor);
+
+/// Walks `node` and its descendants in preorder.
+///
+/// Returns [`Control::Stop`] when the visitor stopped the walk, and
+/// [`Control::Continue`] otherwise.
+pub fn walk<'a, V: Visitor<'a> + ?Sized>(node: NodeView<'a>, visitor: &mut V) -> Control {
+    let end = node.subtree_end().get();
+    let mut open: Vec<NodeView<'a>> = Vec::new();
+    let mut at = node.node_id().get();
+    while at < end {
+        // Close the nodes whose subtrees end before this one.
+        while let Some(&parent) = open.last()
+            && parent.subtree_end().get() <= at
+        {
+            open.pop();
+            visitor.leave(parent);
+        }
+        let view = node
+            .entry_view(NodeId::new(at))
+            .expect("the walk stays inside the subtree");
+        let control = match visitor.enter(view) {
+            Control::Stop => Control::Stop,
+            entered => entered.and(dispatch(visitor, ast::Node::new(view))),
+        };
+        match control {
+            Control::Stop => return Control::Stop,
+            Control::SkipChildren => {
+                visitor.leave(view);
+                at = view.subtree_end().get();
+            }
+            Control::Continue => {
+                open.push(view);
+                at += 1;
+            }
+        }
+    }
+    while let Some(node) = open.pop() {
+        visitor.leave(node);
+    }
+    Control::Continue
+}
+
+/// Walks every root of `tree` in order, as [`walk`] does.
+pub fn walk_tree<'a, V: Visitor<'a> + ?Sized>(tree: &'a SyntaxTree, visitor: &mut V) -> Control {
+    for root in tree.roots() {
+        if walk(root, visitor) == Control::Stop {
+            return Control::Stop;
+        }
+    }
+    Control::Continue
+}
+
+/// Replacement text for nodes, for [`rewrite`].
+pub trait Rewriter<'a> {
+    /// Returns the text that replaces `node`, or `None` to keep it and
+    /// offer its children instead.
+    fn rewrite(&mut self, node: ast::Node<'a>) -> Option<String>;
+}
+
+/// Offers every node of `tree` to `rewriter` in preorder and returns the
+/// edits to apply to the source the tokens were scanned from.
+///
+/// A replaced node's children are not offered. The replacement covers
+/// the node's first through last lexical token, so whitespace and
+/// comments around it stay put. Each edit replaces a byte range of the
+/// source with new text, like
+/// [`format_range`](crate::format::format_range); the edits are in
+/// source order and do not overlap. Nodes without lexical tokens are
+/// not offered.
+pub fn rewrite<'a, R: Rewriter<'a> + ?Sized>(
+    tree: &'a SyntaxTree,
+    rewriter: &mut R,
+) -> Vec<(Range<usize>, String)> {
+    struct Edits<'r, R: ?Sized> {
+        tree: &'r SyntaxTree,
+        rewriter: &'r mut R,
+        edits: Vec<(Range<usize>, String)>,
+    }
+
+    impl<'a, R: Rewriter<'a> + ?Sized> Visitor<'a> for Edits<'_, R> {
+        fn enter(&mut self, node: NodeView<'a>) -> Control {
+            let range = node.lexical_range();
+            if range.is_empty() {
+                return Control::Continue;
+            }
+            let Some(text) = self.rewriter.rewrite(ast::Node::new(node)) else {
+                return Control::Continue;
+            };
+            let tokens = self.tree.tokens();
+            let start = tokens[range.start().get()].start().offset();
+            let end = tokens[range.end().get() - 1].end().offset();
+            self.edits.push((start..end, text));
+            Control::SkipChildren
+        }
+    }
+
+    let mut edits = Edits {
+        tree,
+        rewriter,
+        edits: Vec::new(),
+    };
+    walk_tree(tree, &mut edits);
+    edits.edits
+}
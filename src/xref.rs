@@ -0,0 +1,537 @@
+//! Cross-reference: the calls each function makes.
+//!
+//! [`CallGraph::new`] walks the function declarations of a Module-mode
+//! tree and records every call (`f(X)`, `m:f(X)`) and every fun
+//! reference (`fun f/1`, `fun m:f/1`) as a [`Call`] from the enclosing
+//! function to an [`Mfa`], the way `xref` reads them from BEAM files.
+//! Graphs of several modules can be merged by their [`Mfa`]s to find
+//! callers across a project.
+//!
+//! A local call `f(...)` resolves to the module's own `f` when it is
+//! defined, to the imported module when `-import` lists it, and to
+//! `erlang` when `f` is an auto-imported BIF such as `length/1`. Any
+//! other local call is [`Callee::Undefined`], the call `erlc` rejects
+//! as an undefined function. A call whose module or function is not an
+//! atom, such as `M:f()`, `m:F()`, or `F()`, is [`Callee::Dynamic`].
+//!
+//! ```
+//! # fn main() -> Result<(), erl_tokenize::Error> {
+//! use erl_parse::xref::{CallGraph, Mfa};
+//!
+//! let source = "-module(m).\nf(L) -> lists:map(fun g/1, L).\ng(X) -> X.\n";
+//! let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+//! for token in erl_tokenize::scan_tokens(source)? {
+//!     parser.feed_token(token);
+//! }
+//! let tree = parser.finish();
+//! let graph = CallGraph::new(&tree, source);
+//! let callees: Vec<_> = graph
+//!     .callees(&Mfa::new("m", "f", 1))
+//!     .map(Mfa::to_string)
+//!     .collect();
+//! assert_eq!(callees, ["lists:map/2", "m:g/1"]);
+//! # Ok(())
+//! # }
+//! ```
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::abstract_format::Integer;
+use crate::ast;
+use crate::model::{ModuleModel, NameArity, atom};
+use crate::syntax::NodeId;
+use crate::syntax_tree::SyntaxTree;
+use crate::token_range::TokenIndex;
+
+/// A `Module:Function/Arity` triple.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mfa {
+    module: String,
+    function: String,
+    arity: usize,
+}
+
+impl Mfa {
+    /// Makes a triple.
+    pub fn new(module: impl Into<String>, function: impl Into<String>, arity: usize) -> Self {
+        Self {
+            module: module.into(),
+            function: function.into(),
+            arity,
+        }
+    }
+
+    /// Returns the module name.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Returns the function name.
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+
+    /// Returns the arity.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+}
+
+impl fmt::Display for Mfa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}/{}", self.module, self.function, self.arity)
+    }
+}
+
+/// What a [`Call`] goes to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Callee {
+    /// A function named by atoms and an integer arity.
+    Static(Mfa),
+    /// A call or fun reference with a variable or computed part.
+    Dynamic(DynamicCallee),
+    /// A local call or fun reference to a function the module neither
+    /// defines nor imports and that is not an auto-imported BIF. The
+    /// triple names it in the calling module.
+    Undefined(Mfa),
+}
+
+/// The parts of a dynamic callee that are known.
+///
+/// `M:f(X)` knows the function and arity, `m:F(X)` the module and
+/// arity, and `F(X)` only the arity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynamicCallee {
+    module: Option<String>,
+    function: Option<String>,
+    arity: Option<usize>,
+}
+
+impl DynamicCallee {
+    /// Returns the module, when it is an atom.
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Returns the function, when it is an atom.
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+
+    /// Returns the arity, when it is known.
+    pub fn arity(&self) -> Option<usize> {
+        self.arity
+    }
+}
+
+/// How a [`Call`] refers to its callee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallKind {
+    /// An application, `f(X)` or `m:f(X)`.
+    Call,
+    /// A fun reference, `fun f/1` or `fun m:f/1`.
+    FunRef,
+}
+
+/// One call site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    caller: Mfa,
+    callee: Callee,
+    kind: CallKind,
+    node: NodeId,
+}
+
+impl Call {
+    /// Returns the function the call is in.
+    pub fn caller(&self) -> &Mfa {
+        &self.caller
+    }
+
+    /// Returns what is called.
+    pub fn callee(&self) -> &Callee {
+        &self.callee
+    }
+
+    /// Returns whether this is an application or a fun reference.
+    pub fn kind(&self) -> CallKind {
+        self.kind
+    }
+
+    /// Returns the `CallExpr`, `LocalFunRef`, or `RemoteFunRef` node.
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+}
+
+/// The calls of one module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraph {
+    module: Option<String>,
+    calls: Vec<Call>,
+}
+
+impl CallGraph {
+    /// Collects the calls in `tree`'s function declarations. `source`
+    /// is the text the tokens were scanned from.
+    ///
+    /// Callers take their module from `-module`, or the empty string
+    /// when there is none. Calls outside functions, such as in record
+    /// field defaults, are not collected, and a declaration that
+    /// [`ModuleModel`] reports as a duplicate is skipped.
+    pub fn new(tree: &SyntaxTree, source: &str) -> Self {
+        let model = ModuleModel::new(tree, source);
+        let module = model.name().unwrap_or_default();
+        let mut imports = HashMap::new();
+        for import in model.imports() {
+            imports.entry(import.key()).or_insert(import.module());
+        }
+        let local = |name: String, arity: usize| {
+            if model.function(&name, arity).is_some() {
+                return Callee::Static(Mfa::new(module, name, arity));
+            }
+            let key = NameArity::new(name, arity);
+            if let Some(&import) = imports.get(&key) {
+                Callee::Static(Mfa::new(import, key.name(), arity))
+            } else if is_auto_imported(key.name(), arity) {
+                Callee::Static(Mfa::new("erlang", key.name(), arity))
+            } else {
+                Callee::Undefined(Mfa::new(module, key.name(), arity))
+            }
+        };
+        let name = |node: Option<ast::Node<'_>>| match node? {
+            ast::Node::AtomExpr(n) => atom(tree, source, n.token()?),
+            _ => None,
+        };
+        let token_atom = |at: Option<TokenIndex>| atom(tree, source, at?);
+        let arity = |at: Option<TokenIndex>| {
+            Integer::parse_literal(tree.tokens()[at?.get()].text(source))?
+                .to_i64()
+                .and_then(|n| usize::try_from(n).ok())
+        };
+
+        let mut calls = Vec::new();
+        for function in model.functions() {
+            let caller = Mfa::new(module, function.key().name(), function.key().arity());
+            let Some(decl) = tree.view(function.node()) else {
+                continue;
+            };
+            for view in decl.descendants() {
+                let (callee, kind) = match ast::Node::new(view) {
+                    ast::Node::CallExpr(call) => {
+                        let (Some(target), Some(args)) = (call.target(), call.arguments()) else {
+                            continue;
+                        };
+                        let n = args.args().count();
+                        let callee = match target {
+                            ast::Node::RemoteExpr(remote) => {
+                                match (name(remote.module()), name(remote.function())) {
+                                    (Some(m), Some(f)) => Callee::Static(Mfa::new(m, f, n)),
+                                    (module, function) => Callee::Dynamic(DynamicCallee {
+                                        module,
+                                        function,
+                                        arity: Some(n),
+                                    }),
+                                }
+                            }
+                            target => match name(Some(target)) {
+                                Some(f) => local(f, n),
+                                None => Callee::Dynamic(DynamicCallee {
+                                    module: None,
+                                    function: None,
+                                    arity: Some(n),
+                                }),
+                            },
+                        };
+                        (callee, CallKind::Call)
+                    }
+                    ast::Node::LocalFunRef(fun) => {
+                        let callee = match (token_atom(fun.name()), arity(fun.arity())) {
+                            (Some(f), Some(n)) => local(f, n),
+                            (function, arity) => Callee::Dynamic(DynamicCallee {
+                                module: None,
+                                function,
+                                arity,
+                            }),
+                        };
+                        (callee, CallKind::FunRef)
+                    }
+                    ast::Node::RemoteFunRef(fun) => {
+                        let parts = (
+                            token_atom(fun.module()),
+                            token_atom(fun.name()),
+                            arity(fun.arity()),
+                        );
+                        let callee = match parts {
+                            (Some(m), Some(f), Some(n)) => Callee::Static(Mfa::new(m, f, n)),
+                            (module, function, arity) => Callee::Dynamic(DynamicCallee {
+                                module,
+                                function,
+                                arity,
+                            }),
+                        };
+                        (callee, CallKind::FunRef)
+                    }
+                    _ => continue,
+                };
+                calls.push(Call {
+                    caller: caller.clone(),
+                    callee,
+                    kind,
+                    node: view.node_id(),
+                });
+            }
+        }
+        Self {
+            module: model.name().map(str::to_owned),
+            calls,
+        }
+    }
+
+    /// Returns the `-module` name, if any.
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Returns every call, grouped by caller in declaration order and
+    /// in source order within each caller.
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// Returns the static `(caller, callee)` edges, one per call site.
+    pub fn edges(&self) -> impl Iterator<Item = (&Mfa, &Mfa)> {
+        self.calls.iter().filter_map(|call| match &call.callee {
+            Callee::Static(callee) => Some((&call.caller, callee)),
+            Callee::Dynamic(_) | Callee::Undefined(_) => None,
+        })
+    }
+
+    /// Returns the calls whose callee is not fully known.
+    pub fn dynamic_calls(&self) -> impl Iterator<Item = &Call> {
+        self.calls
+            .iter()
+            .filter(|call| matches!(call.callee, Callee::Dynamic(_)))
+    }
+
+    /// Returns the local calls to functions that are not defined,
+    /// imported, or auto-imported.
+    pub fn undefined_calls(&self) -> impl Iterator<Item = &Call> {
+        self.calls
+            .iter()
+            .filter(|call| matches!(call.callee, Callee::Undefined(_)))
+    }
+
+    /// Returns the distinct static callees of `caller`, sorted.
+    pub fn callees(&self, caller: &Mfa) -> impl Iterator<Item = &Mfa> {
+        let mut callees: Vec<_> = self
+            .edges()
+            .filter(|&(from, _)| from == caller)
+            .map(|(_, to)| to)
+            .collect();
+        callees.sort();
+        callees.dedup();
+        callees.into_iter()
+    }
+
+    /// Returns the distinct functions of this module that call or
+    /// refer to `callee`, sorted.
+    pub fn callers(&self, callee: &Mfa) -> impl Iterator<Item = &Mfa> {
+        let mut callers: Vec<_> = self
+            .edges()
+            .filter(|&(_, to)| to == callee)
+            .map(|(from, _)| from)
+            .collect();
+        callers.sort();
+        callers.dedup();
+        callers.into_iter()
+    }
+}
+
+/// Returns whether `name/arity` is a BIF the compiler auto-imports from
+/// `erlang`, as `erl_internal:bif/2` lists them.
+fn is_auto_imported(name: &str, arity: usize) -> bool {
+    AUTO_IMPORTED.binary_search(&(name, arity)).is_ok()
+}
+
+/// The auto-imported BIFs, sorted.
+const AUTO_IMPORTED: &[(&str, usize)] = &[
+    ("abs", 1),
+    ("alias", 0),
+    ("alias", 1),
+    ("apply", 2),
+    ("apply", 3),
+    ("atom_to_binary", 1),
+    ("atom_to_binary", 2),
+    ("atom_to_list", 1),
+    ("binary_part", 2),
+    ("binary_part", 3),
+    ("binary_to_atom", 1),
+    ("binary_to_atom", 2),
+    ("binary_to_existing_atom", 1),
+    ("binary_to_existing_atom", 2),
+    ("binary_to_float", 1),
+    ("binary_to_integer", 1),
+    ("binary_to_integer", 2),
+    ("binary_to_list", 1),
+    ("binary_to_list", 3),
+    ("binary_to_term", 1),
+    ("binary_to_term", 2),
+    ("bit_size", 1),
+    ("bitstring_to_list", 1),
+    ("byte_size", 1),
+    ("ceil", 1),
+    ("check_old_code", 1),
+    ("check_process_code", 2),
+    ("check_process_code", 3),
+    ("date", 0),
+    ("delete_module", 1),
+    ("demonitor", 1),
+    ("demonitor", 2),
+    ("disconnect_node", 1),
+    ("element", 2),
+    ("erase", 0),
+    ("erase", 1),
+    ("error", 1),
+    ("error", 2),
+    ("error", 3),
+    ("exit", 1),
+    ("exit", 2),
+    ("float", 1),
+    ("float_to_binary", 1),
+    ("float_to_binary", 2),
+    ("float_to_list", 1),
+    ("float_to_list", 2),
+    ("floor", 1),
+    ("garbage_collect", 0),
+    ("garbage_collect", 1),
+    ("garbage_collect", 2),
+    ("get", 0),
+    ("get", 1),
+    ("get_keys", 0),
+    ("get_keys", 1),
+    ("group_leader", 0),
+    ("group_leader", 2),
+    ("halt", 0),
+    ("halt", 1),
+    ("halt", 2),
+    ("hd", 1),
+    ("integer_to_binary", 1),
+    ("integer_to_binary", 2),
+    ("integer_to_list", 1),
+    ("integer_to_list", 2),
+    ("iolist_size", 1),
+    ("iolist_to_binary", 1),
+    ("is_alive", 0),
+    ("is_atom", 1),
+    ("is_binary", 1),
+    ("is_bitstring", 1),
+    ("is_boolean", 1),
+    ("is_float", 1),
+    ("is_function", 1),
+    ("is_function", 2),
+    ("is_integer", 1),
+    ("is_list", 1),
+    ("is_map", 1),
+    ("is_map_key", 2),
+    ("is_number", 1),
+    ("is_pid", 1),
+    ("is_port", 1),
+    ("is_process_alive", 1),
+    ("is_record", 2),
+    ("is_record", 3),
+    ("is_reference", 1),
+    ("is_tuple", 1),
+    ("length", 1),
+    ("link", 1),
+    ("link", 2),
+    ("list_to_atom", 1),
+    ("list_to_binary", 1),
+    ("list_to_bitstring", 1),
+    ("list_to_existing_atom", 1),
+    ("list_to_float", 1),
+    ("list_to_integer", 1),
+    ("list_to_integer", 2),
+    ("list_to_pid", 1),
+    ("list_to_port", 1),
+    ("list_to_ref", 1),
+    ("list_to_tuple", 1),
+    ("load_module", 2),
+    ("make_ref", 0),
+    ("map_get", 2),
+    ("map_size", 1),
+    ("max", 2),
+    ("min", 2),
+    ("module_loaded", 1),
+    ("monitor", 2),
+    ("monitor", 3),
+    ("monitor_node", 2),
+    ("node", 0),
+    ("node", 1),
+    ("nodes", 0),
+    ("nodes", 1),
+    ("nodes", 2),
+    ("now", 0),
+    ("open_port", 2),
+    ("pid_to_list", 1),
+    ("port_close", 1),
+    ("port_command", 2),
+    ("port_command", 3),
+    ("port_connect", 2),
+    ("port_control", 3),
+    ("port_to_list", 1),
+    ("pre_loaded", 0),
+    ("process_flag", 2),
+    ("process_flag", 3),
+    ("process_info", 1),
+    ("process_info", 2),
+    ("processes", 0),
+    ("purge_module", 1),
+    ("put", 2),
+    ("ref_to_list", 1),
+    ("register", 2),
+    ("registered", 0),
+    ("round", 1),
+    ("self", 0),
+    ("setelement", 3),
+    ("size", 1),
+    ("spawn", 1),
+    ("spawn", 2),
+    ("spawn", 3),
+    ("spawn", 4),
+    ("spawn_link", 1),
+    ("spawn_link", 2),
+    ("spawn_link", 3),
+    ("spawn_link", 4),
+    ("spawn_monitor", 1),
+    ("spawn_monitor", 2),
+    ("spawn_monitor", 3),
+    ("spawn_monitor", 4),
+    ("spawn_opt", 2),
+    ("spawn_opt", 3),
+    ("spawn_opt", 4),
+    ("spawn_opt", 5),
+    ("spawn_request", 1),
+    ("spawn_request", 2),
+    ("spawn_request", 3),
+    ("spawn_request", 4),
+    ("spawn_request", 5),
+    ("spawn_request_abandon", 1),
+    ("split_binary", 2),
+    ("statistics", 1),
+    ("term_to_binary", 1),
+    ("term_to_binary", 2),
+    ("term_to_iovec", 1),
+    ("term_to_iovec", 2),
+    ("throw", 1),
+    ("time", 0),
+    ("tl", 1),
+    ("trunc", 1),
+    ("tuple_size", 1),
+    ("tuple_to_list", 1),
+    ("unalias", 1),
+    ("unlink", 1),
+    ("unregister", 1),
+    ("whereis", 1),
+];
@@ -0,0 +1,101 @@
+//! Property-based tests for comment attachment: every comment in a
+//! source with at least one node is attached to exactly one node, and
+//! the per-node accessors agree with `SyntaxTree::comment_attachment`.
+
+#[expect(dead_code, reason = "shared harness; this binary uses only a subset")]
+mod pbt_harness;
+
+/// Inserts comments, some on their own line, at token boundaries of
+/// `source`.
+fn insert_comments(ctx: &mut noprop::TestCaseContext, source: &str) -> String {
+    let Some(tokens) = pbt_harness::scan_all(source) else {
+        return source.to_string();
+    };
+    let mut cuts: Vec<usize> = tokens.iter().map(|t| t.start().offset()).collect();
+    cuts.push(source.len());
+    let n = noprop::sample_usize_in(ctx, 1..=4);
+    let mut picks: Vec<(usize, &str)> = (0..n)
+        .map(|_| {
+            let comment = noprop::sample_choice(ctx, &[" % same line\n", "\n%% own line\n"]);
+            (noprop::sample_choice(ctx, &cuts), comment)
+        })
+        .collect();
+    picks.sort_by_key(|(at, _)| *at);
+    let mut out = String::with_capacity(source.len() + 64);
+    let mut last = 0;
+    for (at, comment) in picks {
+        out.push_str(&source[last..at]);
+        out.push_str(comment);
+        last = at;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// For every mode, each comment is attached to exactly one node, and
+/// collecting the leading, trailing, and dangling comments of every
+/// node yields each comment once with the same placement.
+#[test]
+fn every_comment_is_attached_once() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let placements = pbt_harness::LabelSet::new();
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let mode = noprop::sample_choice(ctx, pbt_harness::ALL_MODES);
+        let base = pbt_harness::sample_source_for_mode(ctx, mode);
+        let source = insert_comments(ctx, &base);
+        let Some(tokens) = pbt_harness::scan_all(&source) else {
+            return Ok(());
+        };
+        let tree = pbt_harness::parse_full(mode, &tokens);
+        let has_nodes = tree.roots().next().is_some();
+
+        let mut expected = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let at = erl_parse::TokenIndex::new(i);
+            let attachment = tree.comment_attachment(at);
+            if token.kind() != erl_tokenize::TokenKind::Comment || !has_nodes {
+                assert!(
+                    attachment.is_none(),
+                    "token {i} attached, source {source:?}"
+                );
+                continue;
+            }
+            let (node, placement) =
+                attachment.unwrap_or_else(|| panic!("comment {i} not attached, source {source:?}"));
+            expected.push((i, node.node_id(), placement));
+            placements.insert(match placement {
+                erl_parse::CommentPlacement::Leading => "leading",
+                erl_parse::CommentPlacement::Trailing => "trailing",
+                erl_parse::CommentPlacement::Dangling => "dangling",
+            });
+        }
+
+        let mut found = Vec::new();
+        for node in pbt_harness::all_views(&tree) {
+            let id = node.node_id();
+            found.extend(
+                node.leading_comments()
+                    .map(|(i, _)| (i.get(), id, erl_parse::CommentPlacement::Leading)),
+            );
+            found.extend(
+                node.trailing_comments()
+                    .map(|(i, _)| (i.get(), id, erl_parse::CommentPlacement::Trailing)),
+            );
+            found.extend(
+                node.dangling_comments()
+                    .map(|(i, _)| (i.get(), id, erl_parse::CommentPlacement::Dangling)),
+            );
+        }
+        found.sort_by_key(|&(i, _, _)| i);
+        assert_eq!(found, expected, "source {source:?}");
+        Ok(())
+    })?;
+    for label in ["leading", "trailing"] {
+        assert!(
+            placements.contains(label),
+            "no comment was {label}\n{runner}"
+        );
+    }
+    Ok(())
+}
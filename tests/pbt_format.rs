@@ -0,0 +1,255 @@
+//! Property-based tests for `erl_parse::format::format`: the output
+//! scans to the same lexical tokens as the input, and formatting the
+//! output again returns it unchanged — at any line width, with
+//! comments anywhere, and for inputs that go through recovery.
+
+#[expect(dead_code, reason = "shared harness; this binary uses only a subset")]
+mod pbt_harness;
+
+/// Draws a function body expression: the harness's expressions,
+/// wrapped now and then in a block, a fun, or a call so clause and
+/// block layout get exercised.
+fn sample_body_expression(ctx: &mut noprop::TestCaseContext, depth: usize) -> String {
+    let inner = pbt_harness::sample_expression(ctx, depth);
+    if depth == 0 {
+        return inner;
+    }
+    let other = sample_body_expression(ctx, depth - 1);
+    match noprop::sample_weighted_index(ctx, &[3, 1, 1, 1, 1, 1, 1]) {
+        0 => inner,
+        1 => format!("case {inner} of {{ok, X}} -> {other}; _ -> error end"),
+        2 => format!("fun(X) -> {inner}, {other} end"),
+        3 => format!("begin {inner}, {other} end"),
+        4 => format!("receive X when X > {inner} -> {other} after 10 -> ok end"),
+        5 => format!("try {inner} of X -> X catch error:R -> {other} end"),
+        _ => format!("lists:map({inner}, {other})"),
+    }
+}
+
+/// Draws a module: attributes and function declarations with several
+/// clauses and comma-separated bodies.
+fn sample_module(ctx: &mut noprop::TestCaseContext) -> String {
+    let n = noprop::sample_usize_in(ctx, 0..=3);
+    let mut out = String::new();
+    for _ in 0..n {
+        if noprop::sample_bool(ctx) {
+            out.push_str(&pbt_harness::sample_module_source(ctx));
+            continue;
+        }
+        let name = pbt_harness::sample_atom_name(ctx);
+        let clauses = noprop::sample_usize_in(ctx, 1..=2);
+        let mut parts = Vec::new();
+        for _ in 0..clauses {
+            let arg = pbt_harness::sample_var_name(ctx);
+            let guard = if noprop::sample_bool(ctx) {
+                format!(" when {arg} > 0; {arg} < -1")
+            } else {
+                String::new()
+            };
+            let body: Vec<_> = (0..noprop::sample_usize_in(ctx, 1..=2))
+                .map(|_| sample_body_expression(ctx, 2))
+                .collect();
+            parts.push(format!("{name}({arg}){guard} -> {}", body.join(", ")));
+        }
+        out.push_str(&parts.join(";\n"));
+        out.push_str(".\n");
+    }
+    out
+}
+
+fn sample_noise(ctx: &mut noprop::TestCaseContext) -> &'static str {
+    noprop::sample_choice(
+        ctx,
+        &[
+            " % comment\n",
+            "\n%% own line\n",
+            "\n\n",
+            "\n\n% after blank\n",
+            ")",
+            ",",
+        ],
+    )
+}
+
+/// Inserts noise fragments at token boundaries of `source`.
+fn insert_noise(ctx: &mut noprop::TestCaseContext, source: &str) -> String {
+    let Some(tokens) = pbt_harness::scan_all(source) else {
+        return source.to_string();
+    };
+    let mut cuts: Vec<usize> = tokens.iter().map(|t| t.start().offset()).collect();
+    cuts.push(source.len());
+    let n = noprop::sample_usize_in(ctx, 0..=3);
+    let mut picks: Vec<(usize, &str)> = (0..n)
+        .map(|_| (noprop::sample_choice(ctx, &cuts), sample_noise(ctx)))
+        .collect();
+    picks.sort_by_key(|(at, _)| *at);
+    let mut out = String::with_capacity(source.len() + 32);
+    let mut last = 0;
+    for (at, noise) in picks {
+        out.push_str(&source[last..at]);
+        out.push_str(noise);
+        last = at;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+fn comment_texts(source: &str) -> Vec<String> {
+    pbt_harness::scan_all(source)
+        .expect("formatted output scans")
+        .into_iter()
+        .filter(|t| t.kind() == erl_tokenize::TokenKind::Comment)
+        .map(|t| t.text(source).to_string())
+        .collect()
+}
+
+fn lexical_texts(source: &str) -> Vec<String> {
+    pbt_harness::scan_all(source)
+        .expect("formatted output scans")
+        .into_iter()
+        .filter(|t| t.kind().is_lexical())
+        .map(|t| t.text(source).to_string())
+        .collect()
+}
+
+fn format(mode: erl_parse::ParseMode, source: &str, options: erl_parse::format::Options) -> String {
+    let tokens = pbt_harness::scan_all(source).expect("source scans");
+    let tree = pbt_harness::parse_full(mode, &tokens);
+    erl_parse::format::format(&tree, source, options)
+}
+
+/// For every mode and line width, formatting keeps the lexical tokens
+/// and is idempotent.
+#[test]
+fn format_keeps_tokens_and_is_idempotent() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let saw_break = pbt_harness::Flag::new();
+    let saw_comment = pbt_harness::Flag::new();
+    let saw_blank = pbt_harness::Flag::new();
+    let saw_recovery = pbt_harness::Flag::new();
+    let modes = pbt_harness::LabelSet::new();
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let mode = noprop::sample_choice(ctx, pbt_harness::ALL_MODES);
+        let base = match mode {
+            erl_parse::ParseMode::Module if noprop::sample_bool(ctx) => sample_module(ctx),
+            _ => pbt_harness::sample_source_for_mode(ctx, mode),
+        };
+        let source = insert_noise(ctx, &base);
+        let Some(tokens) = pbt_harness::scan_all(&source) else {
+            return Ok(());
+        };
+        let width = noprop::sample_usize_in(ctx, 8..=80);
+        let options = erl_parse::format::Options::new().line_width(width);
+
+        let formatted = format(mode, &source, options);
+        assert_eq!(
+            lexical_texts(&formatted),
+            lexical_texts(&source),
+            "mode {mode:?}, width {width}, source {source:?}, formatted {formatted:?}"
+        );
+        let again = format(mode, &formatted, options);
+        assert_eq!(
+            again, formatted,
+            "mode {mode:?}, width {width}, source {source:?}"
+        );
+
+        modes.insert(pbt_harness::mode_label(mode));
+        if formatted.lines().count() > source.lines().count() {
+            saw_break.set();
+        }
+        if formatted.contains('%') {
+            saw_comment.set();
+        }
+        if formatted.contains("\n\n") {
+            saw_blank.set();
+        }
+        if !pbt_harness::parse_full(mode, &tokens)
+            .diagnostics()
+            .is_empty()
+        {
+            saw_recovery.set();
+        }
+        Ok(())
+    })?;
+    assert!(saw_break.hit(), "no case broke a line\n{runner}");
+    assert!(saw_comment.hit(), "no case kept a comment\n{runner}");
+    assert!(saw_blank.hit(), "no case kept a blank line\n{runner}");
+    assert!(
+        saw_recovery.hit(),
+        "no case went through recovery\n{runner}"
+    );
+    assert_eq!(
+        modes.len(),
+        pbt_harness::ALL_MODES.len(),
+        "not every mode was exercised\n{runner}"
+    );
+    Ok(())
+}
+
+/// Range formatting returns sorted, disjoint edits inside the selected
+/// bytes, and applying them keeps the lexical tokens and comments.
+#[test]
+fn format_range_edits_stay_in_the_selection() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let saw_edit = pbt_harness::Flag::new();
+    let saw_refusal = pbt_harness::Flag::new();
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let mode = noprop::sample_choice(ctx, pbt_harness::ALL_MODES);
+        let base = match mode {
+            erl_parse::ParseMode::Module if noprop::sample_bool(ctx) => sample_module(ctx),
+            _ => pbt_harness::sample_source_for_mode(ctx, mode),
+        };
+        let source = insert_noise(ctx, &base);
+        let Some(tokens) = pbt_harness::scan_all(&source) else {
+            return Ok(());
+        };
+        let tree = pbt_harness::parse_full(mode, &tokens);
+        let a = noprop::sample_usize_in(ctx, 0..=tokens.len());
+        let b = noprop::sample_usize_in(ctx, 0..=tokens.len());
+        let selection = erl_parse::TokenRange::new(
+            erl_parse::TokenIndex::new(a.min(b)),
+            erl_parse::TokenIndex::new(a.max(b)),
+        );
+        let width = noprop::sample_usize_in(ctx, 8..=80);
+        let options = erl_parse::format::Options::new().line_width(width);
+
+        let Some(edits) = erl_parse::format::format_range(&tree, &source, selection, options)
+        else {
+            saw_refusal.set();
+            return Ok(());
+        };
+        let index = erl_parse::LineIndex::new(&tree, &source);
+        let bytes = index.byte_range(selection);
+        let mut out = source.clone();
+        let mut end = bytes.start;
+        for (range, _) in &edits {
+            assert!(
+                end <= range.start && range.end <= bytes.end,
+                "edit {range:?} outside {bytes:?} or out of order, source {source:?}"
+            );
+            end = range.end;
+        }
+        for (range, text) in edits.iter().rev() {
+            out.replace_range(range.clone(), text);
+        }
+        if !edits.is_empty() {
+            saw_edit.set();
+        }
+        assert_eq!(
+            lexical_texts(&out),
+            lexical_texts(&source),
+            "source {source:?}, edited {out:?}"
+        );
+        assert_eq!(
+            comment_texts(&out),
+            comment_texts(&source),
+            "source {source:?}, edited {out:?}"
+        );
+        Ok(())
+    })?;
+    assert!(saw_edit.hit(), "no case produced an edit\n{runner}");
+    assert!(saw_refusal.hit(), "no case was refused\n{runner}");
+    Ok(())
+}
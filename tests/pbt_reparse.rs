@@ -0,0 +1,149 @@
+//! Property-based tests for `erl_parse::SyntaxTree::reparse`: after a
+//! random text edit, the incrementally reparsed tree equals a full
+//! parse of the edited source, node for node and diagnostic for
+//! diagnostic.
+
+#[expect(dead_code, reason = "shared harness; this binary uses only a subset")]
+mod pbt_harness;
+
+/// Draws a source with several `.`-terminated units for `mode`, so
+/// edits have units on both sides to reuse.
+fn sample_multi_unit_source(
+    ctx: &mut noprop::TestCaseContext,
+    mode: erl_parse::ParseMode,
+) -> String {
+    match mode {
+        erl_parse::ParseMode::Module | erl_parse::ParseMode::TermList => {
+            pbt_harness::sample_source_for_mode(ctx, mode)
+        }
+        _ => {
+            let n = noprop::sample_usize_in(ctx, 1..=3);
+            let mut out = String::new();
+            for _ in 0..n {
+                out.push_str(&pbt_harness::sample_source_for_mode(ctx, mode));
+                out.push('\n');
+            }
+            out
+        }
+    }
+}
+
+/// Draws the replacement text for an edit: unit terminators and
+/// delimiters (which move unit boundaries), plain leaves, whole
+/// expressions, or nothing (a deletion).
+fn sample_fragment(ctx: &mut noprop::TestCaseContext) -> String {
+    match noprop::sample_weighted_index(ctx, &[2, 2, 2, 2, 1, 1]) {
+        0 => String::new(),
+        1 => noprop::sample_choice(ctx, &[".", ". ", "(", ")", ",", "->", "-", "#"]).to_string(),
+        2 => pbt_harness::sample_leaf(ctx),
+        3 => pbt_harness::sample_expression(ctx, 1),
+        4 => " % note\n".to_string(),
+        _ => format!("\n{}.\n", pbt_harness::sample_expression(ctx, 1)),
+    }
+}
+
+/// Replaces the source text covered by tokens `start..end` (or the
+/// gap before token `start` when the span is empty) with `fragment`.
+fn splice(
+    source: &str,
+    tokens: &[erl_tokenize::Token],
+    start: usize,
+    end: usize,
+    fragment: &str,
+) -> String {
+    let offset = |i: usize| tokens.get(i).map_or(source.len(), |t| t.start().offset());
+    let from = offset(start);
+    let to = if end > start {
+        tokens[end - 1].end().offset()
+    } else {
+        from
+    };
+    format!("{}{fragment}{}", &source[..from], &source[to..])
+}
+
+/// For every mode, a random token-span replacement reparsed with
+/// `reparse` yields the same tokens, preorder `(NodeId, kind, range)`
+/// sequence, and diagnostics as `parse_full` on the edited tokens;
+/// the reported changed ids are roots of the new tree.
+#[test]
+fn reparse_matches_full_parse() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let reused_prefix = pbt_harness::Counter::new();
+    let reused_suffix = pbt_harness::Counter::new();
+    let moved_boundary = pbt_harness::Flag::new();
+    let modes = pbt_harness::LabelSet::new();
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let mode = noprop::sample_choice(ctx, pbt_harness::ALL_MODES);
+        let old_src = sample_multi_unit_source(ctx, mode);
+        let Some(old_tokens) = pbt_harness::scan_all(&old_src) else {
+            return Ok(());
+        };
+        let start = noprop::sample_usize_in(ctx, 0..=old_tokens.len());
+        let end = noprop::sample_usize_in(ctx, start..=old_tokens.len().min(start + 4));
+        let fragment = sample_fragment(ctx);
+        let new_src = splice(&old_src, &old_tokens, start, end, &fragment);
+        let Some(new_tokens) = pbt_harness::scan_all(&new_src) else {
+            return Ok(());
+        };
+
+        let old_tree = pbt_harness::parse_full(mode, &old_tokens);
+        let edit = erl_parse::TokenEdit::diff(&old_tokens, &old_src, &new_tokens, &new_src);
+        let (tree, changed) = old_tree.reparse(edit, &new_tokens);
+        let full = pbt_harness::parse_full(mode, &new_tokens);
+
+        let context = format!("{old_src:?} -> {new_src:?} ({edit:?})");
+        assert_eq!(tree.mode(), mode, "{context}");
+        pbt_harness::assert_tokens_unchanged(tree.tokens(), full.tokens());
+        let preorder = |t: &erl_parse::SyntaxTree| {
+            pbt_harness::all_views(t)
+                .map(|v| (v.node_id(), v.kind(), v.range()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(preorder(&tree), preorder(&full), "{context}");
+        assert_eq!(tree.diagnostics(), full.diagnostics(), "{context}");
+
+        let roots: Vec<_> = tree.roots().map(|r| r.node_id()).collect();
+        assert!(
+            changed.iter().all(|id| roots.contains(id)),
+            "changed ids {changed:?} are not all roots {roots:?}: {context}"
+        );
+        assert!(
+            changed.windows(2).all(|w| w[0] < w[1]),
+            "changed ids out of order: {context}"
+        );
+
+        modes.insert(pbt_harness::mode_label(mode));
+        let first_changed = roots.iter().position(|id| changed.contains(id));
+        if first_changed.is_some_and(|i| i > 0) {
+            reused_prefix.bump();
+        }
+        if let Some(last) = changed.last()
+            && roots.iter().any(|id| id > last)
+        {
+            reused_suffix.bump();
+        }
+        if old_tree.roots().count() != tree.roots().count() {
+            moved_boundary.set();
+        }
+        Ok(())
+    })?;
+    assert!(
+        reused_prefix.get() > 0,
+        "no case reused a unit before the edit\n{runner}"
+    );
+    assert!(
+        reused_suffix.get() > 0,
+        "no case reused a unit after the edit\n{runner}"
+    );
+    assert!(
+        moved_boundary.hit(),
+        "no edit changed the number of roots\n{runner}"
+    );
+    assert_eq!(
+        modes.len(),
+        pbt_harness::ALL_MODES.len(),
+        "not every mode was exercised\n{runner}"
+    );
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Property-based tests for lossless reconstruction:
+//! `erl_parse::SyntaxTree::to_source` gives back the scanned text byte
+//! for byte, and `erl_parse::NodeView::text` of every node equals the
+//! concatenated text of the tokens in its range — for clean inputs and
+//! for inputs that go through recovery.
+
+#[expect(dead_code, reason = "shared harness; this binary uses only a subset")]
+mod pbt_harness;
+
+/// Draws a fragment that stresses the byte-exact path: comments,
+/// escapes, odd whitespace, and stray delimiters that force recovery.
+fn sample_noise(ctx: &mut noprop::TestCaseContext) -> &'static str {
+    noprop::sample_choice(
+        ctx,
+        &[
+            " % comment\n",
+            "%% doc\r\n",
+            "\t",
+            "  \n\n",
+            "\"a\\n\\\"b\\x{41}\"",
+            "'q\\'a'",
+            "$\\n",
+            "$\\s",
+            "16#fF",
+            "1.5e3",
+            ")",
+            "]",
+            ",",
+            ".",
+            "->",
+            "#",
+        ],
+    )
+}
+
+/// Inserts `n` noise fragments at random byte positions that fall on
+/// token boundaries of `source`.
+fn insert_noise(ctx: &mut noprop::TestCaseContext, source: &str) -> String {
+    let Some(tokens) = pbt_harness::scan_all(source) else {
+        return source.to_string();
+    };
+    let mut cuts: Vec<usize> = tokens.iter().map(|t| t.start().offset()).collect();
+    cuts.push(source.len());
+    let n = noprop::sample_usize_in(ctx, 0..=3);
+    let mut picks: Vec<(usize, &str)> = (0..n)
+        .map(|_| (noprop::sample_choice(ctx, &cuts), sample_noise(ctx)))
+        .collect();
+    picks.sort_by_key(|(at, _)| *at);
+    let mut out = String::with_capacity(source.len() + 32);
+    let mut last = 0;
+    for (at, noise) in picks {
+        out.push_str(&source[last..at]);
+        out.push_str(noise);
+        last = at;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// For every mode, `to_source(parse(s)) == s`, and every node's text is
+/// the concatenation of its range's token texts.
+#[test]
+fn to_source_round_trips_every_input() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let saw_error_node = pbt_harness::Flag::new();
+    let saw_comment = pbt_harness::Flag::new();
+    let saw_escape = pbt_harness::Flag::new();
+    let modes = pbt_harness::LabelSet::new();
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let mode = noprop::sample_choice(ctx, pbt_harness::ALL_MODES);
+        let base = pbt_harness::sample_source_for_mode(ctx, mode);
+        let source = insert_noise(ctx, &base);
+        let Some(tokens) = pbt_harness::scan_all(&source) else {
+            return Ok(());
+        };
+        let tree = pbt_harness::parse_full(mode, &tokens);
+
+        assert_eq!(tree.to_source(&source), source, "mode {mode:?}");
+        for node in pbt_harness::all_views(&tree) {
+            let joined: String = node
+                .tokens_in_range()
+                .map(|(_, t)| t.text(&source))
+                .collect();
+            assert_eq!(
+                node.text(&source),
+                joined,
+                "{:?} in {source:?}",
+                node.kind()
+            );
+        }
+
+        modes.insert(pbt_harness::mode_label(mode));
+        if pbt_harness::all_views(&tree).any(|v| v.kind() == erl_parse::SyntaxKind::Error) {
+            saw_error_node.set();
+        }
+        if tokens
+            .iter()
+            .any(|t| t.kind() == erl_tokenize::TokenKind::Comment)
+        {
+            saw_comment.set();
+        }
+        if source.contains('\\') {
+            saw_escape.set();
+        }
+        Ok(())
+    })?;
+    assert!(
+        saw_error_node.hit(),
+        "no case produced an Error node\n{runner}"
+    );
+    assert!(saw_comment.hit(), "no case contained a comment\n{runner}");
+    assert!(saw_escape.hit(), "no case contained an escape\n{runner}");
+    assert_eq!(
+        modes.len(),
+        pbt_harness::ALL_MODES.len(),
+        "not every mode was exercised\n{runner}"
+    );
+    Ok(())
+}
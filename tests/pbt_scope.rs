@@ -0,0 +1,82 @@
+//! Property-based tests for `erl_parse::scope`: every variable
+//! expression in an analysed tree is recorded, and the per-variable
+//! binding and use lists agree with the per-token occurrences.
+
+#[expect(dead_code, reason = "shared harness; this binary uses only a subset")]
+mod pbt_harness;
+
+/// Wraps a sampled expression in function clauses that bind, match,
+/// and shadow variables.
+fn sample_function(ctx: &mut noprop::TestCaseContext) -> String {
+    let body = pbt_harness::sample_expression(ctx, pbt_harness::MAX_GEN_DEPTH);
+    let other = pbt_harness::sample_expression(ctx, 1);
+    let wrapped = match noprop::sample_weighted_index(ctx, &[2, 1, 1, 1, 1, 1]) {
+        0 => body,
+        1 => format!("case {body} of {{X, Y}} -> Y = {other}; _ -> Z = X end, {{Y, Z}}"),
+        2 => format!("F = fun(X) -> {body} end, F(X)"),
+        3 => format!("[{body} || X <- {other}, Y <- X]"),
+        4 => format!("try X = {body} of Y -> Y catch _:R -> X end, R"),
+        _ => format!("X = {body}, {other}"),
+    };
+    format!("f(X, _) -> {wrapped}.\nf(_, Y) -> Y.\n")
+}
+
+/// Every `VarExpr` inside a function (Module mode) or a root
+/// (Expression mode) other than `_` has an occurrence, and each
+/// occurrence is listed once, under its role, by its variable.
+#[test]
+fn every_variable_expression_is_recorded() -> noprop::TestResult {
+    let seed = noprop::seed_from_env_or_time(pbt_harness::SEED_ENV)?;
+    let mut runner = noprop::Runner::new(seed);
+    runner.run(pbt_harness::CASES, |ctx| {
+        let (mode, source) = if noprop::sample_bool(ctx) {
+            (erl_parse::ParseMode::Module, sample_function(ctx))
+        } else {
+            let mode = erl_parse::ParseMode::Expression;
+            (mode, pbt_harness::sample_source_for_mode(ctx, mode))
+        };
+        let Some(tokens) = pbt_harness::scan_all(&source) else {
+            return Ok(());
+        };
+        let tree = pbt_harness::parse_full(mode, &tokens);
+        let scopes = erl_parse::scope::Scopes::new(&tree, &source);
+
+        for node in pbt_harness::all_views(&tree) {
+            let analysed = node
+                .ancestors()
+                .chain(std::iter::once(node))
+                .next()
+                .is_some_and(|root| {
+                    mode == erl_parse::ParseMode::Expression
+                        || root.kind() == erl_parse::SyntaxKind::FunctionDecl
+                });
+            if node.kind() != erl_parse::SyntaxKind::VarExpr || !analysed {
+                continue;
+            }
+            let Some((at, token)) = node
+                .tokens_in_range()
+                .find(|(_, t)| t.kind() == erl_tokenize::TokenKind::Variable)
+            else {
+                continue;
+            };
+            assert!(
+                token.text(&source) == "_" || scopes.occurrence(at).is_some(),
+                "variable {} unrecorded, source {source:?}",
+                at.get()
+            );
+        }
+
+        for &(at, occurrence) in scopes.occurrences() {
+            let variable = scopes.variable(occurrence.variable());
+            assert_eq!(variable.name(), tokens[at.get()].text(&source));
+            let (listed, other) = match occurrence.role() {
+                erl_parse::scope::Role::Binding => (variable.bindings(), variable.uses()),
+                erl_parse::scope::Role::Use => (variable.uses(), variable.bindings()),
+            };
+            assert_eq!(listed.iter().filter(|&&i| i == at).count(), 1);
+            assert!(!other.contains(&at), "source {source:?}");
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
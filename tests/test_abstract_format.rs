@@ -0,0 +1,285 @@
+//! Integration tests for `erl_parse::abstract_format`: conversion to
+//! OTP's abstract format and `~p`-compatible term printing. Expected
+//! texts are what `io:format("~p", [Term])` prints for the terms
+//! `erl_parse` builds from the same source.
+
+use erl_parse::abstract_format::{self, Integer, Location, Term};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(mode: erl_parse::ParseMode, source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+/// `~p` text of every form in `source`, one per entry.
+fn forms(source: &str, location: Location) -> Vec<Option<String>> {
+    let tree = parse(erl_parse::ParseMode::Module, source);
+    tree.roots()
+        .map(|r| abstract_format::form(&tree, source, r, location).map(|t| t.pretty()))
+        .collect()
+}
+
+fn form(source: &str) -> String {
+    let mut all = forms(source, Location::Line);
+    assert_eq!(all.len(), 1, "{source}");
+    all.remove(0)
+        .unwrap_or_else(|| panic!("no abstract form for {source}"))
+}
+
+fn expr(source: &str) -> Option<String> {
+    let tree = parse(erl_parse::ParseMode::Expression, source);
+    let root = tree.roots().next().expect("expression root");
+    abstract_format::expr(&tree, source, root, Location::Line).map(|t| t.pretty())
+}
+
+fn ty(source: &str) -> String {
+    let tree = parse(erl_parse::ParseMode::Type, source);
+    let root = tree.roots().next().expect("type root");
+    abstract_format::ty(&tree, source, root, Location::Line)
+        .unwrap_or_else(|| panic!("no abstract form for {source}"))
+        .pretty()
+}
+
+#[test]
+fn function_breaks_like_io_lib_pretty() {
+    assert_eq!(
+        form("f(X) -> X + 1."),
+        "{function,1,f,1,\n          [{clause,1,\n                   [{var,1,'X'}],\n                   [],\n                   [{op,1,'+',{var,1,'X'},{integer,1,1}}]}]}",
+    );
+    assert_eq!(
+        form("f(X) when X > 0; is_atom(X) -> ok."),
+        "{function,1,f,1,\n          [{clause,1,\n                   [{var,1,'X'}],\n                   [[{op,1,'>',{var,1,'X'},{integer,1,0}}],\n                    [{call,1,{atom,1,is_atom},[{var,1,'X'}]}]],\n                   [{atom,1,ok}]}]}",
+    );
+}
+
+#[test]
+fn annotations_follow_location() {
+    let source = "-module(m).\nf() ->\n  ok.";
+    assert_eq!(
+        forms(source, Location::LineColumn),
+        [
+            Some("{attribute,{1,2},module,m}".to_string()),
+            Some("{function,{2,1},f,0,[{clause,{2,1},[],[],[{atom,{3,3},ok}]}]}".to_string()),
+        ],
+    );
+    assert_eq!(
+        forms(source, Location::Line),
+        [
+            Some("{attribute,1,module,m}".to_string()),
+            Some("{function,2,f,0,[{clause,2,[],[],[{atom,3,ok}]}]}".to_string()),
+        ],
+    );
+}
+
+#[test]
+fn expressions_use_operator_and_first_token_annotations() {
+    let cases = [
+        (
+            "[1, 2 | T]",
+            "{cons,1,{integer,1,1},{cons,1,{integer,1,2},{var,1,'T'}}}",
+        ),
+        ("[a]", "{cons,1,{atom,1,a},{nil,1}}"),
+        ("\"a\" \"b\"", "{string,1,\"ab\"}"),
+        ("$a", "{char,1,97}"),
+        ("16#ff", "{integer,1,255}"),
+        (
+            "m:f(a)",
+            "{call,1,{remote,1,{atom,1,m},{atom,1,f}},[{atom,1,a}]}",
+        ),
+        (
+            "A = B ! c",
+            "{match,1,{var,1,'A'},{op,1,'!',{var,1,'B'},{atom,1,c}}}",
+        ),
+        ("- X", "{op,1,'-',{var,1,'X'}}"),
+        ("a andalso b", "{op,1,'andalso',{atom,1,a},{atom,1,b}}"),
+        ("fun f/1", "{'fun',1,{function,f,1}}"),
+        (
+            "fun m:f/A",
+            "{'fun',1,{function,{atom,1,m},{atom,1,f},{var,1,'A'}}}",
+        ),
+        ("catch x", "{'catch',1,{atom,1,x}}"),
+        ("begin a end", "{block,1,[{atom,1,a}]}"),
+        ("#r.b", "{record_index,1,r,{atom,1,b}}"),
+        ("R#r.a", "{record_field,1,{var,1,'R'},r,{atom,1,a}}"),
+        (
+            "#{a => 1, b := 2}",
+            "{map,1,\n     [{map_field_assoc,1,{atom,1,a},{integer,1,1}},\n      {map_field_exact,1,{atom,1,b},{integer,1,2}}]}",
+        ),
+        (
+            "<<X:8/integer-unit:1>>",
+            "{bin,1,[{bin_element,1,{var,1,'X'},{integer,1,8},[integer,{unit,1}]}]}",
+        ),
+        (
+            "~\"x\"",
+            "{bin,1,[{bin_element,1,{string,1,\"x\"},default,[utf8]}]}",
+        ),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(expr(source).as_deref(), Some(expected), "{source}");
+    }
+}
+
+#[test]
+fn try_catch_clauses_build_class_triples() {
+    assert_eq!(
+        expr("try a catch error:x:S -> b; y -> c end").as_deref(),
+        Some(
+            "{'try',1,\n       [{atom,1,a}],\n       [],\n       [{clause,1,\n                [{tuple,1,[{atom,1,error},{atom,1,x},{var,1,'S'}]}],\n                [],\n                [{atom,1,b}]},\n        {clause,1,\n                [{tuple,1,[{atom,1,throw},{atom,1,y},{var,1,'_'}]}],\n                [],\n                [{atom,1,c}]}],\n       []}"
+        ),
+    );
+}
+
+#[test]
+fn comprehensions_and_zip_generators() {
+    assert_eq!(
+        expr("[X || X <- L && Y <:- M, X > Y]").as_deref(),
+        Some(
+            "{lc,1,\n    {var,1,'X'},\n    [{zip,1,\n          [{generate,1,{var,1,'X'},{var,1,'L'}},\n           {generate_strict,1,{var,1,'Y'},{var,1,'M'}}]},\n     {op,1,'>',{var,1,'X'},{var,1,'Y'}}]}"
+        ),
+    );
+    assert_eq!(
+        expr("#{K => V || K := V <- M}").as_deref(),
+        Some(
+            "{mc,1,\n    {map_field_assoc,1,{var,1,'K'},{var,1,'V'}},\n    [{m_generate,1,{map_field_exact,1,{var,1,'K'},{var,1,'V'}},{var,1,'M'}}]}"
+        ),
+    );
+}
+
+#[test]
+fn constructs_without_an_abstract_form_are_none() {
+    assert_eq!(expr("#m:r{a = 1}"), None);
+    assert_eq!(expr("#{K := V || K := V <- M}"), None);
+    assert_eq!(expr("fun F(X) -> X; G(Y) -> Y end"), None);
+    assert_eq!(forms("-define(X, 1).", Location::Line), [None]);
+    assert_eq!(forms("f() -> .", Location::Line), [None]);
+    assert_eq!(forms("f(X) -> X; f() -> ok.", Location::Line), [None]);
+}
+
+#[test]
+fn types_distinguish_builtin_and_user_types() {
+    assert_eq!(ty("tuple()"), "{type,1,tuple,any}");
+    assert_eq!(ty("foo(atom())"), "{user_type,1,foo,[{type,1,atom,[]}]}");
+    assert_eq!(
+        ty("m:t(x)"),
+        "{remote_type,1,[{atom,1,m},{atom,1,t},[{atom,1,x}]]}"
+    );
+    assert_eq!(ty("<<>>"), "{type,1,binary,[{integer,1,0},{integer,1,0}]}");
+    assert_eq!(ty("[a, ...]"), "{type,1,nonempty_list,[{atom,1,a}]}");
+    assert_eq!(
+        ty("a | (b | c)"),
+        "{type,1,union,[{atom,1,a},{atom,1,b},{atom,1,c}]}"
+    );
+    assert_eq!(
+        ty("fun((...) -> ok)"),
+        "{type,1,'fun',[{type,1,any},{atom,1,ok}]}"
+    );
+}
+
+#[test]
+fn attributes_follow_build_attribute() {
+    assert_eq!(
+        form("-export([f/1, g/0])."),
+        "{attribute,1,export,[{f,1},{g,0}]}"
+    );
+    assert_eq!(
+        form("-import(lists, [map/2])."),
+        "{attribute,1,import,{lists,[{map,2}]}}"
+    );
+    assert_eq!(form("-vsn(\"1.0\")."), "{attribute,1,vsn,\"1.0\"}");
+    assert_eq!(form("-foo({a, b/2})."), "{attribute,1,foo,{a,{b,2}}}");
+    assert_eq!(form("-compile(-1)."), "{attribute,1,compile,-1}");
+    assert_eq!(
+        form("-type t(A) :: [A]."),
+        "{attribute,1,type,{t,{type,1,list,[{var,1,'A'}]},[{var,1,'A'}]}}"
+    );
+    assert_eq!(
+        form("-record(r, {a = 1 :: integer(), b})."),
+        "{attribute,1,record,\n           {r,[{typed_record_field,{record_field,1,{atom,1,a},{integer,1,1}},\n                                   {type,1,integer,[]}},\n               {record_field,1,{atom,1,b}}]}}",
+    );
+    assert_eq!(
+        form("-spec f(X) -> X when X :: atom()."),
+        "{attribute,1,spec,\n           {{f,1},\n            [{type,1,bounded_fun,\n                   [{type,1,'fun',\n                          [{type,1,product,[{var,1,'X'}]},{var,1,'X'}]},\n                    [{type,1,constraint,\n                           [{atom,1,is_subtype},\n                            [{var,1,'X'},{type,1,atom,[]}]]}]]}]}}",
+    );
+}
+
+#[test]
+fn normalise_evaluates_literals() {
+    let tree = parse(
+        erl_parse::ParseMode::TermList,
+        "{a, [1 | b], -2.5, #{k => <<1, \"xy\", 256:16>>}}.",
+    );
+    let source = "{a, [1 | b], -2.5, #{k => <<1, \"xy\", 256:16>>}}.";
+    let root = tree.roots().next().expect("term root");
+    let abstract_term =
+        abstract_format::expr(&tree, source, root, Location::Line).expect("abstract term");
+    assert_eq!(
+        abstract_format::normalise(&abstract_term).map(|t| t.pretty()),
+        Some("{a,[1|b],-2.5,#{k => <<1,120,121,1,0>>}}".to_string()),
+    );
+}
+
+#[test]
+fn pretty_packs_atomic_list_elements() {
+    assert_eq!(
+        Term::list((1..=40).map(Term::from)).pretty(),
+        "[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,\n 29,30,31,32,33,34,35,36,37,38,39,40]",
+    );
+    assert_eq!(
+        Term::Map(vec![
+            (Term::atom("b"), Term::from(1)),
+            (Term::atom("a"), Term::string("x\ny")),
+        ])
+        .pretty(),
+        "#{a => \"x\\ny\",b => 1}",
+    );
+}
+
+#[test]
+fn write_quotes_atoms_and_prints_short_floats() {
+    let atoms: Vec<_> = ["X", "fun", "hello world", "[]", "ok", "a@b"]
+        .iter()
+        .map(|a| Term::atom(a).to_string())
+        .collect();
+    assert_eq!(
+        atoms,
+        ["'X'", "'fun'", "'hello world'", "'[]'", "ok", "a@b"]
+    );
+    let floats: Vec<_> = [0.1, 1.0e20, 100.0, 2.5e-5, 9007199254740992.0]
+        .iter()
+        .map(|f| Term::Float(*f).to_string())
+        .collect();
+    assert_eq!(
+        floats,
+        ["0.1", "1.0e20", "100.0", "2.5e-5", "9.007199254740992e15"]
+    );
+    assert_eq!(Term::Binary(b"abc".to_vec()).to_string(), "<<\"abc\">>");
+    assert_eq!(Term::Binary(vec![1, 2]).to_string(), "<<1,2>>");
+}
+
+#[test]
+fn integers_are_arbitrary_precision() {
+    let big = Integer::parse_literal("1_180_591_620_717_411_303_424").expect("literal");
+    assert_eq!(big.to_string(), "1180591620717411303424");
+    assert_eq!(big.to_i64(), None);
+    assert_eq!(big.negate().to_string(), "-1180591620717411303424");
+    assert_eq!(
+        Integer::parse_literal("36#Z").map(|n| n.to_i64()),
+        Some(Some(35))
+    );
+    assert_eq!(
+        Term::from(-3).term_cmp(&Term::Float(-3.0)),
+        std::cmp::Ordering::Less
+    );
+}
@@ -0,0 +1,559 @@
+//! Integration tests for the typed `erl_parse::ast` layer. Sources are
+//! drawn from the mode test files; every check goes through the public
+//! accessors so it matches what external consumers see.
+
+use erl_parse::ast::{self, AstNode};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(mode: erl_parse::ParseMode, source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+fn module(source: &str) -> erl_parse::SyntaxTree {
+    parse(erl_parse::ParseMode::Module, source)
+}
+
+fn expr(source: &str) -> erl_parse::SyntaxTree {
+    parse(erl_parse::ParseMode::Expression, source)
+}
+
+fn ty(source: &str) -> erl_parse::SyntaxTree {
+    parse(erl_parse::ParseMode::Type, source)
+}
+
+/// First node of type `T` in preorder across every root.
+fn first<'a, T: AstNode<'a>>(tree: &'a erl_parse::SyntaxTree) -> T {
+    tree.roots()
+        .flat_map(|r| std::iter::once(r).chain(r.descendants()))
+        .find_map(T::cast)
+        .unwrap_or_else(|| panic!("no {:?} node in tree", T::KIND))
+}
+
+fn token_text<'s>(
+    tree: &erl_parse::SyntaxTree,
+    source: &'s str,
+    index: Option<erl_parse::TokenIndex>,
+) -> &'s str {
+    let index = index.expect("token present");
+    tree.tokens()[index.get()].text(source)
+}
+
+/// Lexical token text of `node`, joined with single spaces.
+fn node_text(source: &str, node: Option<ast::Node<'_>>) -> String {
+    node.expect("node present")
+        .syntax()
+        .tokens_in_range()
+        .filter(|(_, t)| !t.kind().is_hidden())
+        .map(|(_, t)| t.text(source))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn node_wraps_every_view_in_its_own_kind() {
+    let sources = [
+        (
+            erl_parse::ParseMode::Module,
+            "abs(X) when X < 0 -> -X; abs(X) -> X.",
+        ),
+        (
+            erl_parse::ParseMode::Module,
+            "-record(user, {name = \"a\", age = 0}).",
+        ),
+        (
+            erl_parse::ParseMode::Expression,
+            "try foo() of X -> X catch error:R -> R after cleanup() end.",
+        ),
+        (
+            erl_parse::ParseMode::Type,
+            "fun((atom(), integer()) -> boolean()).",
+        ),
+        (erl_parse::ParseMode::TermList, "{a, [1, 2], \"s\"}."),
+    ];
+    for (mode, source) in sources {
+        let tree = parse(mode, source);
+        for root in tree.roots() {
+            for view in std::iter::once(root).chain(root.descendants()) {
+                let node = ast::Node::new(view);
+                assert_eq!(node.syntax().node_id(), view.node_id());
+                assert_eq!(node.syntax().kind(), view.kind());
+            }
+        }
+    }
+}
+
+#[test]
+fn cast_rejects_a_view_of_another_kind() {
+    let tree = expr("foo(1, 2).");
+    let root = tree.roots().next().expect("root");
+    assert!(ast::CallExpr::cast(root).is_some());
+    assert!(ast::CaseExpr::cast(root).is_none());
+    assert_eq!(ast::CallExpr::KIND, erl_parse::SyntaxKind::CallExpr);
+}
+
+// -----------------------------------------------------------------
+// Atomic expressions.
+// -----------------------------------------------------------------
+
+#[test]
+fn atomic_literals_expose_their_token() {
+    let source = "{ok, Var, 42, 1.5, $c, ~\"sig\"}.";
+    let tree = expr(source);
+    assert_eq!(
+        token_text(&tree, source, first::<ast::AtomExpr>(&tree).token()),
+        "ok"
+    );
+    assert_eq!(
+        token_text(&tree, source, first::<ast::VarExpr>(&tree).token()),
+        "Var"
+    );
+    assert_eq!(
+        token_text(&tree, source, first::<ast::IntegerExpr>(&tree).token()),
+        "42"
+    );
+    assert_eq!(
+        token_text(&tree, source, first::<ast::FloatExpr>(&tree).token()),
+        "1.5"
+    );
+    assert_eq!(
+        token_text(&tree, source, first::<ast::CharExpr>(&tree).token()),
+        "$c"
+    );
+    assert_eq!(
+        token_text(&tree, source, first::<ast::SigilStringExpr>(&tree).token()),
+        "~\"sig\""
+    );
+}
+
+#[test]
+fn string_expr_lists_every_adjacent_string_token() {
+    let source = "\"a\" \"b\" \"c\".";
+    let tree = expr(source);
+    let string = first::<ast::StringExpr>(&tree);
+    let texts: Vec<_> = string
+        .tokens()
+        .map(|i| token_text(&tree, source, Some(i)))
+        .collect();
+    assert_eq!(texts, ["\"a\"", "\"b\"", "\"c\""]);
+}
+
+// -----------------------------------------------------------------
+// Containers.
+// -----------------------------------------------------------------
+
+#[test]
+fn tuple_list_and_cons_elements() {
+    let source = "{[1, 2], [H, I | T], (x)}.";
+    let tree = expr(source);
+    assert_eq!(first::<ast::TupleExpr>(&tree).elements().count(), 3);
+    assert_eq!(first::<ast::ListExpr>(&tree).elements().count(), 2);
+    let cons = first::<ast::ConsExpr>(&tree);
+    assert_eq!(cons.heads().count(), 2);
+    assert_eq!(node_text(source, cons.tail()), "T");
+    assert_eq!(
+        node_text(source, first::<ast::ParenExpr>(&tree).expr()),
+        "x"
+    );
+}
+
+#[test]
+fn bitstring_element_value_size_and_specifiers() {
+    let source = "<<A:8/integer-unit:1, B/binary>>.";
+    let tree = expr(source);
+    let elements: Vec<_> = first::<ast::BitstringExpr>(&tree).elements().collect();
+    assert_eq!(elements.len(), 2);
+    assert_eq!(node_text(source, elements[0].value()), "A");
+    assert_eq!(node_text(source, elements[0].size()), "8");
+    let specs: Vec<_> = elements[0]
+        .type_specifiers()
+        .map(|i| token_text(&tree, source, Some(i)))
+        .collect();
+    assert_eq!(specs, ["integer", "unit"]);
+    assert!(elements[1].size().is_none());
+    assert_eq!(node_text(source, elements[1].value()), "B");
+}
+
+#[test]
+fn map_and_map_update_fields() {
+    let source = "X#{a := 1, b => 2}.";
+    let tree = expr(source);
+    let update = first::<ast::MapUpdateExpr>(&tree);
+    assert_eq!(node_text(source, update.base()), "X");
+    let fields: Vec<_> = update.fields().collect();
+    assert!(fields[0].is_exact());
+    assert!(!fields[1].is_exact());
+    assert_eq!(node_text(source, fields[1].key()), "b");
+    assert_eq!(node_text(source, fields[1].value()), "2");
+
+    let tree = expr("#{k => v}.");
+    assert_eq!(first::<ast::MapExpr>(&tree).fields().count(), 1);
+}
+
+#[test]
+fn record_forms_expose_names_and_fields() {
+    let source = "{#m:r{a = 1}, X#r{b = 2}, Y#r.f, #r.g}.";
+    let tree = expr(source);
+    let lit = first::<ast::RecordExpr>(&tree);
+    assert_eq!(token_text(&tree, source, lit.module()), "m");
+    assert_eq!(token_text(&tree, source, lit.name()), "r");
+    let field = lit.fields().next().expect("field");
+    assert_eq!(token_text(&tree, source, field.name()), "a");
+    assert_eq!(node_text(source, field.value()), "1");
+
+    let update = first::<ast::RecordUpdateExpr>(&tree);
+    assert_eq!(node_text(source, update.base()), "X");
+    assert!(update.module().is_none());
+    assert_eq!(token_text(&tree, source, update.name()), "r");
+    assert_eq!(update.fields().count(), 1);
+
+    let access = first::<ast::RecordFieldAccessExpr>(&tree);
+    assert_eq!(node_text(source, access.base()), "Y");
+    assert_eq!(token_text(&tree, source, access.name()), "r");
+    assert_eq!(token_text(&tree, source, access.field()), "f");
+
+    let index = first::<ast::RecordIndexExpr>(&tree);
+    assert_eq!(token_text(&tree, source, index.name()), "r");
+    assert_eq!(token_text(&tree, source, index.field()), "g");
+}
+
+// -----------------------------------------------------------------
+// Operations, calls, and remote references.
+// -----------------------------------------------------------------
+
+#[test]
+fn operator_nodes_split_around_their_operator() {
+    let source = "{A + 1, -B, P = 1, Pid ! m}.";
+    let tree = expr(source);
+    let bin = first::<ast::BinaryOpExpr>(&tree);
+    assert_eq!(node_text(source, bin.lhs()), "A");
+    assert_eq!(token_text(&tree, source, bin.operator()), "+");
+    assert_eq!(node_text(source, bin.rhs()), "1");
+    let unary = first::<ast::UnaryOpExpr>(&tree);
+    assert_eq!(token_text(&tree, source, unary.operator()), "-");
+    assert_eq!(node_text(source, unary.operand()), "B");
+    let matched = first::<ast::MatchExpr>(&tree);
+    assert_eq!(node_text(source, matched.pattern()), "P");
+    assert_eq!(node_text(source, matched.expr()), "1");
+    let send = first::<ast::SendExpr>(&tree);
+    assert_eq!(node_text(source, send.target()), "Pid");
+    assert_eq!(node_text(source, send.message()), "m");
+}
+
+#[test]
+fn call_target_arguments_and_remote_parts() {
+    let source = "m:f(1, 2).";
+    let tree = expr(source);
+    let call = first::<ast::CallExpr>(&tree);
+    assert_eq!(node_text(source, call.target()), "m : f");
+    assert_eq!(call.arguments().expect("args").args().count(), 2);
+    let remote = first::<ast::RemoteExpr>(&tree);
+    assert_eq!(node_text(source, remote.module()), "m");
+    assert_eq!(node_text(source, remote.function()), "f");
+}
+
+// -----------------------------------------------------------------
+// Blocks.
+// -----------------------------------------------------------------
+
+#[test]
+fn case_subject_and_clauses() {
+    let source = "case X of y when Y -> z; _ -> w end.";
+    let tree = expr(source);
+    let case = first::<ast::CaseExpr>(&tree);
+    assert_eq!(node_text(source, case.subject()), "X");
+    let clauses: Vec<_> = case.clauses().collect();
+    assert_eq!(clauses.len(), 2);
+    assert_eq!(node_text(source, clauses[0].pattern()), "y");
+    assert!(clauses[0].guard().is_some());
+    assert!(clauses[1].guard().is_none());
+    assert_eq!(clauses[1].body().expect("body").exprs().count(), 1);
+}
+
+#[test]
+fn if_begin_and_catch_blocks() {
+    let source = "{if X -> 1; true -> 2 end, begin a, b end, catch c}.";
+    let tree = expr(source);
+    let clauses: Vec<_> = first::<ast::IfExpr>(&tree).clauses().collect();
+    assert_eq!(clauses.len(), 2);
+    assert_eq!(clauses[0].guard().expect("guard").guards().count(), 1);
+    assert!(clauses[1].body().is_some());
+    let begin = first::<ast::BeginExpr>(&tree);
+    assert_eq!(begin.body().expect("body").exprs().count(), 2);
+    assert_eq!(
+        node_text(source, first::<ast::CatchExpr>(&tree).expr()),
+        "c"
+    );
+}
+
+#[test]
+fn receive_clauses_and_after_section() {
+    let source = "receive msg -> ok after 1000 -> timeout end.";
+    let tree = expr(source);
+    let receive = first::<ast::ReceiveExpr>(&tree);
+    assert_eq!(receive.clauses().count(), 1);
+    let after = receive.after().expect("after section");
+    assert_eq!(node_text(source, after.timeout()), "1000");
+    assert!(after.body().is_some());
+    assert!(
+        expr("receive msg -> ok end.")
+            .roots()
+            .next()
+            .and_then(ast::ReceiveExpr::cast)
+            .expect("receive")
+            .after()
+            .is_none()
+    );
+}
+
+#[test]
+fn try_sections_and_catch_clause_parts() {
+    let source = "try foo() of X -> X catch Y -> Y; error:R:S when R -> R after cleanup() end.";
+    let tree = expr(source);
+    let try_expr = first::<ast::TryExpr>(&tree);
+    assert!(try_expr.body().is_some());
+    assert_eq!(try_expr.of_section().expect("of").clauses().count(), 1);
+    let catch: Vec<_> = try_expr.catch_section().expect("catch").clauses().collect();
+    assert!(matches!(catch[0], ast::Node::Clause(_)));
+    let ast::Node::CatchClause(clause) = catch[1] else {
+        panic!("class-qualified clause: {:?}", catch[1]);
+    };
+    assert_eq!(token_text(&tree, source, clause.class()), "error");
+    assert_eq!(node_text(source, clause.pattern()), "R");
+    assert_eq!(token_text(&tree, source, clause.stacktrace()), "S");
+    assert!(clause.guard().is_some());
+    assert!(clause.body().is_some());
+    assert!(try_expr.after_section().expect("after").body().is_some());
+}
+
+#[test]
+fn maybe_body_and_else_section() {
+    let source = "maybe {ok, X} ?= foo() else Other -> Other end.";
+    let tree = expr(source);
+    let maybe = first::<ast::MaybeExpr>(&tree);
+    let cond = first::<ast::MaybeMatchExpr>(&tree);
+    assert_eq!(node_text(source, cond.pattern()), "{ ok , X }");
+    assert_eq!(node_text(source, cond.expr()), "foo ( )");
+    assert_eq!(maybe.body().expect("body").exprs().count(), 1);
+    assert_eq!(maybe.else_section().expect("else").clauses().count(), 1);
+}
+
+// -----------------------------------------------------------------
+// Funs.
+// -----------------------------------------------------------------
+
+#[test]
+fn fun_expressions_and_references() {
+    let source = "{fun (A) -> A end, fun F(0) -> 1; F(N) -> N end, fun foo/1, fun M:f/A}.";
+    let tree = expr(source);
+    let anon = first::<ast::AnonymousFun>(&tree);
+    let clause = anon.clauses().next().expect("clause");
+    assert_eq!(clause.arguments().expect("args").args().count(), 1);
+    assert!(clause.pattern().is_none());
+    assert!(clause.name().is_none());
+
+    let named = first::<ast::NamedFun>(&tree);
+    assert_eq!(token_text(&tree, source, named.name()), "F");
+    assert_eq!(named.clauses().count(), 2);
+
+    let local = first::<ast::LocalFunRef>(&tree);
+    assert_eq!(token_text(&tree, source, local.name()), "foo");
+    assert_eq!(token_text(&tree, source, local.arity()), "1");
+
+    let remote = first::<ast::RemoteFunRef>(&tree);
+    assert_eq!(token_text(&tree, source, remote.module()), "M");
+    assert_eq!(token_text(&tree, source, remote.name()), "f");
+    assert_eq!(token_text(&tree, source, remote.arity()), "A");
+}
+
+// -----------------------------------------------------------------
+// Comprehensions and qualifiers.
+// -----------------------------------------------------------------
+
+#[test]
+fn list_comprehension_template_and_qualifiers() {
+    let source = "[X || X <- L, X > 1, Y <:- Z && {K, V} <- W].";
+    let tree = expr(source);
+    let lc = first::<ast::ListComprehension>(&tree);
+    assert_eq!(node_text(source, lc.template()), "X");
+    let qualifiers: Vec<_> = lc.qualifiers().collect();
+    assert!(matches!(qualifiers[0], ast::Node::Generator(_)));
+    assert!(matches!(qualifiers[1], ast::Node::Filter(_)));
+    let ast::Node::ZipQualifier(zip) = qualifiers[2] else {
+        panic!("zip qualifier: {:?}", qualifiers[2]);
+    };
+    assert_eq!(zip.generators().count(), 2);
+    let strict = first::<ast::StrictGenerator>(&tree);
+    assert_eq!(node_text(source, strict.pattern()), "Y");
+    assert_eq!(node_text(source, strict.expr()), "Z");
+    assert_eq!(
+        node_text(source, first::<ast::Filter>(&tree).expr()),
+        "X > 1"
+    );
+}
+
+#[test]
+fn map_and_binary_comprehension_generators() {
+    let source =
+        "{#{K => V || K := V <- M}, << <<X>> || <<X>> <= B >>, [1 || K := V <:- M, <<Y>> <:= C]}.";
+    let tree = expr(source);
+    let mc = first::<ast::MapComprehension>(&tree);
+    assert!(matches!(mc.template(), Some(ast::Node::MapField(_))));
+    let map_gen = first::<ast::MapGenerator>(&tree);
+    assert_eq!(node_text(source, map_gen.key()), "K");
+    assert_eq!(node_text(source, map_gen.value()), "V");
+    assert_eq!(node_text(source, map_gen.expr()), "M");
+    let bc = first::<ast::BinaryComprehension>(&tree);
+    assert_eq!(bc.qualifiers().count(), 1);
+    let bin_gen = first::<ast::BitstringGenerator>(&tree);
+    assert_eq!(node_text(source, bin_gen.pattern()), "<< X >>");
+    assert_eq!(node_text(source, bin_gen.expr()), "B");
+    let strict_map = first::<ast::StrictMapGenerator>(&tree);
+    assert_eq!(node_text(source, strict_map.expr()), "M");
+    let strict_bin = first::<ast::StrictBitstringGenerator>(&tree);
+    assert_eq!(node_text(source, strict_bin.expr()), "C");
+}
+
+// -----------------------------------------------------------------
+// Types.
+// -----------------------------------------------------------------
+
+#[test]
+fn container_types() {
+    let source = "{[b], [c, ...], #{k => v, x := y}, #r{f :: t}, <<_:8, _:_*4>>}.";
+    let tree = ty(source);
+    assert_eq!(first::<ast::TupleType>(&tree).elements().count(), 5);
+    assert_eq!(
+        node_text(source, first::<ast::ListType>(&tree).element()),
+        "b"
+    );
+    assert_eq!(
+        node_text(source, first::<ast::NonemptyListType>(&tree).element()),
+        "c"
+    );
+    let fields: Vec<_> = first::<ast::MapType>(&tree).fields().collect();
+    assert!(!fields[0].is_exact());
+    assert!(fields[1].is_exact());
+    assert_eq!(node_text(source, fields[1].key()), "x");
+    assert_eq!(node_text(source, fields[1].value()), "y");
+    let record = first::<ast::RecordType>(&tree);
+    assert_eq!(token_text(&tree, source, record.name()), "r");
+    let field = record.fields().next().expect("field");
+    assert_eq!(token_text(&tree, source, field.name()), "f");
+    assert_eq!(node_text(source, field.ty()), "t");
+    let segments: Vec<_> = first::<ast::BitstringType>(&tree).segments().collect();
+    assert_eq!(node_text(source, segments[0].size()), "8");
+    assert!(segments[0].unit().is_none());
+    assert_eq!(node_text(source, segments[1].unit()), "4");
+}
+
+#[test]
+fn function_and_call_types() {
+    let source = "{fun((a) -> b), fun((...) -> c), fun(), m:t(x), m:u}.";
+    let tree = ty(source);
+    let funs: Vec<_> = tree
+        .roots()
+        .flat_map(|r| r.descendants())
+        .filter_map(ast::FunctionType::cast)
+        .collect();
+    let params = funs[0].params().expect("params");
+    assert_eq!(params.params().count(), 1);
+    assert!(!params.is_any_arity());
+    assert_eq!(
+        node_text(source, funs[0].return_type().expect("return").ty()),
+        "b"
+    );
+    assert!(funs[1].params().expect("params").is_any_arity());
+    assert!(funs[2].params().is_none());
+
+    let call = first::<ast::TypeCall>(&tree);
+    assert_eq!(token_text(&tree, source, call.module()), "m");
+    assert_eq!(token_text(&tree, source, call.name()), "t");
+    assert_eq!(call.arguments().expect("args").args().count(), 1);
+    let remote = first::<ast::RemoteType>(&tree);
+    assert_eq!(token_text(&tree, source, remote.module()), "m");
+    assert_eq!(token_text(&tree, source, remote.name()), "u");
+}
+
+#[test]
+fn operator_types() {
+    let source = "{a | b, 1..2, V :: t, 1 + 2, -1}.";
+    let tree = ty(source);
+    assert_eq!(first::<ast::UnionType>(&tree).members().count(), 2);
+    let range = first::<ast::RangeType>(&tree);
+    assert_eq!(node_text(source, range.low()), "1");
+    assert_eq!(node_text(source, range.high()), "2");
+    let annotated = first::<ast::AnnotatedType>(&tree);
+    assert_eq!(node_text(source, annotated.var()), "V");
+    assert_eq!(node_text(source, annotated.ty()), "t");
+    let bin = first::<ast::BinaryOpType>(&tree);
+    assert_eq!(token_text(&tree, source, bin.operator()), "+");
+    let unary = first::<ast::UnaryOpType>(&tree);
+    assert_eq!(node_text(source, unary.operand()), "1");
+}
+
+// -----------------------------------------------------------------
+// Forms.
+// -----------------------------------------------------------------
+
+#[test]
+fn attribute_name_and_payload() {
+    let source = "-module(mymod).";
+    let tree = module(source);
+    let attr = first::<ast::Attribute>(&tree);
+    let name = attr.name().expect("name");
+    assert_eq!(token_text(&tree, source, name.token()), "module");
+    assert!(!attr.payload().expect("payload").range().is_empty());
+}
+
+#[test]
+fn function_decl_clauses_and_parts() {
+    let source = "abs(X) when X < 0 -> -X; abs(X) -> X.";
+    let tree = module(source);
+    let decl = first::<ast::FunctionDecl>(&tree);
+    assert_eq!(token_text(&tree, source, decl.name()), "abs");
+    let clauses: Vec<_> = decl.clauses().collect();
+    assert_eq!(clauses.len(), 2);
+    assert_eq!(token_text(&tree, source, clauses[1].name()), "abs");
+    assert_eq!(clauses[0].arguments().expect("args").args().count(), 1);
+    let guard = clauses[0].guard().expect("guard");
+    let exprs: Vec<_> = guard.guards().next().expect("guard").exprs().collect();
+    assert_eq!(exprs.len(), 1);
+    assert!(clauses[1].guard().is_none());
+    assert!(clauses[1].body().is_some());
+}
+
+// -----------------------------------------------------------------
+// Recovery holes.
+// -----------------------------------------------------------------
+
+#[test]
+fn recovery_holes_surface_as_none_or_error_nodes() {
+    // `foo() ok.` loses its `->`; the clause still casts and the
+    // accessors report what the grammar managed to recover.
+    let tree = module("foo() ok.");
+    assert!(!tree.diagnostics().is_empty());
+    let clause = first::<ast::FunctionClause>(&tree);
+    assert!(clause.arguments().is_some());
+    assert!(clause.guard().is_none());
+
+    // `[1 | ]` has no tail expression; the skipped `]` becomes an
+    // `Error` node in the tail position.
+    let tree = expr("[1 | ].");
+    let cons = first::<ast::ConsExpr>(&tree);
+    assert_eq!(cons.heads().count(), 1);
+    assert!(matches!(cons.tail(), Some(ast::Node::Error(_))));
+    assert!(!tree.diagnostics().is_empty());
+}
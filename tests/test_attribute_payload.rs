@@ -0,0 +1,231 @@
+//! Integration tests for `SyntaxTree::parse_attribute_payload`: the
+//! opt-in second pass that reads well-known Module-mode attribute
+//! payloads into structured nodes.
+
+use erl_parse::SyntaxKind;
+use erl_parse::ast::{self, AstNode};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn module(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+/// Parses `source` and sub-parses the payload of its first attribute.
+fn payload(source: &str) -> (erl_parse::SyntaxTree, Option<erl_parse::SyntaxTree>) {
+    let tree = module(source);
+    let attribute = tree
+        .roots()
+        .find(|r| r.kind() == SyntaxKind::Attribute)
+        .expect("attribute form");
+    let sub = tree.parse_attribute_payload(attribute, source);
+    (tree, sub)
+}
+
+fn root<'a, T: AstNode<'a>>(tree: &'a erl_parse::SyntaxTree) -> T {
+    let root = tree.roots().next().expect("payload root");
+    T::cast(root).unwrap_or_else(|| panic!("expected {:?}, got {:?}", T::KIND, root.kind()))
+}
+
+fn token_text<'s>(
+    tree: &erl_parse::SyntaxTree,
+    source: &'s str,
+    index: Option<erl_parse::TokenIndex>,
+) -> &'s str {
+    let index = index.expect("token present");
+    tree.tokens()[index.get()].text(source)
+}
+
+fn node_text(source: &str, node: erl_parse::NodeView<'_>) -> String {
+    node.tokens_in_range()
+        .filter(|(_, t)| !t.kind().is_hidden())
+        .map(|(_, t)| t.text(source))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn spec_payload_yields_function_type_clauses() {
+    let source = "-spec f(integer()) -> ok; (atom()) -> error.";
+    let (_, sub) = payload(source);
+    let sub = sub.expect("spec payload");
+    assert!(sub.diagnostics().is_empty(), "{:?}", sub.diagnostics());
+    let spec: ast::SpecAttribute<'_> = root(&sub);
+    assert_eq!(token_text(&sub, source, spec.name()), "f");
+    assert_eq!(spec.module(), None);
+    let clauses: Vec<_> = spec.clauses().collect();
+    assert_eq!(clauses.len(), 2);
+    let ret = clauses[1]
+        .return_type()
+        .and_then(|r| r.ty())
+        .expect("return");
+    assert_eq!(node_text(source, ret.syntax()), "error");
+}
+
+#[test]
+fn spec_payload_reads_remote_name_parens_and_guard() {
+    let source = "-callback(m:f(X) -> X when X :: atom()).";
+    let (_, sub) = payload(source);
+    let sub = sub.expect("callback payload");
+    assert!(sub.diagnostics().is_empty(), "{:?}", sub.diagnostics());
+    let spec: ast::SpecAttribute<'_> = root(&sub);
+    assert_eq!(token_text(&sub, source, spec.module()), "m");
+    assert_eq!(token_text(&sub, source, spec.name()), "f");
+    let clause = spec.clauses().next().expect("clause");
+    let constraint = clause
+        .guard()
+        .and_then(|g| g.constraints().next())
+        .expect("constraint");
+    assert_eq!(token_text(&sub, source, constraint.name()), "X");
+}
+
+#[test]
+fn type_payload_yields_type_decl() {
+    for source in ["-type pair(A) :: {A, A}.", "-opaque(pair(A) :: {A, A})."] {
+        let (_, sub) = payload(source);
+        let sub = sub.expect("type payload");
+        assert!(
+            sub.diagnostics().is_empty(),
+            "{source}: {:?}",
+            sub.diagnostics()
+        );
+        let decl: ast::TypeDecl<'_> = root(&sub);
+        assert_eq!(token_text(&sub, source, decl.name()), "pair");
+        assert_eq!(decl.params().map(|p| p.args().count()), Some(1));
+        assert!(matches!(decl.ty(), Some(ast::Node::TupleType(_))));
+    }
+}
+
+#[test]
+fn record_payload_yields_fields_with_defaults_and_types() {
+    let source = "-record(point, {x = 0 :: integer(), y, z :: float()}).";
+    let (_, sub) = payload(source);
+    let sub = sub.expect("record payload");
+    assert!(sub.diagnostics().is_empty(), "{:?}", sub.diagnostics());
+    let record: ast::RecordDecl<'_> = root(&sub);
+    assert_eq!(token_text(&sub, source, record.name()), "point");
+    let fields: Vec<_> = record.fields().collect();
+    assert_eq!(fields.len(), 3);
+    assert_eq!(token_text(&sub, source, fields[0].name()), "x");
+    assert!(matches!(
+        fields[0].default(),
+        Some(ast::Node::IntegerExpr(_))
+    ));
+    assert!(matches!(fields[0].ty(), Some(ast::Node::TypeCall(_))));
+    assert!(fields[1].default().is_none() && fields[1].ty().is_none());
+    assert!(fields[2].default().is_none());
+    assert!(fields[2].ty().is_some());
+}
+
+#[test]
+fn export_payload_yields_name_arity_list() {
+    for (source, expected) in [
+        ("-export([f/1, g/0]).", ["f/1", "g/0"]),
+        ("-export_type([t/0, u/2]).", ["t/0", "u/2"]),
+    ] {
+        let (_, sub) = payload(source);
+        let sub = sub.expect("export payload");
+        assert!(
+            sub.diagnostics().is_empty(),
+            "{source}: {:?}",
+            sub.diagnostics()
+        );
+        let list: ast::ExportList<'_> = root(&sub);
+        let entries: Vec<_> = list
+            .entries()
+            .map(|e| {
+                format!(
+                    "{}/{}",
+                    token_text(&sub, source, e.name()),
+                    token_text(&sub, source, e.arity())
+                )
+            })
+            .collect();
+        assert_eq!(entries, expected);
+    }
+}
+
+#[test]
+fn other_attributes_fall_back_to_expressions() {
+    let (_, sub) = payload("-module(m).");
+    let sub = sub.expect("module payload");
+    assert_eq!(
+        sub.roots().next().map(|r| r.kind()),
+        Some(SyntaxKind::ArgumentList)
+    );
+
+    let (_, sub) = payload("-import(lists, [map/2]).");
+    let sub = sub.expect("import payload");
+    let args: ast::ArgumentList<'_> = root(&sub);
+    assert_eq!(args.args().count(), 2);
+}
+
+#[test]
+fn directives_and_empty_payloads_are_not_sub_parsed() {
+    for source in [
+        "-define(X, 1).",
+        "-include(\"a.hrl\").",
+        "-ifdef(TEST).",
+        "-endif.",
+    ] {
+        let (_, sub) = payload(source);
+        assert!(sub.is_none(), "{source}");
+    }
+}
+
+#[test]
+fn non_attribute_nodes_are_rejected() {
+    let source = "f() -> ok.";
+    let tree = module(source);
+    let root = tree.roots().next().expect("root");
+    assert!(tree.parse_attribute_payload(root, source).is_none());
+}
+
+#[test]
+fn token_indexes_point_into_the_original_buffer() {
+    let source = "-module(m).\n-export([f/1]).\nf(X) -> X.";
+    let tree = module(source);
+    let attribute = tree
+        .roots()
+        .filter(|r| r.kind() == SyntaxKind::Attribute)
+        .nth(1)
+        .expect("export attribute");
+    let payload = attribute
+        .children()
+        .find(|c| c.kind() == SyntaxKind::AttributePayload)
+        .expect("payload");
+    let sub = tree
+        .parse_attribute_payload(attribute, source)
+        .expect("export payload");
+    let list: ast::ExportList<'_> = root(&sub);
+    let list_range = list.range();
+    assert!(payload.range().start() < list_range.start());
+    assert!(list_range.end() < payload.range().end());
+    let entry = list.entries().next().expect("entry");
+    let name = entry.name().expect("name");
+    assert_eq!(tree.tokens()[name.get()].text(source), "f");
+    assert_eq!(sub.tokens()[name.get()].text(source), "f");
+}
+
+#[test]
+fn malformed_payload_recovers_with_diagnostics() {
+    let source = "-export([f/1, g, h/2]) extra.";
+    let (_, sub) = payload(source);
+    let sub = sub.expect("export payload");
+    assert!(!sub.diagnostics().is_empty());
+    let list: ast::ExportList<'_> = root(&sub);
+    assert_eq!(list.entries().count(), 3);
+    assert!(sub.roots().any(|r| r.kind() == SyntaxKind::Error));
+}
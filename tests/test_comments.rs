@@ -0,0 +1,155 @@
+//! Tests for comment attachment: `SyntaxTree::comment_attachment` and
+//! `NodeView::{leading,trailing,dangling}_comments`.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(mode: erl_parse::ParseMode, source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+/// Lists every comment with the kind of its owner and its placement.
+fn attachments(
+    tree: &erl_parse::SyntaxTree,
+    source: &str,
+) -> Vec<(String, erl_parse::SyntaxKind, erl_parse::CommentPlacement)> {
+    (0..tree.tokens().len())
+        .map(erl_parse::TokenIndex::new)
+        .filter(|&i| tree.tokens()[i.get()].kind() == erl_tokenize::TokenKind::Comment)
+        .map(|i| {
+            let (node, placement) = tree.comment_attachment(i).expect("attached");
+            let text = tree.tokens()[i.get()].text(source).to_string();
+            (text, node.kind(), placement)
+        })
+        .collect()
+}
+
+fn texts(
+    comments: impl Iterator<Item = (erl_parse::TokenIndex, erl_tokenize::Token)>,
+    source: &str,
+) -> Vec<&str> {
+    comments.map(|(_, t)| t.text(source)).collect()
+}
+
+#[test]
+fn comments_above_a_form_lead_it() {
+    use erl_parse::CommentPlacement::*;
+    use erl_parse::SyntaxKind::*;
+    let source = "%%% Header.\n-module(m). % name\n\n%% Doc for f.\n%% More.\nf() -> ok.\n";
+    let tree = parse(erl_parse::ParseMode::Module, source);
+    assert_eq!(
+        attachments(&tree, source),
+        [
+            ("%%% Header.".to_string(), Attribute, Leading),
+            ("% name".to_string(), Attribute, Trailing),
+            ("%% Doc for f.".to_string(), FunctionDecl, Leading),
+            ("%% More.".to_string(), FunctionDecl, Leading),
+        ]
+    );
+    let f = tree.roots().nth(1).expect("f/0");
+    assert_eq!(
+        texts(f.leading_comments(), source),
+        ["%% Doc for f.", "%% More."]
+    );
+    assert_eq!(f.trailing_comments().count(), 0);
+    let module = tree.roots().next().expect("-module");
+    assert_eq!(texts(module.leading_comments(), source), ["%%% Header."]);
+    assert_eq!(texts(module.trailing_comments(), source), ["% name"]);
+}
+
+#[test]
+fn comments_between_clauses_lead_the_next_clause() {
+    use erl_parse::CommentPlacement::*;
+    use erl_parse::SyntaxKind::*;
+    let source = "f(a) -> % why\n    one, % first\n    two; % end of a\n%% Otherwise.\nf(_) ->\n    other.\n% last\n";
+    let tree = parse(erl_parse::ParseMode::Module, source);
+    assert_eq!(
+        attachments(&tree, source),
+        [
+            ("% why".to_string(), Body, Leading),
+            ("% first".to_string(), AtomExpr, Trailing),
+            ("% end of a".to_string(), FunctionClause, Trailing),
+            ("%% Otherwise.".to_string(), FunctionClause, Leading),
+            ("% last".to_string(), FunctionDecl, Trailing),
+        ]
+    );
+    let decl = tree.roots().next().expect("f/1");
+    let clauses: Vec<_> = decl.children().collect();
+    assert_eq!(
+        texts(clauses[0].trailing_comments(), source),
+        ["% end of a"]
+    );
+    assert_eq!(
+        texts(clauses[1].leading_comments(), source),
+        ["%% Otherwise."]
+    );
+    assert_eq!(texts(decl.trailing_comments(), source), ["% last"]);
+    assert_eq!(decl.leading_comments().count(), 0);
+}
+
+#[test]
+fn comments_inside_empty_containers_dangle() {
+    use erl_parse::CommentPlacement::*;
+    use erl_parse::SyntaxKind::*;
+    let source = "{[ % nothing yet\n ], #{\n  %% no keys\n}, f( % no args\n)}.";
+    let tree = parse(erl_parse::ParseMode::Expression, source);
+    assert_eq!(
+        attachments(&tree, source),
+        [
+            ("% nothing yet".to_string(), ListExpr, Dangling),
+            ("%% no keys".to_string(), MapExpr, Dangling),
+            ("% no args".to_string(), ArgumentList, Dangling),
+        ]
+    );
+    let tuple = tree.roots().next().expect("tuple");
+    let list = tuple.children().next().expect("list");
+    assert_eq!(texts(list.dangling_comments(), source), ["% nothing yet"]);
+    assert_eq!(list.leading_comments().count(), 0);
+    assert_eq!(tuple.dangling_comments().count(), 0);
+}
+
+#[test]
+fn comments_between_elements_lead_the_next_element() {
+    use erl_parse::CommentPlacement::*;
+    use erl_parse::SyntaxKind::*;
+    let source = "[\n  % the first\n  a,\n  b % the last\n  , c\n  % after c\n].";
+    let tree = parse(erl_parse::ParseMode::Expression, source);
+    assert_eq!(
+        attachments(&tree, source),
+        [
+            ("% the first".to_string(), AtomExpr, Leading),
+            ("% the last".to_string(), AtomExpr, Trailing),
+            ("% after c".to_string(), AtomExpr, Trailing),
+        ]
+    );
+}
+
+#[test]
+fn non_comment_tokens_are_not_attached() {
+    let source = "% only a comment\n";
+    let tree = parse(erl_parse::ParseMode::Module, source);
+    assert!(
+        tree.comment_attachment(erl_parse::TokenIndex::new(0))
+            .is_none()
+    );
+    let tree = parse(erl_parse::ParseMode::Module, "f() -> ok.");
+    assert!(
+        tree.comment_attachment(erl_parse::TokenIndex::new(0))
+            .is_none()
+    );
+    assert!(
+        tree.comment_attachment(erl_parse::TokenIndex::new(99))
+            .is_none()
+    );
+}
@@ -0,0 +1,175 @@
+//! Tests for `erl_parse::docs_chunk`.
+
+use erl_parse::abstract_format::Term;
+use erl_parse::docs_chunk::{Doc, DocsChunk, EntryKind};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+fn text(text: &str) -> Doc {
+    Doc::Text(text.to_string())
+}
+
+#[test]
+fn docs_attach_to_the_next_form() {
+    let source = r#"-module(m).
+-moduledoc "A module.".
+-export([plain/0, hidden/0]).
+
+-doc "Plain.".
+-spec plain() -> ok.
+plain() -> ok.
+
+-doc """
+    Triple
+      quoted.
+    """.
+triple(A, B) -> {A, B}.
+
+-doc ~"Sigil.".
+sigil() -> ok.
+
+-doc false.
+hidden() -> ok.
+
+undocumented() -> ok.
+"#;
+    let tree = parse(source);
+    let chunk = DocsChunk::new(&tree, source);
+    assert_eq!(chunk.line(), 1);
+    assert_eq!(chunk.doc(), &text("A module."));
+    let entries: Vec<_> = chunk
+        .entries()
+        .iter()
+        .map(|e| {
+            (
+                e.kind(),
+                e.name(),
+                e.arity(),
+                e.signature(),
+                e.doc().clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (EntryKind::Function, "plain", 0, "plain()", text("Plain.")),
+            (
+                EntryKind::Function,
+                "triple",
+                2,
+                "triple(A, B)",
+                text("Triple\n  quoted.")
+            ),
+            (EntryKind::Function, "sigil", 0, "sigil()", text("Sigil.")),
+            (EntryKind::Function, "hidden", 0, "hidden()", Doc::Hidden),
+        ]
+    );
+    assert_eq!(chunk.entries()[1].line(), 13);
+}
+
+#[test]
+fn maps_add_metadata_and_types_and_callbacks_are_entries() {
+    let source = r#"-module(m).
+-moduledoc #{since => "1.0"}.
+-moduledoc {file, "README.md"}.
+-export_type([t/1]).
+
+-type t(A) :: [A].
+-type private() :: ok.
+
+-doc #{since => "1.1"}.
+-doc "A callback.".
+-doc #{since => "1.2", deprecated => "Use other/0."}.
+-callback handle(Event :: term(), State) -> State.
+-callback undocumented() -> ok.
+"#;
+    let tree = parse(source);
+    let chunk = DocsChunk::new(&tree, source);
+    assert_eq!(chunk.doc(), &Doc::File("README.md".to_string()));
+    assert_eq!(
+        chunk.metadata(),
+        [(Term::atom("since"), Term::string("1.0"))]
+    );
+    let entries: Vec<_> = chunk
+        .entries()
+        .iter()
+        .map(|e| (e.kind(), e.name(), e.arity(), e.signature()))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            (EntryKind::Type, "t", 1, "t(A)"),
+            (
+                EntryKind::Callback,
+                "handle",
+                2,
+                "handle(Event :: term(), State)"
+            ),
+            (EntryKind::Callback, "undocumented", 0, "undocumented()"),
+        ]
+    );
+    let handle = &chunk.entries()[1];
+    assert_eq!(handle.doc(), &text("A callback."));
+    assert_eq!(
+        handle.metadata(),
+        [
+            (Term::atom("since"), Term::string("1.2")),
+            (Term::atom("deprecated"), Term::string("Use other/0.")),
+        ]
+    );
+    assert_eq!(chunk.entries()[2].doc(), &Doc::None);
+}
+
+#[test]
+fn export_all_and_to_term() {
+    let binary = |text: &str| Term::Binary(text.as_bytes().to_vec());
+    let source = "-module(m).\n-compile(export_all).\nf(X) -> X.\n";
+    let tree = parse(source);
+    let chunk = DocsChunk::new(&tree, source);
+    assert_eq!(
+        chunk.to_term(),
+        Term::tuple([
+            Term::atom("docs_v1"),
+            Term::from(1),
+            Term::atom("erlang"),
+            binary("text/markdown"),
+            Term::atom("none"),
+            Term::Map(Vec::new()),
+            Term::list([Term::tuple([
+                Term::tuple([Term::atom("function"), Term::atom("f"), Term::from(1)]),
+                Term::from(3),
+                Term::list([binary("f(X)")]),
+                Term::atom("none"),
+                Term::Map(Vec::new()),
+            ])]),
+        ])
+    );
+
+    let source = "-moduledoc \"Hi.\".\n-doc false.\n-type t() :: ok.\n";
+    let tree = parse(source);
+    let term = DocsChunk::new(&tree, source).to_term();
+    let fields = term.as_tuple().expect("docs_v1");
+    assert_eq!(fields[4], Term::Map(vec![(binary("en"), binary("Hi."))]));
+    let entries = fields[6].as_list().expect("entries");
+    assert_eq!(
+        entries[0].as_tuple().map(|entry| entry[3].clone()),
+        Some(Term::atom("hidden"))
+    );
+}
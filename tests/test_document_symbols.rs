@@ -0,0 +1,104 @@
+//! Tests for `SyntaxTree::document_symbols`.
+
+use erl_parse::{DocumentSymbol, SymbolKind};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+/// Writes each symbol as `Kind name` (with `*` when exported) followed
+/// by the source text of its range, children indented below.
+fn outline(tree: &erl_parse::SyntaxTree, source: &str) -> Vec<String> {
+    fn walk(
+        tree: &erl_parse::SyntaxTree,
+        source: &str,
+        symbols: &[DocumentSymbol],
+        depth: usize,
+        out: &mut Vec<String>,
+    ) {
+        for symbol in symbols {
+            let text: String = tree.tokens()[symbol.range().as_range()]
+                .iter()
+                .map(|t| t.text(source))
+                .collect();
+            let selection = &tree.tokens()[symbol.selection_range().as_range()];
+            assert_eq!(selection.len(), 1);
+            assert!(symbol.range().contains_range(symbol.selection_range()));
+            out.push(format!(
+                "{}{:?} {}{} = {text}",
+                "  ".repeat(depth),
+                symbol.kind(),
+                symbol.name(),
+                if symbol.is_exported() { "*" } else { "" }
+            ));
+            walk(tree, source, symbol.children(), depth + 1, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(tree, source, &tree.document_symbols(source), 0, &mut out);
+    out
+}
+
+#[test]
+fn module_forms_are_outlined() {
+    let source = "\
+-module(shapes).
+-export([area/1]).
+-export_type([shape/0]).
+-define(PI, 3.14).
+-define(SQ(X), X * X).
+-record(circle, {radius = 1, label :: string()}).
+-type shape() :: #circle{}.
+-callback init(term()) -> ok.
+-spec area(shape()) -> number().
+%% Area of a shape.
+area(#circle{radius = R}) -> 3.14 * R * R;
+area(_) ->
+    0.
+helper(X) -> X.
+";
+    let tree = parse(source);
+    assert!(tree.diagnostics().is_empty());
+    assert_eq!(
+        outline(&tree, source),
+        [
+            "Module shapes = -module(shapes).",
+            "Macro PI = -define(PI, 3.14).",
+            "Macro SQ/1 = -define(SQ(X), X * X).",
+            "Record circle = -record(circle, {radius = 1, label :: string()}).",
+            "  Field radius = radius = 1",
+            "  Field label = label :: string()",
+            "Type shape/0* = -type shape() :: #circle{}.",
+            "Callback init/1 = -callback init(term()) -> ok.",
+            "Spec area/1 = -spec area(shape()) -> number().",
+            "Function area/1* = area(#circle{radius = R}) -> 3.14 * R * R;\narea(_) ->\n    0.",
+            "  Clause area(#circle{radius = R}) = area(#circle{radius = R}) -> 3.14 * R * R",
+            "  Clause area(_) = area(_) ->\n    0",
+            "Function helper/1 = helper(X) -> X.",
+            "  Clause helper(X) = helper(X) -> X",
+        ]
+    );
+    assert_eq!(SymbolKind::Record.lsp(), 23);
+}
+
+#[test]
+fn clause_heads_collapse_whitespace() {
+    let source = "f(A, % first\n  B) -> {A, B}.\n";
+    let tree = parse(source);
+    let symbols = tree.document_symbols(source);
+    assert_eq!(symbols[0].children()[0].name(), "f(A, B)");
+}
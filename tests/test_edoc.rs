@@ -0,0 +1,117 @@
+//! Tests for `erl_parse::edoc`.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+#[test]
+fn function_tags_are_read_from_the_comment_above() {
+    let source = "\
+%% @doc Looks up `Key'.
+%%   Crashes when the table is gone.
+%% @param Table the table to read
+%% @param Key
+%% @returns the stored value
+%% @spec lookup(Table, Key) -> term()
+%% @deprecated Use {@link fetch/2} instead.
+%% @since 1.2
+%% @see fetch/2
+%% @see ets:lookup/2
+lookup(Table, Key) -> ets:lookup(Table, Key).
+";
+    let tree = parse(source);
+    let entries = erl_parse::edoc::entries(&tree, source);
+    assert_eq!(entries.len(), 1);
+    let (node, edoc) = &entries[0];
+    assert_eq!(node.kind(), erl_parse::SyntaxKind::FunctionDecl);
+    assert_eq!(
+        edoc.doc(),
+        Some("Looks up `Key'.\nCrashes when the table is gone.")
+    );
+    assert_eq!(
+        edoc.params().collect::<Vec<_>>(),
+        [("Table", "the table to read"), ("Key", "")]
+    );
+    assert_eq!(edoc.returns(), Some("the stored value"));
+    assert_eq!(edoc.spec(), Some("lookup(Table, Key) -> term()"));
+    assert_eq!(edoc.deprecated(), Some("Use {@link fetch/2} instead."));
+    assert_eq!(edoc.since(), Some("1.2"));
+    assert_eq!(edoc.see().collect::<Vec<_>>(), ["fetch/2", "ets:lookup/2"]);
+}
+
+#[test]
+fn module_and_type_tags_belong_to_their_attributes() {
+    let source = "\
+%%% @author Someone
+%%% @doc A counter.
+%%% @end
+%%% Not part of the docs.
+-module(counter).
+
+%% @type count(). A non-negative count.
+-type count() :: non_neg_integer().
+
+-export([new/0]).
+
+% A plain comment.
+new() -> 0.
+";
+    let tree = parse(source);
+    let entries = erl_parse::edoc::entries(&tree, source);
+    let documented: Vec<_> = entries
+        .iter()
+        .map(|(node, _)| node.text(source).lines().last().unwrap_or_default())
+        .collect();
+    assert_eq!(
+        documented,
+        ["-module(counter)", "-type count() :: non_neg_integer()"]
+    );
+
+    let module = &entries[0].1;
+    let tags: Vec<_> = module
+        .tags()
+        .iter()
+        .map(|tag| (tag.name(), tag.content()))
+        .collect();
+    assert_eq!(tags, [("author", "Someone"), ("doc", "A counter.")]);
+
+    let ty = &entries[1].1;
+    assert_eq!(ty.tag("type"), Some("count(). A non-negative count."));
+    assert_eq!(ty.doc(), None);
+}
+
+#[test]
+fn blocks_without_tags_are_not_documentation() {
+    assert_eq!(
+        erl_parse::edoc::Edoc::parse(["%% Just a note.", "%% email@example.com"]),
+        None
+    );
+    let edoc = erl_parse::edoc::Edoc::parse([
+        "%% Intro text is skipped.",
+        "%% @doc First.",
+        "%%",
+        "%% Second paragraph.",
+        "%%",
+        "%% @private",
+        "%%   @since 2.0",
+    ])
+    .expect("tags");
+    assert_eq!(edoc.doc(), Some("First.\n\nSecond paragraph."));
+    let names: Vec<_> = edoc.tags().iter().map(|tag| tag.name()).collect();
+    assert_eq!(names, ["doc", "private", "since"]);
+    assert_eq!(edoc.tag("private"), Some(""));
+}
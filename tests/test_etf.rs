@@ -0,0 +1,96 @@
+//! Golden-file tests for `Term::to_etf`: the External Term Format bytes
+//! of converted forms must equal `term_to_binary/1` of what OTP's
+//! `erl_parse` returns. Fixtures live in `tests/fixtures/etf/` and are
+//! regenerated with `scripts/dump-etf-fixtures.escript`.
+
+use erl_parse::abstract_format::{self, Integer, Location, Term};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+/// Converts every root of `source` and wraps them in a list, as the
+/// escript does with OTP's results.
+fn convert(mode: erl_parse::ParseMode, source: &str, location: Location) -> Term {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{:?}", tree.diagnostics());
+    Term::list(tree.roots().map(|root| {
+        match mode {
+            erl_parse::ParseMode::TermList => abstract_format::expr(&tree, source, root, location)
+                .and_then(|t| abstract_format::normalise(&t)),
+            _ => abstract_format::form(&tree, source, root, location),
+        }
+        .unwrap_or_else(|| panic!("no abstract form for {:?}", root.kind()))
+    }))
+}
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!(
+        "{}/tests/fixtures/etf/{name}.etf",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read(&path).unwrap_or_else(|e| panic!("read {path}: {e}"))
+}
+
+#[test]
+fn function_with_line_annotations_matches_fixture() {
+    let term = convert(
+        erl_parse::ParseMode::Module,
+        "f(X) -> X + 1.",
+        Location::Line,
+    );
+    assert_eq!(term.to_etf(), fixture("function_line"));
+}
+
+#[test]
+fn forms_with_line_column_annotations_match_fixture() {
+    let term = convert(
+        erl_parse::ParseMode::Module,
+        "-module(m).\nf() ->\n  ok.",
+        Location::LineColumn,
+    );
+    assert_eq!(term.to_etf(), fixture("module_line_column"));
+}
+
+#[test]
+fn literal_terms_match_fixture() {
+    let term = convert(
+        erl_parse::ParseMode::TermList,
+        "{a, \"str\", 1.5, -1, 300, 1180591620717411303424, <<1, 2>>, #{b => 1, a => 2}, [1 | b]}.",
+        Location::Line,
+    );
+    assert_eq!(term.to_etf(), fixture("literal_terms"));
+}
+
+#[test]
+fn size_boundaries_pick_the_wider_tags() {
+    let long_atom = "a".repeat(256);
+    let encoded = Term::atom(&long_atom).to_etf();
+    assert_eq!(encoded[..4], [131, 118, 1, 0]);
+
+    assert_eq!(Term::from(255).to_etf(), [131, 97, 255]);
+    assert_eq!(Term::from(256).to_etf(), [131, 98, 0, 0, 1, 0]);
+    assert_eq!(
+        Term::from(i64::from(i32::MIN) - 1).to_etf(),
+        [131, 110, 4, 1, 1, 0, 0, 128]
+    );
+    let huge = Integer::from_le_bytes(false, &[1; 256]);
+    assert_eq!(Term::from(huge).to_etf()[..7], [131, 111, 0, 0, 1, 0, 0]);
+
+    assert_eq!(
+        Term::list([Term::from(1), Term::from(256)]).to_etf(),
+        [131, 108, 0, 0, 0, 2, 97, 1, 98, 0, 0, 1, 0, 106]
+    );
+    let long_string = Term::list((0..65536).map(|_| Term::from(1)));
+    assert_eq!(long_string.to_etf()[..6], [131, 108, 0, 1, 0, 0]);
+}
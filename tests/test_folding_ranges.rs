@@ -0,0 +1,120 @@
+//! Tests for `SyntaxTree::folding_ranges` over module forms and blocks.
+
+use erl_parse::FoldingKind::{self, *};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn folds(source: &str) -> Vec<(FoldingKind, usize, usize)> {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    tree.folding_ranges(source)
+        .into_iter()
+        .map(|f| (f.kind(), f.start_line(), f.end_line()))
+        .collect()
+}
+
+fn parse_expr(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Expression);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+#[test]
+fn module_forms_fold() {
+    let source = "\
+%% A module.
+%% With a header.
+-module(m).
+-export([f/1]).
+-export([g/0]).
+-import(lists, [map/2]).
+
+f(X) -> X; % trailing
+%% not a run with the line above
+f(_) ->
+    [1,
+     2].
+
+g() ->
+    fun() ->
+        {a,
+         b}
+    end.
+";
+    assert_eq!(
+        folds(source),
+        [
+            (Comment, 1, 2),
+            (Imports, 4, 6),
+            (Region, 8, 12),
+            (Region, 11, 12),
+            (Region, 15, 18),
+            (Region, 16, 17),
+        ]
+    );
+}
+
+#[test]
+fn single_clause_and_single_line_forms_do_not_fold() {
+    assert!(folds("-export([f/0]).\nf() -> ok.\n").is_empty());
+}
+
+const BLOCKS: [&str; 11] = [
+    "receive msg -> ok end.",
+    "receive after 0 -> ok end.",
+    "receive msg -> ok after 1000 -> timeout end.",
+    "try foo() of X -> X catch error:R -> R after cleanup() end.",
+    "try foo() of X -> X catch Y -> Y end.",
+    "try do_thing() after cleanup() end.",
+    "try foo() catch Y -> Y; error:R:S -> R end.",
+    "try foo() catch throw:{error, _} = E -> E end.",
+    "maybe {ok, X} ?= foo() end.",
+    "maybe {ok, X} ?= foo() else Other -> Other end.",
+    "try foo() of X -> X catch Y -> Y after cleanup() end.",
+];
+
+#[test]
+fn single_line_blocks_do_not_fold() {
+    for source in BLOCKS {
+        let tree = parse_expr(source);
+        assert!(tree.folding_ranges(source).is_empty(), "{source:?}");
+    }
+}
+
+#[test]
+fn multi_line_blocks_fold_from_keyword_to_end() {
+    for block in BLOCKS {
+        let source = block
+            .replace(" -> ", " ->\n    ")
+            .replace(" after cleanup", "\nafter\n    cleanup")
+            .replace(" end.", "\nend.");
+        let tree = parse_expr(&source);
+        assert!(tree.diagnostics().is_empty(), "{source:?}");
+        let ranges = tree.folding_ranges(&source);
+        assert_eq!(ranges.len(), 1, "{source:?}: {ranges:?}");
+        let fold = ranges[0];
+        assert_eq!(fold.kind(), Region);
+        assert_eq!(
+            (fold.start_line(), fold.end_line()),
+            (1, source.lines().count()),
+            "{source:?}"
+        );
+        let tokens = &tree.tokens()[fold.range().as_range()];
+        assert_eq!(tokens[0].text(&source), block.split(' ').next().unwrap());
+        assert_eq!(tokens.last().map(|t| t.text(&source)), Some("end"));
+    }
+}
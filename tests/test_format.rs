@@ -0,0 +1,194 @@
+//! Tests for `erl_parse::format::format`.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn format_with(source: &str, options: erl_parse::format::Options) -> String {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    erl_parse::format::format(&parser.finish(), source, options)
+}
+
+fn format(source: &str) -> String {
+    format_with(source, erl_parse::format::Options::new())
+}
+
+#[test]
+fn short_clauses_stay_on_one_line() {
+    assert_eq!(
+        format("f( X )->X+1 .\ng([H|T],#{a:=B})when H>0->{H,T,B}."),
+        "f(X) -> X + 1.\ng([H | T], #{a := B}) when H > 0 -> {H, T, B}.\n"
+    );
+}
+
+#[test]
+fn bodies_with_several_expressions_go_one_per_line() {
+    assert_eq!(
+        format("f(X) -> Y = X * 2, {ok, Y}; f(_) -> error."),
+        "f(X) ->\n    Y = X * 2,\n    {ok, Y};\nf(_) -> error.\n"
+    );
+}
+
+#[test]
+fn blocks_put_clauses_between_keyword_and_end() {
+    assert_eq!(
+        format(
+            "f(X) -> try g(X) of {ok, V} -> V catch error:R -> R after done end, \
+             receive M -> M after 0 -> timeout end."
+        ),
+        "f(X) ->\n    try\n        g(X)\n    of\n        {ok, V} -> V\n    catch\n        \
+         error:R -> R\n    after\n        done\n    end,\n    receive\n        M -> M\n    \
+         after\n        0 -> timeout\n    end.\n"
+    );
+}
+
+#[test]
+fn funs_stay_inline_with_one_clause() {
+    assert_eq!(
+        format("f() -> F = fun(X) -> X end, G = fun(a) -> 1; (_) -> 2 end."),
+        "f() ->\n    F = fun(X) -> X end,\n    G = fun\n        (a) -> 1;\n        (_) -> 2\n    end.\n"
+    );
+}
+
+#[test]
+fn containers_break_one_element_per_line_past_the_width() {
+    let source = "f() -> g(alpha, {beta, gamma}, [delta, epsilon]).";
+    let options = erl_parse::format::Options::new().line_width(30);
+    assert_eq!(
+        format_with(source, options),
+        "f() ->\n    g(\n        alpha,\n        {beta, gamma},\n        [delta, epsilon]\n    ).\n"
+    );
+    assert_eq!(
+        format_with(source, options.indent(2)),
+        "f() ->\n  g(\n    alpha,\n    {beta, gamma},\n    [delta, epsilon]\n  ).\n"
+    );
+}
+
+#[test]
+fn attributes_use_the_structured_payload() {
+    let options = erl_parse::format::Options::new().line_width(20);
+    assert_eq!(
+        format_with("-export([start/0,stop/1]).", options),
+        "-export([\n    start/0,\n    stop/1\n]).\n"
+    );
+    assert_eq!(
+        format_with("-type t() :: alpha | beta | gamma.", options),
+        "-type t() ::\n    alpha\n    | beta\n    | gamma.\n"
+    );
+    assert_eq!(
+        format("-spec f(X) -> X when X :: integer()."),
+        "-spec f(X) -> X when X :: integer().\n"
+    );
+}
+
+#[test]
+fn comments_and_blank_lines_are_kept() {
+    let source = "%% Module.\n-module(m). % name\n\n\n%% f/0\nf() ->\n  % first\n  a, % after a\n\n  b.\n% end\n";
+    assert_eq!(
+        format(source),
+        "%% Module.\n-module(m). % name\n\n%% f/0\nf() ->\n    % first\n    a, % after a\n\n    b.\n% end\n"
+    );
+}
+
+#[test]
+fn comma_first_comments_move_past_the_comma() {
+    assert_eq!(
+        format("f() -> [a % one\n, b % two\n]."),
+        "f() ->\n    [\n        a, % one\n        b % two\n    ].\n"
+    );
+}
+
+#[test]
+fn units_with_diagnostics_are_kept_as_written() {
+    let source = "f( ->  ok.\ng()->ok.";
+    assert_eq!(format(source), "f( ->  ok.\ng() -> ok.\n");
+}
+
+#[test]
+fn output_keeps_tokens_and_formats_to_itself() {
+    let source = "f(X)->case X of{a,B}->-B;_-> - -1 end.\n-define(M, 1+2).\n";
+    let formatted = format(source);
+    let texts = |source: &str| {
+        scan_all(source)
+            .into_iter()
+            .filter(|t| t.kind().is_lexical())
+            .map(|t| t.text(source).to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&formatted), texts(source));
+    assert_eq!(format(&formatted), formatted);
+}
+
+fn format_range(source: &str, selected: &str) -> Option<String> {
+    let tokens = scan_all(source);
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in &tokens {
+        parser.feed_token(*t);
+    }
+    let tree = parser.finish();
+    let start = source.find(selected).expect("selection in source");
+    let end = start + selected.len();
+    let first = tokens.iter().position(|t| t.start().offset() >= start);
+    let last = tokens.iter().rposition(|t| t.end().offset() <= end);
+    let selection = erl_parse::TokenRange::new(
+        erl_parse::TokenIndex::new(first.expect("token")),
+        erl_parse::TokenIndex::new(last.expect("token") + 1),
+    );
+    let edits = erl_parse::format::format_range(
+        &tree,
+        source,
+        selection,
+        erl_parse::format::Options::new(),
+    )?;
+    let mut out = source.to_string();
+    for (range, text) in edits.into_iter().rev() {
+        assert!(
+            start <= range.start && range.end <= end,
+            "edit outside the selection"
+        );
+        out.replace_range(range, &text);
+    }
+    Some(out)
+}
+
+#[test]
+fn range_formatting_uses_the_enclosing_node_and_its_indentation() {
+    let source = "f(X)->\n    case X of {ok,V}->[V,V+1];_->error end,\n  g(X,  Y).\n";
+    assert_eq!(
+        format_range(source, "case X of {ok,V}->[V,V+1];_->error end").as_deref(),
+        Some(
+            "f(X)->\n    case X of\n        {ok, V} -> [V, V + 1];\n        _ -> error\n    end,\n  g(X,  Y).\n"
+        )
+    );
+    assert_eq!(
+        format_range(source, "[V,V+1]").as_deref(),
+        Some("f(X)->\n    case X of {ok,V}->[V, V + 1];_->error end,\n  g(X,  Y).\n")
+    );
+}
+
+#[test]
+fn range_formatting_spans_several_units() {
+    assert_eq!(
+        format_range("a()->1.\nb( )->2.\nc( )->3.\n", "a()->1.\nb( )->2.").as_deref(),
+        Some("a() -> 1.\nb() -> 2.\nc( )->3.\n")
+    );
+}
+
+#[test]
+fn range_formatting_refuses_error_nodes_and_skips_around_them() {
+    let source = "h() ->\n  [a,b], {c,\n  d}, x(\n.\n";
+    assert_eq!(format_range(source, "x(\n."), None);
+    assert_eq!(
+        format_range(source, "[a,b], {c,\n  d}, x(").as_deref(),
+        Some("h() ->\n  [a, b], {c, d}, x(\n.\n")
+    );
+}
@@ -0,0 +1,94 @@
+//! Tests for `erl_parse::lint`.
+
+use erl_parse::DiagnosticKind::*;
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+fn warnings(source: &str) -> Vec<(erl_parse::DiagnosticKind, String)> {
+    let tree = parse(source);
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    erl_parse::lint::variables(&tree, source)
+        .into_iter()
+        .map(|d| (d.kind(), d.render(&tree, source).erlc()))
+        .collect()
+}
+
+#[test]
+fn unused_and_underscore_variables() {
+    assert_eq!(
+        warnings("f(X, _Y, _) -> _Z = 1, W = _Y, fun(V) -> ok end."),
+        [
+            (
+                UnusedVariable,
+                "nofile:1:3: Warning: variable 'X' is unused".to_string()
+            ),
+            (
+                UnusedVariable,
+                "nofile:1:24: Warning: variable 'W' is unused".to_string()
+            ),
+            (
+                UnderscoreVariableUsed,
+                "nofile:1:28: Warning: variable '_Y' is used but its name starts with '_'"
+                    .to_string()
+            ),
+            (
+                UnusedVariable,
+                "nofile:1:36: Warning: variable 'V' is unused".to_string()
+            ),
+        ]
+    );
+    assert!(warnings("f(X) -> X.").is_empty());
+}
+
+#[test]
+fn unsafe_variables() {
+    let source = "\
+f(A) ->
+    case A of 1 -> B = 1; _ -> ok end,
+    try C = g() catch _ -> ok end,
+    {B, C}.
+";
+    assert_eq!(
+        warnings(source),
+        [
+            (
+                UnsafeVariable,
+                "nofile:4:6: variable 'B' unsafe in 'case' (line 2, column 5)".to_string()
+            ),
+            (
+                UnsafeVariable,
+                "nofile:4:9: variable 'C' unsafe in 'try' (line 3, column 5)".to_string()
+            ),
+        ]
+    );
+    let tree = parse(source);
+    let found = erl_parse::lint::variables(&tree, source);
+    let messages: Vec<_> = found.iter().map(|d| d.message(&tree, source)).collect();
+    assert_eq!(
+        messages,
+        [
+            "variable `B` unsafe in `case`",
+            "variable `C` unsafe in `try`"
+        ]
+    );
+    assert_eq!(found[0].severity(), erl_parse::Severity::Error);
+    assert_eq!(found[0].code().code(), "EP0010");
+    let related = found[1].related().expect("related");
+    assert_eq!(tree.tokens()[related.start().get()].text(source), "try");
+}
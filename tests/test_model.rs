@@ -0,0 +1,148 @@
+//! Tests for `erl_parse::model`.
+
+use erl_parse::model::{ModuleModel, NameArity};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+fn keys<'a>(keys: impl Iterator<Item = &'a NameArity>) -> Vec<String> {
+    keys.map(NameArity::to_string).collect()
+}
+
+#[test]
+fn declarations_are_collected() {
+    let source = "\
+-module(shapes).
+-export([area/1, new/0]).
+-export_type([shape/0]).
+-import(lists, [map/2, sum/1]).
+-record(circle, {radius = 1 :: number(), label}).
+-type shape() :: #circle{}.
+-opaque id(A) :: {id, A}.
+-spec area(shape()) -> number().
+-callback init(term()) -> ok.
+
+area(#circle{radius = R}) -> 3.14 * R * R.
+
+new() -> #circle{}.
+
+helper(X, Y) -> X + Y.
+";
+    let tree = parse(source);
+    let model = ModuleModel::new(&tree, source);
+    assert_eq!(model.name(), Some("shapes"));
+    assert_eq!(keys(model.exports().iter()), ["area/1", "new/0"]);
+    let imports: Vec<_> = model
+        .imports()
+        .iter()
+        .map(|i| format!("{}:{}", i.module(), i.key()))
+        .collect();
+    assert_eq!(imports, ["lists:map/2", "lists:sum/1"]);
+
+    let circle = model.record("circle").expect("circle");
+    assert_eq!(circle.fields(), ["radius", "label"]);
+
+    assert_eq!(
+        keys(model.types().iter().map(|t| t.key())),
+        ["shape/0", "id/1"]
+    );
+    let id = model.ty("id", 1).expect("id/1");
+    assert!(id.is_opaque() && !id.is_exported());
+    assert!(model.ty("shape", 0).expect("shape/0").is_exported());
+    assert_eq!(keys(model.callbacks().iter().map(|c| c.key())), ["init/1"]);
+
+    assert_eq!(
+        keys(model.functions().iter().map(|f| f.key())),
+        ["area/1", "new/0", "helper/2"]
+    );
+    let area = model.function("area", 1).expect("area/1");
+    assert_eq!(area.spec(), Some(model.specs()[0].node()));
+    assert!(area.is_exported());
+    let helper = model.function("helper", 2).expect("helper/2");
+    assert_eq!(helper.spec(), None);
+    assert!(!helper.is_exported());
+    assert_eq!(
+        tree.view(helper.node()).expect("node").kind(),
+        erl_parse::SyntaxKind::FunctionDecl
+    );
+    assert!(model.diagnostics().is_empty());
+}
+
+#[test]
+fn export_all_exports_every_function() {
+    let source = "-module(m).\n-compile([debug_info, export_all]).\nf() -> ok.\n";
+    let tree = parse(source);
+    let model = ModuleModel::new(&tree, source);
+    assert!(model.function("f", 0).expect("f/0").is_exported());
+}
+
+#[test]
+fn clause_problems_are_reported() {
+    use erl_parse::DiagnosticKind::*;
+    let source = "\
+f(X) -> X;
+f(X, Y) -> X + Y;
+g(X) -> X.
+f(Y) -> Y.
+";
+    let tree = parse(source);
+    assert!(tree.diagnostics().is_empty());
+    let model = ModuleModel::new(&tree, source);
+    assert_eq!(keys(model.functions().iter().map(|f| f.key())), ["f/1"]);
+    let reports: Vec<_> = model
+        .diagnostics()
+        .iter()
+        .map(|d| (d.kind(), d.render(&tree, source).erlc()))
+        .collect();
+    assert_eq!(
+        reports,
+        [
+            (
+                ClauseArityMismatch,
+                "nofile:2:1: head mismatch: function f with arities 1 and 2 is regarded as two \
+                 distinct functions. Is the number of arguments incorrect or is the semicolon \
+                 in f/1 unwanted?"
+                    .to_string()
+            ),
+            (
+                ClauseNameMismatch,
+                "nofile:3:1: head mismatch: previous function f/1 is distinct from g/1. \
+                 Is the semicolon in f/1 unwanted?"
+                    .to_string()
+            ),
+            (
+                DuplicateFunction,
+                "nofile:4:1: function f/1 already defined".to_string()
+            ),
+        ]
+    );
+    let messages: Vec<_> = model
+        .diagnostics()
+        .iter()
+        .map(|d| d.message(&tree, source))
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "head mismatch: `f/2` among the clauses of `f/1`",
+            "head mismatch: `g/1` among the clauses of `f/1`",
+            "function `f/1` is already defined",
+        ]
+    );
+    assert_eq!(model.diagnostics()[2].code().code(), "EP0006");
+}
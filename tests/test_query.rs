@@ -0,0 +1,192 @@
+//! Tests for `erl_parse::query`.
+
+use erl_parse::ParseMode;
+use erl_parse::query::Query;
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+/// Runs `query` over `source` and returns each match's captures as
+/// `name=text`, joined by spaces.
+fn run(mode: ParseMode, source: &str, query: &str) -> Vec<String> {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    let query = Query::new(query).expect("valid query");
+    query
+        .matches(&tree, source)
+        .iter()
+        .map(|m| {
+            m.captures()
+                .iter()
+                .map(|&(index, node)| {
+                    let text = node.text(source).trim();
+                    format!("{}={text}", query.capture_names()[index])
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+#[test]
+fn module_queries_match_calls_with_predicates_alternations_and_anchors() {
+    let source = "\
+-module(m).
+f(L) ->
+    R = lists:reverse(L),
+    io:format(\"~p~n\", [R]),
+    case R of
+        {ok, V} -> lists:map(fun g/1, V);
+        {error, _} = E -> E
+    end.
+";
+    let remote = "(CallExpr (RemoteExpr (AtomExpr) @mod (AtomExpr) @fun) (ArgumentList))";
+    assert_eq!(
+        run(ParseMode::Module, source, remote),
+        [
+            "mod=lists fun=reverse",
+            "mod=io fun=format",
+            "mod=lists fun=map",
+        ]
+    );
+    // A capture right after the kind names the node itself.
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            "(CallExpr (RemoteExpr (AtomExpr @mod) (AtomExpr @fun)) (ArgumentList))",
+        ),
+        run(ParseMode::Module, source, remote)
+    );
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            &format!("({remote} (#eq? @mod \"lists\"))"),
+        ),
+        ["mod=lists fun=reverse", "mod=lists fun=map"]
+    );
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            &format!("{remote} (#not-any-of? @fun \"reverse\" \"map\")"),
+        ),
+        ["mod=io fun=format"]
+    );
+    // The first argument, whichever kind it is.
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            "(CallExpr (ArgumentList . [(LocalFunRef) (StringExpr)] @first))",
+        ),
+        ["first=\"~p~n\"", "first=fun g/1"]
+    );
+    // A tuple whose first element is an atom, and its last element.
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            "(TupleExpr . (AtomExpr) @tag _ @last .) ; tagged tuples",
+        ),
+        ["tag=ok last=V", "tag=error last=_"]
+    );
+    assert!(run(ParseMode::Module, source, "(TupleExpr . (VarExpr))").is_empty());
+    assert_eq!(
+        run(
+            ParseMode::Module,
+            source,
+            "(Clause (MatchExpr _ . (VarExpr) @var))"
+        ),
+        ["var=E"]
+    );
+}
+
+#[test]
+fn queries_run_over_expression_type_and_term_list_trees() {
+    assert_eq!(
+        run(
+            ParseMode::Expression,
+            "[Y || Y <- L, Y > X + X].",
+            "(BinaryOpExpr (VarExpr) @l . (VarExpr) @r (#eq? @l @r))",
+        ),
+        ["l=X r=X"]
+    );
+    assert_eq!(
+        run(
+            ParseMode::Expression,
+            "[Y || Y <- L, Y > X + X].",
+            "(ListComprehension (Generator (VarExpr) @pat (_) @src))",
+        ),
+        ["pat=Y src=L"]
+    );
+    assert_eq!(
+        run(
+            ParseMode::Type,
+            "{ok, integer()} | [atom()] | #{key => 1..10}.",
+            "(UnionType [(TupleType) (MapType)] @alt) (RangeType) @range",
+        ),
+        ["alt=#{key => 1..10}", "alt={ok, integer()}", "range=1..10"]
+    );
+    assert_eq!(
+        run(
+            ParseMode::TermList,
+            "{app, [{vsn, \"1.0\"}, {env, []}]}. {b, 1}.",
+            "((TupleExpr . (AtomExpr) @key . _ @value .)
+              (#any-of? @key \"vsn\" \"env\"))",
+        ),
+        ["key=vsn value=\"1.0\"", "key=env value=[]"]
+    );
+}
+
+#[test]
+fn malformed_queries_report_where_they_fail() {
+    let error = |query: &str| {
+        let error = Query::new(query).expect_err(query);
+        (error.offset(), error.message().to_string())
+    };
+    assert_eq!(
+        error("(CallExpr (Frob))"),
+        (11, "unknown node kind `Frob`".to_string())
+    );
+    assert_eq!(
+        error("(AtomExpr) @a (#eq? @b \"x\")"),
+        (20, "undefined capture `@b`".to_string())
+    );
+    assert_eq!(
+        error("(#eq? @a \"x\")"),
+        (0, "a predicate must follow a pattern".to_string())
+    );
+    assert_eq!(
+        error("(AtomExpr) @a (#matches? @a \"x\")"),
+        (15, "unknown predicate `#matches?`".to_string())
+    );
+    assert_eq!(
+        error("(TupleExpr . @x)"),
+        (13, "expected a pattern".to_string())
+    );
+    assert_eq!(
+        error("(TupleExpr"),
+        (10, "expected `)` to close the pattern".to_string())
+    );
+    assert_eq!(
+        error("(AtomExpr) @a (#eq? @a \"x)"),
+        (23, "unterminated string".to_string())
+    );
+    let query = Query::new("(CallExpr _ @target) @call").expect("valid");
+    assert_eq!(query.capture_names(), ["target", "call"]);
+    assert_eq!(query.capture_index("call"), Some(1));
+    assert_eq!(query.pattern_count(), 1);
+}
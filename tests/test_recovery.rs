@@ -223,6 +223,14 @@ fn tokenizer_lexer_errors_are_not_reported_as_parser_errors() {
             | erl_parse::DiagnosticKind::SkippedToken
             | erl_parse::DiagnosticKind::MissingToken
             | erl_parse::DiagnosticKind::NestingDepthExceeded => {}
+            erl_parse::DiagnosticKind::DuplicateFunction
+            | erl_parse::DiagnosticKind::ClauseArityMismatch
+            | erl_parse::DiagnosticKind::ClauseNameMismatch
+            | erl_parse::DiagnosticKind::UnusedVariable
+            | erl_parse::DiagnosticKind::UnsafeVariable
+            | erl_parse::DiagnosticKind::UnderscoreVariableUsed => {
+                panic!("analysis diagnostic on the tree: {e:?}")
+            }
         }
     }
 }
@@ -280,3 +288,95 @@ fn hidden_tokens_around_errors_are_kept_in_the_token_buffer() {
     let (tree, _roots) = drive(erl_parse::ParseMode::Module, source);
     assert_eq!(tree.tokens().len(), scanned.len());
 }
+
+#[test]
+fn diagnostics_carry_stable_codes_from_the_registry() {
+    let (tree, _roots) = drive(erl_parse::ParseMode::Module, "f( -> ok.\ng() -> (ok.");
+    let codes: Vec<_> = tree.diagnostics().iter().map(|d| d.code().code()).collect();
+    assert_eq!(codes.first(), Some(&"EP0200"));
+    assert!(codes.contains(&"EP0204"), "{codes:?}");
+    for diagnostic in tree.diagnostics() {
+        assert_eq!(diagnostic.severity(), erl_parse::Severity::Error);
+        let entry =
+            erl_parse::DiagnosticCode::lookup(diagnostic.code().code()).expect("registered code");
+        assert_eq!(entry, diagnostic.code());
+    }
+    let eof = erl_parse::DiagnosticCode::all()
+        .iter()
+        .find(|c| c.kind() == Some(erl_parse::DiagnosticKind::UnexpectedEof))
+        .expect("kind code");
+    assert_eq!(eof.code(), "EP0002");
+}
+
+/// Applies the fix of the first diagnostic that has one.
+fn apply_first_fix(source: &str) -> String {
+    let (tree, _roots) = drive(erl_parse::ParseMode::Module, source);
+    let fix = tree
+        .diagnostics()
+        .iter()
+        .find_map(|d| d.fix())
+        .unwrap_or_else(|| panic!("no fix for {source:?}: {:?}", tree.diagnostics()));
+    let index = erl_parse::LineIndex::new(&tree, source);
+    let (range, text) = fix.byte_edit(&index, source);
+    let mut fixed = source.to_string();
+    fixed.replace_range(range, &text);
+    fixed
+}
+
+#[test]
+fn fixes_repair_the_input_they_report_on() {
+    let cases = [
+        ("f() -> begin ok.", "f() -> begin ok end."),
+        ("f() -> (ok.", "f() -> (ok)."),
+        (
+            "f(X) -> case X of 1 -> a; end.",
+            "f(X) -> case X of 1 -> a end.",
+        ),
+        ("f(1) -> a;.", "f(1) -> a."),
+        (
+            "f(1) -> a;\n-spec g() -> ok.",
+            "f(1) -> a.\n-spec g() -> ok.",
+        ),
+        (
+            "f(X) -> if X > 0 -> a, X < 0 -> b end.",
+            "f(X) -> if X > 0 -> a; X < 0 -> b end.",
+        ),
+        ("f() -> foo(1 2).", "f() -> foo(1 )."),
+        ("f() -> {a}}.", "f() -> {a}."),
+        // Recovery swept up the `.`; the insert goes before it, after
+        // the last token the grammar accepted.
+        ("f() -> {a, b.", "f() -> {a, b}."),
+        ("f( -> ok.", "f() -> ok."),
+    ];
+    for (source, expected) in cases {
+        let fixed = apply_first_fix(source);
+        assert_eq!(fixed, expected, "{source}");
+        let (tree, _roots) = drive(erl_parse::ParseMode::Module, &fixed);
+        assert!(
+            tree.diagnostics().is_empty(),
+            "{fixed}: {:?}",
+            tree.diagnostics()
+        );
+    }
+}
+
+#[test]
+fn fixes_point_at_token_indexes() {
+    let source = "f() -> begin ok.";
+    let (tree, _roots) = drive(erl_parse::ParseMode::Module, source);
+    let diagnostic = find_diagnostic_by_kind(&tree, erl_parse::DiagnosticKind::MissingToken)
+        .expect("missing `end`");
+    assert_eq!(
+        diagnostic.fix(),
+        Some(erl_parse::Fix::Insert {
+            at: diagnostic.range().start(),
+            text: "end",
+        })
+    );
+
+    // A skipped `->` is not a stray closer: deleting it would not help.
+    let (tree, _roots) = drive(erl_parse::ParseMode::Module, "f( -> ok.");
+    let skipped = find_diagnostic_by_kind(&tree, erl_parse::DiagnosticKind::SkippedToken)
+        .expect("skipped `->`");
+    assert_eq!(skipped.fix(), None);
+}
@@ -0,0 +1,119 @@
+//! Tests for `Diagnostic::message` and `Diagnostic::render`.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+fn first_diagnostic(tree: &erl_parse::SyntaxTree) -> erl_parse::Diagnostic {
+    *tree.diagnostics().first().expect("a diagnostic")
+}
+
+#[test]
+fn snippet_underlines_the_offending_token() {
+    let source = "-module(m).\nf( -> ok.\n";
+    let tree = parse(source);
+    let diagnostic = first_diagnostic(&tree);
+    let rendered = diagnostic.render(&tree, source).file("m.erl").to_string();
+    assert_eq!(
+        rendered,
+        "error[EP0200]: expected expression, found `->`\n \
+         --> m.erl:2:4\n  \
+         |\n\
+         2 | f( -> ok.\n  \
+         |    ^^"
+    );
+}
+
+#[test]
+fn snippet_keeps_tabs_in_the_caret_indent() {
+    let source = "f() ->\n\tok ok.";
+    let tree = parse(source);
+    let diagnostic = first_diagnostic(&tree);
+    let rendered = diagnostic.render(&tree, source).to_string();
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[3], "2 | \tok ok.");
+    assert_eq!(lines[4], "  | \t   ^^");
+}
+
+#[test]
+fn colors_wrap_the_labels() {
+    let source = "f( -> ok.";
+    let tree = parse(source);
+    let rendered = first_diagnostic(&tree)
+        .render(&tree, source)
+        .color(true)
+        .to_string();
+    assert!(rendered.starts_with("\x1b[1;31merror[EP0200]\x1b[0m\x1b[1m: expected"));
+    assert!(rendered.ends_with("\x1b[1;31m^^\x1b[0m"));
+}
+
+#[test]
+fn messages_describe_each_kind() {
+    let source = "f() ->\n  ok +";
+    let tree = parse(source);
+    let diagnostic = *tree.diagnostics().last().expect("eof");
+    assert_eq!(diagnostic.kind(), erl_parse::DiagnosticKind::UnexpectedEof);
+    assert_eq!(
+        diagnostic.message(&tree, source),
+        "expected expression, found end of input"
+    );
+
+    let source = format!("f() -> {}ok{}.", "(".repeat(300), ")".repeat(300));
+    let tree = parse(&source);
+    let diagnostic = first_diagnostic(&tree);
+    assert_eq!(
+        diagnostic.message(&tree, &source),
+        "nesting too deep (limit is 256)"
+    );
+}
+
+#[test]
+fn erlc_format_matches_erl_parse_errors() {
+    let cases = [
+        ("f( -> ok.", "nofile:1:4: syntax error before: '->'"),
+        ("f() -> foo bar.", "nofile:1:12: syntax error before: bar"),
+        ("f() -> ok end.", "nofile:1:11: syntax error before: 'end'"),
+        ("f() -> X Y.", "nofile:1:10: syntax error before: Y"),
+        ("f() -> 1 16#1F.", "nofile:1:10: syntax error before: 31"),
+        (
+            "f() -> a \"s\\n\".",
+            "nofile:1:10: syntax error before: \"s\\n\"",
+        ),
+        (
+            "f() -> 'A b' 'c d'.",
+            "nofile:1:14: syntax error before: 'c d'",
+        ),
+        ("f() ->\n  ok +", "nofile:2:7: syntax error before: "),
+    ];
+    for (source, expected) in cases {
+        let tree = parse(source);
+        let diagnostic = first_diagnostic(&tree);
+        assert_eq!(
+            diagnostic.render(&tree, source).erlc(),
+            expected,
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn snippet_ends_with_the_suggested_fix() {
+    let source = "f() -> begin ok.";
+    let tree = parse(source);
+    let rendered = first_diagnostic(&tree).render(&tree, source).to_string();
+    assert!(rendered.ends_with("\n  = help: insert `end`"), "{rendered}");
+}
@@ -0,0 +1,108 @@
+//! Integration tests for `erl_parse::SyntaxTree::reparse` and
+//! `erl_parse::TokenEdit`: which roots are reused and which are
+//! reported as changed for a few representative edits.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn module(tokens: &[erl_tokenize::Token]) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in tokens {
+        parser.feed_token(*t);
+    }
+    parser.finish()
+}
+
+/// Parses `old`, reparses it as `new`, and returns both trees plus the
+/// changed root ids.
+fn edit(
+    old: &str,
+    new: &str,
+) -> (
+    erl_parse::SyntaxTree,
+    erl_parse::SyntaxTree,
+    Vec<erl_parse::NodeId>,
+) {
+    let old_tokens = scan_all(old);
+    let new_tokens = scan_all(new);
+    let old_tree = module(&old_tokens);
+    let edit = erl_parse::TokenEdit::diff(&old_tokens, old, &new_tokens, new);
+    let (tree, changed) = old_tree.reparse(edit, &new_tokens);
+    (old_tree, tree, changed)
+}
+
+fn root_kinds(tree: &erl_parse::SyntaxTree) -> Vec<erl_parse::SyntaxKind> {
+    tree.roots().map(|r| r.kind()).collect()
+}
+
+#[test]
+fn diff_trims_common_prefix_and_suffix() {
+    let old = "f() -> 1.\ng() -> 2.\n";
+    let new = "f() -> 1.\ng() -> 42.\n";
+    let edit = erl_parse::TokenEdit::diff(&scan_all(old), old, &scan_all(new), new);
+    assert_eq!(
+        edit.range(),
+        erl_parse::TokenRange::new(
+            erl_parse::TokenIndex::new(15),
+            erl_parse::TokenIndex::new(16)
+        )
+    );
+    assert_eq!(edit.new_len(), 1);
+}
+
+#[test]
+fn edit_inside_one_form_reparses_only_that_form() {
+    let old = "-module(m).\nf() -> 1.\ng() -> 2.\n";
+    let new = "-module(m).\nf() -> {1, 2}.\ng() -> 2.\n";
+    let (old_tree, tree, changed) = edit(old, new);
+    let old_roots: Vec<_> = old_tree.roots().collect();
+    let roots: Vec<_> = tree.roots().collect();
+    assert_eq!(roots.len(), 3);
+    assert_eq!(changed, vec![roots[1].node_id()]);
+    // The attribute before the edit keeps its id and range.
+    assert_eq!(roots[0].node_id(), old_roots[0].node_id());
+    assert_eq!(roots[0].range(), old_roots[0].range());
+    // The form after the edit is carried over, shifted by the five
+    // inserted tokens.
+    let shift = |r: erl_parse::TokenRange| r.start().get() + 5..r.end().get() + 5;
+    assert_eq!(roots[2].range().as_range(), shift(old_roots[2].range()));
+    assert!(tree.diagnostics().is_empty());
+}
+
+#[test]
+fn deleting_a_dot_merges_forms_and_inserting_one_splits_them() {
+    let joined = "f() -> 1.\ng() -> 2; g(_) -> 3.\nk() -> 4.\n";
+    let split = "f() -> 1.\ng() -> 2. g(_) -> 3.\nk() -> 4.\n";
+
+    let (_, tree, changed) = edit(joined, split);
+    assert_eq!(tree.roots().count(), 4);
+    assert_eq!(changed.len(), 2);
+    assert!(tree.diagnostics().is_empty());
+
+    let (_, tree, changed) = edit(split, joined);
+    assert_eq!(tree.roots().count(), 3);
+    assert_eq!(changed.len(), 1);
+    assert!(tree.diagnostics().is_empty());
+    assert_eq!(
+        root_kinds(&tree),
+        vec![erl_parse::SyntaxKind::FunctionDecl; 3]
+    );
+}
+
+#[test]
+fn unterminated_tail_is_always_reparsed() {
+    let old = "f() -> 1.\ng() ->";
+    let new = "f() -> 1.\ng() -> 2.";
+    let (_, tree, changed) = edit(old, new);
+    let roots: Vec<_> = tree.roots().collect();
+    assert_eq!(roots.len(), 2);
+    assert_eq!(changed, vec![roots[1].node_id()]);
+    assert!(tree.diagnostics().is_empty());
+}
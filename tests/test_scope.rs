@@ -0,0 +1,138 @@
+//! Tests for `erl_parse::scope`.
+
+use erl_parse::scope::{Role, Scopes};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(mode: erl_parse::ParseMode, source: &str) -> erl_parse::SyntaxTree {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    parser.finish()
+}
+
+/// Rewrites every variable token as `Name/b<id>` when it binds and
+/// `Name/u<id>` when it uses, with `!` after an unsafe use.
+fn annotate(mode: erl_parse::ParseMode, source: &str) -> String {
+    let tree = parse(mode, source);
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    let scopes = Scopes::new(&tree, source);
+    let mut out = String::new();
+    for (i, token) in tree.tokens().iter().enumerate() {
+        out.push_str(token.text(source));
+        if let Some(occurrence) = scopes.occurrence(erl_parse::TokenIndex::new(i)) {
+            let role = match occurrence.role() {
+                Role::Binding => 'b',
+                Role::Use => 'u',
+            };
+            out.push_str(&format!("/{role}{}", occurrence.variable().get()));
+            if occurrence.unsafe_in().is_some() {
+                out.push('!');
+            }
+        }
+    }
+    out
+}
+
+fn module(source: &str) -> String {
+    annotate(erl_parse::ParseMode::Module, source)
+}
+
+#[test]
+fn patterns_bind_unless_already_bound() {
+    assert_eq!(
+        module("f(X, X) -> {Y, X} = g(X), Y."),
+        "f(X/b0, X/u0) -> {Y/b1, X/u0} = g(X/u0), Y/u1."
+    );
+    assert_eq!(
+        module("f(N, B) -> <<S, D:S/binary>> = B, #{N := V} = D, {_, _W} = V, _W."),
+        "f(N/b0, B/b1) -> <<S/b2, D/b3:S/u2/binary>> = B/u1, #{N/u0 := V/b4} = D/u3, \
+         {_, _W/b5} = V/u4, _W/u5."
+    );
+}
+
+#[test]
+fn clause_bindings_are_exported_or_unsafe() {
+    assert_eq!(
+        module("f(A) -> case A of 1 -> B = 1, C = 2; _ -> B = 3 end, {B, C}."),
+        "f(A/b0) -> case A/u0 of 1 -> B/b1 = 1, C/b2 = 2; _ -> B/b1 = 3 end, {B/u1, C/u2!}."
+    );
+    assert_eq!(
+        module("f() -> receive {ok, M} -> ok after 0 -> M = none end, M."),
+        "f() -> receive {ok, M/b0} -> ok after 0 -> M/b0 = none end, M/u0."
+    );
+    assert_eq!(
+        module("f(X) -> if X -> Y = 1; true -> ok end, Y."),
+        "f(X/b0) -> if X/u0 -> Y/b1 = 1; true -> ok end, Y/u1!."
+    );
+}
+
+#[test]
+fn try_catch_and_maybe_bindings_are_unsafe() {
+    assert_eq!(
+        module("f() -> try A = g() of B -> A + B catch C:R:S -> {A, C, S} after A end, {B, R}."),
+        "f() -> try A/b0 = g() of B/b1 -> A/u0 + B/u1 catch C/b2:R/b3:S/b4 -> \
+         {A/u0!, C/u2, S/u4} after A/u0! end, {B/u1!, R/u3!}."
+    );
+    assert_eq!(
+        module("f() -> X = (catch Y = 1), maybe {ok, Z} ?= Y, Z end, {X, Y, Z}."),
+        "f() -> X/b0 = (catch Y/b1 = 1), maybe {ok, Z/b2} ?= Y/u1!, Z/u2 end, \
+         {X/u0, Y/u1!, Z/u2!}."
+    );
+}
+
+#[test]
+fn funs_and_comprehensions_shadow_and_do_not_export() {
+    assert_eq!(
+        module("f(X) -> F = fun(X) -> Y = X end, L = fun Loop(0) -> X; Loop(N) -> Loop(N) end, Y."),
+        "f(X/b0) -> F/b1 = fun(X/b2) -> Y/b3 = X/u2 end, L/b4 = fun Loop/b5(0) -> X/u0; \
+         Loop/b5(N/b6) -> Loop/u5(N/u6) end, Y/u7."
+    );
+    assert_eq!(
+        module("f(L) -> [{X, Y} || X <- L, Y <- X, X > Y], X."),
+        "f(L/b0) -> [{X/u1, Y/u2} || X/b1 <- L/u0, Y/b2 <- X/u1, X/u1 > Y/u2], X/u3."
+    );
+    assert_eq!(
+        module("f(L, X) -> [X || X <- L && _ <- X]."),
+        "f(L/b0, X/b1) -> [X/u2 || X/b2 <- L/u0 && _ <- X/u1]."
+    );
+}
+
+#[test]
+fn definitions_and_references_and_expression_mode() {
+    let source = "begin X = 1, case X of 1 -> Y = X; _ -> Y = 2 end, Y + Z end.";
+    assert_eq!(
+        annotate(erl_parse::ParseMode::Expression, source),
+        "begin X/b0 = 1, case X/u0 of 1 -> Y/b1 = X/u0; _ -> Y/b1 = 2 end, Y/u1 + Z/u2 end."
+    );
+    let tree = parse(erl_parse::ParseMode::Expression, source);
+    let scopes = Scopes::new(&tree, source);
+    let offsets = |indexes: &[erl_parse::TokenIndex]| -> Vec<usize> {
+        indexes
+            .iter()
+            .map(|i| tree.tokens()[i.get()].start().offset())
+            .collect()
+    };
+    let y_use = (0..tree.tokens().len())
+        .map(erl_parse::TokenIndex::new)
+        .rfind(|i| tree.tokens()[i.get()].text(source) == "Y")
+        .expect("Y");
+    let at = |text: &str| source.find(text).expect(text);
+    assert_eq!(
+        offsets(scopes.definitions(y_use)),
+        [at("Y = X"), at("Y = 2")]
+    );
+    assert_eq!(offsets(scopes.references(y_use)), [at("Y + Z")]);
+    let z = scopes.variables().last().expect("Z");
+    assert_eq!((z.name(), z.bindings().len()), ("Z", 0));
+    assert!(scopes.definitions(erl_parse::TokenIndex::new(0)).is_empty());
+}
@@ -0,0 +1,94 @@
+//! Tests for `SyntaxTree::selection_ranges`.
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+/// Returns the text of each step when expanding from the token that
+/// starts at byte `offset`.
+fn steps(source: &str, offset: usize) -> Vec<String> {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    let at = tree
+        .tokens()
+        .iter()
+        .position(|t| t.start().offset() == offset)
+        .map(erl_parse::TokenIndex::new)
+        .expect("token at offset");
+    let ranges = tree.selection_ranges(at);
+    for pair in ranges.windows(2) {
+        assert!(pair[1].contains_range(pair[0]) && pair[1] != pair[0]);
+    }
+    ranges
+        .iter()
+        .map(|range| {
+            tree.tokens()[range.as_range()]
+                .iter()
+                .map(|t| t.text(source))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn arguments_expand_through_separator_and_element_steps() {
+    let source = "f(X) ->\n    g(a, b, c),\n    X.\n";
+    assert_eq!(
+        steps(source, source.find("b,").unwrap()),
+        [
+            "b",
+            "b,",
+            "a, b, c",
+            "(a, b, c)",
+            "g(a, b, c)",
+            "g(a, b, c),",
+            "g(a, b, c),\n    X",
+            "f(X) ->\n    g(a, b, c),\n    X",
+            "f(X) ->\n    g(a, b, c),\n    X.",
+        ]
+    );
+}
+
+#[test]
+fn map_fields_include_their_trailing_comma() {
+    let source = "f() -> #{k => 1, l => 2}.";
+    assert_eq!(
+        steps(source, source.find("1").unwrap()),
+        [
+            "1",
+            "k => 1",
+            "k => 1,",
+            "k => 1, l => 2",
+            "#{k => 1, l => 2}",
+            "f() -> #{k => 1, l => 2}",
+            "f() -> #{k => 1, l => 2}.",
+        ]
+    );
+}
+
+#[test]
+fn clause_bodies_and_clauses_are_steps() {
+    let source = "f(0) -> a, b;\nf(_) -> c.";
+    assert_eq!(
+        steps(source, source.find("b;").unwrap()),
+        [
+            "b",
+            "a, b",
+            "f(0) -> a, b",
+            "f(0) -> a, b;",
+            "f(0) -> a, b;\nf(_) -> c",
+            "f(0) -> a, b;\nf(_) -> c.",
+        ]
+    );
+    assert!(steps(source, 0).len() > 1);
+}
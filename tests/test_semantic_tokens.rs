@@ -0,0 +1,143 @@
+//! Tests for `SyntaxTree::semantic_tokens`.
+
+use erl_parse::{SemanticTokenModifiers, SemanticTokenType};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+/// Lists every non-operator token as `text:type`, with `+` after a
+/// declaration and `*` after documentation.
+fn classes(source: &str) -> Vec<String> {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    tree.semantic_tokens(source)
+        .into_iter()
+        .filter(|t| t.token_type() != SemanticTokenType::Operator)
+        .map(|t| {
+            let mut out = format!(
+                "{}:{}",
+                tree.tokens()[t.index().get()].text(source),
+                t.token_type().as_str()
+            );
+            if t.modifiers().contains(SemanticTokenModifiers::DECLARATION) {
+                out.push('+');
+            }
+            if t.modifiers()
+                .contains(SemanticTokenModifiers::DOCUMENTATION)
+            {
+                out.push('*');
+            }
+            out
+        })
+        .collect()
+}
+
+#[test]
+fn expressions_are_classified_by_their_node() {
+    assert_eq!(
+        classes("f(X) -> %% note\n    lists:map(fun g/1, [X, #r{a = 1.5}#r.a, \"s\", ok])."),
+        [
+            "f:function+",
+            "X:variable+",
+            "%% note:comment",
+            "lists:namespace",
+            "map:function",
+            "fun:keyword",
+            "g:function",
+            "1:number",
+            "X:variable",
+            "r:struct",
+            "a:property",
+            "1.5:number",
+            "r:struct",
+            "a:property",
+            "\"s\":string",
+            "ok:enumMember",
+        ]
+    );
+}
+
+#[test]
+fn attribute_payloads_are_classified() {
+    let source = "\
+-module(m).
+-export([f/1]).
+-export_type([t/1]).
+-doc \"Hi\".
+-type t(A) :: {A, other:u()}.
+-record(r, {a :: t(integer())}).
+-spec f(T) -> T when T :: atom().
+";
+    assert_eq!(
+        classes(source),
+        [
+            "module:macro",
+            "m:namespace",
+            "export:macro",
+            "f:function",
+            "1:number",
+            "export_type:macro",
+            "t:type",
+            "1:number",
+            "doc:macro",
+            "\"Hi\":string*",
+            "type:macro",
+            "t:type+",
+            "A:typeParameter",
+            "A:typeParameter",
+            "other:namespace",
+            "u:type",
+            "record:macro",
+            "r:struct+",
+            "a:property+",
+            "t:type",
+            "integer:type",
+            "spec:macro",
+            "f:function",
+            "T:typeParameter",
+            "T:typeParameter",
+            "when:keyword",
+            "T:typeParameter",
+            "atom:type",
+        ]
+    );
+    assert_eq!(SemanticTokenType::ALL[4].as_str(), "variable");
+    assert_eq!(SemanticTokenType::Variable.index(), 4);
+}
+
+#[test]
+fn encoding_splits_multi_line_tokens_and_counts_utf16() {
+    let source = "f(X) ->\r\n  \"é\r\n😀\" ++ X.";
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    let tokens = tree.semantic_tokens(source);
+    let data = erl_parse::SemanticToken::encode(&tokens, &tree, source);
+    let string = SemanticTokenType::String.index();
+    let rows: Vec<_> = data
+        .chunks(5)
+        .filter(|row| row[3] == string)
+        .map(|row| [row[0], row[1], row[2]])
+        .collect();
+    // `"é` on line 1, then `😀"` on line 2, where `😀` is two UTF-16
+    // units.
+    assert_eq!(rows, [[1, 2, 2], [1, 0, 3]]);
+    // `++` and `X` follow on line 2, counted from the `😀"` entry.
+    assert_eq!(
+        data[data.len() - 15..data.len() - 5],
+        [0, 4, 2, 13, 0, 0, 3, 1, 4, 0]
+    );
+}
@@ -0,0 +1,184 @@
+//! Tests for `erl_parse::visit`.
+
+use erl_parse::ast::{self, AstNode};
+use erl_parse::visit::{self, Control, Rewriter, Visitor};
+use erl_parse::{NodeView, ParseMode, SyntaxKind, SyntaxTree};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn parse(mode: ParseMode, source: &str) -> SyntaxTree {
+    let mut parser = erl_parse::Parser::new(mode);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    tree
+}
+
+/// Records `+Kind` on enter and `-Kind` on leave, skipping the children
+/// of `skip` and stopping at `stop`.
+struct Events {
+    events: Vec<String>,
+    skip: SyntaxKind,
+    stop: SyntaxKind,
+}
+
+impl<'a> Visitor<'a> for Events {
+    fn enter(&mut self, node: NodeView<'a>) -> Control {
+        self.events.push(format!("+{:?}", node.kind()));
+        if node.kind() == self.stop {
+            Control::Stop
+        } else {
+            Control::Continue
+        }
+    }
+
+    fn leave(&mut self, node: NodeView<'a>) {
+        self.events.push(format!("-{:?}", node.kind()));
+    }
+
+    fn visit_list_expr(&mut self, node: ast::ListExpr<'a>) -> Control {
+        if node.syntax().kind() == self.skip {
+            Control::SkipChildren
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+#[test]
+fn walk_enters_and_leaves_in_preorder_and_honours_control() {
+    let tree = parse(ParseMode::Expression, "{a, [b, c], d}.");
+    let events = |skip, stop| {
+        let mut visitor = Events {
+            events: Vec::new(),
+            skip,
+            stop,
+        };
+        let control = visit::walk_tree(&tree, &mut visitor);
+        (visitor.events.join(" "), control)
+    };
+    assert_eq!(
+        events(SyntaxKind::Error, SyntaxKind::Error),
+        (
+            "+TupleExpr +AtomExpr -AtomExpr +ListExpr +AtomExpr -AtomExpr +AtomExpr -AtomExpr \
+             -ListExpr +AtomExpr -AtomExpr -TupleExpr"
+                .to_string(),
+            Control::Continue
+        )
+    );
+    assert_eq!(
+        events(SyntaxKind::ListExpr, SyntaxKind::Error),
+        (
+            "+TupleExpr +AtomExpr -AtomExpr +ListExpr -ListExpr +AtomExpr -AtomExpr -TupleExpr"
+                .to_string(),
+            Control::Continue
+        )
+    );
+    assert_eq!(
+        events(SyntaxKind::Error, SyntaxKind::ListExpr),
+        (
+            "+TupleExpr +AtomExpr -AtomExpr +ListExpr".to_string(),
+            Control::Stop
+        )
+    );
+
+    // A subtree walk stays inside the subtree.
+    let list = tree
+        .roots()
+        .flat_map(|root| root.descendants())
+        .find(|node| node.kind() == SyntaxKind::ListExpr)
+        .expect("list");
+    let mut visitor = Events {
+        events: Vec::new(),
+        skip: SyntaxKind::Error,
+        stop: SyntaxKind::Error,
+    };
+    visit::walk(list, &mut visitor);
+    assert_eq!(
+        visitor.events.join(" "),
+        "+ListExpr +AtomExpr -AtomExpr +AtomExpr -AtomExpr -ListExpr"
+    );
+}
+
+#[test]
+fn typed_hooks_see_their_own_kind() {
+    struct Heads<'s> {
+        source: &'s str,
+        heads: Vec<String>,
+        cases: usize,
+    }
+
+    impl<'a> Visitor<'a> for Heads<'_> {
+        fn visit_function_clause(&mut self, clause: ast::FunctionClause<'a>) -> Control {
+            let args = clause.arguments().map(|a| a.args().count());
+            self.heads.push(format!(
+                "{}/{args:?}",
+                clause.syntax().text(self.source).trim()
+            ));
+            Control::Continue
+        }
+
+        fn visit_case_expr(&mut self, _: ast::CaseExpr<'a>) -> Control {
+            self.cases += 1;
+            Control::SkipChildren
+        }
+    }
+
+    let source = "f(X) -> case X of 1 -> case X of _ -> a end end.\ng() -> ok.\n";
+    let tree = parse(ParseMode::Module, source);
+    let mut heads = Heads {
+        source,
+        heads: Vec::new(),
+        cases: 0,
+    };
+    visit::walk_tree(&tree, &mut heads);
+    assert_eq!(heads.cases, 1);
+    assert_eq!(heads.heads.len(), 2);
+    assert!(heads.heads[1].ends_with("/Some(0)"), "{:?}", heads.heads);
+}
+
+#[test]
+fn rewrite_replaces_outermost_nodes_and_keeps_comments() {
+    struct Rename<'s> {
+        source: &'s str,
+    }
+
+    impl<'a> Rewriter<'a> for Rename<'_> {
+        fn rewrite(&mut self, node: ast::Node<'a>) -> Option<String> {
+            match node {
+                ast::Node::AtomExpr(atom) if atom.syntax().text(self.source).trim() == "old" => {
+                    Some("new".to_string())
+                }
+                ast::Node::ListExpr(list)
+                    if list
+                        .syntax()
+                        .children()
+                        .all(|c| c.kind() == SyntaxKind::AtomExpr) =>
+                {
+                    Some("[]".to_string())
+                }
+                _ => None,
+            }
+        }
+    }
+
+    let source = "f() ->\n    %% keep\n    old({old, [old, x]}).\n";
+    let tree = parse(ParseMode::Module, source);
+    let edits = visit::rewrite(&tree, &mut Rename { source });
+    let mut rewritten = source.to_string();
+    for (range, text) in edits.iter().rev() {
+        rewritten.replace_range(range.clone(), text);
+    }
+    assert_eq!(rewritten, "f() ->\n    %% keep\n    new({new, []}).\n");
+    assert!(edits.windows(2).all(|w| w[0].0.end <= w[1].0.start));
+}
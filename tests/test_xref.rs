@@ -0,0 +1,125 @@
+//! Tests for `erl_parse::xref`.
+
+use erl_parse::xref::{CallGraph, CallKind, Callee, Mfa};
+
+fn scan_all(source: &str) -> Vec<erl_tokenize::Token> {
+    let mut out = Vec::new();
+    let mut pos = erl_tokenize::Position::new();
+    while let Some(t) = erl_tokenize::scan_token(source, pos).expect("valid source") {
+        out.push(t);
+        pos = t.end();
+    }
+    out
+}
+
+fn graph(source: &str) -> CallGraph {
+    let mut parser = erl_parse::Parser::new(erl_parse::ParseMode::Module);
+    for t in scan_all(source) {
+        parser.feed_token(t);
+    }
+    let tree = parser.finish();
+    assert!(tree.diagnostics().is_empty(), "{source:?}");
+    CallGraph::new(&tree, source)
+}
+
+#[test]
+fn local_calls_resolve_to_definitions_imports_or_auto_imported_bifs() {
+    let source = "\
+-module(m).
+-import(lists, [map/2]).
+f(L) -> g(L), map(fun g/1, L), length(L), fun lists:sum/1.
+g(X) -> [f(Y) || Y <- X].
+";
+    let graph = graph(source);
+    assert_eq!(graph.module(), Some("m"));
+    let edges: Vec<_> = graph
+        .edges()
+        .map(|(from, to)| format!("{from} -> {to}"))
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            "m:f/1 -> m:g/1",
+            "m:f/1 -> lists:map/2",
+            "m:f/1 -> m:g/1",
+            "m:f/1 -> erlang:length/1",
+            "m:f/1 -> lists:sum/1",
+            "m:g/1 -> m:f/1",
+        ]
+    );
+    let kinds: Vec<_> = graph.calls().iter().map(|c| c.kind()).collect();
+    assert_eq!(
+        kinds,
+        [
+            CallKind::Call,
+            CallKind::Call,
+            CallKind::FunRef,
+            CallKind::Call,
+            CallKind::FunRef,
+            CallKind::Call,
+        ]
+    );
+    let g = Mfa::new("m", "g", 1);
+    let callers: Vec<_> = graph.callers(&g).collect();
+    assert_eq!(callers, [&Mfa::new("m", "f", 1)]);
+    assert_eq!(graph.callees(&Mfa::new("m", "f", 1)).count(), 4);
+    assert_eq!(graph.dynamic_calls().count(), 0);
+}
+
+#[test]
+fn variable_parts_are_dynamic() {
+    let source = "\
+-module(m).
+f(M, F, A) -> M:h(1), m:F(), F(A, A), fun M:F/2, fun m:g/A, (fun() -> ok end)().
+";
+    let graph = graph(source);
+    let dynamic: Vec<_> = graph
+        .dynamic_calls()
+        .map(|call| match call.callee() {
+            Callee::Dynamic(d) => (d.module(), d.function(), d.arity()),
+            callee => panic!("{callee:?}"),
+        })
+        .collect();
+    assert_eq!(
+        dynamic,
+        [
+            (None, Some("h"), Some(1)),
+            (Some("m"), None, Some(0)),
+            (None, None, Some(2)),
+            (None, None, Some(2)),
+            (Some("m"), Some("g"), None),
+            (None, None, Some(0)),
+        ]
+    );
+    assert_eq!(graph.edges().count(), 0);
+}
+
+#[test]
+fn unknown_local_calls_are_undefined() {
+    let source = "\
+-module(m).
+f(L) -> h(L), fun h/2, self(), g(L).
+g(_) -> ok.
+";
+    let graph = graph(source);
+    let undefined: Vec<_> = graph
+        .undefined_calls()
+        .map(|call| match call.callee() {
+            Callee::Undefined(mfa) => (mfa.to_string(), call.kind()),
+            callee => panic!("{callee:?}"),
+        })
+        .collect();
+    assert_eq!(
+        undefined,
+        [
+            ("m:h/1".to_string(), CallKind::Call),
+            ("m:h/2".to_string(), CallKind::FunRef),
+        ]
+    );
+    let callees: Vec<_> = graph
+        .callees(&Mfa::new("m", "f", 1))
+        .map(Mfa::to_string)
+        .collect();
+    assert_eq!(callees, ["erlang:self/0", "m:g/1"]);
+    assert_eq!(graph.dynamic_calls().count(), 0);
+}